@@ -1,14 +1,28 @@
-use std::{iter::once, path::PathBuf, time::Duration};
-
+use std::{
+    io::Write,
+    iter::once,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use auth_rs::{async_card::AsyncCard, card_details::CardDetails, vx_card::VxCard, Event, Watcher};
 use clap::Parser;
-use color_eyre::eyre::bail;
-use electionguard_rs::{config, tally};
+use color_eyre::eyre::{bail, eyre};
+use electionguard_rs::{
+    config,
+    key_wrapping::{KeyEncryptionKey, KeyEncryptionKeys, WrappedKey},
+    tally,
+};
 use sqlx::postgres::PgPoolOptions;
-use types_rs::cacvote::Payload;
+use types_rs::cacvote::{Election, Payload};
 use url::Url;
 use uuid::Uuid;
 
-use cacvote_server_client::{signer, Client};
+use cacvote_server_client::{
+    sealed::SealedSecret, signer, unwrap_authorization_statement, verify_unwrap_authorization,
+    CardSigner, Client,
+};
 
 #[derive(Debug, Parser)]
 struct Opts {
@@ -32,20 +46,109 @@ struct Opts {
 
     #[clap(long, env = "SIGNER")]
     signer: signer::Description,
+
+    /// VX CA certificate, which must have issued the election official's card.
+    #[clap(long, env = "VX_CA_CERT")]
+    vx_cert_authority_cert: PathBuf,
+
+    /// The JX's key-encryption key sealed to its TPM, e.g. `tpm:0x81000002`,
+    /// needed to unwrap the election's private key from the database.
+    #[clap(long, env = "EG_KEY_ENCRYPTION_KEY")]
+    key_encryption_key: SealedSecret,
+
+    #[clap(long, env = "EG_RETIRED_KEY_ENCRYPTION_KEYS", value_delimiter = ',')]
+    retired_key_encryption_keys: Vec<SealedSecret>,
 }
 
 impl Opts {
     fn signing_cert(&self) -> color_eyre::Result<openssl::x509::X509> {
-        let pem = std::fs::read(&self.signing_cert)?;
-        let signing_cert = openssl::x509::X509::from_pem(&pem)?;
-        Ok(signing_cert)
+        load_cert(&self.signing_cert)
+    }
+
+    fn vx_cert_authority_cert(&self) -> color_eyre::Result<openssl::x509::X509> {
+        load_cert(&self.vx_cert_authority_cert)
+    }
+
+    fn signer(&self) -> color_eyre::Result<signer::AnyAsyncSigner> {
+        signer::AnyAsyncSigner::try_from(&self.signer)
     }
 
-    fn signer(&self) -> color_eyre::Result<signer::AnySigner> {
-        signer::AnySigner::try_from(&self.signer)
+    fn key_encryption_keys(&self) -> color_eyre::Result<KeyEncryptionKeys> {
+        let unseal = |sealed_secret: &SealedSecret| {
+            KeyEncryptionKey::from_unsealed(&sealed_secret.unseal()?)
+        };
+        Ok(KeyEncryptionKeys::new(
+            unseal(&self.key_encryption_key)?,
+            self.retired_key_encryption_keys
+                .iter()
+                .map(unseal)
+                .collect::<color_eyre::Result<_>>()?,
+        ))
     }
 }
 
+fn load_cert<P>(path: P) -> color_eyre::Result<openssl::x509::X509>
+where
+    P: AsRef<Path>,
+{
+    let cert = std::fs::read(path)?;
+    Ok(openssl::x509::X509::from_pem(&cert).or_else(|_| openssl::x509::X509::from_der(&cert))?)
+}
+
+/// Just as the JX does before decrypting, requires an election manager for
+/// the election's jurisdiction to insert their card and enter their PIN, then
+/// checks the card's signature over a statement naming the private key.
+async fn require_official_to_unwrap(
+    opts: &Opts,
+    election_id: Uuid,
+    election: &Election,
+) -> color_eyre::Result<()> {
+    let vx_cert_authority_cert = opts.vx_cert_authority_cert()?;
+    let ctx = pcsc::Context::establish(pcsc::Scope::User)?;
+    let mut watcher = Watcher::watch();
+
+    eprintln!("Insert an election manager's card to authorize decrypting…");
+    let reader_name = loop {
+        match watcher.recv().await {
+            Some(Ok(Event::CardInserted { reader_name })) => break reader_name,
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.into()),
+            None => bail!("stopped watching for cards"),
+        }
+    };
+    watcher.stop().await;
+
+    let card = Arc::new(VxCard::new(
+        vx_cert_authority_cert.clone(),
+        opts.signing_cert()?,
+        AsyncCard::connect(&ctx, &reader_name)?,
+    ));
+    let card_details = card.read_card_details().await?.card_details;
+    if !matches!(card_details, CardDetails::ElectionManagerCard(_)) {
+        bail!("only an election manager may authorize decrypting");
+    }
+    if card_details.jurisdiction_code() != election.jurisdiction_code {
+        bail!(
+            "the card is for jurisdiction {}, but the election is in {}",
+            card_details.jurisdiction_code(),
+            election.jurisdiction_code
+        );
+    }
+
+    eprint!("Enter the PIN: ");
+    std::io::stderr().flush()?;
+    let mut pin = String::new();
+    std::io::stdin().read_line(&mut pin)?;
+
+    let (signature, cert) = CardSigner::new(card, pin.trim())
+        .sign_with_certificate(&unwrap_authorization_statement(election_id))
+        .await
+        .map_err(|e| eyre!("error signing with the card: {e}"))?;
+    verify_unwrap_authorization(&vx_cert_authority_cert, election_id, &signature, &cert)?;
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
@@ -101,20 +204,33 @@ async fn main() -> color_eyre::Result<()> {
         .await?;
     let mut connection = pool.acquire().await?;
 
+    require_official_to_unwrap(&opts, cast_ballot.election_object_id, &election).await?;
+
     let record = sqlx::query!(
         r#"
-        SELECT private_key
+        SELECT
+            wrapped_private_key AS "wrapped_private_key!",
+            key_encryption_key_id AS "key_encryption_key_id!"
         FROM eg_private_keys
         WHERE election_object_id = $1
+          AND wrapped_private_key IS NOT NULL
         "#,
         cast_ballot.election_object_id
     )
     .fetch_one(&mut *connection)
     .await?;
 
+    let private_key = opts.key_encryption_keys()?.unwrap_key(
+        &WrappedKey {
+            key_encryption_key_id: record.key_encryption_key_id,
+            wrapped_key: record.wrapped_private_key,
+        },
+        cast_ballot.election_object_id.as_bytes(),
+    )?;
+
     let election_config = config::ElectionConfig {
         public_metadata_blob: election.electionguard_election_metadata_blob,
-        private_metadata_blob: record.private_key,
+        private_metadata_blob: private_key,
     };
 
     let decrypted_tally_bytes = tally::decrypt(
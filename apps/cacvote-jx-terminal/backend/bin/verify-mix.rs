@@ -0,0 +1,151 @@
+//! Checks the proofs of shuffle of an election's mixed ballots as published on
+//! the CACvote Server bulletin board, printing the result as JSON. With
+//! `--publish`, the result is also signed and posted to the server for other
+//! auditors to see. Exits with an error if any phase did not verify.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use color_eyre::eyre::bail;
+use electionguard_rs::mixnet;
+use types_rs::cacvote::{Payload, ShuffledEncryptedCastBallotsVerification, SignedObject};
+use url::Url;
+use uuid::Uuid;
+
+use cacvote_server_client::{signer, Client};
+
+#[derive(Debug, Parser)]
+struct Opts {
+    election_id: Uuid,
+
+    #[clap(
+        long,
+        env = "CACVOTE_SERVER_URL",
+        default_value = "http://localhost:8000"
+    )]
+    cacvote_server_url: Url,
+
+    #[clap(long, env = "EG_CLASSPATH")]
+    electionguard_classpath: PathBuf,
+
+    #[clap(long, env = "SIGNING_CERT")]
+    signing_cert: PathBuf,
+
+    #[clap(long, env = "SIGNER")]
+    signer: signer::Description,
+
+    /// Sign the result and post it to the server.
+    #[clap(long)]
+    publish: bool,
+}
+
+impl Opts {
+    fn signing_cert(&self) -> color_eyre::Result<openssl::x509::X509> {
+        let pem = std::fs::read(&self.signing_cert)?;
+        let signing_cert = openssl::x509::X509::from_pem(&pem)?;
+        Ok(signing_cert)
+    }
+
+    fn signer(&self) -> color_eyre::Result<signer::AnyAsyncSigner> {
+        signer::AnyAsyncSigner::try_from(&self.signer)
+    }
+}
+
+#[tokio::main]
+async fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
+    let opts = Opts::parse();
+    let election_id = opts.election_id;
+    let mut client = Client::new(
+        opts.cacvote_server_url.clone(),
+        opts.signing_cert()?,
+        opts.signer()?,
+    );
+
+    let Some(election_object) = client.get_object_by_id(election_id).await? else {
+        bail!("no Election found with ID {election_id}");
+    };
+    let Payload::Election(election) = election_object.try_to_inner()? else {
+        bail!("object with ID {election_id} is not an Election");
+    };
+
+    let Some(shuffled_ballots_object) =
+        client.get_shuffled_ballots_by_election(election_id).await?
+    else {
+        bail!("the ballots for election {election_id} have not been mixed");
+    };
+    let Payload::ShuffledEncryptedCastBallots(shuffled_ballots) =
+        shuffled_ballots_object.try_to_inner()?
+    else {
+        bail!(
+            "object with ID {} is not a ShuffledEncryptedCastBallots",
+            shuffled_ballots_object.id
+        );
+    };
+
+    // the ballots were mixed in order of their object IDs
+    let mut cast_ballot_ids = client
+        .list_cast_ballots_by_election(election_id)
+        .await?
+        .into_iter()
+        .map(|cast_ballot| cast_ballot.id)
+        .collect::<Vec<_>>();
+    cast_ballot_ids.sort();
+
+    let mut encrypted_ballots = Vec::with_capacity(cast_ballot_ids.len());
+    for cast_ballot_id in cast_ballot_ids {
+        let Some(cast_ballot_object) = client
+            .get_cast_ballot_by_id(election_id, cast_ballot_id)
+            .await?
+        else {
+            bail!("no CastBallot found with ID {cast_ballot_id}");
+        };
+        let Payload::CastBallot(cast_ballot) = cast_ballot_object.try_to_inner()? else {
+            bail!("object with ID {cast_ballot_id} is not a CastBallot");
+        };
+        encrypted_ballots.push(cast_ballot.electionguard_encrypted_ballot);
+    }
+
+    let phases = mixnet::verify_mix(
+        &opts.electionguard_classpath,
+        &election.electionguard_election_metadata_blob,
+        encrypted_ballots.iter().map(Vec::as_slice),
+        &shuffled_ballots.electionguard_shuffled_ballots,
+    )?;
+    let verification = ShuffledEncryptedCastBallotsVerification {
+        jurisdiction_code: election.jurisdiction_code,
+        election_object_id: election_id,
+        shuffled_encrypted_cast_ballots_object_id: shuffled_ballots_object.id,
+        cast_ballot_count: encrypted_ballots.len() as u64,
+        phases,
+    };
+
+    println!("{}", serde_json::to_string_pretty(&verification)?);
+
+    let is_verified = verification.is_verified();
+
+    if opts.publish {
+        let payload = serde_json::to_vec(&Payload::ShuffledEncryptedCastBallotsVerification(
+            verification,
+        ))?;
+        let signature = opts.signer()?.sign(&payload).await?;
+        let object_id = client
+            .create_object(SignedObject {
+                id: Uuid::new_v4(),
+                election_id: Some(election_id),
+                payload,
+                certificate: opts.signing_cert()?.to_pem()?,
+                certificate_chain: None,
+                signature,
+            })
+            .await?;
+        eprintln!("published verification as object {object_id}");
+    }
+
+    if !is_verified {
+        bail!("the mixed ballots for election {election_id} did not verify");
+    }
+
+    Ok(())
+}
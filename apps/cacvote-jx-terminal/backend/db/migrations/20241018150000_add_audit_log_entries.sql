@@ -0,0 +1,36 @@
+CREATE TABLE audit_log_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- the operation that was attempted, e.g. "create-election"
+    operation varchar(255) NOT NULL,
+
+    -- the authenticated card holder, a JSON-serialized `types_rs::auth::User`
+    operator JSONB NOT NULL,
+
+    -- the object produced by the operation, NULL if it failed or produced none
+    object_id UUID,
+
+    -- whether the operation succeeded, "success" or "failure"
+    outcome varchar(255) NOT NULL,
+
+    -- human-readable description of the outcome
+    message TEXT NOT NULL,
+
+    -- when the operation was attempted
+    created_at timestamptz NOT NULL DEFAULT current_timestamp
+);
+
+-- the audit log is append-only: entries may never be changed or removed
+CREATE FUNCTION reject_audit_log_modification() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log_entries is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_entries_append_only
+    BEFORE UPDATE OR DELETE ON audit_log_entries
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_log_modification();
+
+CREATE TRIGGER audit_log_entries_no_truncate
+    BEFORE TRUNCATE ON audit_log_entries
+    FOR EACH STATEMENT EXECUTE FUNCTION reject_audit_log_modification();
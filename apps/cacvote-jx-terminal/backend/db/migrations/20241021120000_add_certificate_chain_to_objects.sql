@@ -0,0 +1,3 @@
+-- PEM-encoded intermediate certificates linking `certificate` to a trusted
+-- root, NULL if the object was signed without one
+ALTER TABLE objects ADD COLUMN certificate_chain BYTEA;
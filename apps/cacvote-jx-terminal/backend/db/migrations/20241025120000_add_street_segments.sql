@@ -0,0 +1,21 @@
+-- maps ranges of addresses on a street to the precinct they vote in, as
+-- maintained by the jurisdiction
+CREATE TABLE street_segments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- street name as written by the jurisdiction, e.g. "Main St"
+    street_name varchar(255) NOT NULL,
+
+    zip_code varchar(10) NOT NULL,
+
+    -- inclusive range of house numbers on the segment
+    low_house_number INTEGER NOT NULL CHECK (low_house_number >= 0),
+    high_house_number INTEGER NOT NULL CHECK (high_house_number >= low_house_number),
+
+    -- which house numbers in the range are on the segment: "all", "odd", or "even"
+    parity varchar(4) NOT NULL DEFAULT 'all',
+
+    precinct_id varchar(255) NOT NULL,
+
+    created_at timestamptz NOT NULL DEFAULT current_timestamp
+);
@@ -0,0 +1,11 @@
+-- ElectionGuard private keys are stored wrapped (encrypted) with a
+-- key-encryption key held outside the database, along with the ID of that
+-- key-encryption key. Keys stored in plaintext before this migration are
+-- wrapped by the JX when it next starts, leaving `private_key` NULL.
+ALTER TABLE eg_private_keys
+    ALTER COLUMN private_key DROP NOT NULL,
+    ADD COLUMN wrapped_private_key BYTEA,
+    ADD COLUMN key_encryption_key_id TEXT,
+    ADD CHECK ((private_key IS NULL) <> (wrapped_private_key IS NULL)),
+    ADD CHECK ((wrapped_private_key IS NULL) = (key_encryption_key_id IS NULL));
+
@@ -0,0 +1,46 @@
+-- long-running ElectionGuard operations, run in the background by the job
+-- worker so that they outlive the request that started them
+CREATE TABLE jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    -- the operation, e.g. "generateEncryptedTally"
+    kind varchar(255) NOT NULL,
+
+    election_object_id UUID NOT NULL,
+
+    -- kind-specific parameters, e.g. the number of mixnet phases
+    parameters JSONB NOT NULL DEFAULT '{}',
+
+    -- who started the job, a JSON-serialized `types_rs::auth::User`
+    operator JSONB NOT NULL,
+
+    -- "pending", "running", "succeeded", "failed", or "cancelled"
+    status varchar(255) NOT NULL DEFAULT 'pending',
+
+    -- human-readable description of what a running job is doing
+    progress TEXT,
+
+    -- set when an operator asks to cancel a running job
+    cancel_requested BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- how many times the job has been started, counting restarts
+    attempts INTEGER NOT NULL DEFAULT 0,
+
+    -- the object the job produced, once it has succeeded
+    result_object_id UUID,
+
+    -- why the job failed
+    error TEXT,
+
+    created_at timestamptz NOT NULL DEFAULT current_timestamp,
+    started_at timestamptz,
+    finished_at timestamptz,
+
+    FOREIGN KEY (election_object_id) REFERENCES objects(id),
+    FOREIGN KEY (result_object_id) REFERENCES objects(id)
+);
+
+-- an election may only have one job of each kind in progress at a time
+CREATE UNIQUE INDEX jobs_one_active_per_election_and_kind
+    ON jobs (election_object_id, kind)
+    WHERE status IN ('pending', 'running');
@@ -0,0 +1,12 @@
+-- cards locked out after too many incorrect PIN attempts, so that removing
+-- the card or restarting the JX does not end a lockout early
+CREATE TABLE pin_lockouts (
+    -- SHA-256 fingerprint of the card's VotingWorks certificate, hex-encoded
+    card_fingerprint TEXT PRIMARY KEY,
+
+    -- the card's incorrect PIN attempts when it was locked out
+    num_incorrect_pin_attempts SMALLINT NOT NULL,
+
+    locked_until timestamptz NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT current_timestamp
+);
@@ -0,0 +1,10 @@
+-- elections that existed before lifecycle transitions were recorded. They were
+-- already open for voting without an `openVoting` transition, so their
+-- lifecycle starts open rather than created and upgrading does not stop them
+-- accepting ballots
+CREATE TABLE legacy_open_elections (
+    election_id UUID PRIMARY KEY
+);
+
+INSERT INTO legacy_open_elections (election_id)
+SELECT id FROM objects WHERE object_type = 'Election';
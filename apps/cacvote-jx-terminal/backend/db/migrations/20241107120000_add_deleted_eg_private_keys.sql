@@ -0,0 +1,10 @@
+-- ElectionGuard private keys deleted once their election was certified, along
+-- with the ID of the key-encryption key that wrapped them. Copies of a deleted
+-- key may survive in dead rows, the WAL, or backups, so the JX uses this to
+-- require that key-encryption key be rotated out and destroyed. The ID is NULL
+-- if the key was deleted while still in plaintext.
+CREATE TABLE deleted_eg_private_keys (
+    election_object_id UUID PRIMARY KEY,
+    key_encryption_key_id TEXT,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
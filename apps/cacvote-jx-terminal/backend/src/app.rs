@@ -6,16 +6,30 @@
 use std::convert::Infallible;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
-use axum::extract::Path;
+use axum::body::Bytes;
+use axum::extract::{FromRef, Path, Query};
 use axum::response::sse::{Event, KeepAlive};
-use axum::response::Sse;
+use axum::response::{Response, Sse};
 use axum::routing::post;
 use axum::Json;
-use axum::{extract::DefaultBodyLimit, routing::get, Router};
-use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use axum::{extract::DefaultBodyLimit, middleware, routing::get, Router};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use cacvote_server_client::Client;
+use electionguard_rs::{
+    key_wrapping::WrappedKey,
+    tally_report::{
+        build_tally_report, count_ballots_by_ballot_style, parse_decrypted_tally, TallyReport,
+    },
+};
 use futures::stream::Stream;
-use serde_json::json;
-use sqlx::PgPool;
+use openssl::x509::X509;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::{Connection, PgPool};
 use tokio_stream::StreamExt;
 use tower_http::services::{ServeDir, ServeFile};
 use tower_http::trace::TraceLayer;
@@ -23,9 +37,15 @@ use tracing::Level;
 use types_rs::cacvote;
 use uuid::Uuid;
 
-use crate::config::{Config, MAX_REQUEST_SIZE};
+use crate::audit;
+use crate::auth::{self, Authenticated, Operator, RequiredOperation};
+use crate::config::{Config, MAX_REQUEST_SIZE, MAX_TRANSFER_BUNDLE_SIZE};
 use crate::db;
+use crate::jobs;
+use crate::registrations;
 use crate::session_manager::SessionManager;
+use crate::sync;
+use crate::tally_report;
 
 #[derive(Clone)]
 struct AppState {
@@ -34,6 +54,24 @@ struct AppState {
     session_manager: SessionManager,
 }
 
+impl FromRef<AppState> for SessionManager {
+    fn from_ref(state: &AppState) -> Self {
+        state.session_manager.clone()
+    }
+}
+
+impl FromRef<AppState> for Config {
+    fn from_ref(state: &AppState) -> Self {
+        state.config.clone()
+    }
+}
+
+impl FromRef<AppState> for PgPool {
+    fn from_ref(state: &AppState) -> Self {
+        state.pool.clone()
+    }
+}
+
 /// Prepares the application with all the routes. Run the application with
 /// `app::run(…)` once you have it.
 pub(crate) fn setup(pool: PgPool, config: Config) -> Router {
@@ -61,7 +99,11 @@ pub(crate) fn setup(pool: PgPool, config: Config) -> Router {
         config
             .machine_cert()
             .expect("missing or invalid VxAdmin CA cert"),
+        config
+            .cac_root_ca_store()
+            .expect("missing or invalid CAC root CA certs"),
         jurisdiction_code,
+        config.session_policy(),
         pool.clone(),
     );
 
@@ -69,9 +111,21 @@ pub(crate) fn setup(pool: PgPool, config: Config) -> Router {
         .route("/api/status", get(get_status))
         .route("/api/status-stream", get(get_status_stream))
         .route("/api/authenticate", post(authenticate))
+        .route("/api/lock", post(lock))
         .route("/api/elections", get(get_elections))
         .route("/api/elections", post(create_election))
         .route("/api/registrations", post(create_registration))
+        .route("/api/registrations/import", post(import_registrations))
+        .route("/api/registrations/export", get(export_registrations))
+        .route("/api/registration-rejections", post(reject_registration))
+        .route(
+            "/api/street-segments",
+            get(get_street_segments).put(replace_street_segments),
+        )
+        .route(
+            "/api/elections/:election_id/lifecycle",
+            post(change_election_lifecycle),
+        )
         .route(
             "/api/elections/:election_id/encrypted-tally",
             post(generate_encrypted_election_tally),
@@ -80,15 +134,42 @@ pub(crate) fn setup(pool: PgPool, config: Config) -> Router {
             "/api/elections/:election_id/decrypted-tally",
             post(decrypt_encrypted_election_tally),
         )
+        .route(
+            "/api/elections/:election_id/tally-report",
+            get(get_tally_report),
+        )
         .route(
             "/api/elections/:election_id/mixed-ballots",
             post(mix_encrypted_ballots),
         )
+        .route(
+            "/api/elections/:election_id/mixed-ballots/verification",
+            post(verify_mixed_ballots),
+        )
+        .route(
+            "/api/elections/:election_id/mixed-ballots/decryption",
+            post(decrypt_mixed_ballots),
+        )
+        .route("/api/jobs", get(get_jobs))
+        .route("/api/jobs/:job_id", get(get_job))
+        .route("/api/jobs/:job_id/cancel", post(cancel_job))
         .route(
             "/api/elections/:election_id/scanned-mailing-labels",
             get(list_scanned_mailing_labels_by_election),
         )
+        .route("/api/audit-log", get(get_audit_log))
+        .route("/api/audit-log/export", get(export_audit_log))
+        .route(
+            "/api/transfer-bundle",
+            get(export_transfer_bundle)
+                .post(import_transfer_bundle)
+                .layer(DefaultBodyLimit::max(MAX_TRANSFER_BUNDLE_SIZE)),
+        )
         .layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE))
+        .layer(middleware::from_fn_with_state(
+            pool.clone(),
+            audit::record_failures,
+        ))
         .layer(TraceLayer::new_for_http())
         .with_state(AppState {
             config,
@@ -139,6 +220,23 @@ async fn authenticate(
     (StatusCode::OK, Json(json!({})))
 }
 
+async fn lock(
+    State(AppState {
+        session_manager, ..
+    }): State<AppState>,
+    _authenticated: Authenticated,
+) -> impl IntoResponse {
+    if let Err(e) = session_manager.lock() {
+        tracing::error!("error locking session: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e })),
+        );
+    }
+
+    (StatusCode::OK, Json(json!({})))
+}
+
 async fn get_elections(State(AppState { pool, .. }): State<AppState>) -> impl IntoResponse {
     let mut connection = match pool.acquire().await {
         Ok(connection) => connection,
@@ -166,9 +264,14 @@ async fn get_elections(State(AppState { pool, .. }): State<AppState>) -> impl In
 }
 
 async fn create_election(
-    State(AppState { config, pool, .. }): State<AppState>,
+    State(AppState {
+        config,
+        pool,
+        session_manager,
+    }): State<AppState>,
+    operator: Operator<auth::CreateElection>,
     Json(election): Json<cacvote::CreateElectionRequest>,
-) -> impl IntoResponse {
+) -> (StatusCode, Json<Value>) {
     let jurisdiction_code = match config.jurisdiction_code() {
         Ok(jurisdiction_code) => jurisdiction_code,
         Err(e) => {
@@ -187,6 +290,22 @@ async fn create_election(
         );
     }
 
+    if let Err(e) = election.check_voting_window_dates() {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": e })));
+    }
+
+    // a broken definition would otherwise panic in ElectionGuard or produce an
+    // election that cannot be voted
+    if let Err(problems) = election.election_definition.election.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("election definition has {} problem(s)", problems.len()),
+                "problems": problems.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            })),
+        );
+    }
+
     let mut transaction = match pool.begin().await {
         Ok(connection) => connection,
         Err(e) => {
@@ -198,6 +317,10 @@ async fn create_election(
         }
     };
 
+    if let Err(response) = require_fresh_key_encryption_key(&config, &mut transaction).await {
+        return response;
+    }
+
     let election_config = match electionguard_rs::config::generate_election_config(
         &config.eg_classpath,
         election.election_definition.election.clone(),
@@ -217,6 +340,9 @@ async fn create_election(
         mailing_address: election.mailing_address,
         election_definition: election.election_definition,
         electionguard_election_metadata_blob: election_config.public_metadata_blob,
+        voting_starts_at: election.voting_starts_at,
+        voting_ends_at: election.voting_ends_at,
+        ballot_receipt_deadline: election.ballot_receipt_deadline,
     });
 
     let serialized_payload = match serde_json::to_vec(&payload) {
@@ -230,16 +356,11 @@ async fn create_election(
         }
     };
 
-    let (signature, signing_cert) = match config.sign(&serialized_payload) {
-        Ok(signed) => signed,
-        Err(e) => {
-            tracing::error!("error signing payload: {e}");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error signing payload" })),
-            );
-        }
-    };
+    let (signature, signing_cert) =
+        match sign_payload(&config, &session_manager, &serialized_payload, election.pin).await {
+            Ok(signed) => signed,
+            Err(response) => return response,
+        };
     let certificate = match signing_cert.to_pem() {
         Ok(certificate) => certificate,
         Err(e) => {
@@ -256,6 +377,7 @@ async fn create_election(
         election_id: None,
         payload: serialized_payload,
         certificate,
+        certificate_chain: None,
         signature,
     };
 
@@ -267,12 +389,17 @@ async fn create_election(
         );
     }
 
-    if let Err(e) = db::add_eg_private_key(
-        &mut transaction,
-        &signed_object.id,
+    let wrapped_private_key = match wrap_private_key(
+        &config,
         &election_config.private_metadata_blob,
-    )
-    .await
+        signed_object.id,
+    ) {
+        Ok(wrapped_private_key) => wrapped_private_key,
+        Err(response) => return response,
+    };
+
+    if let Err(e) =
+        db::add_eg_private_key(&mut transaction, &signed_object.id, &wrapped_private_key).await
     {
         tracing::error!("error adding EG private key to database: {e}");
         return (
@@ -281,6 +408,12 @@ async fn create_election(
         );
     }
 
+    if let Err(rejection) =
+        audit::record_success(&mut transaction, &operator, Some(signed_object.id)).await
+    {
+        return rejection;
+    }
+
     if let Err(e) = transaction.commit().await {
         tracing::error!("error committing transaction: {e}");
         return (
@@ -293,14 +426,20 @@ async fn create_election(
 }
 
 async fn create_registration(
-    State(AppState { config, pool, .. }): State<AppState>,
+    State(AppState {
+        config,
+        pool,
+        session_manager,
+    }): State<AppState>,
+    operator: Operator<auth::CreateRegistration>,
     Json(cacvote::CreateRegistrationRequest {
         registration_request_id,
         election_id,
         ballot_style_id,
         precinct_id,
+        pin,
     }): Json<cacvote::CreateRegistrationRequest>,
-) -> impl IntoResponse {
+) -> (StatusCode, Json<Value>) {
     let jurisdiction_code = match config.jurisdiction_code() {
         Ok(jurisdiction_code) => jurisdiction_code,
         Err(e) => {
@@ -312,37 +451,43 @@ async fn create_registration(
         }
     };
 
-    let mut connection = match pool.acquire().await {
-        Ok(connection) => connection,
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
         Err(e) => {
-            tracing::error!("error getting database connection: {e}");
+            tracing::error!("error starting transaction: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error getting database connection" })),
+                Json(json!({ "error": "error starting transaction" })),
             );
         }
     };
 
-    let registration_request =
-        match db::get_registration_request(&mut connection, registration_request_id).await {
-            Ok(registration_request) => registration_request,
-            Err(e) => {
-                tracing::error!("error getting registration request from database: {e}");
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({ "error": "error getting registration request from database" })),
-                );
-            }
+    let election =
+        match registrations::load_election(&mut transaction, &jurisdiction_code, election_id).await
+        {
+            Ok(election) => election,
+            Err(e) => return e.into_response(),
         };
+    if let Err(rejection) = operator.check_election(&election) {
+        return rejection;
+    }
 
-    let payload = cacvote::Payload::Registration(cacvote::Registration {
-        jurisdiction_code,
-        common_access_card_id: registration_request.common_access_card_id,
-        registration_request_object_id: registration_request_id,
-        election_object_id: election_id,
+    let registration = match registrations::validate_registration(
+        &mut transaction,
+        &jurisdiction_code,
+        registration_request_id,
+        election_id,
+        &election,
         ballot_style_id,
         precinct_id,
-    });
+    )
+    .await
+    {
+        Ok(registration) => registration,
+        Err(e) => return e.into_response(),
+    };
+
+    let payload = cacvote::Payload::Registration(registration);
     let serialized_payload = match serde_json::to_vec(&payload) {
         Ok(serialized_payload) => serialized_payload,
         Err(e) => {
@@ -354,16 +499,11 @@ async fn create_registration(
         }
     };
 
-    let (signature, signing_cert) = match config.sign(&serialized_payload) {
-        Ok(signed) => signed,
-        Err(e) => {
-            tracing::error!("error signing payload: {e}");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error signing payload" })),
-            );
-        }
-    };
+    let (signature, signing_cert) =
+        match sign_payload(&config, &session_manager, &serialized_payload, pin).await {
+            Ok(signed) => signed,
+            Err(response) => return response,
+        };
     let certificate = match signing_cert.to_pem() {
         Ok(certificate) => certificate,
         Err(e) => {
@@ -379,10 +519,11 @@ async fn create_registration(
         election_id: Some(election_id),
         payload: serialized_payload,
         certificate,
+        certificate_chain: None,
         signature,
     };
 
-    if let Err(e) = db::add_object(&mut connection, &signed_object).await {
+    if let Err(e) = db::add_object(&mut transaction, &signed_object).await {
         tracing::error!("error adding object to database: {e}");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -390,101 +531,92 @@ async fn create_registration(
         );
     }
 
+    if let Err(rejection) =
+        audit::record_success(&mut transaction, &operator, Some(signed_object.id)).await
+    {
+        return rejection;
+    }
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("error committing transaction: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error committing transaction" })),
+        );
+    }
+
     (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
 }
 
-async fn generate_encrypted_election_tally(
-    State(AppState { pool, config, .. }): State<AppState>,
-    Path(election_id): Path<Uuid>,
-) -> impl IntoResponse {
-    let mut transaction = match pool.begin().await {
-        Ok(transaction) => transaction,
-        Err(e) => {
-            tracing::error!("error getting database connection: {e}");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error getting database connection" })),
-            );
-        }
-    };
-
-    match db::get_tallies_for_election(&mut transaction, election_id).await {
-        Ok(db::ElectionTallies::OnlyEncrypted(_) | db::ElectionTallies::Both(..)) => {
-            return (
-                StatusCode::CONFLICT,
-                Json(json!({ "error": "tally already exists" })),
-            )
-        }
-        Ok(db::ElectionTallies::Neither) => (),
+async fn reject_registration(
+    State(AppState {
+        config,
+        pool,
+        session_manager,
+    }): State<AppState>,
+    operator: Operator<auth::RejectRegistration>,
+    Json(cacvote::RejectRegistrationRequest {
+        registration_request_id,
+        reason,
+        message,
+        pin,
+    }): Json<cacvote::RejectRegistrationRequest>,
+) -> (StatusCode, Json<Value>) {
+    let jurisdiction_code = match config.jurisdiction_code() {
+        Ok(jurisdiction_code) => jurisdiction_code,
         Err(e) => {
-            tracing::error!("error getting encrypted election tally from database: {e}");
+            tracing::error!("invalid configuration jurisdiction code: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error getting encrypted election tally from database" })),
-            );
-        }
-    }
-
-    let election_object = match db::get_object(&mut transaction, election_id).await {
-        Ok(object) => object,
-        Err(e) => {
-            tracing::error!("error getting election from database: {e}");
-            return (
-                StatusCode::NOT_FOUND,
-                Json(json!({ "error": "error getting election from database" })),
+                Json(json!({ "error": "invalid configuration jurisdiction code" })),
             );
         }
     };
 
-    let election_payload: cacvote::Payload = match election_object.try_to_inner() {
-        Ok(payload) => payload,
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
         Err(e) => {
-            tracing::error!("error deserializing election: {e}");
+            tracing::error!("error starting transaction: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error deserializing election" })),
-            );
-        }
-    };
-
-    let election = match election_payload {
-        cacvote::Payload::Election(election) => election,
-        _ => {
-            tracing::error!("object is not an election");
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({ "error": "object is not an election" })),
+                Json(json!({ "error": "error starting transaction" })),
             );
         }
     };
 
-    let cast_ballots = db::get_cast_ballots_for_election(&mut transaction, &election_id)
-        .await
-        .unwrap();
+    let pending_registration_requests =
+        match db::get_pending_registration_requests(&mut transaction).await {
+            Ok(pending_registration_requests) => pending_registration_requests,
+            Err(e) => {
+                tracing::error!("error getting pending registration requests from database: {e}");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": "error getting pending registration requests from database"
+                    })),
+                );
+            }
+        };
 
-    let encrypted_tally = match electionguard_rs::tally::accumulate(
-        &config.eg_classpath,
-        &election.electionguard_election_metadata_blob,
-        cast_ballots
-            .iter()
-            .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_slice()),
-    ) {
-        Ok(encrypted_tally) => encrypted_tally,
-        Err(e) => {
-            tracing::error!("error accumulating tally: {e}");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error accumulating tally" })),
-            );
-        }
+    let Some(registration_request) = pending_registration_requests
+        .into_iter()
+        .find(|request| request.id == registration_request_id)
+    else {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": format!("registration request {registration_request_id} is not pending")
+            })),
+        );
     };
 
-    let payload = cacvote::Payload::EncryptedElectionTally(cacvote::EncryptedElectionTally {
-        election_object_id: election_id,
-        jurisdiction_code: election.jurisdiction_code,
-        electionguard_encrypted_tally: encrypted_tally,
+    let payload = cacvote::Payload::RegistrationRejection(cacvote::RegistrationRejection {
+        common_access_card_id: registration_request.common_access_card_id.clone(),
+        jurisdiction_code,
+        registration_request_object_id: registration_request_id,
+        reason,
+        message,
     });
-
     let serialized_payload = match serde_json::to_vec(&payload) {
         Ok(serialized_payload) => serialized_payload,
         Err(e) => {
@@ -496,18 +628,12 @@ async fn generate_encrypted_election_tally(
         }
     };
 
-    let (signature, signing_cert) = match config.sign(&serialized_payload) {
-        Ok(signed) => signed,
-        Err(e) => {
-            tracing::error!("error signing payload: {e}");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error signing payload" })),
-            );
-        }
-    };
-
-    let certificate: Vec<u8> = match signing_cert.to_pem() {
+    let (signature, signing_cert) =
+        match sign_payload(&config, &session_manager, &serialized_payload, pin).await {
+            Ok(signed) => signed,
+            Err(response) => return response,
+        };
+    let certificate = match signing_cert.to_pem() {
         Ok(certificate) => certificate,
         Err(e) => {
             tracing::error!("error converting certificate to PEM: {e}");
@@ -517,12 +643,12 @@ async fn generate_encrypted_election_tally(
             );
         }
     };
-
     let signed_object = cacvote::SignedObject {
         id: Uuid::new_v4(),
-        election_id: Some(election_id),
+        election_id: None,
         payload: serialized_payload,
         certificate,
+        certificate_chain: None,
         signature,
     };
 
@@ -534,6 +660,12 @@ async fn generate_encrypted_election_tally(
         );
     }
 
+    if let Err(rejection) =
+        audit::record_success(&mut transaction, &operator, Some(signed_object.id)).await
+    {
+        return rejection;
+    }
+
     if let Err(e) = transaction.commit().await {
         tracing::error!("error committing transaction: {e}");
         return (
@@ -545,165 +677,292 @@ async fn generate_encrypted_election_tally(
     (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
 }
 
-async fn decrypt_encrypted_election_tally(
-    State(AppState { pool, config, .. }): State<AppState>,
-    Path(election_id): Path<Uuid>,
-) -> impl IntoResponse {
-    let mut transaction = match pool.begin().await {
-        Ok(transaction) => transaction,
-        Err(e) => {
-            tracing::error!("error getting database connection: {e}");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error getting database connection" })),
-            );
-        }
-    };
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportRegistrationsQuery {
+    /// Validate the import and report on it without storing anything.
+    #[serde(default)]
+    dry_run: bool,
+}
 
-    let encrypted_tally = match db::get_tallies_for_election(&mut transaction, election_id).await {
-        Ok(db::ElectionTallies::OnlyEncrypted(encrypted_tally)) => encrypted_tally,
-        Ok(db::ElectionTallies::Both(..)) => {
-            return (
-                StatusCode::CONFLICT,
-                Json(json!({ "error": "tally already exists" })),
-            )
-        }
-        Ok(db::ElectionTallies::Neither) => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(json!({ "error": "encrypted tally not found" })),
-            )
-        }
+/// Registers voters in bulk from a CSV file (`Content-Type: text/csv`) or a
+/// JSON array of rows. Every row is validated first; if any is invalid, or if
+/// this is a dry run, nothing is stored. Otherwise all the registrations are
+/// signed with the machine key and stored in a single transaction.
+async fn import_registrations(
+    State(AppState { config, pool, .. }): State<AppState>,
+    operator: Operator<auth::ImportRegistrations>,
+    Query(ImportRegistrationsQuery { dry_run }): Query<ImportRegistrationsQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<Value>) {
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/csv"));
+
+    let rows = match registrations::parse_import_rows(is_csv, &body) {
+        Ok(rows) => rows,
         Err(e) => {
-            tracing::error!("error getting encrypted election tally from database: {e}");
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error getting encrypted election tally from database" })),
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("invalid registration import: {e}") })),
             );
         }
     };
 
-    let election_object = match db::get_object(&mut transaction, election_id).await {
-        Ok(object) => object,
+    let jurisdiction_code = match config.jurisdiction_code() {
+        Ok(jurisdiction_code) => jurisdiction_code,
         Err(e) => {
-            tracing::error!("error getting election from database: {e}");
+            tracing::error!("invalid configuration jurisdiction code: {e}");
             return (
-                StatusCode::NOT_FOUND,
-                Json(json!({ "error": "error getting election from database" })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "invalid configuration jurisdiction code" })),
             );
         }
     };
 
-    let election_payload: cacvote::Payload = match election_object.try_to_inner() {
-        Ok(payload) => payload,
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
         Err(e) => {
-            tracing::error!("error deserializing election: {e}");
+            tracing::error!("error starting transaction: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error deserializing election" })),
-            );
-        }
-    };
-
-    let election = match election_payload {
-        cacvote::Payload::Election(election) => election,
-        _ => {
-            tracing::error!("object is not an election");
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({ "error": "object is not an election" })),
+                Json(json!({ "error": "error starting transaction" })),
             );
         }
     };
 
-    let private_key = match db::get_eg_private_key(&mut transaction, &election_id).await {
-        Ok(record) => record,
+    let row_count = rows.len();
+    let report = match registrations::import(
+        &mut transaction,
+        &jurisdiction_code,
+        &operator.user,
+        rows,
+        dry_run,
+        |payload| config.sign(payload),
+    )
+    .await
+    {
+        Ok(report) => report,
         Err(e) => {
-            tracing::error!("error getting EG private key from database: {e}");
+            tracing::error!("error importing registrations: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error getting EG private key from database" })),
+                Json(json!({ "error": "error importing registrations" })),
             );
         }
     };
 
-    let election_config = electionguard_rs::config::ElectionConfig {
-        public_metadata_blob: election.electionguard_election_metadata_blob,
-        private_metadata_blob: private_key,
-    };
+    let invalid_row_count = report.invalid_row_count();
+    if invalid_row_count > 0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("{invalid_row_count} of {row_count} rows are invalid"),
+                "report": report,
+            })),
+        );
+    }
 
-    let decrypted_tally = match electionguard_rs::tally::decrypt(
-        &config.eg_classpath,
-        &election_config,
-        &encrypted_tally
-            .encrypted_election_tally
-            .electionguard_encrypted_tally,
-    ) {
-        Ok(decrypted_tally) => decrypted_tally,
-        Err(e) => {
-            tracing::error!("error decrypting tally: {e}");
-            return (
+    // a dry run stores nothing but the audit log entry
+    if let Err(rejection) = audit::record_success(&mut transaction, &operator, None).await {
+        return rejection;
+    }
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("error committing transaction: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error committing transaction" })),
+        );
+    }
+
+    let status = if dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    (status, Json(json!({ "report": report })))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum RegistrationExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportRegistrationsQuery {
+    #[serde(default)]
+    format: RegistrationExportFormat,
+}
+
+/// Exports every registration the operator may see as CSV or JSON, i.e. all
+/// of them for system administrators and those for their election for
+/// election managers. The columns can be imported again with
+/// `/api/registrations/import`.
+async fn export_registrations(
+    State(AppState { pool, .. }): State<AppState>,
+    operator: Operator<auth::ExportRegistrations>,
+    Query(ExportRegistrationsQuery { format }): Query<ExportRegistrationsQuery>,
+) -> Response {
+    match load_registration_export(&pool, &operator, format).await {
+        Ok(response) => response,
+        Err(response) => response.into_response(),
+    }
+}
+
+async fn load_registration_export(
+    pool: &PgPool,
+    operator: &Operator<auth::ExportRegistrations>,
+    format: RegistrationExportFormat,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let mut connection = pool.acquire().await.map_err(|e| {
+        tracing::error!("error getting database connection: {e}");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error getting database connection" })),
+        )
+    })?;
+
+    let rows = db::get_registrations(&mut connection)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|registrations| {
+            registrations
+                .iter()
+                .filter(|registration| {
+                    auth::is_election_permitted_for(&operator.user, &registration.election_hash())
+                })
+                .map(registrations::RegistrationExportRow::new)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())
+        })
+        .map_err(|e| {
+            tracing::error!("error getting registrations: {e}");
+            (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error decrypting tally" })),
-            );
+                Json(json!({ "error": "error getting registrations" })),
+            )
+        })?;
+
+    let response = match format {
+        RegistrationExportFormat::Csv => {
+            let csv = registrations::export_csv(&rows).map_err(|e| {
+                tracing::error!("error exporting registrations: {e}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": format!("error exporting registrations: {e}") })),
+                )
+            })?;
+            (
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        "attachment; filename=\"cacvote-jx-registrations.csv\"",
+                    ),
+                ],
+                csv,
+            )
+                .into_response()
         }
+        RegistrationExportFormat::Json => (
+            [(
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"cacvote-jx-registrations.json\"",
+            )],
+            Json(rows),
+        )
+            .into_response(),
     };
 
-    let payload = cacvote::Payload::DecryptedElectionTally(cacvote::DecryptedElectionTally {
-        election_object_id: election_id,
-        jurisdiction_code: election.jurisdiction_code,
-        electionguard_decrypted_tally: decrypted_tally,
-    });
+    audit::record_success(&mut connection, operator, None).await?;
 
-    let serialized_payload = match serde_json::to_vec(&payload) {
-        Ok(serialized_payload) => serialized_payload,
+    Ok(response)
+}
+
+async fn get_street_segments(
+    State(AppState { pool, .. }): State<AppState>,
+    authenticated: Authenticated,
+) -> impl IntoResponse {
+    if let Err(rejection) = authenticated.authorize(auth::Operation::ManageStreetSegments) {
+        return rejection;
+    }
+
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
         Err(e) => {
-            tracing::error!("error serializing payload: {e}");
+            tracing::error!("error getting database connection: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error serializing payload" })),
+                Json(json!({ "error": "error getting database connection" })),
             );
         }
     };
 
-    let (signature, signing_cert) = match config.sign(&serialized_payload) {
-        Ok(signed) => signed,
+    match db::get_street_segments(&mut connection).await {
+        Ok(street_segments) => (StatusCode::OK, Json(json!(street_segments))),
         Err(e) => {
-            tracing::error!("error signing payload: {e}");
-            return (
+            tracing::error!("error getting street segments: {e}");
+            (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error signing payload" })),
+                Json(json!({ "error": "error getting street segments" })),
+            )
+        }
+    }
+}
+
+/// Replaces the jurisdiction's street segments with those in a CSV file
+/// (`Content-Type: text/csv`) or a JSON array. Pending registration requests
+/// get new suggested assignments the next time the session refreshes.
+async fn replace_street_segments(
+    State(AppState { pool, .. }): State<AppState>,
+    operator: Operator<auth::ManageStreetSegments>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<Value>) {
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/csv"));
+
+    let street_segments = match registrations::parse_street_segments(is_csv, &body) {
+        Ok(street_segments) => street_segments,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("invalid street segments: {e}") })),
             );
         }
     };
 
-    let certificate = match signing_cert.to_pem() {
-        Ok(certificate) => certificate,
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
         Err(e) => {
-            tracing::error!("error converting certificate to PEM: {e}");
+            tracing::error!("error starting transaction: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error converting certificate to PEM" })),
+                Json(json!({ "error": "error starting transaction" })),
             );
         }
     };
 
-    let signed_object = cacvote::SignedObject {
-        id: Uuid::new_v4(),
-        election_id: Some(election_id),
-        payload: serialized_payload,
-        certificate,
-        signature,
-    };
-
-    if let Err(e) = db::add_object(&mut transaction, &signed_object).await {
-        tracing::error!("error adding object to database: {e}");
+    if let Err(e) = db::replace_street_segments(&mut transaction, &street_segments).await {
+        tracing::error!("error replacing street segments: {e}");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({ "error": "error adding object to database" })),
+            Json(json!({ "error": "error replacing street segments" })),
         );
     }
 
+    if let Err(rejection) = audit::record_success(&mut transaction, &operator, None).await {
+        return rejection;
+    }
+
     if let Err(e) = transaction.commit().await {
         tracing::error!("error committing transaction: {e}");
         return (
@@ -712,87 +971,229 @@ async fn decrypt_encrypted_election_tally(
         );
     }
 
-    (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
+    (
+        StatusCode::OK,
+        Json(json!({ "count": street_segments.len() })),
+    )
 }
 
-async fn mix_encrypted_ballots(
-    State(AppState { pool, config, .. }): State<AppState>,
-    Path(election_id): Path<Uuid>,
-    Json(cacvote::MixEncryptedBallotsRequest { phases }): Json<cacvote::MixEncryptedBallotsRequest>,
-) -> impl IntoResponse {
-    let mut transaction = match pool.begin().await {
-        Ok(transaction) => transaction,
+/// Signs an `Election`, `Registration`, or `RegistrationRejection` payload
+/// with the official's card if they provided a PIN, or with the machine key
+/// otherwise.
+async fn sign_payload(
+    config: &Config,
+    session_manager: &SessionManager,
+    payload: &[u8],
+    pin: Option<String>,
+) -> Result<(Vec<u8>, X509), (StatusCode, Json<serde_json::Value>)> {
+    let Some(pin) = pin else {
+        return config.sign(payload).map_err(|e| {
+            tracing::error!("error signing payload: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error signing payload" })),
+            )
+        });
+    };
+
+    session_manager
+        .sign(payload.to_vec(), pin)
+        .await
+        .map_err(|e| {
+            tracing::error!("error signing payload with card: {e}");
+            match e {
+                cacvote_server_client::Error::Card(e) if e.is_incorrect_pin_error() => (
+                    StatusCode::UNAUTHORIZED,
+                    Json(json!({ "error": "incorrect PIN" })),
+                ),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": "error signing payload with card" })),
+                ),
+            }
+        })
+}
+
+/// Refuses to wrap a new election's private key with a key-encryption key
+/// that wrapped a key since deleted, which must be rotated out and destroyed
+/// for the deleted key to become unrecoverable.
+async fn require_fresh_key_encryption_key(
+    config: &Config,
+    connection: &mut sqlx::PgConnection,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let key_encryption_keys = match config.key_encryption_keys() {
+        Ok(key_encryption_keys) => key_encryption_keys,
         Err(e) => {
-            tracing::error!("error getting database connection: {e}");
-            return (
+            tracing::error!("error loading key-encryption keys: {e}");
+            return Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error getting database connection" })),
-            );
+                Json(json!({ "error": "error loading key-encryption keys" })),
+            ));
         }
     };
 
-    let election_object = match db::get_object(&mut transaction, election_id).await {
-        Ok(object) => object,
+    match db::get_key_encryption_key_ids_with_deleted_keys(connection, &key_encryption_keys).await {
+        Ok(ids)
+            if ids
+                .iter()
+                .any(|id| id == key_encryption_keys.current().id()) =>
+        {
+            Err((
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": "the key-encryption key wrapped private keys that have been deleted and must be rotated before creating another election"
+                })),
+            ))
+        }
+        Ok(_) => Ok(()),
         Err(e) => {
-            tracing::error!("error getting election from database: {e}");
+            tracing::error!("error checking key-encryption keys: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error checking key-encryption keys" })),
+            ))
+        }
+    }
+}
+
+/// Wraps a private key generated on this machine with the key-encryption key
+/// for storage, binding it to the object it belongs to.
+fn wrap_private_key(
+    config: &Config,
+    private_key: &[u8],
+    object_id: Uuid,
+) -> Result<WrappedKey, (StatusCode, Json<Value>)> {
+    config
+        .key_encryption_keys()
+        .and_then(|keys| keys.wrap_key(private_key, object_id.as_bytes()))
+        .map_err(|e| {
+            tracing::error!("error wrapping private key: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error wrapping private key" })),
+            )
+        })
+}
+
+/// Decrypting requires an election official to be present, so before a
+/// private key is unwrapped to decrypt with, the official's card must sign a
+/// statement naming the key, which it only does given their PIN. The signature
+/// is checked against the card's VotingWorks-issued cert before going ahead.
+async fn require_official_to_unwrap(
+    config: &Config,
+    session_manager: &SessionManager,
+    object_id: Uuid,
+    pin: Option<String>,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let Some(pin) = pin else {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(
+                json!({ "error": "an election official's PIN is required to unwrap the private key" }),
+            ),
+        ));
+    };
+
+    let statement = cacvote_server_client::unwrap_authorization_statement(object_id);
+    let (signature, cert) = sign_payload(config, session_manager, &statement, Some(pin)).await?;
+
+    let verified = config
+        .vx_cert_authority_cert()
+        .and_then(|vx_cert_authority_cert| {
+            Ok(cacvote_server_client::verify_unwrap_authorization(
+                &vx_cert_authority_cert,
+                object_id,
+                &signature,
+                &cert,
+            )?)
+        });
+    if let Err(e) = verified {
+        tracing::error!("election official's authorization to unwrap did not verify: {e}");
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "the election official's card could not be verified" })),
+        ));
+    }
+
+    tracing::info!("election official authorized unwrapping the private key for {object_id}");
+    Ok(())
+}
+
+async fn change_election_lifecycle(
+    State(AppState {
+        config,
+        pool,
+        session_manager,
+    }): State<AppState>,
+    operator: Operator<auth::ChangeElectionLifecycle>,
+    Path(election_id): Path<Uuid>,
+    Json(cacvote::ElectionLifecycleRequest { action, pin }): Json<
+        cacvote::ElectionLifecycleRequest,
+    >,
+) -> (StatusCode, Json<Value>) {
+    let jurisdiction_code = match config.jurisdiction_code() {
+        Ok(jurisdiction_code) => jurisdiction_code,
+        Err(e) => {
+            tracing::error!("invalid configuration jurisdiction code: {e}");
             return (
-                StatusCode::NOT_FOUND,
-                Json(json!({ "error": "error getting election from database" })),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "invalid configuration jurisdiction code" })),
             );
         }
     };
 
-    let election_payload: cacvote::Payload = match election_object.try_to_inner() {
-        Ok(payload) => payload,
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
         Err(e) => {
-            tracing::error!("error deserializing election: {e}");
+            tracing::error!("error starting transaction: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error deserializing election" })),
+                Json(json!({ "error": "error starting transaction" })),
             );
         }
     };
 
-    let election = match election_payload {
-        cacvote::Payload::Election(election) => election,
-        _ => {
-            tracing::error!("object is not an election");
+    match db::get_object(&mut transaction, election_id)
+        .await
+        .and_then(|object| Ok(object.try_to_inner()?))
+    {
+        Ok(cacvote::Payload::Election(_)) => {}
+        Ok(_) => {
             return (
                 StatusCode::BAD_REQUEST,
                 Json(json!({ "error": "object is not an election" })),
             );
         }
-    };
-
-    let cast_ballots = db::get_cast_ballots_for_election(&mut transaction, &election_id)
-        .await
-        .unwrap();
+        Err(e) => {
+            tracing::error!("error getting election from database: {e}");
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "error getting election from database" })),
+            );
+        }
+    }
 
-    let shuffled_ballots = match electionguard_rs::mixnet::mix(
-        &config.eg_classpath,
-        &election.electionguard_election_metadata_blob,
-        cast_ballots
-            .iter()
-            .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_slice()),
-        phases,
-    ) {
-        Ok(shuffled_ballots) => shuffled_ballots,
+    let state = match db::get_election_lifecycle_state(&mut transaction, election_id).await {
+        Ok(state) => state,
         Err(e) => {
-            tracing::error!("error mixing ballots: {e}");
+            tracing::error!("error getting election lifecycle state: {e}");
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error mixing ballots" })),
+                Json(json!({ "error": "error getting election lifecycle state" })),
             );
         }
     };
 
+    if let Err(e) = state.apply(action) {
+        return (StatusCode::CONFLICT, Json(json!({ "error": e })));
+    }
+
     let payload =
-        cacvote::Payload::ShuffledEncryptedCastBallots(cacvote::ShuffledEncryptedCastBallots {
+        cacvote::Payload::ElectionLifecycleTransition(cacvote::ElectionLifecycleTransition {
+            jurisdiction_code,
             election_object_id: election_id,
-            jurisdiction_code: election.jurisdiction_code,
-            electionguard_shuffled_ballots: shuffled_ballots,
+            action,
         });
-
     let serialized_payload = match serde_json::to_vec(&payload) {
         Ok(serialized_payload) => serialized_payload,
         Err(e) => {
@@ -804,17 +1205,11 @@ async fn mix_encrypted_ballots(
         }
     };
 
-    let (signature, signing_cert) = match config.sign(&serialized_payload) {
-        Ok(signed) => signed,
-        Err(e) => {
-            tracing::error!("error signing payload: {e}");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({ "error": "error signing payload" })),
-            );
-        }
-    };
-
+    let (signature, signing_cert) =
+        match sign_payload(&config, &session_manager, &serialized_payload, pin).await {
+            Ok(signed) => signed,
+            Err(response) => return response,
+        };
     let certificate = match signing_cert.to_pem() {
         Ok(certificate) => certificate,
         Err(e) => {
@@ -825,12 +1220,12 @@ async fn mix_encrypted_ballots(
             );
         }
     };
-
     let signed_object = cacvote::SignedObject {
         id: Uuid::new_v4(),
         election_id: Some(election_id),
         payload: serialized_payload,
         certificate,
+        certificate_chain: None,
         signature,
     };
 
@@ -842,6 +1237,25 @@ async fn mix_encrypted_ballots(
         );
     }
 
+    // the private keys are no longer needed once the results are certified
+    if action == cacvote::ElectionLifecycleAction::CertifyResults {
+        match db::delete_private_keys_for_certified_elections(&mut transaction).await {
+            Ok(election_ids) if !election_ids.is_empty() => {
+                tracing::info!("deleted private keys for certified elections: {election_ids:?}");
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!("error deleting private keys for certified elections: {e}");
+            }
+        }
+    }
+
+    if let Err(rejection) =
+        audit::record_success(&mut transaction, &operator, Some(signed_object.id)).await
+    {
+        return rejection;
+    }
+
     if let Err(e) = transaction.commit().await {
         tracing::error!("error committing transaction: {e}");
         return (
@@ -853,22 +1267,972 @@ async fn mix_encrypted_ballots(
     (StatusCode::CREATED, Json(json!({ "id": signed_object.id })))
 }
 
-/// Proxy `list_scanned_mailing_labels_by_election` to the `cacvote-server` host.
-async fn list_scanned_mailing_labels_by_election(
-    State(AppState { config, .. }): State<AppState>,
+/// Tallying and mixing are only allowed once voting has closed, so that the
+/// results cover every ballot that will be accepted.
+async fn require_voting_closed(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    match db::get_election_lifecycle_state(connection, election_id).await {
+        Ok(state) if state.is_voting_closed() => Ok(()),
+        Ok(state) => Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": format!("voting must be closed first; the election is {state}")
+            })),
+        )),
+        Err(e) => {
+            tracing::error!("error getting election lifecycle state: {e}");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting election lifecycle state" })),
+            ))
+        }
+    }
+}
+
+/// Starts a job to accumulate an election's cast ballots into an encrypted
+/// tally.
+async fn generate_encrypted_election_tally(
+    State(AppState { pool, .. }): State<AppState>,
+    operator: Operator<auth::GenerateEncryptedTally>,
     Path(election_id): Path<Uuid>,
-) -> Result<Json<Vec<cacvote::ScannedMailingLabel>>, StatusCode> {
-    let url = config
-        .cacvote_url
-        .join(format!("/api/elections/{election_id}/scanned-mailing-labels").as_str())
-        .expect("invalid URL");
+) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
 
-    let response = reqwest::get(url)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-        .json::<Vec<cacvote::ScannedMailingLabel>>()
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    match db::get_tallies_for_election(&mut connection, election_id).await {
+        Ok(db::ElectionTallies::OnlyEncrypted(_) | db::ElectionTallies::Both(..)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": "tally already exists" })),
+            )
+        }
+        Ok(db::ElectionTallies::Neither) => (),
+        Err(e) => {
+            tracing::error!("error getting encrypted election tally from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting encrypted election tally from database" })),
+            );
+        }
+    }
+
+    if let Err(response) = load_election(&mut connection, election_id).await {
+        return response;
+    }
+
+    if let Err(response) = require_voting_closed(&mut connection, election_id).await {
+        return response;
+    }
+
+    start_job(
+        &mut connection,
+        cacvote::JobKind::GenerateEncryptedTally,
+        election_id,
+        json!({}),
+        &operator,
+    )
+    .await
+}
+
+/// Records a job for the worker to run (see [`jobs`]), responding with
+/// `202 Accepted` and the job's ID, or `409 Conflict` if a job of the same
+/// kind is already in progress for the election.
+async fn start_job<O: RequiredOperation>(
+    connection: &mut sqlx::PgConnection,
+    kind: cacvote::JobKind,
+    election_id: Uuid,
+    parameters: Value,
+    operator: &Operator<O>,
+) -> (StatusCode, Json<Value>) {
+    let mut transaction = match connection.begin().await {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            tracing::error!("error starting transaction: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error starting transaction" })),
+            );
+        }
+    };
+
+    let job_id = match db::add_job(
+        &mut transaction,
+        kind,
+        election_id,
+        &parameters,
+        &operator.user,
+    )
+    .await
+    {
+        Ok(Some(job_id)) => job_id,
+        Ok(None) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": format!("a {kind} job is already in progress for this election")
+                })),
+            )
+        }
+        Err(e) => {
+            tracing::error!("error adding job to database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error adding job to database" })),
+            );
+        }
+    };
+
+    if let Err(rejection) = audit::record_job_started(&mut transaction, operator, job_id).await {
+        return rejection;
+    }
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("error committing transaction: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error committing transaction" })),
+        );
+    }
+
+    (StatusCode::ACCEPTED, Json(json!({ "jobId": job_id })))
+}
 
-    Ok(Json(response))
+async fn get_jobs(State(AppState { pool, .. }): State<AppState>) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    match db::get_jobs(&mut connection).await {
+        Ok(jobs) => (StatusCode::OK, Json(json!({ "jobs": jobs }))),
+        Err(e) => {
+            tracing::error!("error getting jobs from database: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting jobs from database" })),
+            )
+        }
+    }
+}
+
+async fn get_job(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    match db::get_job(&mut connection, job_id).await {
+        Ok(Some(job)) => (StatusCode::OK, Json(json!(job))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "job not found" })),
+        ),
+        Err(e) => {
+            tracing::error!("error getting job from database: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting job from database" })),
+            )
+        }
+    }
+}
+
+/// Cancels a job. A pending job is cancelled at once; a running one stops at
+/// its next progress report. Cancelling needs the same permission as starting
+/// the job, which depends on its kind.
+async fn cancel_job(
+    State(AppState { pool, .. }): State<AppState>,
+    authenticated: Authenticated,
+    Path(job_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    let job = match db::get_job(&mut connection, job_id).await {
+        Ok(Some(job)) => job,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "job not found" })),
+            )
+        }
+        Err(e) => {
+            tracing::error!("error getting job from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting job from database" })),
+            );
+        }
+    };
+
+    // cancelling a job needs the same permission as starting it
+    let operation = jobs::operation(job.kind);
+    if let Err(rejection) = authenticated.authorize(operation) {
+        return rejection;
+    }
+    match load_election(&mut connection, job.election_object_id).await {
+        Ok(election) => {
+            if let Err(rejection) = authenticated.check_election(&election) {
+                return rejection;
+            }
+        }
+        Err(rejection) => return rejection,
+    }
+
+    let mut transaction = match connection.begin().await {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            tracing::error!("error starting transaction: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error starting transaction" })),
+            );
+        }
+    };
+
+    let status = match db::request_job_cancellation(&mut transaction, job_id).await {
+        Ok(Some(status)) => status,
+        Ok(None) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": "job has already finished" })),
+            )
+        }
+        Err(e) => {
+            tracing::error!("error cancelling job: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error cancelling job" })),
+            );
+        }
+    };
+
+    if let Err(rejection) = audit::add_entry(
+        &mut transaction,
+        operation,
+        &authenticated.user,
+        None,
+        audit::Outcome::Success,
+        &format!("{} job {job_id} cancellation requested", operation.as_str()),
+    )
+    .await
+    {
+        return rejection;
+    }
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("error committing transaction: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error committing transaction" })),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({ "id": job_id, "status": status })),
+    )
+}
+
+/// Loads an election, failing with `404 Not Found` if there is none.
+async fn load_election(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+) -> Result<cacvote::Election, (StatusCode, Json<Value>)> {
+    match db::get_object(connection, election_id)
+        .await
+        .and_then(|object| Ok(object.try_to_inner()?))
+    {
+        Ok(cacvote::Payload::Election(election)) => Ok(election),
+        Ok(_) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "object is not an election" })),
+        )),
+        Err(e) => {
+            tracing::error!("error getting election from database: {e}");
+            Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "error getting election from database" })),
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum TallyReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Deserialize)]
+struct TallyReportQuery {
+    #[serde(default)]
+    format: TallyReportFormat,
+}
+
+/// Gets the results of each contest of an election from its decrypted tally,
+/// as JSON or CSV. Needs the permission to decrypt the tally and a card for the
+/// election. Fails with `409 Conflict` if the tally is not decrypted.
+async fn get_tally_report(
+    State(AppState { pool, .. }): State<AppState>,
+    authenticated: Authenticated,
+    Path(election_id): Path<Uuid>,
+    Query(TallyReportQuery { format }): Query<TallyReportQuery>,
+) -> Response {
+    if let Err(rejection) = authenticated.authorize(auth::Operation::DecryptTally) {
+        return rejection.into_response();
+    }
+
+    match load_tally_report(&pool, &authenticated, election_id).await {
+        Ok(report) => match format {
+            TallyReportFormat::Json => Json(report).into_response(),
+            TallyReportFormat::Csv => {
+                match tally_report::export_csv(&tally_report::TallyReportRow::from_report(&report))
+                {
+                    Ok(csv) => (
+                        [
+                            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                            (
+                                header::CONTENT_DISPOSITION,
+                                "attachment; filename=\"cacvote-jx-tally-report.csv\"",
+                            ),
+                        ],
+                        csv,
+                    )
+                        .into_response(),
+                    Err(e) => {
+                        tracing::error!("error exporting tally report: {e}");
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(json!({ "error": format!("error exporting tally report: {e}") })),
+                        )
+                            .into_response()
+                    }
+                }
+            }
+        },
+        Err(response) => response.into_response(),
+    }
+}
+
+async fn load_tally_report(
+    pool: &PgPool,
+    authenticated: &Authenticated,
+    election_id: Uuid,
+) -> Result<TallyReport, (StatusCode, Json<Value>)> {
+    let mut connection = pool.acquire().await.map_err(|e| {
+        tracing::error!("error getting database connection: {e}");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error getting database connection" })),
+        )
+    })?;
+
+    let election = load_election(&mut connection, election_id).await?;
+    authenticated.check_election(&election)?;
+
+    let (encrypted_tally, decrypted_tally) =
+        match db::get_tallies_for_election(&mut connection, election_id).await {
+            Ok(db::ElectionTallies::Both(encrypted_tally, decrypted_tally)) => {
+                (encrypted_tally, decrypted_tally)
+            }
+            Ok(_) => {
+                return Err((
+                    StatusCode::CONFLICT,
+                    Json(json!({ "error": "election tally has not been decrypted" })),
+                ));
+            }
+            Err(e) => {
+                tracing::error!("error getting tallies from database: {e}");
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": "error getting tallies from database" })),
+                ));
+            }
+        };
+
+    // count the ballots the tally accumulates by the ballot style each was
+    // encrypted with. Tallies made before their ballots were recorded
+    // accumulate every ballot cast in the election.
+    let cast_ballot_ids = &encrypted_tally.encrypted_election_tally.cast_ballot_ids;
+    let cast_ballots = if cast_ballot_ids.is_empty() {
+        db::get_cast_ballots_for_election(&mut connection, &election_id)
+            .await
+            .map(|cast_ballots| {
+                cast_ballots
+                    .into_iter()
+                    .map(|(_, cast_ballot)| cast_ballot)
+                    .collect::<Vec<_>>()
+            })
+    } else {
+        db::get_cast_ballots_by_id(&mut connection, &election_id, cast_ballot_ids).await
+    };
+    let ballot_counts = cast_ballots
+        .and_then(|cast_ballots| {
+            count_ballots_by_ballot_style(
+                &election.election_definition.election,
+                cast_ballots
+                    .iter()
+                    .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_slice()),
+            )
+        })
+        .map_err(|e| {
+            tracing::error!("error counting cast ballots: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error counting cast ballots" })),
+            )
+        })?;
+
+    parse_decrypted_tally(
+        &decrypted_tally
+            .decrypted_election_tally
+            .electionguard_decrypted_tally,
+    )
+    .map_err(color_eyre::Report::from)
+    .and_then(|tally| {
+        build_tally_report(
+            &tally,
+            &election.election_definition.election,
+            &ballot_counts,
+        )
+    })
+    .map_err(|e| {
+        tracing::error!("error building tally report: {e}");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": format!("error building tally report: {e}") })),
+        )
+    })
+}
+
+/// Starts a job to decrypt an election's encrypted tally. An official must
+/// authorize unwrapping the election's key now, since the job runs after this
+/// request has finished.
+async fn decrypt_encrypted_election_tally(
+    State(AppState {
+        pool,
+        config,
+        session_manager,
+    }): State<AppState>,
+    operator: Operator<auth::DecryptTally>,
+    Path(election_id): Path<Uuid>,
+    Json(cacvote::DecryptElectionTallyRequest { pin }): Json<cacvote::DecryptElectionTallyRequest>,
+) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    match db::get_tallies_for_election(&mut connection, election_id).await {
+        Ok(db::ElectionTallies::OnlyEncrypted(_)) => (),
+        Ok(db::ElectionTallies::Both(..)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": "tally already exists" })),
+            )
+        }
+        Ok(db::ElectionTallies::Neither) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "encrypted tally not found" })),
+            )
+        }
+        Err(e) => {
+            tracing::error!("error getting encrypted election tally from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting encrypted election tally from database" })),
+            );
+        }
+    }
+
+    if let Err(response) = load_election(&mut connection, election_id).await {
+        return response;
+    }
+
+    if let Err(response) =
+        require_official_to_unwrap(&config, &session_manager, election_id, pin).await
+    {
+        return response;
+    }
+
+    start_job(
+        &mut connection,
+        cacvote::JobKind::DecryptTally,
+        election_id,
+        json!({}),
+        &operator,
+    )
+    .await
+}
+
+/// Starts a job to shuffle an election's cast ballots through the mixnet.
+async fn mix_encrypted_ballots(
+    State(AppState { pool, .. }): State<AppState>,
+    operator: Operator<auth::MixBallots>,
+    Path(election_id): Path<Uuid>,
+    Json(request): Json<cacvote::MixEncryptedBallotsRequest>,
+) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    if let Err(response) = load_election(&mut connection, election_id).await {
+        return response;
+    }
+
+    if let Err(response) = require_voting_closed(&mut connection, election_id).await {
+        return response;
+    }
+
+    start_job(
+        &mut connection,
+        cacvote::JobKind::MixBallots,
+        election_id,
+        json!(request),
+        &operator,
+    )
+    .await
+}
+
+/// Starts a job to check the proofs of shuffle of an election's mixed ballots
+/// and record the result.
+async fn verify_mixed_ballots(
+    State(AppState { pool, .. }): State<AppState>,
+    operator: Operator<auth::VerifyMixedBallots>,
+    Path(election_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    if let Err(response) = load_election(&mut connection, election_id).await {
+        return response;
+    }
+
+    match db::get_shuffled_encrypted_cast_ballots_object(&mut connection, election_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": "the ballots have not been mixed" })),
+            );
+        }
+        Err(e) => {
+            tracing::error!("error getting mixed ballots from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting mixed ballots from database" })),
+            );
+        }
+    }
+
+    start_job(
+        &mut connection,
+        cacvote::JobKind::VerifyMixedBallots,
+        election_id,
+        json!({}),
+        &operator,
+    )
+    .await
+}
+
+/// Starts a job to decrypt an election's mixed ballots into anonymous ballots.
+/// Only a mix whose proofs of shuffle have been verified may be decrypted, and
+/// an official must authorize unwrapping the election's key now, since the job
+/// runs after this request has finished.
+async fn decrypt_mixed_ballots(
+    State(AppState {
+        pool,
+        config,
+        session_manager,
+    }): State<AppState>,
+    operator: Operator<auth::DecryptMixedBallots>,
+    Path(election_id): Path<Uuid>,
+    Json(cacvote::DecryptMixedBallotsRequest { pin }): Json<cacvote::DecryptMixedBallotsRequest>,
+) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    if let Err(response) = load_election(&mut connection, election_id).await {
+        return response;
+    }
+
+    match db::get_decrypted_shuffled_ballots(&mut connection, election_id).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": "the mixed ballots have already been decrypted" })),
+            );
+        }
+        Err(e) => {
+            tracing::error!("error getting decrypted mixed ballots from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting decrypted mixed ballots from database" })),
+            );
+        }
+    }
+
+    let shuffled_ballots_id =
+        match db::get_shuffled_encrypted_cast_ballots_object(&mut connection, election_id).await {
+            Ok(Some((shuffled_ballots_id, _))) => shuffled_ballots_id,
+            Ok(None) => {
+                return (
+                    StatusCode::CONFLICT,
+                    Json(json!({ "error": "the ballots have not been mixed" })),
+                );
+            }
+            Err(e) => {
+                tracing::error!("error getting mixed ballots from database: {e}");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": "error getting mixed ballots from database" })),
+                );
+            }
+        };
+
+    match db::get_latest_shuffled_encrypted_cast_ballots_verification(&mut connection, election_id)
+        .await
+    {
+        Ok(Some(verification))
+            if verification
+                .shuffled_encrypted_cast_ballots_verification
+                .shuffled_encrypted_cast_ballots_object_id
+                == shuffled_ballots_id
+                && verification
+                    .shuffled_encrypted_cast_ballots_verification
+                    .is_verified() => {}
+        Ok(_) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": "the mixed ballots have not been verified" })),
+            );
+        }
+        Err(e) => {
+            tracing::error!("error getting mixed ballots verification from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting mixed ballots verification from database" })),
+            );
+        }
+    }
+
+    // checked before the official's PIN is used, since only the JX that
+    // created the election holds its private key
+    match db::has_eg_private_key(&mut connection, &election_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": "this JX does not hold the election's private key, so only the JX that created the election can decrypt its ballots"
+                })),
+            );
+        }
+        Err(e) => {
+            tracing::error!("error getting election private key from database: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting election private key from database" })),
+            );
+        }
+    }
+
+    if let Err(response) =
+        require_official_to_unwrap(&config, &session_manager, election_id, pin).await
+    {
+        return response;
+    }
+
+    start_job(
+        &mut connection,
+        cacvote::JobKind::DecryptMixedBallots,
+        election_id,
+        json!({}),
+        &operator,
+    )
+    .await
+}
+
+/// Lists the mailing labels the `cacvote-server` host has scanned for an
+/// election, flagging any that were received after the election's ballot
+/// receipt deadline.
+async fn list_scanned_mailing_labels_by_election(
+    State(AppState { config, pool, .. }): State<AppState>,
+    Path(election_id): Path<Uuid>,
+) -> Result<Json<Vec<cacvote::ScannedMailingLabelPresenter>>, StatusCode> {
+    let mut connection = pool.acquire().await.map_err(|e| {
+        tracing::error!("error getting database connection: {e}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let election = match db::get_object(&mut connection, election_id)
+        .await
+        .and_then(|object| Ok(object.try_to_inner()?))
+    {
+        Ok(cacvote::Payload::Election(election)) => election,
+        Ok(_) => return Err(StatusCode::BAD_REQUEST),
+        Err(e) => {
+            tracing::error!("error getting election from database: {e}");
+            return Err(StatusCode::NOT_FOUND);
+        }
+    };
+
+    let (machine_cert, signer) = match (config.machine_cert(), config.async_signer()) {
+        (Ok(machine_cert), Ok(signer)) => (machine_cert, signer),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!("invalid configuration for CACvote Server client: {e}");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let client = Client::new(config.cacvote_url.clone(), machine_cert, signer);
+
+    match client
+        .list_scanned_mailing_labels_by_election(election_id)
+        .await
+    {
+        Ok(scanned_mailing_labels) => Ok(Json(
+            scanned_mailing_labels
+                .into_iter()
+                .map(|label| cacvote::ScannedMailingLabelPresenter::new(label, &election))
+                .collect(),
+        )),
+        Err(e) => {
+            tracing::error!("error listing scanned mailing labels: {e}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn export_transfer_bundle(
+    State(AppState { config, pool, .. }): State<AppState>,
+    operator: Operator<auth::ExportTransferBundle>,
+) -> (StatusCode, Json<Value>) {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    let signed_bundle = match sync::export_bundle(&mut connection, &config).await {
+        Ok(signed_bundle) => signed_bundle,
+        Err(e) => {
+            tracing::error!("error exporting transfer bundle: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": format!("error exporting transfer bundle: {e}") })),
+            );
+        }
+    };
+
+    if let Err(rejection) = audit::record_success(&mut connection, &operator, None).await {
+        return rejection;
+    }
+
+    (StatusCode::OK, Json(json!(signed_bundle)))
+}
+
+async fn import_transfer_bundle(
+    State(AppState { config, pool, .. }): State<AppState>,
+    operator: Operator<auth::ImportTransferBundle>,
+    Json(signed_bundle): Json<cacvote::SignedTransferBundle>,
+) -> (StatusCode, Json<Value>) {
+    let mut transaction = match pool.begin().await {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            tracing::error!("error starting transaction: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error starting transaction" })),
+            );
+        }
+    };
+
+    let receipt = match sync::import_bundle(&mut transaction, &signed_bundle, &config).await {
+        Ok(receipt) => receipt,
+        Err(e) => {
+            tracing::error!("error importing transfer bundle: {e}");
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("error importing transfer bundle: {e}") })),
+            );
+        }
+    };
+
+    if let Err(rejection) =
+        audit::record_bundle_imported(&mut transaction, &operator, receipt.bundle_id).await
+    {
+        return rejection;
+    }
+
+    if let Err(e) = transaction.commit().await {
+        tracing::error!("error committing transaction: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "error committing transaction" })),
+        );
+    }
+
+    (StatusCode::OK, Json(json!(receipt)))
+}
+
+async fn get_audit_log(
+    State(AppState { pool, .. }): State<AppState>,
+    authenticated: Authenticated,
+) -> impl IntoResponse {
+    if let Err(rejection) = authenticated.authorize(auth::Operation::ExportAuditLog) {
+        return rejection;
+    }
+
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            );
+        }
+    };
+
+    match db::get_audit_log_entries(&mut connection).await {
+        Ok(entries) => (StatusCode::OK, Json(json!({ "entries": entries }))),
+        Err(e) => {
+            tracing::error!("error getting audit log entries: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting audit log entries" })),
+            )
+        }
+    }
+}
+
+/// Exports the audit log in the VotingWorks log format, one JSON object per
+/// line. The export itself is recorded in the audit log.
+async fn export_audit_log(
+    State(AppState { pool, .. }): State<AppState>,
+    operator: Operator<auth::ExportAuditLog>,
+) -> Response {
+    let mut connection = match pool.acquire().await {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("error getting database connection: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            )
+                .into_response();
+        }
+    };
+
+    let entries = match db::get_audit_log_entries(&mut connection).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::error!("error getting audit log entries: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting audit log entries" })),
+            )
+                .into_response();
+        }
+    };
+
+    let logs = match audit::export_vx_logs(&entries) {
+        Ok(logs) => logs,
+        Err(e) => {
+            tracing::error!("error exporting audit log: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": format!("error exporting audit log: {e}") })),
+            )
+                .into_response();
+        }
+    };
+
+    if let Err(rejection) = audit::record_success(&mut connection, &operator, None).await {
+        return rejection.into_response();
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"cacvote-jx-audit-log.log\"",
+            ),
+        ],
+        logs,
+    )
+        .into_response()
 }
@@ -0,0 +1,403 @@
+//! Append-only audit trail of operator actions on the JX terminal.
+//!
+//! Each authorized action records who performed it, what it produced, and
+//! whether it succeeded. A successful action records its entry in the same
+//! transaction as the action itself, so that neither is stored without the
+//! other; failed actions are recorded by the [`record_failures`] middleware.
+//! The trail can be exported in the VotingWorks log format so it can be
+//! reviewed alongside logs from other VotingWorks machines.
+
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    body::{self, Body},
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use types_rs::auth::User;
+use uuid::Uuid;
+use vx_logging::{Disposition, EventId, EventType, Log};
+
+use crate::auth::{Operation, Operator, Rejection, RequiredOperation};
+use crate::db;
+use crate::log;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Outcome {
+    Success,
+    Failure,
+}
+
+impl Outcome {
+    pub(crate) const fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "success" => Ok(Self::Success),
+            "failure" => Ok(Self::Failure),
+            _ => Err(format!("unknown outcome: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AuditLogEntry {
+    pub(crate) id: Uuid,
+    pub(crate) operation: Operation,
+    pub(crate) user: User,
+    pub(crate) object_id: Option<Uuid>,
+    pub(crate) outcome: Outcome,
+    pub(crate) message: String,
+    #[serde(with = "time::serde::iso8601")]
+    pub(crate) created_at: OffsetDateTime,
+}
+
+impl AuditLogEntry {
+    /// Converts the entry to a VotingWorks log line.
+    pub(crate) fn to_vx_log(&self) -> Log {
+        let mut message = self.message.clone();
+        if let Some(jurisdiction) = self.user.jurisdiction_code() {
+            message.push_str(&format!(" (jurisdiction: {jurisdiction})"));
+        }
+
+        Log {
+            time_log_written: self.created_at.format(&Rfc3339).ok(),
+            source: log::APP_NAME.to_owned(),
+            event_id: event_id(self.operation),
+            message,
+            event_type: EventType::UserAction,
+            user: log::vx_user(&self.user),
+            disposition: match self.outcome {
+                Outcome::Success => Disposition::Success,
+                Outcome::Failure => Disposition::Failure,
+            },
+        }
+    }
+}
+
+const fn event_id(operation: Operation) -> EventId {
+    match operation {
+        Operation::CreateElection => EventId::CacvoteElectionCreated,
+        Operation::CreateRegistration => EventId::CacvoteRegistrationCreated,
+        Operation::RejectRegistration => EventId::CacvoteRegistrationRejected,
+        Operation::ImportRegistrations => EventId::CacvoteRegistrationsImported,
+        Operation::ExportRegistrations => EventId::CacvoteRegistrationsExported,
+        Operation::ManageStreetSegments => EventId::CacvoteStreetSegmentsUpdated,
+        Operation::ChangeElectionLifecycle => EventId::CacvoteElectionLifecycleChanged,
+        Operation::GenerateEncryptedTally => EventId::CacvoteEncryptedTallyGenerated,
+        Operation::DecryptTally => EventId::CacvoteTallyDecrypted,
+        Operation::MixBallots => EventId::CacvoteEncryptedBallotsMixed,
+        Operation::VerifyMixedBallots => EventId::CacvoteMixedBallotsVerified,
+        Operation::DecryptMixedBallots => EventId::CacvoteMixedBallotsDecrypted,
+        Operation::ImportTransferBundle => EventId::CacvoteTransferBundleImported,
+        Operation::ExportTransferBundle => EventId::CacvoteTransferBundleExported,
+        Operation::ExportAuditLog => EventId::CacvoteAuditLogExported,
+    }
+}
+
+/// Exports the entries as VotingWorks logs, one JSON object per line.
+pub(crate) fn export_vx_logs(entries: &[AuditLogEntry]) -> Result<String, serde_json::Error> {
+    let mut output = String::new();
+    for entry in entries {
+        output.push_str(&serde_json::to_string(&entry.to_vx_log())?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Records that an operator's action succeeded, having stored the object with
+/// `object_id` if it stored one. Call it with the transaction that carries out
+/// the action before committing it, so that the action fails if it cannot be
+/// recorded.
+pub(crate) async fn record_success<O: RequiredOperation>(
+    connection: &mut sqlx::PgConnection,
+    operator: &Operator<O>,
+    object_id: Option<Uuid>,
+) -> Result<(), Rejection> {
+    let message = match object_id {
+        Some(object_id) => format!(
+            "{} succeeded with object {object_id}",
+            O::OPERATION.as_str()
+        ),
+        None => format!("{} succeeded", O::OPERATION.as_str()),
+    };
+    add_entry(
+        connection,
+        O::OPERATION,
+        &operator.user,
+        object_id,
+        Outcome::Success,
+        &message,
+    )
+    .await
+}
+
+/// Records that an operator imported the transfer bundle with `bundle_id`. A
+/// bundle is not an object, so its ID goes in the message rather than in the
+/// entry's object ID.
+pub(crate) async fn record_bundle_imported<O: RequiredOperation>(
+    connection: &mut sqlx::PgConnection,
+    operator: &Operator<O>,
+    bundle_id: Uuid,
+) -> Result<(), Rejection> {
+    let message = format!(
+        "{} succeeded with bundle {bundle_id}",
+        O::OPERATION.as_str()
+    );
+    add_entry(
+        connection,
+        O::OPERATION,
+        &operator.user,
+        None,
+        Outcome::Success,
+        &message,
+    )
+    .await
+}
+
+/// Records that an operator started the job with `job_id`. The job records
+/// its own outcome when it finishes.
+pub(crate) async fn record_job_started<O: RequiredOperation>(
+    connection: &mut sqlx::PgConnection,
+    operator: &Operator<O>,
+    job_id: Uuid,
+) -> Result<(), Rejection> {
+    let message = format!("{} started as job {job_id}", O::OPERATION.as_str());
+    add_entry(
+        connection,
+        O::OPERATION,
+        &operator.user,
+        None,
+        Outcome::Success,
+        &message,
+    )
+    .await
+}
+
+/// Adds an entry for an action that has not been committed yet, rejecting the
+/// request with `500 Internal Server Error` if it cannot be added.
+pub(crate) async fn add_entry(
+    connection: &mut sqlx::PgConnection,
+    operation: Operation,
+    user: &User,
+    object_id: Option<Uuid>,
+    outcome: Outcome,
+    message: &str,
+) -> Result<(), Rejection> {
+    db::add_audit_log_entry(connection, operation, user, object_id, outcome, message)
+        .await
+        .map(|_| ())
+        .map_err(|e| {
+            tracing::error!("error recording audit log entry ({message}): {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error recording audit log entry" })),
+            )
+        })
+}
+
+/// The operator acting in a request and the operation they were authorized
+/// for. [`Operator`] fills it in so that [`record_failures`] knows whom to
+/// attribute a failed action to.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditContext(Arc<Mutex<Option<(Operation, User)>>>);
+
+impl AuditContext {
+    pub(crate) fn set(&self, operation: Operation, user: User) {
+        if let Ok(mut context) = self.0.lock() {
+            *context = Some((operation, user));
+        }
+    }
+
+    fn take(&self) -> Option<(Operation, User)> {
+        self.0.lock().ok()?.take()
+    }
+}
+
+/// Middleware recording the failure of any action an [`Operator`] was
+/// authorized for, with the error the response describes. The action has
+/// already failed, so failing to record it is only logged.
+pub(crate) async fn record_failures(
+    State(pool): State<PgPool>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let context = AuditContext::default();
+    request.extensions_mut().insert(context.clone());
+
+    let response = next.run(request).await;
+    if response.status().is_success() {
+        return response;
+    }
+    let Some((operation, user)) = context.take() else {
+        return response;
+    };
+
+    let (parts, body) = response.into_parts();
+    let body = match body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("error reading failed response: {e}");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    if let Err(e) = record_failure(&pool, operation, &user, parts.status, &body).await {
+        tracing::error!("error recording audit log entry for failed {operation:?}: {e}");
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+async fn record_failure(
+    pool: &PgPool,
+    operation: Operation,
+    user: &User,
+    status: StatusCode,
+    body: &[u8],
+) -> color_eyre::Result<()> {
+    let error = serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|body| Some(body.get("error")?.as_str()?.to_owned()))
+        .unwrap_or_else(|| {
+            status
+                .canonical_reason()
+                .unwrap_or("unknown error")
+                .to_owned()
+        });
+    let message = format!("{} failed: {error}", operation.as_str());
+
+    let mut connection = pool.acquire().await?;
+    db::add_audit_log_entry(
+        &mut connection,
+        operation,
+        user,
+        None,
+        Outcome::Failure,
+        &message,
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{middleware, routing::post, Extension, Router};
+    use types_rs::{auth::SystemAdministratorUser, cacvote::JurisdictionCode};
+
+    use super::*;
+
+    fn system_administrator() -> User {
+        User::SystemAdministrator(SystemAdministratorUser::new(
+            JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+        ))
+    }
+
+    #[test]
+    fn test_to_vx_log() {
+        let entry = AuditLogEntry {
+            id: Uuid::new_v4(),
+            operation: Operation::CreateElection,
+            user: system_administrator(),
+            object_id: None,
+            outcome: Outcome::Failure,
+            message: "create-election failed: invalid election".to_owned(),
+            created_at: OffsetDateTime::UNIX_EPOCH,
+        };
+
+        assert_eq!(
+            serde_json::to_value(entry.to_vx_log()).unwrap(),
+            serde_json::json!({
+                "timeLogWritten": "1970-01-01T00:00:00Z",
+                "source": "cacvote-jx-terminal",
+                "eventId": "cacvote-election-created",
+                "message": "create-election failed: invalid election (jurisdiction: st.dev-jurisdiction)",
+                "eventType": "user-action",
+                "user": "system_administrator",
+                "disposition": "failure",
+            })
+        );
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_record_failures(pool: PgPool) -> color_eyre::Result<()> {
+        async fn authorized(
+            Extension(context): Extension<AuditContext>,
+            Json(status): Json<u16>,
+        ) -> (StatusCode, Json<Value>) {
+            context.set(Operation::ImportTransferBundle, system_administrator());
+            (
+                StatusCode::from_u16(status).unwrap(),
+                Json(json!({ "error": "invalid bundle" })),
+            )
+        }
+
+        async fn unauthorized() -> (StatusCode, Json<Value>) {
+            (
+                StatusCode::FORBIDDEN,
+                Json(json!({ "error": "card is not permitted" })),
+            )
+        }
+
+        let app = Router::new()
+            .route("/authorized", post(authorized))
+            .route("/unauthorized", post(unauthorized))
+            .layer(middleware::from_fn_with_state(
+                pool.clone(),
+                record_failures,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let post = |path: &str, body: Value| client.post(format!("{url}{path}")).json(&body).send();
+
+        // successful actions are recorded by their handlers
+        assert_eq!(post("/authorized", json!(200)).await?.status(), 200);
+        // requests that were never authorized are not actions
+        assert_eq!(post("/unauthorized", json!(null)).await?.status(), 403);
+
+        let response = post("/authorized", json!(400)).await?;
+        assert_eq!(response.status(), 400);
+        assert_eq!(
+            response.json::<Value>().await?,
+            json!({ "error": "invalid bundle" })
+        );
+
+        let mut connection = pool.acquire().await?;
+        match db::get_audit_log_entries(&mut connection).await?.as_slice() {
+            [entry] => {
+                assert_eq!(entry.operation, Operation::ImportTransferBundle);
+                assert_eq!(entry.user, system_administrator());
+                assert_eq!(entry.outcome, Outcome::Failure);
+                assert_eq!(
+                    entry.message,
+                    "import-transfer-bundle failed: invalid bundle"
+                );
+            }
+            entries => panic!("expected one audit log entry, got {entries:?}"),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,525 @@
+//! Authorization of operator actions on the JX terminal.
+//!
+//! Routes that change state take an [`Operator`] extractor parameterized by
+//! the operation they perform, e.g. `Operator<CreateElection>`. The extractor
+//! rejects the request unless the session is authenticated with a card for
+//! this machine's jurisdiction whose role permits that operation. Election
+//! managers and poll workers may only act on the election their card is
+//! for, which the extractor checks for routes with an `:election_id`.
+//! Handlers that find the election some other way check it themselves with
+//! [`Operator::check_election`].
+
+use std::{marker::PhantomData, str::FromStr};
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts, RawPathParams},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use types_rs::{auth::User, cacvote, election::ElectionHash};
+use uuid::Uuid;
+
+use crate::audit;
+use crate::config::Config;
+use crate::db;
+use crate::session_manager::SessionManager;
+
+pub(crate) type Rejection = (StatusCode, Json<Value>);
+
+/// Operations that require an authenticated operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operation {
+    CreateElection,
+    CreateRegistration,
+    RejectRegistration,
+    ImportRegistrations,
+    ExportRegistrations,
+    ManageStreetSegments,
+    ChangeElectionLifecycle,
+    GenerateEncryptedTally,
+    DecryptTally,
+    MixBallots,
+    VerifyMixedBallots,
+    DecryptMixedBallots,
+    ImportTransferBundle,
+    ExportTransferBundle,
+    ExportAuditLog,
+}
+
+impl Operation {
+    /// Determines whether `user` may perform this operation. System
+    /// administrators configure the machine and its elections, election
+    /// managers run the election, and poll workers only handle individual
+    /// registration requests.
+    pub(crate) const fn is_permitted_for(self, user: &User) -> bool {
+        match user {
+            User::SystemAdministrator(_) => matches!(
+                self,
+                Self::CreateElection
+                    | Self::CreateRegistration
+                    | Self::RejectRegistration
+                    | Self::ImportRegistrations
+                    | Self::ExportRegistrations
+                    | Self::ManageStreetSegments
+                    | Self::ImportTransferBundle
+                    | Self::ExportTransferBundle
+                    | Self::ExportAuditLog
+            ),
+            User::ElectionManager(_) => matches!(
+                self,
+                Self::CreateRegistration
+                    | Self::RejectRegistration
+                    | Self::ImportRegistrations
+                    | Self::ExportRegistrations
+                    | Self::ChangeElectionLifecycle
+                    | Self::GenerateEncryptedTally
+                    | Self::DecryptTally
+                    | Self::MixBallots
+                    | Self::VerifyMixedBallots
+                    | Self::DecryptMixedBallots
+                    | Self::ImportTransferBundle
+                    | Self::ExportTransferBundle
+                    | Self::ExportAuditLog
+            ),
+            User::PollWorker(_) => {
+                matches!(self, Self::CreateRegistration | Self::RejectRegistration)
+            }
+            User::CardlessVoter(_) => false,
+        }
+    }
+
+    pub(crate) const fn description(self) -> &'static str {
+        match self {
+            Self::CreateElection => "create elections",
+            Self::CreateRegistration => "register voters",
+            Self::RejectRegistration => "reject registration requests",
+            Self::ImportRegistrations => "import registrations",
+            Self::ExportRegistrations => "export registrations",
+            Self::ManageStreetSegments => "manage street segments",
+            Self::ChangeElectionLifecycle => "open, close or certify elections",
+            Self::GenerateEncryptedTally => "generate encrypted tallies",
+            Self::DecryptTally => "decrypt tallies",
+            Self::MixBallots => "mix encrypted ballots",
+            Self::VerifyMixedBallots => "verify mixed ballots",
+            Self::DecryptMixedBallots => "decrypt mixed ballots",
+            Self::ImportTransferBundle => "import transfer bundles",
+            Self::ExportTransferBundle => "export transfer bundles",
+            Self::ExportAuditLog => "export the audit log",
+        }
+    }
+
+    pub(crate) const fn as_str(self) -> &'static str {
+        match self {
+            Self::CreateElection => "create-election",
+            Self::CreateRegistration => "create-registration",
+            Self::RejectRegistration => "reject-registration",
+            Self::ImportRegistrations => "import-registrations",
+            Self::ExportRegistrations => "export-registrations",
+            Self::ManageStreetSegments => "manage-street-segments",
+            Self::ChangeElectionLifecycle => "change-election-lifecycle",
+            Self::GenerateEncryptedTally => "generate-encrypted-tally",
+            Self::DecryptTally => "decrypt-tally",
+            Self::MixBallots => "mix-ballots",
+            Self::VerifyMixedBallots => "verify-mixed-ballots",
+            Self::DecryptMixedBallots => "decrypt-mixed-ballots",
+            Self::ImportTransferBundle => "import-transfer-bundle",
+            Self::ExportTransferBundle => "export-transfer-bundle",
+            Self::ExportAuditLog => "export-audit-log",
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create-election" => Ok(Self::CreateElection),
+            "create-registration" => Ok(Self::CreateRegistration),
+            "reject-registration" => Ok(Self::RejectRegistration),
+            "import-registrations" => Ok(Self::ImportRegistrations),
+            "export-registrations" => Ok(Self::ExportRegistrations),
+            "manage-street-segments" => Ok(Self::ManageStreetSegments),
+            "change-election-lifecycle" => Ok(Self::ChangeElectionLifecycle),
+            "generate-encrypted-tally" => Ok(Self::GenerateEncryptedTally),
+            "decrypt-tally" => Ok(Self::DecryptTally),
+            "mix-ballots" => Ok(Self::MixBallots),
+            "verify-mixed-ballots" => Ok(Self::VerifyMixedBallots),
+            "decrypt-mixed-ballots" => Ok(Self::DecryptMixedBallots),
+            "import-transfer-bundle" => Ok(Self::ImportTransferBundle),
+            "export-transfer-bundle" => Ok(Self::ExportTransferBundle),
+            "export-audit-log" => Ok(Self::ExportAuditLog),
+            _ => Err(format!("unknown operation: {s}")),
+        }
+    }
+}
+
+impl Serialize for Operation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Ties a marker type to an [`Operation`] for use with [`Operator`].
+pub(crate) trait RequiredOperation {
+    const OPERATION: Operation;
+}
+
+macro_rules! required_operations {
+    ($($name:ident),* $(,)?) => {
+        $(
+            #[derive(Debug)]
+            pub(crate) struct $name;
+
+            impl RequiredOperation for $name {
+                const OPERATION: Operation = Operation::$name;
+            }
+        )*
+    };
+}
+
+required_operations!(
+    CreateElection,
+    CreateRegistration,
+    RejectRegistration,
+    ImportRegistrations,
+    ExportRegistrations,
+    ManageStreetSegments,
+    ChangeElectionLifecycle,
+    GenerateEncryptedTally,
+    DecryptTally,
+    MixBallots,
+    VerifyMixedBallots,
+    DecryptMixedBallots,
+    ImportTransferBundle,
+    ExportTransferBundle,
+    ExportAuditLog,
+);
+
+/// Determines whether `user` may act on the election with `election_hash`.
+/// Election managers and poll workers are limited to the election their card
+/// is for.
+pub(crate) fn is_election_permitted_for(user: &User, election_hash: &ElectionHash) -> bool {
+    match user {
+        User::SystemAdministrator(_) => true,
+        User::ElectionManager(user) => user.election_hash == election_hash.as_str(),
+        User::PollWorker(user) => user.election_hash == election_hash.as_str(),
+        User::CardlessVoter(_) => false,
+    }
+}
+
+/// Extracts the authenticated user, rejecting the request with
+/// `401 Unauthorized` if the session is not authenticated and
+/// `403 Forbidden` if the card is for another jurisdiction. Use it directly
+/// for routes any operator may use, routes that only read, or routes that
+/// only know which operation they perform once they have looked at the
+/// request, see [`Authenticated::authorize`]. Otherwise use [`Operator`].
+#[derive(Debug)]
+pub(crate) struct Authenticated {
+    pub(crate) user: User,
+}
+
+impl Authenticated {
+    /// Rejects the request with `403 Forbidden` if the user may not perform
+    /// `operation`.
+    pub(crate) fn authorize(&self, operation: Operation) -> Result<(), Rejection> {
+        if operation.is_permitted_for(&self.user) {
+            return Ok(());
+        }
+
+        tracing::warn!("rejecting operation {operation:?} for user {:?}", self.user);
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "error": format!("card is not permitted to {}", operation.description())
+            })),
+        ))
+    }
+
+    /// Rejects the request with `403 Forbidden` if the user may not act on
+    /// `election`.
+    pub(crate) fn check_election(&self, election: &cacvote::Election) -> Result<(), Rejection> {
+        if is_election_permitted_for(&self.user, &election.election_definition.election_hash) {
+            return Ok(());
+        }
+
+        tracing::warn!(
+            "rejecting action on election {} for user {:?}",
+            election.election_definition.election_hash,
+            self.user
+        );
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "card is not for this election" })),
+        ))
+    }
+
+    /// Loads the election with `election_id` and checks the user may act on
+    /// it.
+    async fn check_election_id(&self, pool: &PgPool, election_id: Uuid) -> Result<(), Rejection> {
+        let mut connection = pool.acquire().await.map_err(|e| {
+            tracing::error!("error getting database connection: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "error getting database connection" })),
+            )
+        })?;
+
+        match db::get_object(&mut connection, election_id)
+            .await
+            .and_then(|object| Ok(object.try_to_inner()?))
+        {
+            Ok(cacvote::Payload::Election(election)) => self.check_election(&election),
+            Ok(_) => Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "object is not an election" })),
+            )),
+            Err(e) => {
+                tracing::error!("error getting election from database: {e}");
+                Err((
+                    StatusCode::NOT_FOUND,
+                    Json(json!({ "error": format!("election {election_id} not found") })),
+                ))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Authenticated
+where
+    Config: FromRef<S>,
+    SessionManager: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Rejection;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let session_manager = SessionManager::from_ref(state);
+
+        let Some(user) = session_manager.authenticated_user() else {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "error": "an authenticated session is required" })),
+            ));
+        };
+
+        let jurisdiction_code = Config::from_ref(state).jurisdiction_code().map_err(|e| {
+            tracing::error!("invalid configuration jurisdiction code: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "invalid configuration jurisdiction code" })),
+            )
+        })?;
+        if user.jurisdiction_code() != Some(&jurisdiction_code) {
+            tracing::warn!("rejecting user {user:?} from another jurisdiction");
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(
+                    json!({ "error": format!("card is not for jurisdiction {jurisdiction_code}") }),
+                ),
+            ));
+        }
+
+        session_manager.record_activity();
+
+        Ok(Self { user })
+    }
+}
+
+/// Extracts the authenticated operator, rejecting the request as
+/// [`Authenticated`] does, and with `403 Forbidden` if the user may not
+/// perform `O` or, for routes with an `:election_id`, act on that election.
+/// Operators' actions are recorded in the audit log: the handler records its
+/// success with [`audit::record_success`], and [`audit::record_failures`]
+/// records its failure.
+#[derive(Debug)]
+pub(crate) struct Operator<O> {
+    pub(crate) user: User,
+    _operation: PhantomData<O>,
+}
+
+impl<O> Operator<O> {
+    /// See [`Authenticated::check_election`].
+    pub(crate) fn check_election(&self, election: &cacvote::Election) -> Result<(), Rejection> {
+        Authenticated {
+            user: self.user.clone(),
+        }
+        .check_election(election)
+    }
+}
+
+#[async_trait]
+impl<O, S> FromRequestParts<S> for Operator<O>
+where
+    O: RequiredOperation,
+    Config: FromRef<S>,
+    PgPool: FromRef<S>,
+    SessionManager: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let authenticated = Authenticated::from_request_parts(parts, state).await?;
+        authenticated.authorize(O::OPERATION)?;
+
+        let election_id = RawPathParams::from_request_parts(parts, state)
+            .await
+            .ok()
+            .and_then(|params| {
+                params
+                    .iter()
+                    .find(|(name, _)| *name == "election_id")
+                    .map(|(_, value)| value.parse::<Uuid>())
+            });
+        match election_id {
+            Some(Ok(election_id)) => {
+                authenticated
+                    .check_election_id(&PgPool::from_ref(state), election_id)
+                    .await?;
+            }
+            Some(Err(_)) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({ "error": "invalid election ID" })),
+                ));
+            }
+            None => {}
+        }
+
+        if let Some(context) = parts.extensions.get::<audit::AuditContext>() {
+            context.set(O::OPERATION, authenticated.user.clone());
+        }
+
+        Ok(Self {
+            user: authenticated.user,
+            _operation: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types_rs::{
+        auth::{ElectionManagerUser, PollWorkerUser, SystemAdministratorUser},
+        cacvote::JurisdictionCode,
+    };
+
+    use super::*;
+
+    const ALL_OPERATIONS: [Operation; 15] = [
+        Operation::CreateElection,
+        Operation::CreateRegistration,
+        Operation::RejectRegistration,
+        Operation::ImportRegistrations,
+        Operation::ExportRegistrations,
+        Operation::ManageStreetSegments,
+        Operation::ChangeElectionLifecycle,
+        Operation::GenerateEncryptedTally,
+        Operation::DecryptTally,
+        Operation::MixBallots,
+        Operation::VerifyMixedBallots,
+        Operation::DecryptMixedBallots,
+        Operation::ImportTransferBundle,
+        Operation::ExportTransferBundle,
+        Operation::ExportAuditLog,
+    ];
+
+    fn permitted_operations(user: &User) -> Vec<Operation> {
+        ALL_OPERATIONS
+            .into_iter()
+            .filter(|operation| operation.is_permitted_for(user))
+            .collect()
+    }
+
+    #[test]
+    fn test_permitted_operations() {
+        let jurisdiction = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+
+        assert_eq!(
+            permitted_operations(&User::SystemAdministrator(SystemAdministratorUser::new(
+                jurisdiction.clone()
+            ))),
+            vec![
+                Operation::CreateElection,
+                Operation::CreateRegistration,
+                Operation::RejectRegistration,
+                Operation::ImportRegistrations,
+                Operation::ExportRegistrations,
+                Operation::ManageStreetSegments,
+                Operation::ImportTransferBundle,
+                Operation::ExportTransferBundle,
+                Operation::ExportAuditLog,
+            ]
+        );
+        assert_eq!(
+            permitted_operations(&User::ElectionManager(ElectionManagerUser::new(
+                jurisdiction.clone(),
+                "hash".to_owned()
+            ))),
+            vec![
+                Operation::CreateRegistration,
+                Operation::RejectRegistration,
+                Operation::ImportRegistrations,
+                Operation::ExportRegistrations,
+                Operation::ChangeElectionLifecycle,
+                Operation::GenerateEncryptedTally,
+                Operation::DecryptTally,
+                Operation::MixBallots,
+                Operation::VerifyMixedBallots,
+                Operation::DecryptMixedBallots,
+                Operation::ImportTransferBundle,
+                Operation::ExportTransferBundle,
+                Operation::ExportAuditLog,
+            ]
+        );
+        assert_eq!(
+            permitted_operations(&User::PollWorker(PollWorkerUser::new(
+                jurisdiction,
+                "hash".to_owned()
+            ))),
+            vec![Operation::CreateRegistration, Operation::RejectRegistration]
+        );
+    }
+
+    #[test]
+    fn test_is_election_permitted_for() {
+        let jurisdiction = JurisdictionCode::try_from("st.dev-jurisdiction").unwrap();
+        let election_hash = ElectionHash::from_str(&"a".repeat(64)).unwrap();
+        let other_election_hash = ElectionHash::from_str(&"b".repeat(64)).unwrap();
+
+        let system_administrator =
+            User::SystemAdministrator(SystemAdministratorUser::new(jurisdiction.clone()));
+        assert!(is_election_permitted_for(
+            &system_administrator,
+            &election_hash
+        ));
+
+        for user in [
+            User::ElectionManager(ElectionManagerUser::new(
+                jurisdiction.clone(),
+                election_hash.as_str().to_owned(),
+            )),
+            User::PollWorker(PollWorkerUser::new(
+                jurisdiction.clone(),
+                election_hash.as_str().to_owned(),
+            )),
+        ] {
+            assert!(is_election_permitted_for(&user, &election_hash));
+            assert!(!is_election_permitted_for(&user, &other_election_hash));
+        }
+    }
+
+    #[test]
+    fn test_operation_round_trip() {
+        for operation in ALL_OPERATIONS {
+            assert_eq!(operation.as_str().parse(), Ok(operation));
+        }
+        assert!("launch-missiles".parse::<Operation>().is_err());
+    }
+}
@@ -1,24 +1,82 @@
+//! Verification of the CAC signatures on cast ballots.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
 use openssl::hash::MessageDigest;
 use openssl::pkey::{PKey, Public};
 use openssl::sign::Verifier;
 use openssl::x509::X509;
-use types_rs::cacvote::VerificationStatus;
+use types_rs::cacvote::{
+    verify_cert, CacCaStore, CastBallot, SignedObject, VerificationError, VerificationStatus,
+};
+use uuid::Uuid;
 
-#[allow(dead_code)]
-const DOD_TEST_CAC_CERTIFICATE_BYTES: &[u8] = include_bytes!("../certs/DODJITCEMAILCA_63.cer");
+/// Verifies cast ballots against the configured CAC CA store, remembering
+/// the outcome for each ballot so that it is only computed once.
+pub(crate) struct CastBallotVerifier {
+    cac_root_ca_store: CacCaStore,
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub(crate) enum CertificateAuthority {
-    #[allow(dead_code)]
-    DodTest,
+    /// Verification status by cast ballot object ID. Stored objects never
+    /// change, so neither does the outcome of verifying them.
+    cache: Mutex<HashMap<Uuid, VerificationStatus>>,
 }
 
-#[allow(dead_code)]
-pub(crate) fn verify_cast_vote_record(
+impl CastBallotVerifier {
+    pub(crate) fn new(cac_root_ca_store: CacCaStore) -> Self {
+        Self {
+            cac_root_ca_store,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the verification status of `cast_ballot`, which is the inner
+    /// payload of `object`.
+    pub(crate) fn verify(
+        &self,
+        object: &SignedObject,
+        cast_ballot: &CastBallot,
+    ) -> VerificationStatus {
+        let mut cache = self
+            .cache
+            .lock()
+            .expect("cast ballot verification cache lock poisoned");
+
+        cache
+            .entry(object.id)
+            .or_insert_with(|| {
+                let verification_status = verify_cast_vote_record(
+                    &object.certificate,
+                    object.certificate_chain.as_deref(),
+                    &object.payload,
+                    &object.signature,
+                    &cast_ballot.common_access_card_id,
+                    &self.cac_root_ca_store,
+                );
+
+                if !matches!(verification_status, VerificationStatus::Success { .. }) {
+                    tracing::warn!(
+                        "cast ballot {} failed verification: {verification_status:?}",
+                        object.id
+                    );
+                }
+
+                verification_status
+            })
+            .clone()
+    }
+}
+
+/// Verifies that the cast vote record was signed by a CAC whose certificate
+/// chains up to the CAC CA store, possibly through the PEM-encoded
+/// intermediates in `certificate_chain`, and that belongs to the expected card.
+fn verify_cast_vote_record(
     common_access_card_certificate: &[u8],
+    certificate_chain: Option<&[u8]>,
     cast_vote_record: &[u8],
     cast_vote_record_signature: &[u8],
-    ca: CertificateAuthority,
+    expected_common_access_card_id: &str,
+    cac_root_ca_store: &CacCaStore,
 ) -> VerificationStatus {
     let common_access_card_certificate = match X509::from_pem(common_access_card_certificate) {
         Ok(x509) => x509,
@@ -29,16 +87,31 @@ pub(crate) fn verify_cast_vote_record(
         }
     };
 
-    match verify_common_access_card_certificate(&common_access_card_certificate, ca) {
-        Ok(true) => {
-            // certificate is valid, continue
+    let intermediates = match certificate_chain.map(X509::stack_from_pem).transpose() {
+        Ok(intermediates) => intermediates.unwrap_or_default(),
+        Err(err) => {
+            return VerificationStatus::Error {
+                message: format!("error parsing certificate chain from PEM format: {err}"),
+            };
         }
-        Ok(false) => {
-            return VerificationStatus::Failure;
+    };
+
+    match verify_cert(
+        cac_root_ca_store,
+        &common_access_card_certificate,
+        &intermediates,
+    ) {
+        Ok(()) => {
+            // certificate chain is valid, continue
+        }
+        Err(VerificationError::CertificateChain(err)) => {
+            return VerificationStatus::Failure {
+                reason: err.to_string(),
+            };
         }
         Err(err) => {
             return VerificationStatus::Error {
-                message: format!("error verifying CAC certificate against {ca:?} CA: {err}"),
+                message: format!("error verifying CAC certificate chain: {err}"),
             };
         }
     }
@@ -57,7 +130,9 @@ pub(crate) fn verify_cast_vote_record(
             // signature is valid, continue
         }
         Ok(false) => {
-            return VerificationStatus::Failure;
+            return VerificationStatus::Failure {
+                reason: "ballot signature does not match the CAC certificate".to_owned(),
+            };
         }
         Err(err) => {
             return VerificationStatus::Error {
@@ -77,6 +152,15 @@ pub(crate) fn verify_cast_vote_record(
             message: "could not extract and parse CN field from X509 certificate".to_owned(),
         };
     };
+
+    if common_access_card_id != expected_common_access_card_id {
+        return VerificationStatus::Failure {
+            reason: format!(
+                "ballot is for CAC ID {expected_common_access_card_id}, but was signed by CAC ID {common_access_card_id}"
+            ),
+        };
+    }
+
     let display_name = format!("{surname}, {given_name} {middle_name}")
         .trim()
         .to_owned();
@@ -87,41 +171,6 @@ pub(crate) fn verify_cast_vote_record(
     }
 }
 
-/// Verify that the certificate is signed by the given certificate authority.
-/// This function only supports the DOD Test CA.
-fn verify_common_access_card_certificate(
-    common_access_card_certificate: &X509,
-    ca: CertificateAuthority,
-) -> color_eyre::Result<bool> {
-    match ca {
-        CertificateAuthority::DodTest => {
-            let signing_cert = match X509::from_der(DOD_TEST_CAC_CERTIFICATE_BYTES) {
-                Ok(signing_cert) => signing_cert,
-                Err(err) => {
-                    return Err(color_eyre::eyre::eyre!(
-                        "error parsing {ca:?} certificate from DER format: {err}"
-                    ));
-                }
-            };
-
-            let public_key = match signing_cert.public_key() {
-                Ok(public_key) => public_key,
-                Err(err) => {
-                    return Err(color_eyre::eyre::eyre!(
-                        "error extracting public key from {ca:?} certificate: {err}"
-                    ));
-                }
-            };
-
-            // directly verifies the certificate against the DOD Test CA because
-            // we're only verifying against the one certificate for now. when we
-            // need to verify the whole chain, we'll need to use the appropriate
-            // openssl APIs to verify the chain or perform this check in a loop
-            Ok(common_access_card_certificate.verify(&public_key)?)
-        }
-    }
-}
-
 fn verify_signature(
     message_buffer: &[u8],
     signature_buffer: &[u8],
@@ -194,6 +243,196 @@ mod test {
         );
     }
 
+    fn build_certificate(
+        common_name: &str,
+        key: &PKey<openssl::pkey::Private>,
+        issuer: Option<(&X509, &PKey<openssl::pkey::Private>)>,
+        is_ca: bool,
+    ) -> X509 {
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_nid(openssl::nid::Nid::COMMONNAME, common_name)
+            .unwrap();
+        let name = name.build();
+
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_pubkey(key).unwrap();
+        builder
+            .set_not_before(&openssl::asn1::Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&openssl::asn1::Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+
+        if is_ca {
+            builder
+                .append_extension(
+                    openssl::x509::extension::BasicConstraints::new()
+                        .critical()
+                        .ca()
+                        .build()
+                        .unwrap(),
+                )
+                .unwrap();
+        }
+
+        match issuer {
+            Some((issuer_cert, issuer_key)) => {
+                builder.set_issuer_name(issuer_cert.subject_name()).unwrap();
+                builder.sign(issuer_key, MessageDigest::sha256()).unwrap();
+            }
+            None => {
+                builder.set_issuer_name(&name).unwrap();
+                builder.sign(key, MessageDigest::sha256()).unwrap();
+            }
+        }
+
+        builder.build()
+    }
+
+    fn generate_key() -> PKey<openssl::pkey::Private> {
+        let group =
+            openssl::ec::EcGroup::from_curve_name(openssl::nid::Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(openssl::ec::EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    fn sign(payload: &[u8], key: &PKey<openssl::pkey::Private>) -> Vec<u8> {
+        let mut signer = openssl::sign::Signer::new(MessageDigest::sha256(), key).unwrap();
+        signer.update(payload).unwrap();
+        signer.sign_to_vec().unwrap()
+    }
+
+    fn build_store(ca_cert: &X509) -> CacCaStore {
+        types_rs::cacvote::build_cac_ca_store([ca_cert.clone()], []).unwrap()
+    }
+
+    #[test]
+    fn verify_cast_vote_record_statuses() {
+        let ca_key = generate_key();
+        let ca_cert = build_certificate("TEST CA", &ca_key, None, true);
+        let store = build_store(&ca_cert);
+
+        let cac_key = generate_key();
+        let cac_cert = build_certificate(
+            "DOE.JANE.QUINN.1234567890",
+            &cac_key,
+            Some((&ca_cert, &ca_key)),
+            false,
+        );
+        let cac_cert_pem = cac_cert.to_pem().unwrap();
+
+        let payload = b"cast vote record";
+        let signature = sign(payload, &cac_key);
+
+        assert_eq!(
+            verify_cast_vote_record(
+                &cac_cert_pem,
+                None,
+                payload,
+                &signature,
+                "1234567890",
+                &store
+            ),
+            VerificationStatus::Success {
+                common_access_card_id: "1234567890".to_owned(),
+                display_name: "DOE, JANE QUINN".to_owned(),
+            }
+        );
+
+        // signed by a different key than the certificate's
+        assert_eq!(
+            verify_cast_vote_record(
+                &cac_cert_pem,
+                None,
+                payload,
+                &sign(payload, &generate_key()),
+                "1234567890",
+                &store
+            ),
+            VerificationStatus::Failure {
+                reason: "ballot signature does not match the CAC certificate".to_owned(),
+            }
+        );
+
+        // ballot claims to be from a different card
+        assert_eq!(
+            verify_cast_vote_record(
+                &cac_cert_pem,
+                None,
+                payload,
+                &signature,
+                "0987654321",
+                &store
+            ),
+            VerificationStatus::Failure {
+                reason: "ballot is for CAC ID 0987654321, but was signed by CAC ID 1234567890"
+                    .to_owned(),
+            }
+        );
+
+        // certificate issued by a CA that is not in the store
+        let other_ca_key = generate_key();
+        let other_ca_cert = build_certificate("OTHER CA", &other_ca_key, None, true);
+        let untrusted_cert = build_certificate(
+            "DOE.JANE.QUINN.1234567890",
+            &cac_key,
+            Some((&other_ca_cert, &other_ca_key)),
+            false,
+        );
+        assert_eq!(
+            verify_cast_vote_record(
+                &untrusted_cert.to_pem().unwrap(),
+                None,
+                payload,
+                &signature,
+                "1234567890",
+                &store
+            ),
+            VerificationStatus::Failure {
+                reason: "signing certificate (CN=DOE.JANE.QUINN.1234567890) failed verification: unable to get local issuer certificate".to_owned(),
+            }
+        );
+
+        // certificate issued by an intermediate CA included with the ballot
+        let intermediate_key = generate_key();
+        let intermediate_cert = build_certificate(
+            "INTERMEDIATE CA",
+            &intermediate_key,
+            Some((&ca_cert, &ca_key)),
+            true,
+        );
+        let chained_cert = build_certificate(
+            "DOE.JANE.QUINN.1234567890",
+            &cac_key,
+            Some((&intermediate_cert, &intermediate_key)),
+            false,
+        );
+        assert!(matches!(
+            verify_cast_vote_record(
+                &chained_cert.to_pem().unwrap(),
+                Some(&intermediate_cert.to_pem().unwrap()),
+                payload,
+                &signature,
+                "1234567890",
+                &store
+            ),
+            VerificationStatus::Success { .. }
+        ));
+
+        assert!(matches!(
+            verify_cast_vote_record(
+                b"not a certificate",
+                None,
+                payload,
+                &signature,
+                "1234567890",
+                &store
+            ),
+            VerificationStatus::Error { .. }
+        ));
+    }
+
     proptest! {
         #[test]
         /// Generate a random X509 certificate and verify that the common name
@@ -6,16 +6,22 @@ use std::{
 };
 
 use auth_rs::{card_details::extract_field_value, certs::VX_CUSTOM_CERT_FIELD_JURISDICTION};
-use cacvote_server_client::{signer, AnySigner};
+use cacvote_server_client::{sealed::SealedSecret, signer, AnyAsyncSigner, AnySigner};
 use clap::Parser;
 use color_eyre::eyre::{bail, Context};
+use electionguard_rs::key_wrapping::{KeyEncryptionKey, KeyEncryptionKeys};
 use openssl::{hash::MessageDigest, sign::Verifier, x509::X509};
-use types_rs::cacvote::JurisdictionCode;
+use types_rs::cacvote::{build_cac_ca_store, CacCaStore, JurisdictionCode};
+
+use crate::session_manager::SessionPolicy;
 
 const TEN_MB: usize = 10 * 1024 * 1024;
 
 pub(crate) const MAX_REQUEST_SIZE: usize = TEN_MB;
+pub(crate) const MAX_TRANSFER_BUNDLE_SIZE: usize = 50 * TEN_MB;
 pub(crate) const SYNC_INTERVAL: Duration = Duration::from_secs(5);
+pub(crate) const REGISTRATION_EXPIRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+pub(crate) const JOB_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone, Parser)]
 #[command(author, version, about)]
@@ -41,6 +47,12 @@ pub(crate) struct Config {
     #[arg(long, env = "CAC_ROOT_CA_CERTS", value_delimiter = ',')]
     pub(crate) cac_root_ca_certs: Vec<PathBuf>,
 
+    /// Directory of intermediate certificate authority certificates, e.g. the
+    /// DoD email CAs, used to link CAC certificates up to `CAC_ROOT_CA_CERTS`.
+    /// Each file may hold any number of PEM certificates or a single DER one.
+    #[arg(long, env = "CAC_INTERMEDIATE_CA_CERTS_DIR")]
+    pub(crate) cac_intermediate_ca_certs_dir: Option<PathBuf>,
+
     /// Certificate associated with this machine's unique private key. Issued by
     /// the VX CA.
     #[arg(long, env = "MACHINE_CERT")]
@@ -65,10 +77,51 @@ pub(crate) struct Config {
     /// ElectionGuard Java CLI CLASSPATH.
     #[arg(long, env = "EG_CLASSPATH")]
     pub(crate) eg_classpath: PathBuf,
+
+    /// TPM sealed data object holding the key-encryption key that wraps the
+    /// ElectionGuard private keys stored in the database, as 64 hex digits,
+    /// e.g. `tpm:0x81000002`.
+    #[arg(long, env = "EG_KEY_ENCRYPTION_KEY")]
+    pub(crate) eg_key_encryption_key: SealedSecret,
+
+    /// TPM sealed data objects holding key-encryption keys that have been
+    /// rotated out. Private keys wrapped with them are rewrapped with
+    /// `EG_KEY_ENCRYPTION_KEY` at startup, after which they may be evicted.
+    #[arg(long, env = "EG_RETIRED_KEY_ENCRYPTION_KEYS", value_delimiter = ',')]
+    pub(crate) eg_retired_key_encryption_keys: Vec<SealedSecret>,
+
+    /// Seconds without activity after which an authenticated session is
+    /// locked until the PIN is entered again. Zero disables the timeout.
+    #[arg(long, env = "INACTIVITY_TIMEOUT_SECONDS", default_value = "900")]
+    pub(crate) inactivity_timeout_seconds: u64,
+
+    /// Number of incorrect PIN attempts allowed before the card is locked out.
+    #[arg(long, env = "MAX_INCORRECT_PIN_ATTEMPTS", default_value = "5")]
+    pub(crate) max_incorrect_pin_attempts: u8,
+
+    /// Seconds a card is locked out for after reaching the incorrect PIN
+    /// attempt limit. Doubles with each further incorrect attempt.
+    #[arg(long, env = "PIN_LOCKOUT_SECONDS", default_value = "15")]
+    pub(crate) pin_lockout_seconds: u64,
+
+    /// Days a registration request may stay pending before it is rejected
+    /// automatically as expired. Zero disables expiry.
+    #[arg(long, env = "REGISTRATION_REQUEST_EXPIRY_DAYS", default_value = "0")]
+    pub(crate) registration_request_expiry_days: u32,
 }
 
 impl Config {
     pub(crate) fn verify(&self) -> color_eyre::Result<()> {
+        // Verify that the key-encryption keys can be unsealed at all. They are
+        // only ever held in the TPM, so without TPM support nothing could be
+        // decrypted.
+        if !cfg!(feature = "tpm") {
+            bail!(
+                "EG_KEY_ENCRYPTION_KEY is sealed in the TPM, but this JX was built without \
+                 the `tpm` feature; build it with `--features tpm`"
+            );
+        }
+
         // Verify that the MACHINE_CERT is signed by the VX CA.
         let machine_cert = self.machine_cert()?;
         let vx_cert_authority_cert = self.vx_cert_authority_cert()?;
@@ -89,17 +142,24 @@ impl Config {
             bail!("signature from SIGNER is not verifiable by MACHINE_CERT");
         }
 
+        // Verify that the key-encryption keys can be loaded.
+        let _ = self.key_encryption_keys()?;
+
         Ok(())
     }
 
-    pub(crate) fn cac_root_ca_store(&self) -> color_eyre::Result<openssl::x509::store::X509Store> {
-        let mut builder = openssl::x509::store::X509StoreBuilder::new()?;
+    pub(crate) fn cac_root_ca_store(&self) -> color_eyre::Result<CacCaStore> {
+        let root_certs = self
+            .cac_root_ca_certs
+            .iter()
+            .map(load_cert)
+            .collect::<color_eyre::Result<Vec<_>>>()?;
+        let intermediate_certs = match &self.cac_intermediate_ca_certs_dir {
+            Some(dir) => load_certs_in_dir(dir)?,
+            None => vec![],
+        };
 
-        for ca_cert in &self.cac_root_ca_certs {
-            builder.add_cert(load_cert(ca_cert)?)?;
-        }
-
-        Ok(builder.build())
+        Ok(build_cac_ca_store(root_certs, intermediate_certs)?)
     }
 
     pub(crate) fn machine_cert(&self) -> color_eyre::Result<openssl::x509::X509> {
@@ -124,10 +184,40 @@ impl Config {
         }
     }
 
+    /// Unseals the keys used to wrap ElectionGuard private keys at rest.
+    pub(crate) fn key_encryption_keys(&self) -> color_eyre::Result<KeyEncryptionKeys> {
+        Ok(KeyEncryptionKeys::new(
+            unseal_key_encryption_key(&self.eg_key_encryption_key)?,
+            self.eg_retired_key_encryption_keys
+                .iter()
+                .map(unseal_key_encryption_key)
+                .collect::<color_eyre::Result<_>>()?,
+        ))
+    }
+
+    pub(crate) fn session_policy(&self) -> SessionPolicy {
+        SessionPolicy {
+            inactivity_timeout: (self.inactivity_timeout_seconds > 0)
+                .then(|| Duration::from_secs(self.inactivity_timeout_seconds)),
+            max_incorrect_pin_attempts: self.max_incorrect_pin_attempts,
+            pin_lockout_duration: Duration::from_secs(self.pin_lockout_seconds),
+        }
+    }
+
+    /// How long a registration request may stay pending, if requests expire.
+    pub(crate) fn registration_request_max_age(&self) -> Option<time::Duration> {
+        (self.registration_request_expiry_days > 0)
+            .then(|| time::Duration::days(i64::from(self.registration_request_expiry_days)))
+    }
+
     pub(crate) fn signer(&self) -> color_eyre::Result<AnySigner> {
         AnySigner::try_from(&self.signer)
     }
 
+    pub(crate) fn async_signer(&self) -> color_eyre::Result<AnyAsyncSigner> {
+        AnyAsyncSigner::try_from(&self.signer)
+    }
+
     pub(crate) fn sign(&self, payload: &[u8]) -> color_eyre::Result<(Vec<u8>, X509)> {
         let signer = self.signer()?;
         let signature = signer.sign(payload)?;
@@ -136,6 +226,11 @@ impl Config {
     }
 }
 
+fn unseal_key_encryption_key(sealed_secret: &SealedSecret) -> color_eyre::Result<KeyEncryptionKey> {
+    KeyEncryptionKey::from_unsealed(&sealed_secret.unseal()?)
+        .with_context(|| format!("invalid key-encryption key in {sealed_secret:?}"))
+}
+
 fn load_cert<P>(path: P) -> color_eyre::Result<openssl::x509::X509>
 where
     P: AsRef<Path>,
@@ -144,3 +239,34 @@ where
     Ok(openssl::x509::X509::from_pem(&ca_cert)
         .or_else(|_| openssl::x509::X509::from_der(&ca_cert))?)
 }
+
+/// Loads every certificate in the directory. Files may hold any number of PEM
+/// certificates or a single DER one.
+fn load_certs_in_dir<P>(dir: P) -> color_eyre::Result<Vec<openssl::x509::X509>>
+where
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    let mut certs = Vec::new();
+
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read certificate directory {dir:?}"))?
+    {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let contents = std::fs::read(&path)
+            .with_context(|| format!("failed to read certificate at {path:?}"))?;
+        match openssl::x509::X509::stack_from_pem(&contents) {
+            Ok(pem_certs) if !pem_certs.is_empty() => certs.extend(pem_certs),
+            _ => certs.push(
+                openssl::x509::X509::from_der(&contents)
+                    .with_context(|| format!("failed to parse certificate at {path:?}"))?,
+            ),
+        }
+    }
+
+    Ok(certs)
+}
@@ -7,17 +7,22 @@
 //! To enable `cargo sqlx prepare --workspace`, install it via `cargo install
 //! --locked sqlx-cli`.
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use base64_serde::base64_serde_type;
-use color_eyre::eyre::bail;
+use color_eyre::eyre::{bail, eyre};
+use electionguard_rs::key_wrapping::{KeyEncryptionKeys, WrappedKey};
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Connection, PgPool};
 use tracing::Level;
-use types_rs::cacvote;
+use types_rs::{auth::User, cacvote, election::PrecinctId};
 use uuid::Uuid;
 
+use crate::audit::{AuditLogEntry, Outcome};
+use crate::auth::Operation;
+use crate::cac::CastBallotVerifier;
 use crate::config::Config;
+use crate::jobs::JobRecord;
 
 base64_serde_type!(Base64Standard, base64::engine::general_purpose::STANDARD);
 
@@ -32,6 +37,34 @@ pub(crate) async fn setup(config: &Config) -> color_eyre::Result<PgPool> {
         .await?;
     tracing::debug!("Running database migrations");
     sqlx::migrate!("db/migrations").run(&pool).await?;
+
+    let mut connection = pool.acquire().await?;
+    let key_encryption_keys = config.key_encryption_keys()?;
+    let rewrapped = rewrap_private_keys(&mut connection, &key_encryption_keys).await?;
+    if rewrapped > 0 {
+        tracing::info!(
+            "Wrapped {rewrapped} ElectionGuard private keys with the current key-encryption key"
+        );
+    }
+
+    for key_encryption_key_id in
+        get_key_encryption_key_ids_with_deleted_keys(&mut connection, &key_encryption_keys).await?
+    {
+        if key_encryption_key_id == key_encryption_keys.current().id() {
+            tracing::warn!(
+                "The current key-encryption key {key_encryption_key_id} wrapped private keys \
+                 that have been deleted, which can be recovered from database remnants until \
+                 it is rotated out and destroyed; no new election can be created until then"
+            );
+        } else {
+            tracing::warn!(
+                "The retired key-encryption key {key_encryption_key_id} wrapped private keys \
+                 that have been deleted, which can be recovered from database remnants until \
+                 it is destroyed; evict it once no private key is left wrapped with it"
+            );
+        }
+    }
+
     Ok(pool)
 }
 
@@ -46,6 +79,7 @@ pub(crate) async fn get_elections(
             election_id,
             payload,
             certificate,
+            certificate_chain,
             signature
         FROM objects
         WHERE object_type = 'Election'
@@ -78,20 +112,95 @@ pub(crate) async fn get_elections(
         let shuffled_encrypted_cast_ballots =
             get_shuffled_encrypted_cast_ballots(&mut *connection, &object.id).await?;
 
+        let shuffled_encrypted_cast_ballots_verification =
+            get_latest_shuffled_encrypted_cast_ballots_verification(&mut *connection, object.id)
+                .await?;
+
+        let decrypted_shuffled_ballots =
+            get_decrypted_shuffled_ballots(&mut *connection, object.id).await?;
+
+        let lifecycle_state = get_election_lifecycle_state(&mut *connection, object.id).await?;
+
         if let cacvote::Payload::Election(election) = payload {
-            elections.push(cacvote::ElectionPresenter::new(
-                object.id,
-                election,
-                encrypted_tally,
-                decrypted_tally,
-                shuffled_encrypted_cast_ballots,
-            ));
+            elections.push(
+                cacvote::ElectionPresenter::new(
+                    object.id,
+                    election,
+                    encrypted_tally,
+                    decrypted_tally,
+                    shuffled_encrypted_cast_ballots,
+                )
+                .with_shuffled_encrypted_cast_ballots_verification(
+                    shuffled_encrypted_cast_ballots_verification,
+                )
+                .with_decrypted_shuffled_ballots(decrypted_shuffled_ballots)
+                .with_lifecycle_state(lifecycle_state),
+            );
         }
     }
 
     Ok(elections)
 }
 
+/// Replays the lifecycle transitions recorded for an election, in the order
+/// they were created, to find its current state. Elections that existed before
+/// transitions were recorded start out open.
+#[tracing::instrument(skip(connection))]
+pub(crate) async fn get_election_lifecycle_state(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+) -> color_eyre::Result<cacvote::ElectionLifecycleState> {
+    let is_legacy_open_election = sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM legacy_open_elections WHERE election_id = $1
+        ) AS "exists!"
+        "#,
+        election_id,
+    )
+    .fetch_one(&mut *connection)
+    .await?
+    .exists;
+    let initial_state = if is_legacy_open_election {
+        cacvote::ElectionLifecycleState::Open
+    } else {
+        cacvote::ElectionLifecycleState::Created
+    };
+
+    let objects = sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+        SELECT
+            id,
+            election_id,
+            payload,
+            certificate,
+            certificate_chain,
+            signature
+        FROM objects
+        WHERE object_type = $1
+          AND election_id = $2
+        ORDER BY created_at ASC, id ASC
+        "#,
+        cacvote::Payload::election_lifecycle_transition_object_type(),
+        election_id,
+    )
+    .fetch_all(connection)
+    .await?;
+
+    let actions = objects
+        .iter()
+        .map(|object| match object.try_to_inner()? {
+            cacvote::Payload::ElectionLifecycleTransition(transition) => Ok(transition.action),
+            payload => bail!("unexpected payload for lifecycle transition: {payload:?}"),
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    initial_state
+        .replay_from(actions)
+        .map_err(|e| eyre!("invalid lifecycle for election {election_id}: {e}"))
+}
+
 #[tracing::instrument(skip(connection))]
 pub async fn get_pending_registration_requests(
     connection: &mut sqlx::PgConnection,
@@ -103,6 +212,7 @@ pub async fn get_pending_registration_requests(
             rr.election_id,
             rr.payload,
             rr.certificate,
+            rr.certificate_chain,
             rr.signature,
             rr.created_at
         FROM
@@ -116,11 +226,20 @@ pub async fn get_pending_registration_requests(
                 WHERE r.object_type = $2
                   AND rr.id = (convert_from(r.payload, 'UTF8')::jsonb ->> $3)::uuid
             )
+          AND
+            NOT EXISTS (
+                SELECT 1
+                FROM objects AS rj
+                WHERE rj.object_type = $4
+                  AND rr.id = (convert_from(rj.payload, 'UTF8')::jsonb ->> $5)::uuid
+            )
         ORDER BY rr.created_at DESC
         "#,
         cacvote::Payload::registration_request_object_type(),
         cacvote::Payload::registration_object_type(),
         cacvote::Registration::registration_request_object_id_field_name(),
+        cacvote::Payload::registration_rejection_object_type(),
+        cacvote::RegistrationRejection::registration_request_object_id_field_name(),
     )
     .fetch_all(connection)
     .await?;
@@ -133,6 +252,7 @@ pub async fn get_pending_registration_requests(
             election_id: record.election_id,
             payload: record.payload,
             certificate: record.certificate,
+            certificate_chain: record.certificate_chain,
             signature: record.signature,
         };
 
@@ -164,16 +284,19 @@ pub async fn get_registrations(
             r.id AS registration_id,
             r.payload AS registration_payload,
             r.certificate AS registration_certificate,
+            r.certificate_chain AS registration_certificate_chain,
             r.signature AS registration_signature,
             e.id AS election_id,
             e.election_id AS election_election_id,
             e.payload AS election_payload,
             e.certificate AS election_certificate,
+            e.certificate_chain AS election_certificate_chain,
             e.signature AS election_signature,
             rr.id AS registration_request_id,
             rr.election_id AS registration_request_election_id,
             rr.payload AS registration_request_payload,
             rr.certificate AS registration_request_certificate,
+            rr.certificate_chain AS registration_request_certificate_chain,
             rr.signature AS registration_request_signature,
             r.created_at AS created_at,
             r.server_synced_at IS NOT NULL AS "is_synced!: bool"
@@ -202,6 +325,7 @@ pub async fn get_registrations(
             election_id: Some(record.election_id),
             payload: record.registration_payload,
             certificate: record.registration_certificate,
+            certificate_chain: record.registration_certificate_chain,
             signature: record.registration_signature,
         };
         let election_object = cacvote::SignedObject {
@@ -209,6 +333,7 @@ pub async fn get_registrations(
             election_id: record.election_election_id,
             payload: record.election_payload,
             certificate: record.election_certificate,
+            certificate_chain: record.election_certificate_chain,
             signature: record.election_signature,
         };
         let registration_request_object = cacvote::SignedObject {
@@ -216,6 +341,7 @@ pub async fn get_registrations(
             election_id: record.registration_request_election_id,
             payload: record.registration_request_payload,
             certificate: record.registration_request_certificate,
+            certificate_chain: record.registration_request_certificate_chain,
             signature: record.registration_request_signature,
         };
 
@@ -246,6 +372,54 @@ pub async fn get_registrations(
     Ok(registrations)
 }
 
+/// Determines whether the registration request already has a registration
+/// for the election.
+pub(crate) async fn has_registration_for_election(
+    connection: &mut sqlx::PgConnection,
+    registration_request_id: Uuid,
+    election_id: Uuid,
+) -> color_eyre::Result<bool> {
+    Ok(sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM objects
+            WHERE object_type = $1
+              AND election_id = $2
+              AND (convert_from(payload, 'UTF8')::jsonb ->> $3)::uuid = $4
+        ) AS "exists!"
+        "#,
+        cacvote::Payload::registration_object_type(),
+        election_id,
+        cacvote::Registration::registration_request_object_id_field_name(),
+        registration_request_id,
+    )
+    .fetch_one(connection)
+    .await?)
+}
+
+/// Determines whether the registration request has been rejected.
+pub(crate) async fn is_registration_request_rejected(
+    connection: &mut sqlx::PgConnection,
+    registration_request_id: Uuid,
+) -> color_eyre::Result<bool> {
+    Ok(sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM objects
+            WHERE object_type = $1
+              AND (convert_from(payload, 'UTF8')::jsonb ->> $2)::uuid = $3
+        ) AS "exists!"
+        "#,
+        cacvote::Payload::registration_rejection_object_type(),
+        cacvote::RegistrationRejection::registration_request_object_id_field_name(),
+        registration_request_id,
+    )
+    .fetch_one(connection)
+    .await?)
+}
+
 #[tracing::instrument(skip(connection))]
 pub async fn get_registration_request(
     connection: &mut sqlx::PgConnection,
@@ -273,6 +447,7 @@ pub async fn get_object(
             election_id,
             payload,
             certificate,
+            certificate_chain,
             signature
         FROM objects
         WHERE id = $1
@@ -407,8 +582,8 @@ pub async fn add_object_from_server(
 
     sqlx::query!(
         r#"
-        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificate, signature, server_synced_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, now())
+        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificate, certificate_chain, signature, server_synced_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, now())
         "#,
         &object.id,
         object.election_id,
@@ -416,6 +591,7 @@ pub async fn add_object_from_server(
         object_type,
         &object.payload,
         &object.certificate,
+        object.certificate_chain.as_deref(),
         &object.signature
     )
     .execute(connection)
@@ -441,8 +617,8 @@ pub async fn add_object(
 
     sqlx::query!(
         r#"
-        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificate, signature)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificate, certificate_chain, signature)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
         "#,
         &object.id,
         object.election_id,
@@ -450,6 +626,7 @@ pub async fn add_object(
         object_type,
         &object.payload,
         &object.certificate,
+        object.certificate_chain.as_deref(),
         &object.signature
     )
     .execute(connection)
@@ -460,14 +637,17 @@ pub async fn add_object(
     Ok(object.id)
 }
 
+/// Adds journal entries from the CACvote Server, ignoring any that are already
+/// present. Returns the number of entries that were newly added.
 #[tracing::instrument(skip(connection, entries))]
 pub(crate) async fn add_journal_entries(
     connection: &mut sqlx::PgConnection,
     entries: Vec<cacvote::JournalEntry>,
-) -> color_eyre::eyre::Result<()> {
+) -> color_eyre::eyre::Result<u64> {
     let mut txn = connection.begin().await?;
+    let mut added = 0;
     for entry in entries {
-        sqlx::query!(
+        added += sqlx::query!(
             r#"
             INSERT INTO journal_entries (id, object_id, jurisdiction, object_type, action, created_at)
             VALUES ($1, $2, $3, $4, $5, $6)
@@ -481,10 +661,11 @@ pub(crate) async fn add_journal_entries(
             entry.created_at
         )
         .execute(&mut *txn)
-        .await?;
+        .await?
+        .rows_affected();
     }
     txn.commit().await?;
-    Ok(())
+    Ok(added)
 }
 
 pub(crate) async fn get_latest_journal_entry(
@@ -521,15 +702,36 @@ pub(crate) async fn get_unsynced_objects(
             election_id,
             payload,
             certificate,
+            certificate_chain,
             signature
         FROM objects
         WHERE server_synced_at IS NULL
+        ORDER BY created_at
         "#,
     )
     .fetch_all(&mut *executor)
     .await?)
 }
 
+/// Returns `None` if there is no object with the given ID, otherwise whether
+/// the object has been synced with the CACvote Server.
+pub(crate) async fn get_object_sync_status(
+    executor: &mut sqlx::PgConnection,
+    id: uuid::Uuid,
+) -> color_eyre::eyre::Result<Option<bool>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT server_synced_at IS NOT NULL AS "is_synced!"
+        FROM objects
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *executor)
+    .await?
+    .map(|record| record.is_synced))
+}
+
 pub(crate) async fn mark_object_synced(
     executor: &mut sqlx::PgConnection,
     id: uuid::Uuid,
@@ -576,6 +778,7 @@ pub(crate) async fn get_journal_entries_for_objects_to_pull(
 
 pub(crate) async fn get_cast_ballots(
     executor: &mut sqlx::PgConnection,
+    verifier: &CastBallotVerifier,
 ) -> color_eyre::Result<Vec<cacvote::CastBallotPresenter>> {
     let records = sqlx::query!(
         r#"
@@ -584,16 +787,19 @@ pub(crate) async fn get_cast_ballots(
             cb.election_id as cast_ballot_election_id,
             cb.payload AS cast_ballot_payload,
             cb.certificate AS cast_ballot_certificate,
+            cb.certificate_chain AS cast_ballot_certificate_chain,
             cb.signature AS cast_ballot_signature,
             rr.id AS registration_request_id,
             rr.election_id AS registration_request_election_id,
             rr.payload AS registration_request_payload,
             rr.certificate AS registration_request_certificate,
+            rr.certificate_chain AS registration_request_certificate_chain,
             rr.signature AS registration_request_signature,
             r.id AS registration_id,
             r.election_id AS registration_election_id,
             r.payload AS registration_payload,
             r.certificate AS registration_certificate,
+            r.certificate_chain AS registration_certificate_chain,
             r.signature AS registration_signature,
             cb.created_at AS created_at
         FROM objects AS cb
@@ -625,6 +831,7 @@ pub(crate) async fn get_cast_ballots(
             election_id: record.cast_ballot_election_id,
             payload: record.cast_ballot_payload,
             certificate: record.cast_ballot_certificate,
+            certificate_chain: record.cast_ballot_certificate_chain,
             signature: record.cast_ballot_signature,
         };
         let registration_object = cacvote::SignedObject {
@@ -632,6 +839,7 @@ pub(crate) async fn get_cast_ballots(
             election_id: record.registration_election_id,
             payload: record.registration_payload,
             certificate: record.registration_certificate,
+            certificate_chain: record.registration_certificate_chain,
             signature: record.registration_signature,
         };
         let registration_request_object = cacvote::SignedObject {
@@ -639,6 +847,7 @@ pub(crate) async fn get_cast_ballots(
             election_id: record.registration_request_election_id,
             payload: record.registration_request_payload,
             certificate: record.registration_request_certificate,
+            certificate_chain: record.registration_request_certificate_chain,
             signature: record.registration_request_signature,
         };
 
@@ -649,13 +858,7 @@ pub(crate) async fn get_cast_ballots(
                 if let cacvote::Payload::Registration(registration) =
                     registration_object.try_to_inner()?
                 {
-                    // TODO: remove this or replace with actual verification status
-                    // we already verify the signature as part of adding the object to the DB,
-                    // so we can assume that the verification status is success
-                    let verification_status = cacvote::VerificationStatus::Success {
-                        common_access_card_id: cast_ballot.common_access_card_id.clone(),
-                        display_name: "test".to_owned(),
-                    };
+                    let verification_status = verifier.verify(&cast_ballot_object, &cast_ballot);
                     let created_at = record.created_at;
                     cast_ballots.push(cacvote::CastBallotPresenter::new(
                         cast_ballot,
@@ -673,10 +876,13 @@ pub(crate) async fn get_cast_ballots(
     Ok(cast_ballots)
 }
 
+/// Gets an election's cast ballots, with their object IDs, ordered by object
+/// ID, which every machine agrees on. Mixing and verifying the mix must see the
+/// ballots in the same order.
 pub(crate) async fn get_cast_ballots_for_election(
     executor: &mut sqlx::PgConnection,
     election_object_id: &Uuid,
-) -> color_eyre::Result<Vec<cacvote::CastBallot>> {
+) -> color_eyre::Result<Vec<(Uuid, cacvote::CastBallot)>> {
     let records = sqlx::query!(
         r#"
         SELECT
@@ -684,10 +890,12 @@ pub(crate) async fn get_cast_ballots_for_election(
             cb.election_id as cast_ballot_election_id,
             cb.payload AS cast_ballot_payload,
             cb.certificate AS cast_ballot_certificate,
+            cb.certificate_chain AS cast_ballot_certificate_chain,
             cb.signature AS cast_ballot_signature
         FROM objects AS cb
         WHERE cb.object_type = $1
           AND (convert_from(cb.payload, 'UTF8')::jsonb ->> $2)::uuid = $3
+        ORDER BY cb.id ASC
         "#,
         cacvote::Payload::cast_ballot_object_type(),
         cacvote::CastBallot::election_object_id_field_name(),
@@ -704,17 +912,73 @@ pub(crate) async fn get_cast_ballots_for_election(
             election_id: record.cast_ballot_election_id,
             payload: record.cast_ballot_payload,
             certificate: record.cast_ballot_certificate,
+            certificate_chain: record.cast_ballot_certificate_chain,
             signature: record.cast_ballot_signature,
         };
 
         if let cacvote::Payload::CastBallot(cast_ballot) = cast_ballot.try_to_inner()? {
-            cast_ballots.push(cast_ballot);
+            cast_ballots.push((record.cast_ballot_id, cast_ballot));
         }
     }
 
     Ok(cast_ballots)
 }
 
+/// Gets the given cast ballots of an election, in the given order. Fails if
+/// any of them is missing or was cast in another election.
+pub(crate) async fn get_cast_ballots_by_id(
+    executor: &mut sqlx::PgConnection,
+    election_object_id: &Uuid,
+    cast_ballot_ids: &[Uuid],
+) -> color_eyre::Result<Vec<cacvote::CastBallot>> {
+    let records = sqlx::query!(
+        r#"
+        SELECT
+            cb.id AS cast_ballot_id,
+            cb.election_id as cast_ballot_election_id,
+            cb.payload AS cast_ballot_payload,
+            cb.certificate AS cast_ballot_certificate,
+            cb.certificate_chain AS cast_ballot_certificate_chain,
+            cb.signature AS cast_ballot_signature
+        FROM objects AS cb
+        WHERE cb.object_type = $1
+          AND cb.id = ANY($2)
+        "#,
+        cacvote::Payload::cast_ballot_object_type(),
+        cast_ballot_ids,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    let mut cast_ballots_by_id = HashMap::new();
+
+    for record in records {
+        let cast_ballot = cacvote::SignedObject {
+            id: record.cast_ballot_id,
+            election_id: record.cast_ballot_election_id,
+            payload: record.cast_ballot_payload,
+            certificate: record.cast_ballot_certificate,
+            certificate_chain: record.cast_ballot_certificate_chain,
+            signature: record.cast_ballot_signature,
+        };
+
+        if let cacvote::Payload::CastBallot(cast_ballot) = cast_ballot.try_to_inner()? {
+            if cast_ballot.election_object_id == *election_object_id {
+                cast_ballots_by_id.insert(record.cast_ballot_id, cast_ballot);
+            }
+        }
+    }
+
+    cast_ballot_ids
+        .iter()
+        .map(|id| {
+            cast_ballots_by_id
+                .remove(id)
+                .ok_or_else(|| eyre!("cast ballot {id} not found in election"))
+        })
+        .collect()
+}
+
 pub(crate) async fn get_shuffled_encrypted_cast_ballots(
     executor: &mut sqlx::PgConnection,
     election_object_id: &Uuid,
@@ -726,6 +990,7 @@ pub(crate) async fn get_shuffled_encrypted_cast_ballots(
             b.election_id AS shuffled_encrypted_cast_ballots_election_id,
             b.payload AS shuffled_encrypted_cast_ballots_payload,
             b.certificate AS shuffled_encrypted_cast_ballots_certificate,
+            b.certificate_chain AS shuffled_encrypted_cast_ballots_certificate_chain,
             b.signature AS shuffled_encrypted_cast_ballots_signature,
             b.created_at AS shuffled_encrypted_cast_ballots_created_at,
             b.server_synced_at AS shuffled_encrypted_cast_ballots_server_synced_at
@@ -748,6 +1013,7 @@ pub(crate) async fn get_shuffled_encrypted_cast_ballots(
         election_id: record.shuffled_encrypted_cast_ballots_election_id,
         payload: record.shuffled_encrypted_cast_ballots_payload,
         certificate: record.shuffled_encrypted_cast_ballots_certificate,
+        certificate_chain: record.shuffled_encrypted_cast_ballots_certificate_chain,
         signature: record.shuffled_encrypted_cast_ballots_signature,
     };
 
@@ -766,19 +1032,171 @@ pub(crate) async fn get_shuffled_encrypted_cast_ballots(
     Ok(Some(shuffled_encrypted_cast_ballots))
 }
 
+/// Gets the object holding an election's shuffled encrypted cast ballots, if
+/// they have been mixed.
+pub(crate) async fn get_shuffled_encrypted_cast_ballots_object(
+    executor: &mut sqlx::PgConnection,
+    election_object_id: Uuid,
+) -> color_eyre::Result<Option<(Uuid, cacvote::ShuffledEncryptedCastBallots)>> {
+    let Some(object) = sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+        SELECT
+            id,
+            election_id,
+            payload,
+            certificate,
+            certificate_chain,
+            signature
+        FROM objects
+        WHERE election_id = $1
+          AND object_type = $2
+        "#,
+        election_object_id,
+        cacvote::Payload::shuffled_encrypted_cast_ballots_object_type(),
+    )
+    .fetch_optional(executor)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let cacvote::Payload::ShuffledEncryptedCastBallots(shuffled_encrypted_cast_ballots) =
+        object.try_to_inner()?
+    else {
+        bail!("Object is not a shuffled encrypted cast ballots")
+    };
+
+    Ok(Some((object.id, shuffled_encrypted_cast_ballots)))
+}
+
+/// Gets the most recent check of the proofs of shuffle of an election's
+/// shuffled encrypted cast ballots, whichever machine made it.
+pub(crate) async fn get_latest_shuffled_encrypted_cast_ballots_verification(
+    executor: &mut sqlx::PgConnection,
+    election_object_id: Uuid,
+) -> color_eyre::Result<Option<cacvote::ShuffledEncryptedCastBallotsVerificationPresenter>> {
+    let Some(record) = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            election_id,
+            payload,
+            certificate,
+            certificate_chain,
+            signature,
+            created_at,
+            server_synced_at
+        FROM objects
+        WHERE election_id = $1
+          AND object_type = $2
+        ORDER BY created_at DESC, id DESC
+        LIMIT 1
+        "#,
+        election_object_id,
+        cacvote::Payload::shuffled_encrypted_cast_ballots_verification_object_type(),
+    )
+    .fetch_optional(executor)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let object = cacvote::SignedObject {
+        id: record.id,
+        election_id: record.election_id,
+        payload: record.payload,
+        certificate: record.certificate,
+        certificate_chain: record.certificate_chain,
+        signature: record.signature,
+    };
+
+    let cacvote::Payload::ShuffledEncryptedCastBallotsVerification(
+        shuffled_encrypted_cast_ballots_verification,
+    ) = object.try_to_inner()?
+    else {
+        bail!("Object is not a shuffled encrypted cast ballots verification")
+    };
+
+    Ok(Some(
+        cacvote::ShuffledEncryptedCastBallotsVerificationPresenter {
+            shuffled_encrypted_cast_ballots_verification,
+            created_at: record.created_at,
+            synced_at: record.server_synced_at,
+        },
+    ))
+}
+
+/// Gets an election's decrypted mixed ballots, if they have been decrypted. If
+/// more than one machine decrypted them, the first to do so is used.
+pub(crate) async fn get_decrypted_shuffled_ballots(
+    executor: &mut sqlx::PgConnection,
+    election_object_id: Uuid,
+) -> color_eyre::Result<Option<cacvote::DecryptedShuffledBallotsPresenter>> {
+    let Some(record) = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            election_id,
+            payload,
+            certificate,
+            certificate_chain,
+            signature,
+            created_at,
+            server_synced_at
+        FROM objects
+        WHERE election_id = $1
+          AND object_type = $2
+        ORDER BY created_at ASC, id ASC
+        LIMIT 1
+        "#,
+        election_object_id,
+        cacvote::Payload::decrypted_shuffled_ballots_object_type(),
+    )
+    .fetch_optional(executor)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let object = cacvote::SignedObject {
+        id: record.id,
+        election_id: record.election_id,
+        payload: record.payload,
+        certificate: record.certificate,
+        certificate_chain: record.certificate_chain,
+        signature: record.signature,
+    };
+
+    let cacvote::Payload::DecryptedShuffledBallots(decrypted_shuffled_ballots) =
+        object.try_to_inner()?
+    else {
+        bail!("Object is not a decrypted shuffled ballots")
+    };
+
+    Ok(Some(cacvote::DecryptedShuffledBallotsPresenter {
+        decrypted_shuffled_ballots,
+        created_at: record.created_at,
+        synced_at: record.server_synced_at,
+    }))
+}
+
+/// Stores an election's private key, which must already be wrapped with a
+/// key-encryption key using the election's object ID as the context.
 pub(crate) async fn add_eg_private_key(
     executor: &mut sqlx::PgConnection,
     election_object_id: &Uuid,
-    private_metadata_blob: &[u8],
+    wrapped_private_key: &WrappedKey,
 ) -> color_eyre::Result<Uuid> {
     let record = sqlx::query!(
         r#"
-        INSERT INTO eg_private_keys (election_object_id, private_key)
-        VALUES ($1, $2)
+        INSERT INTO eg_private_keys (election_object_id, wrapped_private_key, key_encryption_key_id)
+        VALUES ($1, $2, $3)
         RETURNING id
         "#,
         election_object_id,
-        private_metadata_blob
+        wrapped_private_key.wrapped_key,
+        wrapped_private_key.key_encryption_key_id
     )
     .fetch_one(executor)
     .await?;
@@ -786,133 +1204,1708 @@ pub(crate) async fn add_eg_private_key(
     Ok(record.id)
 }
 
+/// Gets an election's wrapped private key. See [`add_eg_private_key`].
 pub(crate) async fn get_eg_private_key(
     executor: &mut sqlx::PgConnection,
     election_object_id: &Uuid,
-) -> color_eyre::Result<Vec<u8>> {
+) -> color_eyre::Result<WrappedKey> {
     let record = sqlx::query!(
         r#"
-        SELECT private_key
+        SELECT
+            wrapped_private_key AS "wrapped_private_key!",
+            key_encryption_key_id AS "key_encryption_key_id!"
         FROM eg_private_keys
         WHERE election_object_id = $1
+          AND wrapped_private_key IS NOT NULL
         "#,
         election_object_id
     )
     .fetch_one(executor)
     .await?;
 
-    Ok(record.private_key)
+    Ok(WrappedKey {
+        key_encryption_key_id: record.key_encryption_key_id,
+        wrapped_key: record.wrapped_private_key,
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use openssl::{
-        pkey::{PKey, Private, Public},
-        x509::X509,
-    };
-    use types_rs::{cacvote::JurisdictionCode, election::ElectionDefinition};
+/// Determines whether this JX holds a wrapped private key for the election,
+/// which only the JX that created the election does.
+pub(crate) async fn has_eg_private_key(
+    executor: &mut sqlx::PgConnection,
+    election_object_id: &Uuid,
+) -> color_eyre::Result<bool> {
+    let record = sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1
+            FROM eg_private_keys
+            WHERE election_object_id = $1
+              AND wrapped_private_key IS NOT NULL
+        ) AS "exists!"
+        "#,
+        election_object_id
+    )
+    .fetch_one(executor)
+    .await?;
 
-    use super::*;
+    Ok(record.exists)
+}
 
-    fn load_keypair() -> color_eyre::Result<(X509, PKey<Public>, PKey<Private>)> {
-        // uses the dev VxAdmin keypair because it has the Jurisdiction field
-        let private_key_pem =
-            include_bytes!("../../../../libs/auth/certs/dev/vx-admin-private-key.pem");
-        let private_key = PKey::private_key_from_pem(private_key_pem)?;
-        let certificate =
-            include_bytes!("../../../../libs/auth/certs/dev/vx-admin-cert-authority-cert.pem")
-                .to_vec();
-        let x509 = X509::from_pem(&certificate)?;
-        let public_key = x509.public_key()?;
-        Ok((x509, public_key, private_key))
-    }
+/// Wraps any private keys stored in plaintext before keys were wrapped, and
+/// rewraps any wrapped with a retired key-encryption key, so that every key is
+/// wrapped with the current one. Returns the number of keys (re)wrapped.
+///
+/// Keys wrapped with a key-encryption key that is not configured are left
+/// as-is and logged, since they cannot be unwrapped.
+pub(crate) async fn rewrap_private_keys(
+    connection: &mut sqlx::PgConnection,
+    key_encryption_keys: &KeyEncryptionKeys,
+) -> color_eyre::Result<usize> {
+    let current_key_encryption_key_id = key_encryption_keys.current().id();
+    let mut count = 0;
+    let mut txn = connection.begin().await?;
 
-    fn load_election_definition() -> color_eyre::Result<ElectionDefinition> {
-        Ok(ElectionDefinition::try_from(
-            &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
-        )?)
-    }
+    let records = sqlx::query!(
+        r#"
+        SELECT id, election_object_id, private_key, wrapped_private_key, key_encryption_key_id
+        FROM eg_private_keys
+        WHERE key_encryption_key_id IS DISTINCT FROM $1
+        "#,
+        current_key_encryption_key_id
+    )
+    .fetch_all(&mut *txn)
+    .await?;
 
-    #[sqlx::test(migrations = "db/migrations")]
-    async fn test_pending_registration_requests(pool: sqlx::PgPool) -> color_eyre::Result<()> {
-        let (certificate, _, private_key) = load_keypair()?;
-        let election_definition = load_election_definition()?;
-        let connection = &mut pool.acquire().await?;
-        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+    for record in records {
+        let Some(wrapped_private_key) = wrap_or_rewrap_private_key(
+            key_encryption_keys,
+            record.election_object_id,
+            record.private_key,
+            record.wrapped_private_key,
+            record.key_encryption_key_id,
+        )?
+        else {
+            continue;
+        };
 
-        let election_payload = cacvote::Payload::Election(cacvote::Election {
-            jurisdiction_code: jurisdiction_code.clone(),
+        sqlx::query!(
+            r#"
+            UPDATE eg_private_keys
+            SET private_key = NULL, wrapped_private_key = $2, key_encryption_key_id = $3
+            WHERE id = $1
+            "#,
+            record.id,
+            wrapped_private_key.wrapped_key,
+            wrapped_private_key.key_encryption_key_id
+        )
+        .execute(&mut *txn)
+        .await?;
+        count += 1;
+    }
+
+    txn.commit().await?;
+
+    Ok(count)
+}
+
+fn wrap_or_rewrap_private_key(
+    key_encryption_keys: &KeyEncryptionKeys,
+    object_id: Uuid,
+    private_key: Option<Vec<u8>>,
+    wrapped_private_key: Option<Vec<u8>>,
+    key_encryption_key_id: Option<String>,
+) -> color_eyre::Result<Option<WrappedKey>> {
+    let wrapped_private_key = match (private_key, wrapped_private_key, key_encryption_key_id) {
+        (Some(private_key), _, _) => {
+            return Ok(Some(
+                key_encryption_keys.wrap_key(&private_key, object_id.as_bytes())?,
+            ))
+        }
+        (None, Some(wrapped_key), Some(key_encryption_key_id)) => WrappedKey {
+            key_encryption_key_id,
+            wrapped_key,
+        },
+        _ => bail!("private key for object {object_id} is neither plaintext nor wrapped"),
+    };
+
+    match key_encryption_keys.rewrap_key(&wrapped_private_key, object_id.as_bytes()) {
+        Ok(rewrapped) => Ok(rewrapped),
+        Err(e) => {
+            tracing::error!("unable to rewrap private key for object {object_id}: {e}");
+            Ok(None)
+        }
+    }
+}
+
+/// Deletes the private keys this machine holds for elections whose results
+/// have been certified, returning the IDs of the elections whose keys were
+/// deleted.
+///
+/// A `DELETE` does not erase the wrapped key: copies remain in dead rows until
+/// vacuumed, in the WAL, and in any backups. All that is guaranteed is that
+/// those copies are unreadable once every key-encryption key that wrapped
+/// them is destroyed, so the ID of the wrapping key-encryption key is recorded
+/// for [`get_key_encryption_key_ids_with_deleted_keys`], which the JX uses to
+/// require that it be rotated out and destroyed.
+pub(crate) async fn delete_private_keys_for_certified_elections(
+    connection: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Vec<Uuid>> {
+    let elections_with_transitions = sqlx::query!(
+        r#"
+        SELECT DISTINCT election_id AS "election_id!"
+        FROM objects
+        WHERE object_type = $1
+          AND election_id IS NOT NULL
+        "#,
+        cacvote::Payload::election_lifecycle_transition_object_type(),
+    )
+    .fetch_all(&mut *connection)
+    .await?;
+
+    let mut deleted_election_ids = Vec::new();
+
+    for record in elections_with_transitions {
+        let election_id = record.election_id;
+        if get_election_lifecycle_state(connection, election_id).await?
+            != cacvote::ElectionLifecycleState::Certified
+        {
+            continue;
+        }
+
+        let deleted_keys = sqlx::query!(
+            r#"
+            DELETE FROM eg_private_keys
+            WHERE election_object_id = $1
+            RETURNING key_encryption_key_id
+            "#,
+            election_id
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        for deleted_key in &deleted_keys {
+            sqlx::query!(
+                r#"
+                INSERT INTO deleted_eg_private_keys (election_object_id, key_encryption_key_id)
+                VALUES ($1, $2)
+                ON CONFLICT (election_object_id) DO NOTHING
+                "#,
+                election_id,
+                deleted_key.key_encryption_key_id
+            )
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        if !deleted_keys.is_empty() {
+            deleted_election_ids.push(election_id);
+        }
+    }
+
+    Ok(deleted_election_ids)
+}
+
+/// Returns which of the given key-encryption keys wrapped a private key that
+/// has since been deleted. Until such a key-encryption key is destroyed, the
+/// deleted key can be recovered from any copy left behind by the deletion.
+pub(crate) async fn get_key_encryption_key_ids_with_deleted_keys(
+    connection: &mut sqlx::PgConnection,
+    key_encryption_keys: &KeyEncryptionKeys,
+) -> color_eyre::Result<Vec<String>> {
+    let key_encryption_key_ids = key_encryption_keys
+        .iter()
+        .map(|key_encryption_key| key_encryption_key.id().to_owned())
+        .collect::<Vec<_>>();
+
+    let records = sqlx::query!(
+        r#"
+        SELECT DISTINCT key_encryption_key_id AS "key_encryption_key_id!"
+        FROM deleted_eg_private_keys
+        WHERE key_encryption_key_id = ANY($1)
+        ORDER BY key_encryption_key_id
+        "#,
+        &key_encryption_key_ids
+    )
+    .fetch_all(connection)
+    .await?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| record.key_encryption_key_id)
+        .collect())
+}
+
+pub(crate) async fn add_audit_log_entry(
+    executor: &mut sqlx::PgConnection,
+    operation: Operation,
+    user: &User,
+    object_id: Option<Uuid>,
+    outcome: Outcome,
+    message: &str,
+) -> color_eyre::Result<Uuid> {
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO audit_log_entries (operation, operator, object_id, outcome, message)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+        operation.as_str(),
+        serde_json::to_value(user)?,
+        object_id,
+        outcome.as_str(),
+        message
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(record.id)
+}
+
+/// Gets all audit log entries, oldest first.
+pub(crate) async fn get_audit_log_entries(
+    executor: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Vec<AuditLogEntry>> {
+    let records = sqlx::query!(
+        r#"
+        SELECT id, operation, operator, object_id, outcome, message, created_at
+        FROM audit_log_entries
+        ORDER BY created_at, id
+        "#
+    )
+    .fetch_all(executor)
+    .await?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(AuditLogEntry {
+                id: record.id,
+                operation: record.operation.parse().map_err(|e: String| eyre!(e))?,
+                user: serde_json::from_value(record.operator)?,
+                object_id: record.object_id,
+                outcome: record.outcome.parse().map_err(|e: String| eyre!(e))?,
+                message: record.message,
+                created_at: record.created_at,
+            })
+        })
+        .collect()
+}
+
+/// Gets the jurisdiction's street segments, in the order they were added.
+pub(crate) async fn get_street_segments(
+    executor: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Vec<cacvote::StreetSegment>> {
+    let records = sqlx::query!(
+        r#"
+        SELECT street_name, zip_code, low_house_number, high_house_number, parity, precinct_id
+        FROM street_segments
+        ORDER BY created_at, id
+        "#
+    )
+    .fetch_all(executor)
+    .await?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(cacvote::StreetSegment {
+                street_name: record.street_name,
+                zip_code: record.zip_code,
+                low_house_number: u32::try_from(record.low_house_number)?,
+                high_house_number: u32::try_from(record.high_house_number)?,
+                parity: record.parity.parse().map_err(|e: String| eyre!(e))?,
+                precinct_id: PrecinctId::from(record.precinct_id),
+            })
+        })
+        .collect()
+}
+
+/// Replaces all of the jurisdiction's street segments. Callers should run
+/// this in a transaction so a failed replacement leaves the old segments.
+pub(crate) async fn replace_street_segments(
+    executor: &mut sqlx::PgConnection,
+    street_segments: &[cacvote::StreetSegment],
+) -> color_eyre::Result<()> {
+    sqlx::query!("DELETE FROM street_segments")
+        .execute(&mut *executor)
+        .await?;
+
+    for street_segment in street_segments {
+        sqlx::query!(
+            r#"
+            INSERT INTO street_segments (
+                street_name,
+                zip_code,
+                low_house_number,
+                high_house_number,
+                parity,
+                precinct_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            street_segment.street_name,
+            street_segment.zip_code,
+            i32::try_from(street_segment.low_house_number)?,
+            i32::try_from(street_segment.high_house_number)?,
+            street_segment.parity.as_str(),
+            street_segment.precinct_id.to_string()
+        )
+        .execute(&mut *executor)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// A row of the `jobs` table.
+struct JobRow {
+    id: Uuid,
+    kind: String,
+    election_object_id: Uuid,
+    parameters: serde_json::Value,
+    operator: serde_json::Value,
+    status: String,
+    progress: Option<String>,
+    cancel_requested: bool,
+    attempts: i32,
+    result_object_id: Option<Uuid>,
+    error: Option<String>,
+    created_at: time::OffsetDateTime,
+    started_at: Option<time::OffsetDateTime>,
+    finished_at: Option<time::OffsetDateTime>,
+}
+
+impl TryFrom<JobRow> for JobRecord {
+    type Error = color_eyre::Report;
+
+    fn try_from(row: JobRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            job: cacvote::Job {
+                id: row.id,
+                kind: row.kind.parse().map_err(|e: String| eyre!(e))?,
+                election_object_id: row.election_object_id,
+                status: row.status.parse().map_err(|e: String| eyre!(e))?,
+                progress: row.progress,
+                cancel_requested: row.cancel_requested,
+                attempts: u32::try_from(row.attempts)?,
+                result_object_id: row.result_object_id,
+                error: row.error,
+                created_at: row.created_at,
+                started_at: row.started_at,
+                finished_at: row.finished_at,
+            },
+            parameters: row.parameters,
+            operator: serde_json::from_value(row.operator)?,
+        })
+    }
+}
+
+/// Records a job for the worker to run, returning its ID, or `None` if a job
+/// of the same kind is already in progress for the election.
+pub(crate) async fn add_job(
+    executor: &mut sqlx::PgConnection,
+    kind: cacvote::JobKind,
+    election_object_id: Uuid,
+    parameters: &serde_json::Value,
+    operator: &User,
+) -> color_eyre::Result<Option<Uuid>> {
+    let record = sqlx::query!(
+        r#"
+        INSERT INTO jobs (kind, election_object_id, parameters, operator)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (election_object_id, kind) WHERE status IN ('pending', 'running')
+        DO NOTHING
+        RETURNING id
+        "#,
+        kind.as_str(),
+        election_object_id,
+        parameters,
+        serde_json::to_value(operator)?
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(record.map(|record| record.id))
+}
+
+/// Gets all jobs, newest first.
+pub(crate) async fn get_jobs(
+    executor: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Vec<cacvote::Job>> {
+    let rows = sqlx::query_as!(
+        JobRow,
+        r#"
+        SELECT
+            id, kind, election_object_id, parameters, operator, status, progress,
+            cancel_requested, attempts, result_object_id, error, created_at,
+            started_at, finished_at
+        FROM jobs
+        ORDER BY created_at DESC, id
+        "#
+    )
+    .fetch_all(executor)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| Ok(JobRecord::try_from(row)?.job))
+        .collect()
+}
+
+pub(crate) async fn get_job(
+    executor: &mut sqlx::PgConnection,
+    job_id: Uuid,
+) -> color_eyre::Result<Option<cacvote::Job>> {
+    let row = sqlx::query_as!(
+        JobRow,
+        r#"
+        SELECT
+            id, kind, election_object_id, parameters, operator, status, progress,
+            cancel_requested, attempts, result_object_id, error, created_at,
+            started_at, finished_at
+        FROM jobs
+        WHERE id = $1
+        "#,
+        job_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    row.map(|row| Ok(JobRecord::try_from(row)?.job)).transpose()
+}
+
+/// Marks the oldest pending job as running and returns it, or `None` if
+/// there is nothing to run.
+pub(crate) async fn claim_next_job(
+    executor: &mut sqlx::PgConnection,
+) -> color_eyre::Result<Option<JobRecord>> {
+    let row = sqlx::query_as!(
+        JobRow,
+        r#"
+        UPDATE jobs
+        SET
+            status = 'running',
+            attempts = attempts + 1,
+            started_at = current_timestamp,
+            progress = NULL
+        WHERE id = (
+            SELECT id
+            FROM jobs
+            WHERE status = 'pending'
+            ORDER BY created_at, id
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            id, kind, election_object_id, parameters, operator, status, progress,
+            cancel_requested, attempts, result_object_id, error, created_at,
+            started_at, finished_at
+        "#
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    row.map(JobRecord::try_from).transpose()
+}
+
+/// Describes what a running job is doing, returning whether it has been
+/// asked to cancel.
+pub(crate) async fn set_job_progress(
+    executor: &mut sqlx::PgConnection,
+    job_id: Uuid,
+    progress: &str,
+) -> color_eyre::Result<bool> {
+    let record = sqlx::query!(
+        r#"
+        UPDATE jobs
+        SET progress = $2
+        WHERE id = $1 AND status = 'running'
+        RETURNING cancel_requested
+        "#,
+        job_id,
+        progress
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    match record {
+        Some(record) => Ok(record.cancel_requested),
+        None => bail!("job {job_id} is not running"),
+    }
+}
+
+/// Determines whether a running job has been asked to cancel. Within a
+/// transaction, the job stays locked against cancellation until it ends.
+pub(crate) async fn is_job_cancel_requested(
+    executor: &mut sqlx::PgConnection,
+    job_id: Uuid,
+) -> color_eyre::Result<bool> {
+    let record = sqlx::query!(
+        r#"
+        SELECT cancel_requested
+        FROM jobs
+        WHERE id = $1 AND status = 'running'
+        FOR UPDATE
+        "#,
+        job_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    match record {
+        Some(record) => Ok(record.cancel_requested),
+        None => bail!("job {job_id} is not running"),
+    }
+}
+
+/// Records how a running job ended, returning the finished job.
+pub(crate) async fn finish_job(
+    executor: &mut sqlx::PgConnection,
+    job_id: Uuid,
+    status: cacvote::JobStatus,
+    result_object_id: Option<Uuid>,
+    error: Option<&str>,
+) -> color_eyre::Result<cacvote::Job> {
+    if !status.is_finished() {
+        bail!("cannot finish job {job_id} as {status}");
+    }
+
+    let row = sqlx::query_as!(
+        JobRow,
+        r#"
+        UPDATE jobs
+        SET
+            status = $2,
+            result_object_id = $3,
+            error = $4,
+            progress = NULL,
+            finished_at = current_timestamp
+        WHERE id = $1 AND status = 'running'
+        RETURNING
+            id, kind, election_object_id, parameters, operator, status, progress,
+            cancel_requested, attempts, result_object_id, error, created_at,
+            started_at, finished_at
+        "#,
+        job_id,
+        status.as_str(),
+        result_object_id,
+        error
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    match row {
+        Some(row) => Ok(JobRecord::try_from(row)?.job),
+        None => bail!("job {job_id} is not running"),
+    }
+}
+
+/// Cancels a pending job outright, or asks a running one to stop at its next
+/// progress report. Returns the job's new status, or `None` if it is not
+/// pending or running.
+pub(crate) async fn request_job_cancellation(
+    executor: &mut sqlx::PgConnection,
+    job_id: Uuid,
+) -> color_eyre::Result<Option<cacvote::JobStatus>> {
+    let record = sqlx::query!(
+        r#"
+        UPDATE jobs
+        SET
+            cancel_requested = TRUE,
+            status = CASE WHEN status = 'pending' THEN 'cancelled' ELSE status END,
+            finished_at = CASE WHEN status = 'pending' THEN current_timestamp END
+        WHERE id = $1 AND status IN ('pending', 'running')
+        RETURNING status
+        "#,
+        job_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    record
+        .map(|record| record.status.parse().map_err(|e: String| eyre!(e)))
+        .transpose()
+}
+
+/// Deals with jobs left running when the JX stopped: cancels those that were
+/// asked to cancel, fails those started `max_attempts` times, and returns the
+/// rest to the queue. Jobs of the `unrestartable_kinds` are never run after a
+/// restart, whether they had started or not, so they are failed too. Returns
+/// every job that was changed.
+pub(crate) async fn recover_interrupted_jobs(
+    executor: &mut sqlx::PgConnection,
+    max_attempts: u32,
+    unrestartable_kinds: &[cacvote::JobKind],
+) -> color_eyre::Result<Vec<JobRecord>> {
+    let unrestartable_kinds = unrestartable_kinds
+        .iter()
+        .map(|kind| kind.as_str().to_owned())
+        .collect::<Vec<_>>();
+    let rows = sqlx::query_as!(
+        JobRow,
+        r#"
+        UPDATE jobs
+        SET
+            status = CASE
+                WHEN cancel_requested THEN 'cancelled'
+                WHEN attempts >= $1 OR kind = ANY($2) THEN 'failed'
+                ELSE 'pending'
+            END,
+            error = CASE
+                WHEN cancel_requested THEN NULL
+                WHEN kind = ANY($2)
+                THEN 'the JX restarted before the job finished, so it must be started again'
+                WHEN attempts >= $1
+                THEN 'the JX stopped while the job was running'
+            END,
+            finished_at = CASE
+                WHEN cancel_requested OR attempts >= $1 OR kind = ANY($2)
+                THEN current_timestamp
+            END,
+            progress = NULL
+        WHERE status = 'running'
+           OR (status = 'pending' AND kind = ANY($2))
+        RETURNING
+            id, kind, election_object_id, parameters, operator, status, progress,
+            cancel_requested, attempts, result_object_id, error, created_at,
+            started_at, finished_at
+        "#,
+        i32::try_from(max_attempts)?,
+        &unrestartable_kinds,
+    )
+    .fetch_all(executor)
+    .await?;
+
+    rows.into_iter().map(JobRecord::try_from).collect()
+}
+
+/// A card's lockout after too many incorrect PIN attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PinLockout {
+    pub(crate) num_incorrect_pin_attempts: u8,
+    pub(crate) locked_until: time::OffsetDateTime,
+}
+
+/// Gets the most recent lockout of the card with `card_fingerprint`, which
+/// may have ended.
+pub(crate) async fn get_pin_lockout(
+    executor: &mut sqlx::PgConnection,
+    card_fingerprint: &str,
+) -> color_eyre::Result<Option<PinLockout>> {
+    let record = sqlx::query!(
+        r#"
+        SELECT num_incorrect_pin_attempts, locked_until
+        FROM pin_lockouts
+        WHERE card_fingerprint = $1
+        "#,
+        card_fingerprint
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    record
+        .map(|record| {
+            Ok(PinLockout {
+                num_incorrect_pin_attempts: u8::try_from(record.num_incorrect_pin_attempts)?,
+                locked_until: record.locked_until,
+            })
+        })
+        .transpose()
+}
+
+/// Records that the card with `card_fingerprint` is locked out, replacing any
+/// earlier lockout.
+pub(crate) async fn set_pin_lockout(
+    executor: &mut sqlx::PgConnection,
+    card_fingerprint: &str,
+    lockout: PinLockout,
+) -> color_eyre::Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO pin_lockouts (card_fingerprint, num_incorrect_pin_attempts, locked_until)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (card_fingerprint) DO UPDATE
+        SET
+            num_incorrect_pin_attempts = EXCLUDED.num_incorrect_pin_attempts,
+            locked_until = EXCLUDED.locked_until,
+            created_at = current_timestamp
+        "#,
+        card_fingerprint,
+        i16::from(lockout.num_incorrect_pin_attempts),
+        lockout.locked_until
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Forgets the lockout of the card with `card_fingerprint`, e.g. once its
+/// correct PIN has been entered.
+pub(crate) async fn delete_pin_lockout(
+    executor: &mut sqlx::PgConnection,
+    card_fingerprint: &str,
+) -> color_eyre::Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM pin_lockouts
+        WHERE card_fingerprint = $1
+        "#,
+        card_fingerprint
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use electionguard_rs::key_wrapping::KeyEncryptionKey;
+    use openssl::{
+        pkey::{PKey, Private, Public},
+        x509::X509,
+    };
+    use types_rs::{
+        cacvote::JurisdictionCode,
+        election::{ContestId, ElectionDefinition},
+    };
+
+    use super::*;
+
+    fn load_keypair() -> color_eyre::Result<(X509, PKey<Public>, PKey<Private>)> {
+        // uses the dev VxAdmin keypair because it has the Jurisdiction field
+        let private_key_pem =
+            include_bytes!("../../../../libs/auth/certs/dev/vx-admin-private-key.pem");
+        let private_key = PKey::private_key_from_pem(private_key_pem)?;
+        let certificate =
+            include_bytes!("../../../../libs/auth/certs/dev/vx-admin-cert-authority-cert.pem")
+                .to_vec();
+        let x509 = X509::from_pem(&certificate)?;
+        let public_key = x509.public_key()?;
+        Ok((x509, public_key, private_key))
+    }
+
+    fn load_election_definition() -> color_eyre::Result<ElectionDefinition> {
+        Ok(ElectionDefinition::try_from(
+            &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+        )?)
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_pending_registration_requests(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificate, _, private_key) = load_keypair()?;
+        let election_definition = load_election_definition()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+
+        let election_payload = cacvote::Payload::Election(cacvote::Election {
+            jurisdiction_code: jurisdiction_code.clone(),
             election_definition: election_definition.clone(),
             mailing_address: "123 Main St".to_owned(),
             electionguard_election_metadata_blob: vec![],
+            voting_starts_at: None,
+            voting_ends_at: None,
+            ballot_receipt_deadline: None,
         });
         let election_object = cacvote::SignedObject::from_payload(
             &election_payload,
             certificate.clone(),
             &private_key,
         )?;
-
-        add_object_from_server(connection, &election_object).await?;
+
+        add_object_from_server(connection, &election_object).await?;
+
+        let pending_registration_requests = get_pending_registration_requests(connection).await?;
+
+        assert!(
+            pending_registration_requests.is_empty(),
+            "Expected no pending registration requests, got {pending_registration_requests:?}",
+        );
+
+        let registration_request_payload =
+            cacvote::Payload::RegistrationRequest(cacvote::RegistrationRequest {
+                jurisdiction_code: jurisdiction_code.clone(),
+                common_access_card_id: "0123456789".to_owned(),
+                family_name: "Smith".to_owned(),
+                given_name: "John".to_owned(),
+                address: None,
+            });
+        let registration_request_object = cacvote::SignedObject::from_payload(
+            &registration_request_payload,
+            certificate.clone(),
+            &private_key,
+        )?;
+
+        add_object_from_server(connection, &registration_request_object).await?;
+
+        let pending_registration_requests = get_pending_registration_requests(connection).await?;
+
+        match pending_registration_requests.as_slice() {
+            [registration_request] => {
+                assert_eq!(registration_request.common_access_card_id, "0123456789");
+                assert_eq!(registration_request.given_name, "John");
+                assert_eq!(registration_request.family_name, "Smith");
+                assert_eq!(registration_request.jurisdiction_code, jurisdiction_code);
+            }
+            _ => panic!("Expected one registration request, got {pending_registration_requests:?}"),
+        }
+
+        let ballot_style_id = election_definition.election.ballot_styles[0].id.clone();
+        let precinct_id = election_definition.election.precincts[0].id.clone();
+
+        let registration_payload = cacvote::Payload::Registration(cacvote::Registration {
+            jurisdiction_code: jurisdiction_code.clone(),
+            common_access_card_id: "0123456789".to_owned(),
+            registration_request_object_id: registration_request_object.id,
+            election_object_id: election_object.id,
+            ballot_style_id,
+            precinct_id,
+        });
+        assert!(
+            !has_registration_for_election(
+                connection,
+                registration_request_object.id,
+                election_object.id
+            )
+            .await?
+        );
+
+        let registration_object = cacvote::SignedObject::from_payload(
+            &registration_payload,
+            certificate.clone(),
+            &private_key,
+        )?;
+
+        add_object_from_server(connection, &registration_object).await?;
+
+        assert!(
+            has_registration_for_election(
+                connection,
+                registration_request_object.id,
+                election_object.id
+            )
+            .await?
+        );
+
+        let pending_registration_requests = get_pending_registration_requests(connection).await?;
+
+        assert!(
+            pending_registration_requests.is_empty(),
+            "Expected no pending registration requests, got {pending_registration_requests:?}",
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_rejected_registration_requests_are_not_pending(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let (certificate, _, private_key) = load_keypair()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+
+        let registration_request_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::RegistrationRequest(cacvote::RegistrationRequest {
+                jurisdiction_code: jurisdiction_code.clone(),
+                common_access_card_id: "0123456789".to_owned(),
+                family_name: "Smith".to_owned(),
+                given_name: "John".to_owned(),
+                address: None,
+            }),
+            certificate.clone(),
+            &private_key,
+        )?;
+        add_object_from_server(connection, &registration_request_object).await?;
+        assert_eq!(
+            get_pending_registration_requests(connection).await?.len(),
+            1
+        );
+
+        let rejection_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::RegistrationRejection(cacvote::RegistrationRejection {
+                common_access_card_id: "0123456789".to_owned(),
+                jurisdiction_code,
+                registration_request_object_id: registration_request_object.id,
+                reason: cacvote::RegistrationRejectionReason::NotEligible,
+                message: None,
+            }),
+            certificate,
+            &private_key,
+        )?;
+        assert!(
+            !is_registration_request_rejected(connection, registration_request_object.id).await?
+        );
+        add_object_from_server(connection, &rejection_object).await?;
+        assert!(
+            is_registration_request_rejected(connection, registration_request_object.id).await?
+        );
 
         let pending_registration_requests = get_pending_registration_requests(connection).await?;
-
         assert!(
             pending_registration_requests.is_empty(),
             "Expected no pending registration requests, got {pending_registration_requests:?}",
         );
 
-        let registration_request_payload =
-            cacvote::Payload::RegistrationRequest(cacvote::RegistrationRequest {
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_election_lifecycle_state(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificate, _, private_key) = load_keypair()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+
+        let election_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::Election(cacvote::Election {
                 jurisdiction_code: jurisdiction_code.clone(),
-                common_access_card_id: "0123456789".to_owned(),
-                family_name: "Smith".to_owned(),
-                given_name: "John".to_owned(),
-            });
-        let registration_request_object = cacvote::SignedObject::from_payload(
-            &registration_request_payload,
+                election_definition: load_election_definition()?,
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![],
+                voting_starts_at: None,
+                voting_ends_at: None,
+                ballot_receipt_deadline: None,
+            }),
             certificate.clone(),
             &private_key,
         )?;
+        add_object_from_server(connection, &election_object).await?;
+        assert_eq!(
+            get_election_lifecycle_state(connection, election_object.id).await?,
+            cacvote::ElectionLifecycleState::Created
+        );
 
-        add_object_from_server(connection, &registration_request_object).await?;
-
-        let pending_registration_requests = get_pending_registration_requests(connection).await?;
+        for (action, expected_state) in [
+            (
+                cacvote::ElectionLifecycleAction::OpenVoting,
+                cacvote::ElectionLifecycleState::Open,
+            ),
+            (
+                cacvote::ElectionLifecycleAction::CloseVoting,
+                cacvote::ElectionLifecycleState::Closed,
+            ),
+        ] {
+            let transition_object = cacvote::SignedObject::from_payload(
+                &cacvote::Payload::ElectionLifecycleTransition(
+                    cacvote::ElectionLifecycleTransition {
+                        jurisdiction_code: jurisdiction_code.clone(),
+                        election_object_id: election_object.id,
+                        action,
+                    },
+                ),
+                certificate.clone(),
+                &private_key,
+            )?;
+            add_object(connection, &transition_object).await?;
+            assert_eq!(
+                get_election_lifecycle_state(connection, election_object.id).await?,
+                expected_state
+            );
+        }
 
-        match pending_registration_requests.as_slice() {
-            [registration_request] => {
-                assert_eq!(registration_request.common_access_card_id, "0123456789");
-                assert_eq!(registration_request.given_name, "John");
-                assert_eq!(registration_request.family_name, "Smith");
-                assert_eq!(registration_request.jurisdiction_code, jurisdiction_code);
-            }
-            _ => panic!("Expected one registration request, got {pending_registration_requests:?}"),
+        match get_elections(connection).await?.as_slice() {
+            [election] => assert_eq!(
+                election.lifecycle_state(),
+                cacvote::ElectionLifecycleState::Closed
+            ),
+            elections => panic!("Expected one election, got {elections:?}"),
         }
 
-        let ballot_style_id = election_definition.election.ballot_styles[0].id.clone();
-        let precinct_id = election_definition.election.precincts[0].id.clone();
+        Ok(())
+    }
 
-        let registration_payload = cacvote::Payload::Registration(cacvote::Registration {
+    #[sqlx::test(migrations = false)]
+    async fn test_legacy_election_lifecycle_state(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        const LEGACY_OPEN_ELECTIONS_MIGRATION: i64 = 20241106120000;
+        let (certificate, _, private_key) = load_keypair()?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+        let election_object = || {
+            cacvote::SignedObject::from_payload(
+                &cacvote::Payload::Election(cacvote::Election {
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    election_definition: load_election_definition()?,
+                    mailing_address: "123 Main St".to_owned(),
+                    electionguard_election_metadata_blob: vec![],
+                    voting_starts_at: None,
+                    voting_ends_at: None,
+                    ballot_receipt_deadline: None,
+                }),
+                certificate.clone(),
+                &private_key,
+            )
+        };
+
+        // an election stored before lifecycle transitions were recorded
+        let mut migrator = sqlx::migrate!("db/migrations");
+        migrator
+            .migrations
+            .to_mut()
+            .retain(|migration| migration.version < LEGACY_OPEN_ELECTIONS_MIGRATION);
+        migrator.run(&pool).await?;
+        let connection = &mut pool.acquire().await?;
+        let legacy_election_object = election_object()?;
+        add_object_from_server(connection, &legacy_election_object).await?;
+
+        sqlx::migrate!("db/migrations").run(&pool).await?;
+        assert_eq!(
+            get_election_lifecycle_state(connection, legacy_election_object.id).await?,
+            cacvote::ElectionLifecycleState::Open
+        );
+
+        let close_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::ElectionLifecycleTransition(cacvote::ElectionLifecycleTransition {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_object_id: legacy_election_object.id,
+                action: cacvote::ElectionLifecycleAction::CloseVoting,
+            }),
+            certificate.clone(),
+            &private_key,
+        )?;
+        add_object(connection, &close_object).await?;
+        assert_eq!(
+            get_election_lifecycle_state(connection, legacy_election_object.id).await?,
+            cacvote::ElectionLifecycleState::Closed
+        );
+
+        // elections created since start out created
+        let new_election_object = election_object()?;
+        add_object_from_server(connection, &new_election_object).await?;
+        assert_eq!(
+            get_election_lifecycle_state(connection, new_election_object.id).await?,
+            cacvote::ElectionLifecycleState::Created
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_mix_verification(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificate, _, private_key) = load_keypair()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+        let sign = |payload: cacvote::Payload| {
+            cacvote::SignedObject::from_payload(&payload, certificate.clone(), &private_key)
+        };
+
+        let election_object = sign(cacvote::Payload::Election(cacvote::Election {
             jurisdiction_code: jurisdiction_code.clone(),
-            common_access_card_id: "0123456789".to_owned(),
-            registration_request_object_id: registration_request_object.id,
-            election_object_id: election_object.id,
-            ballot_style_id,
-            precinct_id,
-        });
-        let registration_object = cacvote::SignedObject::from_payload(
-            &registration_payload,
+            election_definition: load_election_definition()?,
+            mailing_address: "123 Main St".to_owned(),
+            electionguard_election_metadata_blob: vec![],
+            voting_starts_at: None,
+            voting_ends_at: None,
+            ballot_receipt_deadline: None,
+        }))?;
+        let election_id = election_object.id;
+        add_object_from_server(connection, &election_object).await?;
+
+        assert!(
+            get_shuffled_encrypted_cast_ballots_object(connection, election_id)
+                .await?
+                .is_none()
+        );
+        assert!(
+            get_latest_shuffled_encrypted_cast_ballots_verification(connection, election_id)
+                .await?
+                .is_none()
+        );
+
+        let shuffled_ballots = cacvote::ShuffledEncryptedCastBallots {
+            jurisdiction_code: jurisdiction_code.clone(),
+            election_object_id: election_id,
+            electionguard_shuffled_ballots: b"mix".to_vec(),
+        };
+        let shuffled_ballots_object = sign(cacvote::Payload::ShuffledEncryptedCastBallots(
+            shuffled_ballots.clone(),
+        ))?;
+        add_object(connection, &shuffled_ballots_object).await?;
+        assert_eq!(
+            get_shuffled_encrypted_cast_ballots_object(connection, election_id).await?,
+            Some((shuffled_ballots_object.id, shuffled_ballots))
+        );
+
+        // a failed check from another machine, then a passing one from this one
+        let verification =
+            |error: Option<&str>| cacvote::ShuffledEncryptedCastBallotsVerification {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_object_id: election_id,
+                shuffled_encrypted_cast_ballots_object_id: shuffled_ballots_object.id,
+                cast_ballot_count: 1,
+                phases: vec![cacvote::MixPhaseVerification {
+                    mix_name: "mix1".to_owned(),
+                    ballot_count: 1,
+                    error: error.map(ToOwned::to_owned),
+                }],
+            };
+        add_object_from_server(
+            connection,
+            &sign(cacvote::Payload::ShuffledEncryptedCastBallotsVerification(
+                verification(Some("proof of shuffle is invalid")),
+            ))?,
+        )
+        .await?;
+        let latest =
+            get_latest_shuffled_encrypted_cast_ballots_verification(connection, election_id)
+                .await?
+                .unwrap();
+        assert!(!latest
+            .shuffled_encrypted_cast_ballots_verification
+            .is_verified());
+        assert!(latest.synced_at.is_some());
+
+        add_object(
+            connection,
+            &sign(cacvote::Payload::ShuffledEncryptedCastBallotsVerification(
+                verification(None),
+            ))?,
+        )
+        .await?;
+        let latest =
+            get_latest_shuffled_encrypted_cast_ballots_verification(connection, election_id)
+                .await?
+                .unwrap();
+        assert_eq!(
+            latest.shuffled_encrypted_cast_ballots_verification,
+            verification(None)
+        );
+        assert_eq!(latest.synced_at, None);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_decrypted_shuffled_ballots(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificate, _, private_key) = load_keypair()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+        let sign = |payload: cacvote::Payload| {
+            cacvote::SignedObject::from_payload(&payload, certificate.clone(), &private_key)
+        };
+
+        let election_object = sign(cacvote::Payload::Election(cacvote::Election {
+            jurisdiction_code: jurisdiction_code.clone(),
+            election_definition: load_election_definition()?,
+            mailing_address: "123 Main St".to_owned(),
+            electionguard_election_metadata_blob: vec![],
+            voting_starts_at: None,
+            voting_ends_at: None,
+            ballot_receipt_deadline: None,
+        }))?;
+        let election_id = election_object.id;
+        add_object_from_server(connection, &election_object).await?;
+
+        assert!(get_decrypted_shuffled_ballots(connection, election_id)
+            .await?
+            .is_none());
+
+        let decrypted_shuffled_ballots = |vote: u32| cacvote::DecryptedShuffledBallots {
+            jurisdiction_code: jurisdiction_code.clone(),
+            election_object_id: election_id,
+            shuffled_encrypted_cast_ballots_object_id: Uuid::new_v4(),
+            ballots: vec![cacvote::AnonymousBallot {
+                contests: vec![cacvote::AnonymousBallotContest {
+                    contest_id: ContestId::from("mayor".to_owned()),
+                    selections: vec![cacvote::AnonymousBallotSelection {
+                        option_id: "sherlock-holmes".to_owned(),
+                        vote,
+                    }],
+                }],
+            }],
+            electionguard_decrypted_ballots: vec![],
+        };
+
+        // decrypted on another machine first, then on this one
+        let first = decrypted_shuffled_ballots(1);
+        add_object_from_server(
+            connection,
+            &sign(cacvote::Payload::DecryptedShuffledBallots(first.clone()))?,
+        )
+        .await?;
+        add_object(
+            connection,
+            &sign(cacvote::Payload::DecryptedShuffledBallots(
+                decrypted_shuffled_ballots(0),
+            ))?,
+        )
+        .await?;
+
+        let decrypted = get_decrypted_shuffled_ballots(connection, election_id)
+            .await?
+            .unwrap();
+        assert_eq!(decrypted.decrypted_shuffled_ballots, first);
+        assert!(decrypted.synced_at.is_some());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_cast_ballots_by_id(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificate, _, private_key) = load_keypair()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+        let sign = |payload: cacvote::Payload| {
+            cacvote::SignedObject::from_payload(&payload, certificate.clone(), &private_key)
+        };
+
+        let election = |mailing_address: &str| -> color_eyre::Result<cacvote::SignedObject> {
+            sign(cacvote::Payload::Election(cacvote::Election {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_definition: load_election_definition()?,
+                mailing_address: mailing_address.to_owned(),
+                electionguard_election_metadata_blob: vec![],
+                voting_starts_at: None,
+                voting_ends_at: None,
+                ballot_receipt_deadline: None,
+            }))
+        };
+        let election_object = election("123 Main St")?;
+        let election_id = election_object.id;
+        add_object_from_server(connection, &election_object).await?;
+        let other_election_object = election("456 Elm St")?;
+        add_object_from_server(connection, &other_election_object).await?;
+
+        let cast_ballot = |election_object_id: Uuid, encrypted_ballot: &[u8]| {
+            sign(cacvote::Payload::CastBallot(cacvote::CastBallot {
+                common_access_card_id: "0123456789".to_owned(),
+                jurisdiction_code: jurisdiction_code.clone(),
+                registration_request_object_id: Uuid::new_v4(),
+                registration_object_id: Uuid::new_v4(),
+                election_object_id,
+                electionguard_encrypted_ballot: encrypted_ballot.to_vec(),
+            }))
+        };
+        let first = cast_ballot(election_id, b"first")?;
+        let second = cast_ballot(election_id, b"second")?;
+        let other = cast_ballot(other_election_object.id, b"other")?;
+        for object in [&first, &second, &other] {
+            add_object_from_server(connection, object).await?;
+        }
+
+        assert!(get_cast_ballots_by_id(connection, &election_id, &[])
+            .await?
+            .is_empty());
+
+        // in the order asked for
+        let cast_ballots =
+            get_cast_ballots_by_id(connection, &election_id, &[second.id, first.id]).await?;
+        assert_eq!(
+            cast_ballots
+                .iter()
+                .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_slice())
+                .collect::<Vec<_>>(),
+            vec![b"second".as_slice(), b"first".as_slice()]
+        );
+
+        // a ballot cast in another election
+        assert!(
+            get_cast_ballots_by_id(connection, &election_id, &[first.id, other.id])
+                .await
+                .is_err()
+        );
+
+        // a ballot that does not exist
+        assert!(
+            get_cast_ballots_by_id(connection, &election_id, &[first.id, Uuid::new_v4()])
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_private_key_custody(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificate, _, private_key) = load_keypair()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+        let old_key_encryption_key = || {
+            KeyEncryptionKey::from_hex(
+                "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            )
+        };
+        let new_key_encryption_key = || {
+            KeyEncryptionKey::from_hex(
+                "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100",
+            )
+        };
+
+        let mut election_ids = Vec::new();
+        for _ in 0..2 {
+            let election_object = cacvote::SignedObject::from_payload(
+                &cacvote::Payload::Election(cacvote::Election {
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    election_definition: load_election_definition()?,
+                    mailing_address: "123 Main St".to_owned(),
+                    electionguard_election_metadata_blob: vec![],
+                    voting_starts_at: None,
+                    voting_ends_at: None,
+                    ballot_receipt_deadline: None,
+                }),
+                certificate.clone(),
+                &private_key,
+            )?;
+            add_object_from_server(connection, &election_object).await?;
+            election_ids.push(election_object.id);
+        }
+        let [certified_election_id, other_election_id] = election_ids[..] else {
+            unreachable!()
+        };
+
+        // one key was stored before keys were wrapped
+        let old_key_encryption_keys = KeyEncryptionKeys::new(old_key_encryption_key()?, vec![]);
+        sqlx::query!(
+            r#"
+            INSERT INTO eg_private_keys (election_object_id, private_key)
+            VALUES ($1, $2)
+            "#,
+            certified_election_id,
+            b"secret 1".as_slice()
+        )
+        .execute(&mut **connection)
+        .await?;
+        add_eg_private_key(
+            connection,
+            &other_election_id,
+            &old_key_encryption_keys.wrap_key(b"secret 2", other_election_id.as_bytes())?,
+        )
+        .await?;
+
+        // a key stored in plaintext cannot be used until it is wrapped
+        assert!(!has_eg_private_key(connection, &certified_election_id).await?);
+        assert!(has_eg_private_key(connection, &other_election_id).await?);
+        assert!(!has_eg_private_key(connection, &Uuid::new_v4()).await?);
+
+        assert_eq!(
+            rewrap_private_keys(connection, &old_key_encryption_keys).await?,
+            1
+        );
+        assert!(has_eg_private_key(connection, &certified_election_id).await?);
+        assert_eq!(
+            rewrap_private_keys(connection, &old_key_encryption_keys).await?,
+            0
+        );
+        assert_eq!(
+            old_key_encryption_keys.unwrap_key(
+                &get_eg_private_key(connection, &certified_election_id).await?,
+                certified_election_id.as_bytes()
+            )?,
+            b"secret 1"
+        );
+
+        // rotating rewraps every key with the new key-encryption key
+        let new_key_encryption_keys =
+            KeyEncryptionKeys::new(new_key_encryption_key()?, vec![old_key_encryption_key()?]);
+        assert_eq!(
+            rewrap_private_keys(connection, &new_key_encryption_keys).await?,
+            2
+        );
+        let rotated_key_encryption_keys = KeyEncryptionKeys::new(new_key_encryption_key()?, vec![]);
+        for (election_id, secret) in [
+            (certified_election_id, b"secret 1"),
+            (other_election_id, b"secret 2"),
+        ] {
+            assert_eq!(
+                rotated_key_encryption_keys.unwrap_key(
+                    &get_eg_private_key(connection, &election_id).await?,
+                    election_id.as_bytes()
+                )?,
+                secret
+            );
+        }
+
+        // keys are deleted once the election is certified
+        for action in [
+            cacvote::ElectionLifecycleAction::OpenVoting,
+            cacvote::ElectionLifecycleAction::CloseVoting,
+            cacvote::ElectionLifecycleAction::CertifyResults,
+        ] {
+            assert_eq!(
+                delete_private_keys_for_certified_elections(connection).await?,
+                Vec::<Uuid>::new()
+            );
+
+            let transition_object = cacvote::SignedObject::from_payload(
+                &cacvote::Payload::ElectionLifecycleTransition(
+                    cacvote::ElectionLifecycleTransition {
+                        jurisdiction_code: jurisdiction_code.clone(),
+                        election_object_id: certified_election_id,
+                        action,
+                    },
+                ),
+                certificate.clone(),
+                &private_key,
+            )?;
+            add_object(connection, &transition_object).await?;
+        }
+
+        assert_eq!(
+            delete_private_keys_for_certified_elections(connection).await?,
+            vec![certified_election_id]
+        );
+        assert_eq!(
+            delete_private_keys_for_certified_elections(connection).await?,
+            Vec::<Uuid>::new()
+        );
+        assert!(get_eg_private_key(connection, &certified_election_id)
+            .await
+            .is_err());
+        assert!(get_eg_private_key(connection, &other_election_id)
+            .await
+            .is_ok());
+
+        // the deleted key's copies stay readable until its key-encryption key
+        // is rotated out and destroyed
+        let rotated_key_encryption_key_id = rotated_key_encryption_keys.current().id().to_owned();
+        assert_eq!(
+            get_key_encryption_key_ids_with_deleted_keys(connection, &new_key_encryption_keys)
+                .await?,
+            vec![rotated_key_encryption_key_id]
+        );
+        assert_eq!(
+            get_key_encryption_key_ids_with_deleted_keys(connection, &old_key_encryption_keys)
+                .await?,
+            Vec::<String>::new()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_jobs(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (certificate, _, private_key) = load_keypair()?;
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+        let user = User::ElectionManager(types_rs::auth::ElectionManagerUser::new(
+            jurisdiction_code.clone(),
+            "election-hash".to_owned(),
+        ));
+
+        let election_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::Election(cacvote::Election {
+                jurisdiction_code: jurisdiction_code.clone(),
+                election_definition: load_election_definition()?,
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![],
+                voting_starts_at: None,
+                voting_ends_at: None,
+                ballot_receipt_deadline: None,
+            }),
             certificate.clone(),
             &private_key,
         )?;
+        add_object_from_server(connection, &election_object).await?;
+        let election_id = election_object.id;
+        let parameters = serde_json::json!({ "phases": 2 });
 
-        add_object_from_server(connection, &registration_object).await?;
+        let mix_job_id = add_job(
+            connection,
+            cacvote::JobKind::MixBallots,
+            election_id,
+            &parameters,
+            &user,
+        )
+        .await?
+        .expect("job should be added");
+
+        // only one job of each kind may be in progress for an election
+        assert_eq!(
+            add_job(
+                connection,
+                cacvote::JobKind::MixBallots,
+                election_id,
+                &parameters,
+                &user,
+            )
+            .await?,
+            None
+        );
+        let tally_job_id = add_job(
+            connection,
+            cacvote::JobKind::GenerateEncryptedTally,
+            election_id,
+            &serde_json::json!({}),
+            &user,
+        )
+        .await?
+        .expect("job should be added");
+
+        // jobs are claimed oldest first
+        let record = claim_next_job(connection).await?.expect("a pending job");
+        assert_eq!(record.job.id, mix_job_id);
+        assert_eq!(record.job.status, cacvote::JobStatus::Running);
+        assert_eq!(record.job.attempts, 1);
+        assert_eq!(record.parameters, parameters);
+        assert_eq!(record.operator, user);
+
+        assert!(!set_job_progress(connection, mix_job_id, "Mixing").await?);
+        assert_eq!(
+            get_job(connection, mix_job_id).await?.unwrap().progress,
+            Some("Mixing".to_owned())
+        );
 
-        let pending_registration_requests = get_pending_registration_requests(connection).await?;
+        // a running job is asked to stop, and learns so at its next report
+        assert_eq!(
+            request_job_cancellation(connection, mix_job_id).await?,
+            Some(cacvote::JobStatus::Running)
+        );
+        assert!(set_job_progress(connection, mix_job_id, "Still mixing").await?);
+        assert!(is_job_cancel_requested(connection, mix_job_id).await?);
+        let job = finish_job(
+            connection,
+            mix_job_id,
+            cacvote::JobStatus::Cancelled,
+            None,
+            None,
+        )
+        .await?;
+        assert_eq!(job.status, cacvote::JobStatus::Cancelled);
+        assert_eq!(job.progress, None);
+        assert!(job.finished_at.is_some());
+        assert_eq!(
+            request_job_cancellation(connection, mix_job_id).await?,
+            None
+        );
+
+        // a job interrupted by a restart is run again, until it has been
+        // started too many times
+        let record = claim_next_job(connection).await?.expect("a pending job");
+        assert_eq!(record.job.id, tally_job_id);
+        assert!(claim_next_job(connection).await?.is_none());
+        match recover_interrupted_jobs(connection, 2, &[])
+            .await?
+            .as_slice()
+        {
+            [record] => {
+                assert_eq!(record.job.id, tally_job_id);
+                assert_eq!(record.job.status, cacvote::JobStatus::Pending);
+            }
+            records => panic!("Expected one recovered job, got {records:?}"),
+        }
+        let record = claim_next_job(connection).await?.expect("a pending job");
+        assert_eq!(record.job.attempts, 2);
+        match recover_interrupted_jobs(connection, 2, &[])
+            .await?
+            .as_slice()
+        {
+            [record] => {
+                assert_eq!(record.job.status, cacvote::JobStatus::Failed);
+                assert!(record.job.error.is_some());
+            }
+            records => panic!("Expected one recovered job, got {records:?}"),
+        }
+
+        // a pending job is cancelled at once
+        let mix_job_id = add_job(
+            connection,
+            cacvote::JobKind::MixBallots,
+            election_id,
+            &parameters,
+            &user,
+        )
+        .await?
+        .expect("job should be added");
+        assert_eq!(
+            request_job_cancellation(connection, mix_job_id).await?,
+            Some(cacvote::JobStatus::Cancelled)
+        );
+        assert!(claim_next_job(connection).await?.is_none());
+
+        assert_eq!(
+            get_jobs(connection)
+                .await?
+                .iter()
+                .map(|job| (job.kind, job.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (cacvote::JobKind::MixBallots, cacvote::JobStatus::Cancelled),
+                (
+                    cacvote::JobKind::GenerateEncryptedTally,
+                    cacvote::JobStatus::Failed
+                ),
+                (cacvote::JobKind::MixBallots, cacvote::JobStatus::Cancelled),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_street_segments(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let connection = &mut pool.acquire().await?;
+        assert!(get_street_segments(connection).await?.is_empty());
+
+        let segment = |low_house_number, high_house_number, parity, precinct_id: &str| {
+            cacvote::StreetSegment {
+                street_name: "Main St".to_owned(),
+                zip_code: "12345".to_owned(),
+                low_house_number,
+                high_house_number,
+                parity,
+                precinct_id: PrecinctId::from(precinct_id.to_owned()),
+            }
+        };
+
+        let street_segments = vec![
+            segment(1, 99, cacvote::HouseNumberParity::Odd, "20"),
+            segment(2, 98, cacvote::HouseNumberParity::Even, "21"),
+        ];
+        replace_street_segments(connection, &street_segments).await?;
+        assert_eq!(get_street_segments(connection).await?, street_segments);
+
+        // replacing removes the old segments
+        let street_segments = vec![segment(1, 200, cacvote::HouseNumberParity::All, "22")];
+        replace_street_segments(connection, &street_segments).await?;
+        assert_eq!(get_street_segments(connection).await?, street_segments);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_audit_log_is_append_only(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let connection = &mut pool.acquire().await?;
+        let user = User::ElectionManager(types_rs::auth::ElectionManagerUser::new(
+            JurisdictionCode::try_from("st.test-jurisdiction").unwrap(),
+            "election-hash".to_owned(),
+        ));
+        let object_id = Uuid::new_v4();
+
+        let entry_id = add_audit_log_entry(
+            connection,
+            Operation::DecryptTally,
+            &user,
+            Some(object_id),
+            Outcome::Success,
+            "decrypt-tally succeeded",
+        )
+        .await?;
+
+        match get_audit_log_entries(connection).await?.as_slice() {
+            [entry] => {
+                assert_eq!(entry.id, entry_id);
+                assert_eq!(entry.operation, Operation::DecryptTally);
+                assert_eq!(entry.user, user);
+                assert_eq!(entry.object_id, Some(object_id));
+                assert_eq!(entry.outcome, Outcome::Success);
+            }
+            entries => panic!("Expected one audit log entry, got {entries:?}"),
+        }
 
         assert!(
-            pending_registration_requests.is_empty(),
-            "Expected no pending registration requests, got {pending_registration_requests:?}",
+            sqlx::query!("UPDATE audit_log_entries SET outcome = 'failure'")
+                .execute(&mut **connection)
+                .await
+                .is_err(),
+            "Expected audit log entries to be immutable"
+        );
+        assert!(
+            sqlx::query!("DELETE FROM audit_log_entries")
+                .execute(&mut **connection)
+                .await
+                .is_err(),
+            "Expected audit log entries to be undeletable"
         );
+        assert_eq!(get_audit_log_entries(connection).await?.len(), 1);
 
         Ok(())
     }
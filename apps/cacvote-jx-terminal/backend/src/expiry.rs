@@ -0,0 +1,193 @@
+//! Automatic rejection of registration requests that were never acted on.
+//!
+//! A request that stays pending longer than the configured maximum age is
+//! rejected with [`RegistrationRejectionReason::Expired`]. The rejection is
+//! signed by the machine and synced like any other object, so the voter sees
+//! why their request was not approved.
+
+use openssl::x509::X509;
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use tokio::time::sleep;
+use types_rs::cacvote::{
+    JurisdictionCode, Payload, RegistrationRejection, RegistrationRejectionReason, SignedObject,
+};
+use uuid::Uuid;
+
+use crate::{
+    config::{Config, REGISTRATION_EXPIRY_INTERVAL},
+    db,
+};
+
+/// Spawns an async loop that expires stale registration requests on a fixed
+/// schedule. Does nothing if expiry is disabled.
+pub(crate) fn expire_periodically(pool: &PgPool, config: Config) {
+    let Some(max_age) = config.registration_request_max_age() else {
+        tracing::info!("registration request expiry is disabled");
+        return;
+    };
+
+    let jurisdiction_code = config.jurisdiction_code().expect(
+        "missing or invalid jurisdiction code in CA certificate; check that the CA certificate is valid and contains a jurisdiction code",
+    );
+    let pool = pool.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let result = async {
+                let mut connection = pool.acquire().await?;
+                expire_stale_registration_requests(
+                    &mut connection,
+                    &jurisdiction_code,
+                    max_age,
+                    OffsetDateTime::now_utc(),
+                    |payload| config.sign(payload),
+                )
+                .await
+            }
+            .await;
+
+            match result {
+                Ok(expired) if !expired.is_empty() => {
+                    tracing::info!("expired {} stale registration requests", expired.len());
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("failed to expire stale registration requests: {e}");
+                }
+            }
+            sleep(REGISTRATION_EXPIRY_INTERVAL).await;
+        }
+    });
+}
+
+/// Rejects every pending registration request received more than `max_age`
+/// before `now`, returning the IDs of the requests that were rejected.
+pub(crate) async fn expire_stale_registration_requests(
+    connection: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    max_age: Duration,
+    now: OffsetDateTime,
+    sign: impl Fn(&[u8]) -> color_eyre::Result<(Vec<u8>, X509)>,
+) -> color_eyre::Result<Vec<Uuid>> {
+    let mut expired = Vec::new();
+
+    for registration_request in db::get_pending_registration_requests(connection).await? {
+        if now - registration_request.created_at() < max_age {
+            continue;
+        }
+
+        let payload = Payload::RegistrationRejection(RegistrationRejection {
+            common_access_card_id: registration_request.common_access_card_id.clone(),
+            jurisdiction_code: jurisdiction_code.clone(),
+            registration_request_object_id: registration_request.id,
+            reason: RegistrationRejectionReason::Expired,
+            message: None,
+        });
+        let serialized_payload = serde_json::to_vec(&payload)?;
+        let (signature, certificate) = sign(&serialized_payload)?;
+
+        db::add_object(
+            connection,
+            &SignedObject {
+                id: Uuid::new_v4(),
+                election_id: None,
+                payload: serialized_payload,
+                certificate: certificate.to_pem()?,
+                certificate_chain: None,
+                signature,
+            },
+        )
+        .await?;
+
+        expired.push(registration_request.id);
+    }
+
+    Ok(expired)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+    use types_rs::cacvote::RegistrationRequest;
+
+    use super::*;
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_expire_stale_registration_requests(pool: PgPool) -> color_eyre::Result<()> {
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-admin-private-key.pem"
+        ))?;
+        let certificate = X509::from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))?;
+        let sign = |payload: &[u8]| {
+            let mut signer = Signer::new(MessageDigest::sha256(), &private_key)?;
+            signer.update(payload)?;
+            Ok((signer.sign_to_vec()?, certificate.clone()))
+        };
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from("st.test-jurisdiction").unwrap();
+
+        let registration_request_object = SignedObject::from_payload(
+            &Payload::RegistrationRequest(RegistrationRequest {
+                jurisdiction_code: jurisdiction_code.clone(),
+                common_access_card_id: "0123456789".to_owned(),
+                family_name: "Smith".to_owned(),
+                given_name: "John".to_owned(),
+                address: None,
+            }),
+            certificate.clone(),
+            &private_key,
+        )?;
+        db::add_object_from_server(connection, &registration_request_object).await?;
+
+        let max_age = Duration::days(30);
+        let now = OffsetDateTime::now_utc();
+
+        // not yet stale
+        let expired =
+            expire_stale_registration_requests(connection, &jurisdiction_code, max_age, now, sign)
+                .await?;
+        assert!(
+            expired.is_empty(),
+            "Expected no expired requests, got {expired:?}"
+        );
+        assert_eq!(
+            db::get_pending_registration_requests(connection)
+                .await?
+                .len(),
+            1
+        );
+
+        let expired = expire_stale_registration_requests(
+            connection,
+            &jurisdiction_code,
+            max_age,
+            now + max_age,
+            sign,
+        )
+        .await?;
+        assert_eq!(expired, vec![registration_request_object.id]);
+        assert!(db::get_pending_registration_requests(connection)
+            .await?
+            .is_empty());
+
+        // the rejection is signed, unsynced, and names the expired request
+        match db::get_unsynced_objects(connection).await?.as_slice() {
+            [rejection_object] => match rejection_object.try_to_inner()? {
+                Payload::RegistrationRejection(rejection) => {
+                    assert_eq!(
+                        rejection.registration_request_object_id,
+                        registration_request_object.id
+                    );
+                    assert_eq!(rejection.reason, RegistrationRejectionReason::Expired);
+                }
+                payload => panic!("Expected a registration rejection, got {payload:?}"),
+            },
+            objects => panic!("Expected one unsynced object, got {objects:?}"),
+        }
+
+        Ok(())
+    }
+}
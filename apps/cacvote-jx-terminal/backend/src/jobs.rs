@@ -0,0 +1,1009 @@
+//! Background worker for long-running ElectionGuard operations.
+//!
+//! Starting a tally, decryption, or mix only records a [`cacvote::Job`]. The
+//! worker spawned by [`run_jobs_in_background`] picks jobs up one at a time and
+//! runs ElectionGuard without holding a database transaction open. The object a
+//! job produces is stored in the same transaction that marks the job
+//! succeeded, so a job either finishes completely or leaves nothing behind.
+//!
+//! ElectionGuard cannot be interrupted once it has started a step, so a
+//! cancelled job stops at its next progress report and anything it produced
+//! is discarded. A job that was running when the JX stopped is started again
+//! when the JX comes back, up to [`MAX_JOB_ATTEMPTS`] times, after which it
+//! fails. Jobs that unwrap an election's private key are the exception: an
+//! election official authorizes the unwrap when the job is started, and no
+//! official is present after a restart, so they fail instead.
+
+use std::{fmt, num::NonZeroUsize};
+
+use sqlx::PgPool;
+use tokio::time::sleep;
+use types_rs::{auth::User, cacvote};
+use uuid::Uuid;
+
+use crate::{
+    audit::Outcome,
+    auth::Operation,
+    config::{Config, JOB_POLL_INTERVAL},
+    db,
+};
+
+/// How many times a job may be started before it is failed rather than
+/// started again after a restart.
+pub(crate) const MAX_JOB_ATTEMPTS: u32 = 3;
+
+/// Jobs that unwrap the election's private key, which an election official
+/// authorizes only for the job they start. They are never run after a restart.
+const UNRESTARTABLE_JOB_KINDS: [cacvote::JobKind; 2] = [
+    cacvote::JobKind::DecryptTally,
+    cacvote::JobKind::DecryptMixedBallots,
+];
+
+/// A job along with what the worker needs to run it.
+#[derive(Debug, Clone)]
+pub(crate) struct JobRecord {
+    pub(crate) job: cacvote::Job,
+
+    /// Kind-specific parameters, e.g. [`cacvote::MixEncryptedBallotsRequest`].
+    pub(crate) parameters: serde_json::Value,
+
+    /// Who started the job, to whom its outcome is attributed.
+    pub(crate) operator: User,
+}
+
+/// The operation whose permission is needed to start or cancel a job.
+pub(crate) const fn operation(kind: cacvote::JobKind) -> Operation {
+    match kind {
+        cacvote::JobKind::GenerateEncryptedTally => Operation::GenerateEncryptedTally,
+        cacvote::JobKind::DecryptTally => Operation::DecryptTally,
+        cacvote::JobKind::MixBallots => Operation::MixBallots,
+        cacvote::JobKind::VerifyMixedBallots => Operation::VerifyMixedBallots,
+        cacvote::JobKind::DecryptMixedBallots => Operation::DecryptMixedBallots,
+    }
+}
+
+/// Why a job did not produce an object.
+#[derive(Debug)]
+enum JobError {
+    Cancelled,
+    Failed(String),
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => f.write_str("cancelled"),
+            Self::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+impl From<color_eyre::Report> for JobError {
+    fn from(error: color_eyre::Report) -> Self {
+        Self::Failed(format!("{error:#}"))
+    }
+}
+
+impl From<sqlx::Error> for JobError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Failed(error.to_string())
+    }
+}
+
+/// Spawns the worker that runs jobs. Jobs interrupted by a restart are dealt
+/// with first.
+pub(crate) fn run_jobs_in_background(pool: &PgPool, config: Config) {
+    let pool = pool.clone();
+
+    tokio::spawn(async move {
+        if let Err(e) = recover_interrupted_jobs(&pool).await {
+            tracing::error!("error recovering interrupted jobs: {e}");
+        }
+
+        loop {
+            let claimed = async {
+                let mut connection = pool.acquire().await?;
+                db::claim_next_job(&mut connection).await
+            }
+            .await;
+
+            match claimed {
+                Ok(Some(record)) => run_job(&pool, &config, record).await,
+                Ok(None) => sleep(JOB_POLL_INTERVAL).await,
+                Err(e) => {
+                    tracing::error!("error claiming next job: {e}");
+                    sleep(JOB_POLL_INTERVAL).await;
+                }
+            }
+        }
+    });
+}
+
+/// Returns jobs that were running when the JX stopped to the queue, or fails
+/// or cancels them if they cannot be run again. Jobs that unwrap a private key
+/// are failed even if they had not started, since the official who authorized
+/// them may no longer be present.
+async fn recover_interrupted_jobs(pool: &PgPool) -> color_eyre::Result<()> {
+    let mut transaction = pool.begin().await?;
+
+    for record in
+        db::recover_interrupted_jobs(&mut transaction, MAX_JOB_ATTEMPTS, &UNRESTARTABLE_JOB_KINDS)
+            .await?
+    {
+        let job = &record.job;
+        match job.status {
+            cacvote::JobStatus::Pending => {
+                tracing::info!("restarting {} job {} after a restart", job.kind, job.id);
+            }
+            _ => {
+                tracing::warn!(
+                    "{} job {} was {} after a restart",
+                    job.kind,
+                    job.id,
+                    job.status
+                );
+                record_outcome(&mut transaction, &record, None).await?;
+            }
+        }
+    }
+
+    transaction.commit().await?;
+    Ok(())
+}
+
+async fn run_job(pool: &PgPool, config: &Config, record: JobRecord) {
+    let job = &record.job;
+    tracing::info!(
+        "running {} job {} for election {} (attempt {})",
+        job.kind,
+        job.id,
+        job.election_object_id,
+        job.attempts
+    );
+
+    let progress = JobProgress {
+        pool: pool.clone(),
+        job_id: job.id,
+    };
+    let result = match job.kind {
+        cacvote::JobKind::GenerateEncryptedTally => {
+            generate_encrypted_tally(pool, config, &progress, job.election_object_id).await
+        }
+        cacvote::JobKind::DecryptTally => {
+            decrypt_tally(pool, config, &progress, job.election_object_id).await
+        }
+        cacvote::JobKind::MixBallots => {
+            match serde_json::from_value::<cacvote::MixEncryptedBallotsRequest>(
+                record.parameters.clone(),
+            ) {
+                Ok(cacvote::MixEncryptedBallotsRequest { phases }) => {
+                    mix_ballots(pool, config, &progress, job.election_object_id, phases).await
+                }
+                Err(e) => Err(JobError::Failed(format!("invalid job parameters: {e}"))),
+            }
+        }
+        cacvote::JobKind::VerifyMixedBallots => {
+            verify_mixed_ballots(pool, config, &progress, job.election_object_id).await
+        }
+        cacvote::JobKind::DecryptMixedBallots => {
+            decrypt_mixed_ballots(pool, config, &progress, job.election_object_id).await
+        }
+    };
+
+    let result = match result {
+        Ok(payload) => succeed_job(pool, config, &record, &payload).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(object_id) => {
+            tracing::info!("{} job {} produced object {object_id}", job.kind, job.id);
+        }
+        Err(error) => {
+            tracing::warn!("{} job {} stopped: {error}", job.kind, job.id);
+            if let Err(e) = stop_job(pool, &record, &error).await {
+                // the job is still running as far as the database knows, so it
+                // will be recovered after the next restart
+                tracing::error!("error recording that job {} stopped: {e}", job.id);
+            }
+        }
+    }
+}
+
+/// Signs and stores the object a job produced and marks the job succeeded,
+/// unless it was cancelled while it ran.
+async fn succeed_job(
+    pool: &PgPool,
+    config: &Config,
+    record: &JobRecord,
+    payload: &cacvote::Payload,
+) -> Result<Uuid, JobError> {
+    let mut transaction = pool.begin().await?;
+
+    // locks the job, so it cannot be cancelled once its object is stored
+    if db::is_job_cancel_requested(&mut transaction, record.job.id).await? {
+        return Err(JobError::Cancelled);
+    }
+
+    let serialized_payload = serde_json::to_vec(payload).map_err(color_eyre::Report::from)?;
+    let (signature, signing_cert) = config.sign(&serialized_payload)?;
+    let signed_object = cacvote::SignedObject {
+        id: Uuid::new_v4(),
+        election_id: Some(record.job.election_object_id),
+        payload: serialized_payload,
+        certificate: signing_cert.to_pem().map_err(color_eyre::Report::from)?,
+        certificate_chain: None,
+        signature,
+    };
+
+    db::add_object(&mut transaction, &signed_object).await?;
+    db::finish_job(
+        &mut transaction,
+        record.job.id,
+        cacvote::JobStatus::Succeeded,
+        Some(signed_object.id),
+        None,
+    )
+    .await?;
+    record_outcome(&mut transaction, record, Some(signed_object.id)).await?;
+    transaction.commit().await?;
+
+    Ok(signed_object.id)
+}
+
+/// Marks a job that did not produce an object as failed or cancelled.
+async fn stop_job(pool: &PgPool, record: &JobRecord, error: &JobError) -> color_eyre::Result<()> {
+    let mut transaction = pool.begin().await?;
+    let (status, error) = match error {
+        JobError::Cancelled => (cacvote::JobStatus::Cancelled, None),
+        JobError::Failed(error) => (cacvote::JobStatus::Failed, Some(error.as_str())),
+    };
+    let job = db::finish_job(&mut transaction, record.job.id, status, None, error).await?;
+    record_outcome(
+        &mut transaction,
+        &JobRecord {
+            job,
+            ..record.clone()
+        },
+        None,
+    )
+    .await?;
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Adds an audit log entry for a finished job, attributed to the operator who
+/// started it.
+async fn record_outcome(
+    connection: &mut sqlx::PgConnection,
+    record: &JobRecord,
+    result_object_id: Option<Uuid>,
+) -> color_eyre::Result<()> {
+    let job = &record.job;
+    let operation = operation(job.kind);
+    let (outcome, message) = match (result_object_id, &job.error) {
+        (Some(object_id), _) => (
+            Outcome::Success,
+            format!(
+                "{} job {} succeeded with object {object_id}",
+                operation.as_str(),
+                job.id
+            ),
+        ),
+        (None, Some(error)) => (
+            Outcome::Failure,
+            format!("{} job {} failed: {error}", operation.as_str(), job.id),
+        ),
+        (None, None) => (
+            Outcome::Failure,
+            format!("{} job {} was cancelled", operation.as_str(), job.id),
+        ),
+    };
+
+    db::add_audit_log_entry(
+        connection,
+        operation,
+        &record.operator,
+        result_object_id,
+        outcome,
+        &message,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Lets a running job report what it is doing, which also gives it a chance to
+/// stop if it has been cancelled.
+struct JobProgress {
+    pool: PgPool,
+    job_id: Uuid,
+}
+
+impl JobProgress {
+    async fn report(&self, progress: impl Into<String>) -> Result<(), JobError> {
+        let progress = progress.into();
+        tracing::debug!("job {}: {progress}", self.job_id);
+        let mut connection = self.pool.acquire().await?;
+        if db::set_job_progress(&mut connection, self.job_id, &progress).await? {
+            return Err(JobError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+/// Runs blocking ElectionGuard work off the async runtime.
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> color_eyre::Result<T> + Send + 'static,
+) -> Result<T, JobError> {
+    Ok(tokio::task::spawn_blocking(f)
+        .await
+        .map_err(color_eyre::Report::from)??)
+}
+
+async fn load_election(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+) -> Result<cacvote::Election, JobError> {
+    match db::get_object(connection, election_id)
+        .await?
+        .try_to_inner()
+        .map_err(color_eyre::Report::from)?
+    {
+        cacvote::Payload::Election(election) => Ok(election),
+        _ => Err(JobError::Failed("object is not an election".to_owned())),
+    }
+}
+
+async fn require_voting_closed(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+) -> Result<(), JobError> {
+    let state = db::get_election_lifecycle_state(connection, election_id).await?;
+    if !state.is_voting_closed() {
+        return Err(JobError::Failed(format!(
+            "voting must be closed first; the election is {state}"
+        )));
+    }
+    Ok(())
+}
+
+async fn generate_encrypted_tally(
+    pool: &PgPool,
+    config: &Config,
+    progress: &JobProgress,
+    election_id: Uuid,
+) -> Result<cacvote::Payload, JobError> {
+    progress.report("Loading cast ballots").await?;
+    let mut connection = pool.acquire().await?;
+    if !matches!(
+        db::get_tallies_for_election(&mut connection, election_id).await?,
+        db::ElectionTallies::Neither
+    ) {
+        return Err(JobError::Failed("tally already exists".to_owned()));
+    }
+    let election = load_election(&mut connection, election_id).await?;
+    require_voting_closed(&mut connection, election_id).await?;
+    let (cast_ballot_ids, cast_ballots): (Vec<_>, Vec<_>) =
+        db::get_cast_ballots_for_election(&mut connection, &election_id)
+            .await?
+            .into_iter()
+            .unzip();
+    drop(connection);
+
+    progress
+        .report(format!("Accumulating {} cast ballots", cast_ballots.len()))
+        .await?;
+    let classpath = config.eg_classpath.clone();
+    let metadata_blob = election.electionguard_election_metadata_blob.clone();
+    let encrypted_tally = run_blocking(move || {
+        Ok(electionguard_rs::tally::accumulate(
+            &classpath,
+            &metadata_blob,
+            cast_ballots
+                .iter()
+                .map(|cast_ballot| cast_ballot.electionguard_encrypted_ballot.as_slice()),
+        )?)
+    })
+    .await?;
+
+    Ok(cacvote::Payload::EncryptedElectionTally(
+        cacvote::EncryptedElectionTally {
+            election_object_id: election_id,
+            jurisdiction_code: election.jurisdiction_code,
+            electionguard_encrypted_tally: encrypted_tally,
+            cast_ballot_ids,
+        },
+    ))
+}
+
+async fn decrypt_tally(
+    pool: &PgPool,
+    config: &Config,
+    progress: &JobProgress,
+    election_id: Uuid,
+) -> Result<cacvote::Payload, JobError> {
+    progress.report("Loading encrypted tally").await?;
+    let mut connection = pool.acquire().await?;
+    let encrypted_tally = match db::get_tallies_for_election(&mut connection, election_id).await? {
+        db::ElectionTallies::OnlyEncrypted(encrypted_tally) => {
+            encrypted_tally
+                .encrypted_election_tally
+                .electionguard_encrypted_tally
+        }
+        db::ElectionTallies::Both(..) => {
+            return Err(JobError::Failed("tally already exists".to_owned()))
+        }
+        db::ElectionTallies::Neither => {
+            return Err(JobError::Failed("encrypted tally not found".to_owned()))
+        }
+    };
+    let election = load_election(&mut connection, election_id).await?;
+
+    // an official authorized unwrapping the key when the job was started, and
+    // the job is not run again after a restart
+    let wrapped_private_key = db::get_eg_private_key(&mut connection, &election_id).await?;
+    drop(connection);
+    let private_key = config
+        .key_encryption_keys()?
+        .unwrap_key(&wrapped_private_key, election_id.as_bytes())?;
+
+    progress.report("Decrypting tally").await?;
+    let classpath = config.eg_classpath.clone();
+    let election_config = electionguard_rs::config::ElectionConfig {
+        public_metadata_blob: election.electionguard_election_metadata_blob.clone(),
+        private_metadata_blob: private_key,
+    };
+    let decrypted_tally = run_blocking(move || {
+        Ok(electionguard_rs::tally::decrypt(
+            &classpath,
+            &election_config,
+            &encrypted_tally,
+        )?)
+    })
+    .await?;
+
+    Ok(cacvote::Payload::DecryptedElectionTally(
+        cacvote::DecryptedElectionTally {
+            election_object_id: election_id,
+            jurisdiction_code: election.jurisdiction_code,
+            electionguard_decrypted_tally: decrypted_tally,
+        },
+    ))
+}
+
+async fn mix_ballots(
+    pool: &PgPool,
+    config: &Config,
+    progress: &JobProgress,
+    election_id: Uuid,
+    phases: NonZeroUsize,
+) -> Result<cacvote::Payload, JobError> {
+    progress.report("Loading cast ballots").await?;
+    let mut connection = pool.acquire().await?;
+    let election = load_election(&mut connection, election_id).await?;
+    require_voting_closed(&mut connection, election_id).await?;
+    let cast_ballots = db::get_cast_ballots_for_election(&mut connection, &election_id).await?;
+    drop(connection);
+
+    progress
+        .report(format!(
+            "Mixing {} cast ballots through {phases} phases",
+            cast_ballots.len()
+        ))
+        .await?;
+    let classpath = config.eg_classpath.clone();
+    let metadata_blob = election.electionguard_election_metadata_blob.clone();
+    let shuffled_ballots = run_blocking(move || {
+        Ok(electionguard_rs::mixnet::mix(
+            &classpath,
+            &metadata_blob,
+            cast_ballots
+                .iter()
+                .map(|(_, cast_ballot)| cast_ballot.electionguard_encrypted_ballot.as_slice()),
+            phases,
+        )?)
+    })
+    .await?;
+
+    Ok(cacvote::Payload::ShuffledEncryptedCastBallots(
+        cacvote::ShuffledEncryptedCastBallots {
+            election_object_id: election_id,
+            jurisdiction_code: election.jurisdiction_code,
+            electionguard_shuffled_ballots: shuffled_ballots,
+        },
+    ))
+}
+
+/// Checks the proof of shuffle of every phase of an election's mixed ballots.
+/// The job succeeds whether or not the proofs hold, since its result records
+/// which phases did not.
+async fn verify_mixed_ballots(
+    pool: &PgPool,
+    config: &Config,
+    progress: &JobProgress,
+    election_id: Uuid,
+) -> Result<cacvote::Payload, JobError> {
+    progress.report("Loading mixed ballots").await?;
+    let mut connection = pool.acquire().await?;
+    let election = load_election(&mut connection, election_id).await?;
+    let Some((shuffled_ballots_id, shuffled_ballots)) =
+        db::get_shuffled_encrypted_cast_ballots_object(&mut connection, election_id).await?
+    else {
+        return Err(JobError::Failed(
+            "the ballots have not been mixed".to_owned(),
+        ));
+    };
+    let cast_ballots = db::get_cast_ballots_for_election(&mut connection, &election_id).await?;
+    drop(connection);
+
+    progress
+        .report(format!(
+            "Verifying proofs of shuffle for {} cast ballots",
+            cast_ballots.len()
+        ))
+        .await?;
+    let cast_ballot_count = cast_ballots.len() as u64;
+    let classpath = config.eg_classpath.clone();
+    let metadata_blob = election.electionguard_election_metadata_blob.clone();
+    let phases = run_blocking(move || {
+        Ok(electionguard_rs::mixnet::verify_mix(
+            &classpath,
+            &metadata_blob,
+            cast_ballots
+                .iter()
+                .map(|(_, cast_ballot)| cast_ballot.electionguard_encrypted_ballot.as_slice()),
+            &shuffled_ballots.electionguard_shuffled_ballots,
+        )?)
+    })
+    .await?;
+
+    let verification = cacvote::ShuffledEncryptedCastBallotsVerification {
+        jurisdiction_code: election.jurisdiction_code,
+        election_object_id: election_id,
+        shuffled_encrypted_cast_ballots_object_id: shuffled_ballots_id,
+        cast_ballot_count,
+        phases,
+    };
+    if !verification.is_verified() {
+        tracing::warn!(
+            "mixed ballots {shuffled_ballots_id} for election {election_id} failed verification"
+        );
+    }
+
+    Ok(cacvote::Payload::ShuffledEncryptedCastBallotsVerification(
+        verification,
+    ))
+}
+
+/// Decrypts the ballots output by the final phase of an election's mixed
+/// ballots with the election's trustee key. The mix must have been verified
+/// first, since a mix that did not hide which voter cast which ballot would
+/// reveal how they voted.
+async fn decrypt_mixed_ballots(
+    pool: &PgPool,
+    config: &Config,
+    progress: &JobProgress,
+    election_id: Uuid,
+) -> Result<cacvote::Payload, JobError> {
+    progress.report("Loading mixed ballots").await?;
+    let mut connection = pool.acquire().await?;
+    let election = load_election(&mut connection, election_id).await?;
+    if db::get_decrypted_shuffled_ballots(&mut connection, election_id)
+        .await?
+        .is_some()
+    {
+        return Err(JobError::Failed(
+            "the mixed ballots have already been decrypted".to_owned(),
+        ));
+    }
+    let Some((shuffled_ballots_id, shuffled_ballots)) =
+        db::get_shuffled_encrypted_cast_ballots_object(&mut connection, election_id).await?
+    else {
+        return Err(JobError::Failed(
+            "the ballots have not been mixed".to_owned(),
+        ));
+    };
+    if !db::get_latest_shuffled_encrypted_cast_ballots_verification(&mut connection, election_id)
+        .await?
+        .is_some_and(|verification| {
+            let verification = verification.shuffled_encrypted_cast_ballots_verification;
+            verification.shuffled_encrypted_cast_ballots_object_id == shuffled_ballots_id
+                && verification.is_verified()
+        })
+    {
+        return Err(JobError::Failed(
+            "the mixed ballots have not been verified".to_owned(),
+        ));
+    }
+
+    // an official authorized unwrapping the key when the job was started, and
+    // the job is not run again after a restart
+    let wrapped_private_key = db::get_eg_private_key(&mut connection, &election_id).await?;
+    drop(connection);
+    let private_key = config
+        .key_encryption_keys()?
+        .unwrap_key(&wrapped_private_key, election_id.as_bytes())?;
+
+    progress.report("Decrypting mixed ballots").await?;
+    let classpath = config.eg_classpath.clone();
+    let election_config = electionguard_rs::config::ElectionConfig {
+        public_metadata_blob: election.electionguard_election_metadata_blob.clone(),
+        private_metadata_blob: private_key,
+    };
+    let vx_election = election.election_definition.election.clone();
+    let (decrypted_ballots, ballots) = run_blocking(move || {
+        let decrypted_ballots = electionguard_rs::mixnet::decrypt_mixed_ballots(
+            &classpath,
+            &election_config,
+            &shuffled_ballots.electionguard_shuffled_ballots,
+        )?;
+        let ballots = electionguard_rs::mixnet::read_decrypted_ballots(&decrypted_ballots)?
+            .iter()
+            .map(|ballot| {
+                electionguard_rs::decrypted::convert_eg_decrypted_ballot_to_vx(ballot, &vx_election)
+            })
+            .collect::<color_eyre::Result<Vec<_>>>()?;
+        Ok((decrypted_ballots, ballots))
+    })
+    .await?;
+
+    Ok(cacvote::Payload::DecryptedShuffledBallots(
+        cacvote::DecryptedShuffledBallots {
+            jurisdiction_code: election.jurisdiction_code,
+            election_object_id: election_id,
+            shuffled_encrypted_cast_ballots_object_id: shuffled_ballots_id,
+            ballots,
+            electionguard_decrypted_ballots: decrypted_ballots,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use cacvote_server_client::{sealed::SealedSecret, signer};
+    use openssl::{pkey::PKey, x509::X509};
+    use tracing::Level;
+    use types_rs::{
+        auth::ElectionManagerUser, cacvote::JurisdictionCode, election::ElectionDefinition,
+    };
+
+    use crate::audit::AuditLogEntry;
+
+    use super::*;
+
+    fn dev_cert_path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../libs/auth/certs/dev")
+            .join(name)
+    }
+
+    /// A config that signs job results with the dev VxAdmin key. ElectionGuard
+    /// is not available, so any job that gets as far as running it fails.
+    fn dev_config() -> Config {
+        Config {
+            cacvote_url: "http://localhost:0".parse().unwrap(),
+            database_url: "".to_owned(),
+            machine_id: "".to_owned(),
+            port: 0,
+            public_dir: None,
+            log_level: Level::DEBUG,
+            machine_cert: dev_cert_path("vx-admin-cert-authority-cert.pem"),
+            vx_cert_authority_cert: dev_cert_path("vx-cert-authority-cert.pem"),
+            cac_root_ca_certs: vec![dev_cert_path("vx-cert-authority-cert.pem")],
+            cac_intermediate_ca_certs_dir: None,
+            signer: signer::Description::File(dev_cert_path("vx-admin-private-key.pem")),
+            eg_classpath: PathBuf::from("/not/real/path"),
+            eg_key_encryption_key: SealedSecret::Tpm(0x8100_0002),
+            eg_retired_key_encryption_keys: vec![],
+            inactivity_timeout_seconds: 0,
+            max_incorrect_pin_attempts: 5,
+            pin_lockout_seconds: 15,
+            registration_request_expiry_days: 0,
+        }
+    }
+
+    fn election_manager() -> User {
+        User::ElectionManager(ElectionManagerUser::new(
+            JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            "election-hash".to_owned(),
+        ))
+    }
+
+    /// Adds an election whose voting has not closed yet, returning its ID.
+    async fn add_election(connection: &mut sqlx::PgConnection) -> color_eyre::Result<Uuid> {
+        let certificate = X509::from_pem(&std::fs::read(dev_cert_path(
+            "vx-admin-cert-authority-cert.pem",
+        ))?)?;
+        let private_key =
+            PKey::private_key_from_pem(&std::fs::read(dev_cert_path("vx-admin-private-key.pem"))?)?;
+        let election_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::Election(cacvote::Election {
+                jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+                election_definition: ElectionDefinition::try_from(
+                    &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+                )?,
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![],
+                voting_starts_at: None,
+                voting_ends_at: None,
+                ballot_receipt_deadline: None,
+            }),
+            certificate,
+            &private_key,
+        )?;
+        db::add_object_from_server(connection, &election_object).await
+    }
+
+    async fn add_job(
+        connection: &mut sqlx::PgConnection,
+        kind: cacvote::JobKind,
+        election_id: Uuid,
+    ) -> color_eyre::Result<Uuid> {
+        Ok(db::add_job(
+            connection,
+            kind,
+            election_id,
+            &serde_json::json!({}),
+            &election_manager(),
+        )
+        .await?
+        .expect("job should be added"))
+    }
+
+    async fn claim_next_job(connection: &mut sqlx::PgConnection) -> color_eyre::Result<JobRecord> {
+        Ok(db::claim_next_job(connection)
+            .await?
+            .expect("a pending job"))
+    }
+
+    fn encrypted_tally(election_id: Uuid) -> cacvote::Payload {
+        cacvote::Payload::EncryptedElectionTally(cacvote::EncryptedElectionTally {
+            election_object_id: election_id,
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            electionguard_encrypted_tally: b"encrypted tally".to_vec(),
+            cast_ballot_ids: vec![],
+        })
+    }
+
+    fn audit_outcomes(entries: &[AuditLogEntry]) -> Vec<(Operation, Option<Uuid>, Outcome)> {
+        entries
+            .iter()
+            .map(|entry| (entry.operation, entry.object_id, entry.outcome))
+            .collect()
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_succeed_job(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let config = dev_config();
+        let connection = &mut pool.acquire().await?;
+        let election_id = add_election(connection).await?;
+        let job_id = add_job(
+            connection,
+            cacvote::JobKind::GenerateEncryptedTally,
+            election_id,
+        )
+        .await?;
+
+        let record = claim_next_job(connection).await?;
+        assert_eq!(record.job.id, job_id);
+        let object_id = succeed_job(&pool, &config, &record, &encrypted_tally(election_id))
+            .await
+            .unwrap();
+
+        // the object is signed and stored along with the job's success
+        let object = db::get_object(connection, object_id).await?;
+        assert_eq!(object.election_id, Some(election_id));
+        assert!(matches!(
+            object.try_to_inner()?,
+            cacvote::Payload::EncryptedElectionTally(tally)
+                if tally.electionguard_encrypted_tally == b"encrypted tally"
+        ));
+        let job = db::get_job(connection, job_id).await?.unwrap();
+        assert_eq!(job.status, cacvote::JobStatus::Succeeded);
+        assert_eq!(job.result_object_id, Some(object_id));
+        assert!(job.finished_at.is_some());
+
+        let entries = db::get_audit_log_entries(connection).await?;
+        assert_eq!(
+            audit_outcomes(&entries),
+            vec![(
+                Operation::GenerateEncryptedTally,
+                Some(object_id),
+                Outcome::Success
+            )]
+        );
+        assert_eq!(entries[0].user, election_manager());
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_run_job_records_failure(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let config = dev_config();
+        let connection = &mut pool.acquire().await?;
+        let election_id = add_election(connection).await?;
+        let job_id = add_job(
+            connection,
+            cacvote::JobKind::GenerateEncryptedTally,
+            election_id,
+        )
+        .await?;
+
+        // voting is still open, so the job fails before it gets to ElectionGuard
+        let record = claim_next_job(connection).await?;
+        run_job(&pool, &config, record).await;
+
+        let job = db::get_job(connection, job_id).await?.unwrap();
+        assert_eq!(job.status, cacvote::JobStatus::Failed);
+        assert_eq!(job.result_object_id, None);
+        assert!(
+            job.error
+                .as_deref()
+                .is_some_and(|error| error.contains("voting must be closed first")),
+            "unexpected error: {:?}",
+            job.error
+        );
+        assert!(matches!(
+            db::get_tallies_for_election(connection, election_id).await?,
+            db::ElectionTallies::Neither
+        ));
+        assert_eq!(
+            audit_outcomes(&db::get_audit_log_entries(connection).await?),
+            vec![(Operation::GenerateEncryptedTally, None, Outcome::Failure)]
+        );
+
+        // a failed job does not stop another of the same kind from starting
+        add_job(
+            connection,
+            cacvote::JobKind::GenerateEncryptedTally,
+            election_id,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_cancel_running_job(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let config = dev_config();
+        let connection = &mut pool.acquire().await?;
+        let election_id = add_election(connection).await?;
+
+        // a job learns it was cancelled at its next progress report
+        let job_id = add_job(connection, cacvote::JobKind::MixBallots, election_id).await?;
+        let record = claim_next_job(connection).await?;
+        let progress = JobProgress {
+            pool: pool.clone(),
+            job_id,
+        };
+        progress.report("Mixing").await.unwrap();
+        assert_eq!(
+            db::request_job_cancellation(connection, job_id).await?,
+            Some(cacvote::JobStatus::Running)
+        );
+        let error = progress.report("Still mixing").await.unwrap_err();
+        assert!(matches!(error, JobError::Cancelled));
+        stop_job(&pool, &record, &error).await?;
+
+        let job = db::get_job(connection, job_id).await?.unwrap();
+        assert_eq!(job.status, cacvote::JobStatus::Cancelled);
+        assert_eq!(job.error, None);
+
+        // one cancelled after its work is done stores nothing
+        let job_id = add_job(
+            connection,
+            cacvote::JobKind::GenerateEncryptedTally,
+            election_id,
+        )
+        .await?;
+        let record = claim_next_job(connection).await?;
+        db::request_job_cancellation(connection, job_id).await?;
+        let error = succeed_job(&pool, &config, &record, &encrypted_tally(election_id))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, JobError::Cancelled));
+        stop_job(&pool, &record, &error).await?;
+
+        let job = db::get_job(connection, job_id).await?.unwrap();
+        assert_eq!(job.status, cacvote::JobStatus::Cancelled);
+        assert_eq!(job.result_object_id, None);
+        assert!(matches!(
+            db::get_tallies_for_election(connection, election_id).await?,
+            db::ElectionTallies::Neither
+        ));
+
+        let entries = db::get_audit_log_entries(connection).await?;
+        assert_eq!(
+            audit_outcomes(&entries),
+            vec![
+                (Operation::MixBallots, None, Outcome::Failure),
+                (Operation::GenerateEncryptedTally, None, Outcome::Failure),
+            ]
+        );
+        assert!(entries
+            .iter()
+            .all(|entry| entry.message.ends_with("was cancelled")));
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_recover_interrupted_jobs(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let connection = &mut pool.acquire().await?;
+        let election_id = add_election(connection).await?;
+
+        // the JX stops while each of these jobs is running
+        let tally_job_id = add_job(
+            connection,
+            cacvote::JobKind::GenerateEncryptedTally,
+            election_id,
+        )
+        .await?;
+        let mix_job_id = add_job(connection, cacvote::JobKind::MixBallots, election_id).await?;
+        claim_next_job(connection).await?;
+        claim_next_job(connection).await?;
+        db::request_job_cancellation(connection, mix_job_id).await?;
+
+        recover_interrupted_jobs(&pool).await?;
+
+        // the cancelled job stays stopped, and the other is run again
+        let mix_job = db::get_job(connection, mix_job_id).await?.unwrap();
+        assert_eq!(mix_job.status, cacvote::JobStatus::Cancelled);
+        let tally_job = db::get_job(connection, tally_job_id).await?.unwrap();
+        assert_eq!(tally_job.status, cacvote::JobStatus::Pending);
+        assert_eq!(tally_job.attempts, 1);
+        assert_eq!(
+            audit_outcomes(&db::get_audit_log_entries(connection).await?),
+            vec![(Operation::MixBallots, None, Outcome::Failure)]
+        );
+
+        // jobs that unwrap the private key are not run again, whether they had
+        // started or not
+        let running_decrypt_job_id =
+            add_job(connection, cacvote::JobKind::DecryptTally, election_id).await?;
+        let record = claim_next_job(connection).await?;
+        assert_eq!(record.job.id, tally_job_id);
+        let record = claim_next_job(connection).await?;
+        assert_eq!(record.job.id, running_decrypt_job_id);
+        let pending_decrypt_job_id = add_job(
+            connection,
+            cacvote::JobKind::DecryptMixedBallots,
+            election_id,
+        )
+        .await?;
+        recover_interrupted_jobs(&pool).await?;
+        for job_id in [running_decrypt_job_id, pending_decrypt_job_id] {
+            let job = db::get_job(connection, job_id).await?.unwrap();
+            assert_eq!(job.status, cacvote::JobStatus::Failed);
+            assert!(job.error.is_some());
+        }
+        let outcomes = audit_outcomes(&db::get_audit_log_entries(connection).await?);
+        assert_eq!(outcomes.len(), 3);
+        for operation in [Operation::DecryptTally, Operation::DecryptMixedBallots] {
+            assert!(outcomes.contains(&(operation, None, Outcome::Failure)));
+        }
+
+        // other jobs are run again until they have been started too many times
+        for attempt in 3..=MAX_JOB_ATTEMPTS {
+            let record = claim_next_job(connection).await?;
+            assert_eq!(record.job.id, tally_job_id);
+            assert_eq!(record.job.attempts, attempt);
+            recover_interrupted_jobs(&pool).await?;
+        }
+
+        let tally_job = db::get_job(connection, tally_job_id).await?.unwrap();
+        assert_eq!(tally_job.status, cacvote::JobStatus::Failed);
+        assert!(tally_job.error.is_some());
+        assert!(db::claim_next_job(connection).await?.is_none());
+        let outcomes = audit_outcomes(&db::get_audit_log_entries(connection).await?);
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(
+            outcomes.last(),
+            Some(&(Operation::GenerateEncryptedTally, None, Outcome::Failure))
+        );
+
+        Ok(())
+    }
+}
@@ -6,15 +6,33 @@
 //!
 //! You may use the `RUST_LOG` environment variable to configure logging at
 //! runtime (see [`EnvFilter`][`tracing_subscriber::EnvFilter`]).
+//!
+//! Events that VotingWorks logs track, such as PIN lockouts, are additionally
+//! logged in the VotingWorks log format via [`vx_logging`].
 
 use tracing_subscriber::{prelude::*, util::SubscriberInitExt};
+use types_rs::auth::User;
 
 use crate::config::Config;
 
+/// The `source` of VotingWorks logs from this application.
+pub(crate) const APP_NAME: &str = "cacvote-jx-terminal";
+
+/// Maps a card user to the user recorded in VotingWorks logs.
+pub(crate) const fn vx_user(user: &User) -> vx_logging::User {
+    match user {
+        User::SystemAdministrator(_) => vx_logging::User::SystemAdministrator,
+        User::ElectionManager(_) => vx_logging::User::ElectionManager,
+        User::PollWorker(_) => vx_logging::User::PollWorker,
+        User::CardlessVoter(_) => vx_logging::User::CardlessVoter,
+    }
+}
+
 /// Sets up logging for the application. Call this early in the process
 /// lifecycle to ensure logs are not silently ignored.
 pub(crate) fn setup(config: &Config) -> color_eyre::Result<()> {
     color_eyre::install()?;
+    vx_logging::set_app_name(APP_NAME);
     let stdout_log = tracing_subscriber::fmt::layer().pretty();
     tracing_subscriber::registry()
         .with(
@@ -44,12 +44,18 @@
 use clap::Parser;
 
 mod app;
+mod audit;
+mod auth;
 mod cac;
 mod config;
 mod db;
+mod expiry;
+mod jobs;
 mod log;
+mod registrations;
 mod session_manager;
 mod sync;
+mod tally_report;
 
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
@@ -61,5 +67,7 @@ async fn main() -> color_eyre::Result<()> {
     tracing::info!("Starting CACvote JX with config: {config:#?}");
     let pool = db::setup(&config).await?;
     sync::sync_periodically(&pool, config.clone()).await;
+    expiry::expire_periodically(&pool, config.clone());
+    jobs::run_jobs_in_background(&pool, config.clone());
     app::run(app::setup(pool, config.clone()), &config).await
 }
@@ -0,0 +1,903 @@
+//! Validation of voter registrations, individually or in bulk.
+//!
+//! A registration assigns a pending registration request to an election,
+//! precinct, and ballot style. Every registration is checked against the
+//! election definition before it is signed, whether it comes from a single
+//! approval or from a bulk import. When the precinct or ballot style is left
+//! out, it is worked out from the voter's address and the jurisdiction's
+//! street segments.
+
+use std::collections::{HashMap, HashSet};
+
+use axum::{http::StatusCode, Json};
+use color_eyre::eyre::bail;
+use openssl::x509::X509;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use time::format_description::well_known::Rfc3339;
+use types_rs::{
+    auth::User,
+    cacvote::{self, JurisdictionCode},
+    election::{BallotStyleId, PrecinctId},
+};
+use uuid::Uuid;
+
+use crate::auth;
+use crate::db;
+
+/// Why a registration may not be created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RegistrationError {
+    pub(crate) status: StatusCode,
+    pub(crate) message: String,
+}
+
+impl RegistrationError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Logs `error` and hides its details from the response.
+    fn internal(message: &str, error: impl std::fmt::Display) -> Self {
+        tracing::error!("{message}: {error}");
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    pub(crate) fn into_response(self) -> (StatusCode, Json<Value>) {
+        (self.status, Json(json!({ "error": self.message })))
+    }
+}
+
+/// Loads the election a registration is for, checking that it belongs to
+/// this jurisdiction.
+pub(crate) async fn load_election(
+    connection: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    election_id: Uuid,
+) -> Result<cacvote::Election, RegistrationError> {
+    let election_object = db::get_object(connection, election_id).await.map_err(|e| {
+        tracing::error!("error getting election from database: {e}");
+        RegistrationError::new(
+            StatusCode::NOT_FOUND,
+            format!("election {election_id} not found"),
+        )
+    })?;
+
+    let election = match election_object.try_to_inner() {
+        Ok(cacvote::Payload::Election(election)) => election,
+        Ok(_) => {
+            return Err(RegistrationError::new(
+                StatusCode::BAD_REQUEST,
+                format!("object {election_id} is not an election"),
+            ))
+        }
+        Err(e) => {
+            return Err(RegistrationError::internal(
+                "error deserializing election",
+                e,
+            ))
+        }
+    };
+
+    if &election.jurisdiction_code != jurisdiction_code {
+        return Err(RegistrationError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "election is for jurisdiction {}, not {jurisdiction_code}",
+                election.jurisdiction_code
+            ),
+        ));
+    }
+
+    Ok(election)
+}
+
+/// Fills in whichever of the precinct and ballot style the caller left out. A
+/// missing precinct is found from the voter's address using the street
+/// segments, and a missing ballot style is the only one used in the precinct.
+pub(crate) fn resolve_assignment(
+    election: &cacvote::Election,
+    street_segments: &[cacvote::StreetSegment],
+    address: Option<&cacvote::VoterAddress>,
+    ballot_style_id: Option<BallotStyleId>,
+    precinct_id: Option<PrecinctId>,
+) -> Result<(BallotStyleId, PrecinctId), String> {
+    let precinct_id = match precinct_id {
+        Some(precinct_id) => precinct_id,
+        None => {
+            let Some(address) = address else {
+                return Err(
+                    "precinct is required because the registration request has no address"
+                        .to_owned(),
+                );
+            };
+            cacvote::find_precinct(street_segments, address)
+                .cloned()
+                .ok_or_else(|| format!("no single precinct found for address {address}"))?
+        }
+    };
+
+    let ballot_style_id = match ballot_style_id {
+        Some(ballot_style_id) => ballot_style_id,
+        None => match election
+            .election
+            .ballot_styles_for_precinct(&precinct_id)
+            .as_slice()
+        {
+            [ballot_style] => ballot_style.id.clone(),
+            [] => return Err(format!("no ballot style is used in precinct {precinct_id}")),
+            _ => {
+                return Err(format!(
+                    "multiple ballot styles are used in precinct {precinct_id}; specify one"
+                ))
+            }
+        },
+    };
+
+    Ok((ballot_style_id, precinct_id))
+}
+
+/// Suggests a precinct and ballot style for the registration request in each
+/// election, for elections where its address determines both.
+pub(crate) fn suggest_assignments(
+    elections: &[cacvote::ElectionPresenter],
+    street_segments: &[cacvote::StreetSegment],
+    registration_request: &cacvote::RegistrationRequestPresenter,
+) -> Vec<cacvote::SuggestedAssignment> {
+    let Some(address) = &registration_request.address else {
+        return Vec::new();
+    };
+
+    elections
+        .iter()
+        .filter(|election| election.jurisdiction_code == registration_request.jurisdiction_code)
+        .filter_map(|election| {
+            let (ballot_style_id, precinct_id) =
+                resolve_assignment(election, street_segments, Some(address), None, None).ok()?;
+            Some(cacvote::SuggestedAssignment {
+                election_id: election.id,
+                precinct_id,
+                ballot_style_id,
+            })
+        })
+        .collect()
+}
+
+/// Checks that the registration request may be registered for `election`
+/// with the given precinct and ballot style, returning the registration to
+/// sign if so. See [`resolve_assignment`] for how missing values are filled
+/// in.
+pub(crate) async fn validate_registration(
+    connection: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    registration_request_id: Uuid,
+    election_id: Uuid,
+    election: &cacvote::Election,
+    ballot_style_id: Option<BallotStyleId>,
+    precinct_id: Option<PrecinctId>,
+) -> Result<cacvote::Registration, RegistrationError> {
+    let registration_request = db::get_registration_request(connection, registration_request_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("error getting registration request from database: {e}");
+            RegistrationError::new(
+                StatusCode::NOT_FOUND,
+                format!("registration request {registration_request_id} not found"),
+            )
+        })?;
+
+    if &registration_request.jurisdiction_code != jurisdiction_code {
+        return Err(RegistrationError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "registration request is for jurisdiction {}, not {jurisdiction_code}",
+                registration_request.jurisdiction_code
+            ),
+        ));
+    }
+
+    if db::is_registration_request_rejected(connection, registration_request_id)
+        .await
+        .map_err(|e| RegistrationError::internal("error checking for registration rejection", e))?
+    {
+        return Err(RegistrationError::new(
+            StatusCode::CONFLICT,
+            format!("registration request {registration_request_id} was rejected"),
+        ));
+    }
+
+    let street_segments = if precinct_id.is_none() {
+        db::get_street_segments(connection)
+            .await
+            .map_err(|e| RegistrationError::internal("error getting street segments", e))?
+    } else {
+        Vec::new()
+    };
+
+    let (ballot_style_id, precinct_id) = resolve_assignment(
+        election,
+        &street_segments,
+        registration_request.address.as_ref(),
+        ballot_style_id,
+        precinct_id,
+    )
+    .map_err(|e| RegistrationError::new(StatusCode::BAD_REQUEST, e))?;
+
+    election
+        .election
+        .check_ballot_style_assignment(&ballot_style_id, &precinct_id)
+        .map_err(|e| RegistrationError::new(StatusCode::BAD_REQUEST, e))?;
+
+    if db::has_registration_for_election(connection, registration_request_id, election_id)
+        .await
+        .map_err(|e| RegistrationError::internal("error checking for existing registration", e))?
+    {
+        return Err(RegistrationError::new(
+            StatusCode::CONFLICT,
+            format!(
+                "registration request {registration_request_id} is already registered for election {election_id}"
+            ),
+        ));
+    }
+
+    Ok(cacvote::Registration {
+        common_access_card_id: registration_request.common_access_card_id,
+        jurisdiction_code: jurisdiction_code.clone(),
+        registration_request_object_id: registration_request_id,
+        election_object_id: election_id,
+        ballot_style_id,
+        precinct_id,
+    })
+}
+
+/// One row of a bulk registration import. The registration request is named
+/// either by its ID or by the CAC ID of its only pending request. The ballot
+/// style and precinct may be left blank as for a single registration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegistrationImportRow {
+    #[serde(default)]
+    pub(crate) registration_request_id: Option<Uuid>,
+    #[serde(default)]
+    pub(crate) common_access_card_id: Option<String>,
+    pub(crate) election_id: Uuid,
+    #[serde(default)]
+    pub(crate) ballot_style_id: Option<BallotStyleId>,
+    #[serde(default)]
+    pub(crate) precinct_id: Option<PrecinctId>,
+}
+
+/// Parses import rows from a CSV file with a header row, or from a JSON
+/// array.
+pub(crate) fn parse_import_rows(
+    is_csv: bool,
+    body: &[u8],
+) -> color_eyre::Result<Vec<RegistrationImportRow>> {
+    if is_csv {
+        Ok(csv::Reader::from_reader(body)
+            .deserialize()
+            .collect::<Result<_, _>>()?)
+    } else {
+        Ok(serde_json::from_slice(body)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegistrationImportReport {
+    pub(crate) dry_run: bool,
+    pub(crate) rows: Vec<RegistrationImportRowResult>,
+}
+
+impl RegistrationImportReport {
+    pub(crate) fn invalid_row_count(&self) -> usize {
+        self.rows.iter().filter(|row| row.error.is_some()).count()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegistrationImportRowResult {
+    /// 1-based position of the row in the import, not counting any header.
+    pub(crate) row: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) registration_request_id: Option<Uuid>,
+    /// The registration created for this row, if the import was applied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) registration_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+/// Validates every row and, unless this is a dry run or any row is invalid,
+/// signs and stores a registration for each one. Run it in a transaction so
+/// that either every registration is stored or none are.
+pub(crate) async fn import(
+    connection: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    user: &User,
+    rows: Vec<RegistrationImportRow>,
+    dry_run: bool,
+    sign: impl Fn(&[u8]) -> color_eyre::Result<(Vec<u8>, X509)>,
+) -> color_eyre::Result<RegistrationImportReport> {
+    let pending_registration_requests = db::get_pending_registration_requests(connection).await?;
+    let mut elections: HashMap<Uuid, Result<cacvote::Election, RegistrationError>> = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut results = Vec::with_capacity(rows.len());
+    let mut registrations = Vec::with_capacity(rows.len());
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let registration_request_id = match (
+            row.registration_request_id,
+            &row.common_access_card_id,
+        ) {
+            (Some(registration_request_id), _) => Ok(registration_request_id),
+            (None, Some(common_access_card_id)) => {
+                let mut matches = pending_registration_requests
+                    .iter()
+                    .filter(|request| &request.common_access_card_id == common_access_card_id);
+                match (matches.next(), matches.next()) {
+                    (Some(request), None) => Ok(request.id),
+                    (None, _) => Err(format!(
+                        "no pending registration request for CAC ID {common_access_card_id}"
+                    )),
+                    (Some(_), Some(_)) => Err(format!(
+                        "multiple pending registration requests for CAC ID {common_access_card_id}; specify registrationRequestId"
+                    )),
+                }
+            }
+            (None, None) => {
+                Err("either registrationRequestId or commonAccessCardId is required".to_owned())
+            }
+        };
+
+        let registration = match &registration_request_id {
+            Ok(registration_request_id) => {
+                validate_import_row(
+                    connection,
+                    jurisdiction_code,
+                    user,
+                    &mut elections,
+                    &mut seen,
+                    *registration_request_id,
+                    row,
+                )
+                .await?
+            }
+            Err(e) => Err(e.clone()),
+        };
+
+        results.push(RegistrationImportRowResult {
+            row: index + 1,
+            registration_request_id: registration_request_id.ok(),
+            registration_id: None,
+            error: registration.as_ref().err().cloned(),
+        });
+        registrations.push(registration.ok());
+    }
+
+    let mut report = RegistrationImportReport {
+        dry_run,
+        rows: results,
+    };
+
+    if dry_run || report.invalid_row_count() > 0 {
+        return Ok(report);
+    }
+
+    for (result, registration) in report.rows.iter_mut().zip(registrations) {
+        let Some(registration) = registration else {
+            bail!("row {} has no registration", result.row);
+        };
+        let election_id = registration.election_object_id;
+        let payload = serde_json::to_vec(&cacvote::Payload::Registration(registration))?;
+        let (signature, certificate) = sign(&payload)?;
+        let signed_object = cacvote::SignedObject {
+            id: Uuid::new_v4(),
+            election_id: Some(election_id),
+            payload,
+            certificate: certificate.to_pem()?,
+            certificate_chain: None,
+            signature,
+        };
+        db::add_object(connection, &signed_object).await?;
+        result.registration_id = Some(signed_object.id);
+    }
+
+    Ok(report)
+}
+
+/// Validates one import row, returning the registration to sign or why the
+/// row is invalid. Fails only for errors unrelated to the row itself.
+async fn validate_import_row(
+    connection: &mut sqlx::PgConnection,
+    jurisdiction_code: &JurisdictionCode,
+    user: &User,
+    elections: &mut HashMap<Uuid, Result<cacvote::Election, RegistrationError>>,
+    seen: &mut HashSet<(Uuid, Uuid)>,
+    registration_request_id: Uuid,
+    row: RegistrationImportRow,
+) -> color_eyre::Result<Result<cacvote::Registration, String>> {
+    let election = match elections.get(&row.election_id) {
+        Some(election) => election.clone(),
+        None => {
+            let election = load_election(connection, jurisdiction_code, row.election_id)
+                .await
+                .and_then(|election| {
+                    if auth::is_election_permitted_for(
+                        user,
+                        &election.election_definition.election_hash,
+                    ) {
+                        Ok(election)
+                    } else {
+                        Err(RegistrationError::new(
+                            StatusCode::FORBIDDEN,
+                            format!("card is not for election {}", row.election_id),
+                        ))
+                    }
+                });
+            elections.insert(row.election_id, election.clone());
+            election
+        }
+    };
+
+    let registration = match election {
+        Ok(election) => {
+            validate_registration(
+                connection,
+                jurisdiction_code,
+                registration_request_id,
+                row.election_id,
+                &election,
+                row.ballot_style_id,
+                row.precinct_id,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+
+    let registration = match registration {
+        Ok(registration) => registration,
+        Err(e) if e.status.is_server_error() => bail!(e.message),
+        Err(e) => return Ok(Err(e.message)),
+    };
+
+    if let Some(common_access_card_id) = &row.common_access_card_id {
+        if common_access_card_id != &registration.common_access_card_id {
+            return Ok(Err(format!(
+                "CAC ID {common_access_card_id} does not match registration request {registration_request_id}"
+            )));
+        }
+    }
+
+    if !seen.insert((registration_request_id, row.election_id)) {
+        return Ok(Err(format!(
+            "registration request {registration_request_id} appears more than once for election {}",
+            row.election_id
+        )));
+    }
+
+    Ok(Ok(registration))
+}
+
+/// Parses street segments from a CSV file with a header row, or from a JSON
+/// array, checking that each one is usable.
+pub(crate) fn parse_street_segments(
+    is_csv: bool,
+    body: &[u8],
+) -> color_eyre::Result<Vec<cacvote::StreetSegment>> {
+    let street_segments: Vec<cacvote::StreetSegment> = if is_csv {
+        csv::Reader::from_reader(body)
+            .deserialize()
+            .collect::<Result<_, _>>()?
+    } else {
+        serde_json::from_slice(body)?
+    };
+
+    for (index, street_segment) in street_segments.iter().enumerate() {
+        if street_segment.street_name.trim().is_empty() {
+            bail!("street segment {} has no street name", index + 1);
+        }
+        if street_segment.low_house_number > street_segment.high_house_number {
+            bail!(
+                "street segment {} has a low house number greater than its high house number",
+                index + 1
+            );
+        }
+    }
+
+    Ok(street_segments)
+}
+
+/// One row of a registration export. The columns are a superset of
+/// [`RegistrationImportRow`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RegistrationExportRow {
+    pub(crate) registration_id: Uuid,
+    pub(crate) registration_request_id: Uuid,
+    pub(crate) common_access_card_id: String,
+    pub(crate) display_name: String,
+    pub(crate) election_id: Uuid,
+    pub(crate) election_title: String,
+    pub(crate) ballot_style_id: BallotStyleId,
+    pub(crate) precinct_id: PrecinctId,
+    pub(crate) created_at: String,
+}
+
+impl RegistrationExportRow {
+    pub(crate) fn new(
+        registration: &cacvote::RegistrationPresenter,
+    ) -> Result<Self, time::error::Format> {
+        Ok(Self {
+            registration_id: registration.id,
+            registration_request_id: registration.registration_request_object_id,
+            common_access_card_id: registration.common_access_card_id.clone(),
+            display_name: registration.display_name(),
+            election_id: registration.election_object_id,
+            election_title: registration.election_title(),
+            ballot_style_id: registration.ballot_style_id(),
+            precinct_id: registration.precinct_id(),
+            created_at: registration.created_at().format(&Rfc3339)?,
+        })
+    }
+}
+
+/// Writes the rows as CSV with a header row.
+pub(crate) fn export_csv(rows: &[RegistrationExportRow]) -> color_eyre::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{hash::MessageDigest, pkey::PKey, sign::Signer};
+    use sqlx::PgPool;
+    use types_rs::{
+        auth::{ElectionManagerUser, SystemAdministratorUser},
+        election::ElectionDefinition,
+    };
+
+    use super::*;
+
+    const JURISDICTION_CODE: &str = "st.test-jurisdiction";
+
+    #[test]
+    fn test_parse_import_rows() {
+        let election_id = Uuid::new_v4();
+        let registration_request_id = Uuid::new_v4();
+
+        let csv = format!(
+            "registrationRequestId,commonAccessCardId,electionId,ballotStyleId,precinctId\n\
+             {registration_request_id},,{election_id},1,20\n\
+             ,0123456789,{election_id},1,21\n"
+        );
+        let rows = parse_import_rows(true, csv.as_bytes()).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                RegistrationImportRow {
+                    registration_request_id: Some(registration_request_id),
+                    common_access_card_id: None,
+                    election_id,
+                    ballot_style_id: Some(BallotStyleId::from("1".to_owned())),
+                    precinct_id: Some(PrecinctId::from("20".to_owned())),
+                },
+                RegistrationImportRow {
+                    registration_request_id: None,
+                    common_access_card_id: Some("0123456789".to_owned()),
+                    election_id,
+                    ballot_style_id: Some(BallotStyleId::from("1".to_owned())),
+                    precinct_id: Some(PrecinctId::from("21".to_owned())),
+                },
+            ]
+        );
+
+        let rows = parse_import_rows(
+            true,
+            format!("commonAccessCardId,electionId,ballotStyleId,precinctId\n0123456789,{election_id},,\n")
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(rows[0].ballot_style_id, None);
+        assert_eq!(rows[0].precinct_id, None);
+
+        let json = serde_json::to_vec(&rows).unwrap();
+        assert_eq!(parse_import_rows(false, &json).unwrap(), rows);
+
+        assert!(parse_import_rows(true, b"electionId\nnot-a-uuid\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_street_segments() {
+        let street_segments = parse_street_segments(
+            true,
+            b"streetName,zipCode,lowHouseNumber,highHouseNumber,parity,precinctId\n\
+              Main St,12345,1,99,odd,20\n\
+              Main St,12345,2,98,even,21\n",
+        )
+        .unwrap();
+        assert_eq!(street_segments.len(), 2);
+        assert_eq!(street_segments[0].parity, cacvote::HouseNumberParity::Odd);
+        assert_eq!(
+            street_segments[1].precinct_id,
+            PrecinctId::from("21".to_owned())
+        );
+
+        // parity may be left out
+        let json = br#"[{"streetName":"Elm Ave","zipCode":"12345","lowHouseNumber":1,"highHouseNumber":9,"precinctId":"22"}]"#;
+        assert_eq!(
+            parse_street_segments(false, json).unwrap()[0].parity,
+            cacvote::HouseNumberParity::All
+        );
+
+        assert!(parse_street_segments(
+            true,
+            b"streetName,zipCode,lowHouseNumber,highHouseNumber,parity,precinctId\n\
+              Main St,12345,99,1,all,20\n",
+        )
+        .is_err());
+        assert!(parse_street_segments(
+            true,
+            b"streetName,zipCode,lowHouseNumber,highHouseNumber,parity,precinctId\n\
+              ,12345,1,99,all,20\n",
+        )
+        .is_err());
+    }
+
+    fn load_election(jurisdiction_code: &JurisdictionCode) -> cacvote::Election {
+        cacvote::Election {
+            jurisdiction_code: jurisdiction_code.clone(),
+            election_definition: ElectionDefinition::try_from(
+                &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+            )
+            .unwrap(),
+            mailing_address: "123 Main St".to_owned(),
+            electionguard_election_metadata_blob: vec![],
+            voting_starts_at: None,
+            voting_ends_at: None,
+            ballot_receipt_deadline: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_assignment() {
+        let election = load_election(&JurisdictionCode::try_from(JURISDICTION_CODE).unwrap());
+        let street_segments = vec![
+            cacvote::StreetSegment {
+                street_name: "Main St".to_owned(),
+                zip_code: "12345".to_owned(),
+                low_house_number: 1,
+                high_house_number: 99,
+                parity: cacvote::HouseNumberParity::All,
+                precinct_id: PrecinctId::from("21".to_owned()),
+            },
+            cacvote::StreetSegment {
+                street_name: "Elm Ave".to_owned(),
+                zip_code: "12345".to_owned(),
+                low_house_number: 1,
+                high_house_number: 99,
+                parity: cacvote::HouseNumberParity::All,
+                precinct_id: PrecinctId::from("no-such-precinct".to_owned()),
+            },
+        ];
+        let address = |street_name: &str| cacvote::VoterAddress {
+            house_number: 12,
+            street_name: street_name.to_owned(),
+            unit: None,
+            city: "Springfield".to_owned(),
+            zip_code: "12345".to_owned(),
+        };
+        let resolve = |address: Option<&cacvote::VoterAddress>,
+                       ballot_style_id: Option<&str>,
+                       precinct_id: Option<&str>| {
+            resolve_assignment(
+                &election,
+                &street_segments,
+                address,
+                ballot_style_id.map(|id| BallotStyleId::from(id.to_owned())),
+                precinct_id.map(|id| PrecinctId::from(id.to_owned())),
+            )
+            .map(|(ballot_style_id, precinct_id)| {
+                (ballot_style_id.to_string(), precinct_id.to_string())
+            })
+        };
+
+        assert_eq!(
+            resolve(Some(&address("Main St")), None, None),
+            Ok(("1".to_owned(), "21".to_owned()))
+        );
+        // explicit values win over the address
+        assert_eq!(
+            resolve(Some(&address("Main St")), Some("1"), Some("22")),
+            Ok(("1".to_owned(), "22".to_owned()))
+        );
+        assert_eq!(
+            resolve(None, None, Some("20")),
+            Ok(("1".to_owned(), "20".to_owned()))
+        );
+        assert_eq!(
+            resolve(None, None, None),
+            Err("precinct is required because the registration request has no address".to_owned())
+        );
+        assert_eq!(
+            resolve(Some(&address("Oak St")), None, None),
+            Err("no single precinct found for address 12 Oak St, Springfield 12345".to_owned())
+        );
+        assert_eq!(
+            resolve(Some(&address("Elm Ave")), None, None),
+            Err("no ballot style is used in precinct no-such-precinct".to_owned())
+        );
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import(pool: PgPool) -> color_eyre::Result<()> {
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-admin-private-key.pem"
+        ))?;
+        let certificate = X509::from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))?;
+        let sign = |payload: &[u8]| {
+            let mut signer = Signer::new(MessageDigest::sha256(), &private_key)?;
+            signer.update(payload)?;
+            Ok((signer.sign_to_vec()?, certificate.clone()))
+        };
+        let connection = &mut pool.acquire().await?;
+        let jurisdiction_code = JurisdictionCode::try_from(JURISDICTION_CODE).unwrap();
+        let user =
+            User::SystemAdministrator(SystemAdministratorUser::new(jurisdiction_code.clone()));
+
+        let election_object = cacvote::SignedObject::from_payload(
+            &cacvote::Payload::Election(load_election(&jurisdiction_code)),
+            certificate.clone(),
+            &private_key,
+        )?;
+        db::add_object_from_server(connection, &election_object).await?;
+
+        let mut registration_request_ids = Vec::new();
+        for common_access_card_id in ["0123456789", "1234567890"] {
+            let registration_request_object = cacvote::SignedObject::from_payload(
+                &cacvote::Payload::RegistrationRequest(cacvote::RegistrationRequest {
+                    jurisdiction_code: jurisdiction_code.clone(),
+                    common_access_card_id: common_access_card_id.to_owned(),
+                    family_name: "Smith".to_owned(),
+                    given_name: "John".to_owned(),
+                    address: None,
+                }),
+                certificate.clone(),
+                &private_key,
+            )?;
+            db::add_object_from_server(connection, &registration_request_object).await?;
+            registration_request_ids.push(registration_request_object.id);
+        }
+
+        let row = |registration_request_id: Option<Uuid>,
+                   common_access_card_id: Option<&str>,
+                   precinct_id: &str| RegistrationImportRow {
+            registration_request_id,
+            common_access_card_id: common_access_card_id.map(ToOwned::to_owned),
+            election_id: election_object.id,
+            ballot_style_id: Some(BallotStyleId::from("1".to_owned())),
+            precinct_id: Some(PrecinctId::from(precinct_id.to_owned())),
+        };
+
+        // one bad row rejects the whole import
+        let report = import(
+            connection,
+            &jurisdiction_code,
+            &user,
+            vec![
+                row(Some(registration_request_ids[0]), None, "20"),
+                row(None, Some("1234567890"), "no-such-precinct"),
+                row(None, Some("5555555555"), "20"),
+                row(Some(registration_request_ids[0]), None, "21"),
+            ],
+            false,
+            sign,
+        )
+        .await?;
+        assert_eq!(
+            report
+                .rows
+                .iter()
+                .map(|row| row.error.as_deref())
+                .collect::<Vec<_>>(),
+            vec![
+                None,
+                Some("precinct no-such-precinct is not in the election"),
+                Some("no pending registration request for CAC ID 5555555555"),
+                Some(&*format!(
+                    "registration request {} appears more than once for election {}",
+                    registration_request_ids[0], election_object.id
+                )),
+            ]
+        );
+        assert!(report.rows.iter().all(|row| row.registration_id.is_none()));
+        assert!(db::get_registrations(connection).await?.is_empty());
+
+        let rows = vec![
+            row(Some(registration_request_ids[0]), None, "20"),
+            row(None, Some("1234567890"), "21"),
+        ];
+
+        // election managers may only import registrations for their election
+        let other_election_manager = User::ElectionManager(ElectionManagerUser::new(
+            jurisdiction_code.clone(),
+            "0".repeat(64),
+        ));
+        let report = import(
+            connection,
+            &jurisdiction_code,
+            &other_election_manager,
+            rows.clone(),
+            true,
+            sign,
+        )
+        .await?;
+        assert_eq!(report.invalid_row_count(), 2);
+        assert_eq!(
+            report.rows[0].error,
+            Some(format!("card is not for election {}", election_object.id))
+        );
+
+        // a dry run validates without storing anything
+        let report = import(
+            connection,
+            &jurisdiction_code,
+            &user,
+            rows.clone(),
+            true,
+            sign,
+        )
+        .await?;
+        assert_eq!(report.invalid_row_count(), 0);
+        assert!(report.rows.iter().all(|row| row.registration_id.is_none()));
+        assert!(db::get_registrations(connection).await?.is_empty());
+
+        let report = import(
+            connection,
+            &jurisdiction_code,
+            &user,
+            rows.clone(),
+            false,
+            sign,
+        )
+        .await?;
+        assert_eq!(report.invalid_row_count(), 0);
+        assert!(report.rows.iter().all(|row| row.registration_id.is_some()));
+
+        let registrations = db::get_registrations(connection).await?;
+        assert_eq!(registrations.len(), 2);
+
+        let export_rows = registrations
+            .iter()
+            .map(RegistrationExportRow::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        let csv = String::from_utf8(export_csv(&export_rows)?)?;
+        assert!(csv.starts_with(
+            "registrationId,registrationRequestId,commonAccessCardId,displayName,electionId,electionTitle,ballotStyleId,precinctId,createdAt\n"
+        ));
+
+        // the export can be read back as import rows, which are now duplicates
+        let report = import(
+            connection,
+            &jurisdiction_code,
+            &user,
+            parse_import_rows(true, csv.as_bytes())?,
+            true,
+            sign,
+        )
+        .await?;
+        assert_eq!(report.invalid_row_count(), 2);
+
+        Ok(())
+    }
+}
@@ -1,11 +1,242 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use auth_rs::{async_card, card_details::PinInfo, vx_card::VxCard, CardReaderError, CertObject};
-use openssl::x509::X509;
-use tokio::sync::{mpsc, oneshot, watch};
-use types_rs::cacvote;
+use auth_rs::{async_card, card_details::PinInfo, vx_card::VxCard, CardReaderError};
+use cacvote_server_client::CardSigner;
+use openssl::{hash::MessageDigest, x509::X509};
+use time::OffsetDateTime;
+use tokio::{
+    sync::{mpsc, oneshot, watch},
+    time::Instant,
+};
+use types_rs::{auth::User, cacvote};
+use vx_logging::{Disposition, EventId, EventType};
 
+use crate::cac::CastBallotVerifier;
 use crate::db;
+use crate::log;
+use crate::registrations;
+
+/// Upper bound on how long a card is locked out for, however many incorrect
+/// PIN attempts it has.
+const MAX_PIN_LOCKOUT_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Limits on how long a session stays authenticated and how many incorrect
+/// PINs are tolerated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SessionPolicy {
+    /// How long an authenticated session may go without activity before it
+    /// is locked, or `None` to never lock it automatically.
+    pub(crate) inactivity_timeout: Option<Duration>,
+
+    /// Number of incorrect PIN attempts allowed before the card is locked out.
+    pub(crate) max_incorrect_pin_attempts: u8,
+
+    /// How long the card is locked out for on reaching the limit. Doubles
+    /// with each further incorrect attempt, up to a day.
+    pub(crate) pin_lockout_duration: Duration,
+}
+
+impl SessionPolicy {
+    /// Returns how long a card with the given number of incorrect PIN
+    /// attempts is locked out for, or `None` if it is not locked out.
+    pub(crate) fn lockout_duration(&self, num_incorrect_pin_attempts: u8) -> Option<Duration> {
+        let excess_attempts =
+            num_incorrect_pin_attempts.checked_sub(self.max_incorrect_pin_attempts)?;
+        Some(
+            self.pin_lockout_duration
+                .saturating_mul(2u32.saturating_pow(u32::from(excess_attempts)))
+                .min(MAX_PIN_LOCKOUT_DURATION),
+        )
+    }
+
+    fn locked_until(&self, num_incorrect_pin_attempts: u8) -> Option<OffsetDateTime> {
+        self.lockout_duration(num_incorrect_pin_attempts)
+            .map(|duration| OffsetDateTime::now_utc() + duration)
+    }
+}
+
+/// Counts the inserted card's PIN attempts, whether the PIN was entered to
+/// unlock the session or to sign, and locks the card out once there are too
+/// many. Lockouts are stored in the database so that removing the card or
+/// restarting the JX does not end them early.
+struct PinAttempts {
+    policy: SessionPolicy,
+    pool: sqlx::PgPool,
+    card_fingerprint: String,
+    user: User,
+    num_incorrect_pin_attempts: u8,
+    locked_until: Option<OffsetDateTime>,
+}
+
+impl PinAttempts {
+    /// Starts counting PIN attempts for a newly inserted card, picking up
+    /// its stored lockout if the card has not had its PIN attempts reset
+    /// since.
+    async fn new(
+        policy: SessionPolicy,
+        pool: sqlx::PgPool,
+        card_vx_cert: &X509,
+        user: User,
+        num_incorrect_pin_attempts: u8,
+    ) -> color_eyre::Result<Self> {
+        let card_fingerprint = hex::encode(card_vx_cert.digest(MessageDigest::sha256())?);
+        let mut pin_attempts = Self {
+            policy,
+            pool,
+            card_fingerprint,
+            user,
+            num_incorrect_pin_attempts,
+            locked_until: None,
+        };
+
+        let mut connection = pin_attempts.pool.acquire().await?;
+        match db::get_pin_lockout(&mut connection, &pin_attempts.card_fingerprint).await? {
+            Some(lockout) if lockout.num_incorrect_pin_attempts == num_incorrect_pin_attempts => {
+                pin_attempts.locked_until = Some(lockout.locked_until);
+            }
+            stored_lockout => {
+                pin_attempts.locked_until = policy.locked_until(num_incorrect_pin_attempts);
+                match pin_attempts.locked_until {
+                    Some(locked_until) => {
+                        let lockout = db::PinLockout {
+                            num_incorrect_pin_attempts,
+                            locked_until,
+                        };
+                        db::set_pin_lockout(
+                            &mut connection,
+                            &pin_attempts.card_fingerprint,
+                            lockout,
+                        )
+                        .await?;
+                    }
+                    None if stored_lockout.is_some() => {
+                        db::delete_pin_lockout(&mut connection, &pin_attempts.card_fingerprint)
+                            .await?;
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        if let Some(locked_until) = pin_attempts.locked_until() {
+            log_pin_lockout(&pin_attempts.user, num_incorrect_pin_attempts, locked_until);
+        }
+
+        Ok(pin_attempts)
+    }
+
+    /// Returns when the card's lockout ends, or `None` if it is not locked
+    /// out.
+    fn locked_until(&self) -> Option<OffsetDateTime> {
+        self.locked_until
+            .filter(|locked_until| *locked_until > OffsetDateTime::now_utc())
+    }
+
+    fn check_not_locked_out(&self) -> Result<(), CardReaderError> {
+        match self.locked_until() {
+            Some(locked_until) => Err(CardReaderError::Other(format!(
+                "card is locked out until {locked_until}"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks `pin` against the card, unless it is locked out.
+    async fn check_pin(&mut self, card: &VxCard, pin: &str) -> Result<(), CardReaderError> {
+        self.check_not_locked_out()?;
+
+        let result = card.check_pin(pin).await;
+        tracing::debug!("check_pin result={result:?}");
+
+        match result {
+            Ok(()) => self.record_correct_pin().await,
+            Err(ref e) if e.is_incorrect_pin_error() => self.record_incorrect_pin(card).await,
+            Err(_) => {}
+        }
+
+        result
+    }
+
+    /// Signs `data` with the card after verifying `pin`, unless it is locked
+    /// out.
+    async fn sign(
+        &mut self,
+        card: &Arc<VxCard>,
+        data: &[u8],
+        pin: String,
+    ) -> Result<(Vec<u8>, X509), cacvote_server_client::Error> {
+        self.check_not_locked_out()?;
+
+        let result = CardSigner::new(Arc::clone(card), pin)
+            .sign_with_certificate(data)
+            .await;
+
+        match result {
+            Ok(_) => self.record_correct_pin().await,
+            Err(cacvote_server_client::Error::Card(ref e)) if e.is_incorrect_pin_error() => {
+                self.record_incorrect_pin(card).await;
+            }
+            Err(_) => {}
+        }
+
+        result
+    }
+
+    async fn record_correct_pin(&mut self) {
+        log_pin_entry(
+            &self.user,
+            Disposition::Success,
+            "User entered correct PIN.",
+        );
+        self.num_incorrect_pin_attempts = 0;
+
+        if self.locked_until.take().is_some() {
+            if let Err(e) = self.delete_lockout().await {
+                tracing::error!("error deleting PIN lockout: {e}");
+            }
+        }
+    }
+
+    async fn record_incorrect_pin(&mut self, card: &VxCard) {
+        log_pin_entry(
+            &self.user,
+            Disposition::Failure,
+            "User entered incorrect PIN.",
+        );
+
+        let num_incorrect_pin_attempts = match card.get_num_incorrect_pin_attempts().await {
+            Ok(num_incorrect_pin_attempts) => num_incorrect_pin_attempts,
+            Err(e) => {
+                tracing::error!("error reading incorrect PIN attempts: {e}");
+                return;
+            }
+        };
+
+        self.num_incorrect_pin_attempts = num_incorrect_pin_attempts;
+        self.locked_until = self.policy.locked_until(num_incorrect_pin_attempts);
+        if let Some(locked_until) = self.locked_until {
+            log_pin_lockout(&self.user, num_incorrect_pin_attempts, locked_until);
+
+            let lockout = db::PinLockout {
+                num_incorrect_pin_attempts,
+                locked_until,
+            };
+            if let Err(e) = self.store_lockout(lockout).await {
+                tracing::error!("error storing PIN lockout: {e}");
+            }
+        }
+    }
+
+    async fn store_lockout(&self, lockout: db::PinLockout) -> color_eyre::Result<()> {
+        let mut connection = self.pool.acquire().await?;
+        db::set_pin_lockout(&mut connection, &self.card_fingerprint, lockout).await
+    }
+
+    async fn delete_lockout(&self) -> color_eyre::Result<()> {
+        let mut connection = self.pool.acquire().await?;
+        db::delete_pin_lockout(&mut connection, &self.card_fingerprint).await
+    }
+}
 
 /// Manages the smartcard session, including authentication and signing.
 /// Monitors the smartcard for insertion and removal.
@@ -20,6 +251,16 @@ struct SessionManagerInner {
     /// current session data.
     session_data_tx: watch::Sender<cacvote::SessionData>,
 
+    /// The sender for the authenticated user watch channel. Holds the user
+    /// described by the inserted card once the session is authenticated, and
+    /// `None` otherwise.
+    user_tx: watch::Sender<Option<User>>,
+
+    /// The sender for the last activity watch channel. Updated whenever the
+    /// authenticated operator does something, and used to lock the session
+    /// after a period of inactivity.
+    last_activity_tx: watch::Sender<Instant>,
+
     /// The sender for the session operations channel. Operations to be
     /// performed on the smartcard are sent here.
     session_ops_tx: mpsc::UnboundedSender<SessionOperation>,
@@ -37,12 +278,16 @@ impl SessionManager {
     pub(crate) fn new(
         vx_cert_authority_cert: openssl::x509::X509,
         vx_admin_cert_authority_cert: openssl::x509::X509,
+        cac_root_ca_store: cacvote::CacCaStore,
         jurisdiction_code: cacvote::JurisdictionCode,
+        policy: SessionPolicy,
         pool: sqlx::PgPool,
     ) -> Self {
         let (session_data_tx, _) = watch::channel(cacvote::SessionData::Unauthenticated {
             has_smartcard: false,
         });
+        let (user_tx, _) = watch::channel(None);
+        let (last_activity_tx, _) = watch::channel(Instant::now());
         let (session_ops_tx, mut session_ops_rx) = mpsc::unbounded_channel();
 
         // The task that manages the smartcard session. Interacts with the
@@ -50,34 +295,56 @@ impl SessionManager {
         // `SessionManagerInner`.
         tokio::spawn({
             let session_data_tx = session_data_tx.clone();
+            let user_tx = user_tx.clone();
+            let last_activity_tx = last_activity_tx.clone();
             let session_ops_tx = session_ops_tx.clone();
             let vx_cert_authority_cert = vx_cert_authority_cert.clone();
             let vx_admin_cert_authority_cert = vx_admin_cert_authority_cert.clone();
             let mut db_interval = tokio::time::interval(tokio::time::Duration::from_millis(100));
             let mut last_session_data = None;
+            let cast_ballot_verifier = CastBallotVerifier::new(cac_root_ca_store);
 
             async move {
                 // spawn the context within the task
                 let ctx = pcsc::Context::establish(pcsc::Scope::User).unwrap();
                 let mut watcher = auth_rs::Watcher::watch();
-                let mut vx_card: Option<VxCard> = None;
+                let mut vx_card: Option<Arc<VxCard>> = None;
+                let mut card_user: Option<User> = None;
+                let mut card_has_pin = false;
+                let mut pin_attempts: Option<PinAttempts> = None;
 
                 let refresh_authenticated_session = || async {
                     let mut connection = pool.acquire().await.unwrap();
 
                     let elections = db::get_elections(&mut connection).await.unwrap();
+                    let street_segments = db::get_street_segments(&mut connection).await.unwrap();
                     let pending_registration_requests =
                         db::get_pending_registration_requests(&mut connection)
                             .await
-                            .unwrap();
+                            .unwrap()
+                            .into_iter()
+                            .map(|registration_request| {
+                                let suggested_assignments = registrations::suggest_assignments(
+                                    &elections,
+                                    &street_segments,
+                                    &registration_request,
+                                );
+                                registration_request
+                                    .with_suggested_assignments(suggested_assignments)
+                            })
+                            .collect();
                     let registrations = db::get_registrations(&mut connection).await.unwrap();
-                    let cast_ballots = db::get_cast_ballots(&mut connection).await.unwrap();
+                    let cast_ballots = db::get_cast_ballots(&mut connection, &cast_ballot_verifier)
+                        .await
+                        .unwrap();
+                    let jobs = db::get_jobs(&mut connection).await.unwrap();
                     cacvote::SessionData::Authenticated {
                         jurisdiction_code: jurisdiction_code.clone(),
                         elections,
                         pending_registration_requests,
                         registrations,
                         cast_ballots,
+                        jobs,
                     }
                 };
 
@@ -109,8 +376,36 @@ impl SessionManager {
                                             }
                                         };
 
-                                        // hold on to the card for future operations
-                                        vx_card = Some(card);
+                                        if card_details.card_details.jurisdiction_code() != jurisdiction_code {
+                                            tracing::warn!(
+                                                "card is for jurisdiction {}, not {jurisdiction_code}",
+                                                card_details.card_details.jurisdiction_code()
+                                            );
+                                            session_data_tx.send_replace(cacvote::SessionData::Authenticating {
+                                                auth_error: Some("card is for a different jurisdiction".to_owned()),
+                                                locked_until: None,
+                                            });
+                                            continue;
+                                        }
+
+                                        let user = card_details.card_details.user();
+                                        let num_incorrect_pin_attempts = match card_details.pin_info {
+                                            PinInfo::NoPin => 0,
+                                            PinInfo::HasPin { num_incorrect_pin_attempts } => num_incorrect_pin_attempts,
+                                        };
+                                        let attempts = match PinAttempts::new(
+                                            policy,
+                                            pool.clone(),
+                                            &card_details.card_vx_cert,
+                                            user.clone(),
+                                            num_incorrect_pin_attempts,
+                                        ).await {
+                                            Ok(attempts) => attempts,
+                                            Err(e) => {
+                                                tracing::error!("error loading PIN lockout: {e}");
+                                                continue;
+                                            }
+                                        };
 
                                         match card_details.pin_info {
                                             PinInfo::NoPin => {
@@ -126,13 +421,25 @@ impl SessionManager {
                                                     } else {
                                                         Some(format!("incorrect PIN attempts: {num_incorrect_pin_attempts}"))
                                                     },
+                                                    locked_until: attempts.locked_until(),
                                                 });
                                             }
                                         }
+
+                                        // hold on to the card for future operations
+                                        vx_card = Some(Arc::new(card));
+                                        card_user = Some(user);
+                                        card_has_pin = matches!(card_details.pin_info, PinInfo::HasPin { .. });
+                                        pin_attempts = Some(attempts);
                                     }
                                     auth_rs::Event::CardRemoved { .. } | auth_rs::Event::ReaderRemoved { .. } => {
                                         // clear the card so we don't try to use it
                                         vx_card = None;
+                                        card_user = None;
+                                        card_has_pin = false;
+                                        pin_attempts = None;
+                                        last_session_data = None;
+                                        user_tx.send_replace(None);
                                         session_data_tx.send_replace(cacvote::SessionData::Unauthenticated {
                                             has_smartcard: false,
                                         });
@@ -157,27 +464,74 @@ impl SessionManager {
                             tracing::debug!("received session_op={session_op:?}");
                             match session_op {
                                 Some(SessionOperation::SetAuthenticated) => {
+                                    if card_user.is_none() {
+                                        tracing::warn!("no card inserted, not authenticating");
+                                        continue;
+                                    }
+                                    user_tx.send_replace(card_user.clone());
+                                    last_activity_tx.send_replace(Instant::now());
                                     let new_session_data = refresh_authenticated_session().await;
                                     last_session_data = Some(new_session_data.clone());
                                     session_data_tx.send_replace(new_session_data);
                                 }
                                 Some(SessionOperation::CheckPin { pin, respond }) => {
-                                    if let Some(ref mut vx_card) = vx_card {
-                                        let result = vx_card.check_pin(&pin).await;
-                                        tracing::debug!("check_pin result={result:?}");
-                                        let _ = respond.send(result);
-                                    } else {
+                                    let (Some(vx_card), Some(pin_attempts)) = (&vx_card, &mut pin_attempts) else {
                                         let _ = respond.send(Err(CardReaderError::NoCardFound));
+                                        continue;
+                                    };
+
+                                    let result = pin_attempts.check_pin(vx_card, &pin).await;
+                                    if matches!(result, Err(ref e) if e.is_incorrect_pin_error()) {
+                                        session_data_tx.send_replace(cacvote::SessionData::Authenticating {
+                                            auth_error: Some(format!(
+                                                "incorrect PIN attempts: {}",
+                                                pin_attempts.num_incorrect_pin_attempts
+                                            )),
+                                            locked_until: pin_attempts.locked_until(),
+                                        });
                                     }
+
+                                    let _ = respond.send(result);
                                 }
-                                Some(SessionOperation::Sign { signing_cert, data, pin, respond }) => {
-                                    if let Some(ref mut vx_card) = vx_card {
-                                        let result = vx_card.sign(signing_cert, &data, pin.as_deref()).await;
-                                        let _ = respond.send(result);
-                                    } else {
-                                        let _ = respond.send(Err(CardReaderError::NoCardFound));
+                                Some(SessionOperation::Lock { reason }) => {
+                                    if card_user.is_some() {
+                                        tracing::info!("locking session: {}", reason.as_deref().unwrap_or("requested by operator"));
+                                        user_tx.send_replace(None);
+                                        last_session_data = None;
+                                        if !card_has_pin {
+                                            // forget the card so that only reinserting it unlocks the session
+                                            vx_card = None;
+                                            card_user = None;
+                                            pin_attempts = None;
+                                        }
+                                        session_data_tx.send_replace(locked_session_data(card_has_pin, reason));
                                     }
                                 }
+                                Some(SessionOperation::Sign { data, pin, respond }) => {
+                                    let (Some(vx_card), Some(pin_attempts)) = (&vx_card, &mut pin_attempts) else {
+                                        let _ = respond.send(Err(CardReaderError::NoCardFound.into()));
+                                        continue;
+                                    };
+
+                                    let result = pin_attempts.sign(vx_card, &data, pin).await;
+
+                                    // too many incorrect PINs while signing lock the session too
+                                    if let Some(locked_until) = pin_attempts.locked_until() {
+                                        if user_tx.borrow().is_some() {
+                                            user_tx.send_replace(None);
+                                            last_session_data = None;
+                                            session_data_tx.send_replace(cacvote::SessionData::Authenticating {
+                                                auth_error: Some(format!(
+                                                    "incorrect PIN attempts: {}",
+                                                    pin_attempts.num_incorrect_pin_attempts
+                                                )),
+                                                locked_until: Some(locked_until),
+                                            });
+                                        }
+                                    }
+
+                                    let _ = respond.send(result);
+                                }
                                 None => {
                                     tracing::debug!("session_ops channel closed?!");
                                 }
@@ -192,6 +546,18 @@ impl SessionManager {
                                         continue;
                                     }
 
+                                    if policy
+                                        .inactivity_timeout
+                                        .is_some_and(|timeout| last_activity_tx.borrow().elapsed() >= timeout)
+                                    {
+                                        if let Err(e) = session_ops_tx.send(SessionOperation::Lock {
+                                            reason: Some("session locked due to inactivity".to_owned()),
+                                        }) {
+                                            tracing::error!("error locking inactive session: {e}");
+                                        }
+                                        continue;
+                                    }
+
                                     let new_session_data = refresh_authenticated_session().await;
 
                                     if new_session_data != *last_session_data {
@@ -213,6 +579,8 @@ impl SessionManager {
         Self {
             inner: Arc::new(SessionManagerInner {
                 session_data_tx,
+                user_tx,
+                last_activity_tx,
                 session_ops_tx,
             }),
         }
@@ -247,20 +615,44 @@ impl SessionManager {
         self.inner.session_data_tx.subscribe()
     }
 
-    /// Signs the given data with the given signing certificate. If a PIN is
-    /// required, provide it as the `pin` argument.
-    #[allow(dead_code)]
+    /// Locks an authenticated session, requiring the PIN to be entered again,
+    /// or the card to be reinserted if it has no PIN.
+    pub(crate) fn lock(&self) -> Result<(), String> {
+        self.inner
+            .session_ops_tx
+            .send(SessionOperation::Lock { reason: None })
+            .map_err(|e| format!("error locking session: {e}"))
+    }
+
+    /// Records operator activity, postponing the inactivity timeout.
+    pub(crate) fn record_activity(&self) {
+        self.inner.last_activity_tx.send_replace(Instant::now());
+    }
+
+    /// Returns the user described by the inserted card if the session is
+    /// authenticated.
+    pub(crate) fn authenticated_user(&self) -> Option<User> {
+        if !matches!(
+            *self.inner.session_data_tx.borrow(),
+            cacvote::SessionData::Authenticated { .. }
+        ) {
+            return None;
+        }
+
+        self.inner.user_tx.borrow().clone()
+    }
+
+    /// Signs the given data with the inserted card after verifying `pin`,
+    /// returning the signature and the card's signing certificate.
     pub(crate) async fn sign(
         &self,
-        signing_cert: CertObject,
         data: Vec<u8>,
-        pin: Option<String>,
-    ) -> Result<(Vec<u8>, X509), CardReaderError> {
+        pin: String,
+    ) -> Result<(Vec<u8>, X509), cacvote_server_client::Error> {
         let (tx, rx) = oneshot::channel();
         self.inner
             .session_ops_tx
             .send(SessionOperation::Sign {
-                signing_cert,
                 data,
                 pin,
                 respond: tx,
@@ -276,15 +668,300 @@ impl SessionManager {
 #[derive(Debug)]
 enum SessionOperation {
     SetAuthenticated,
+    Lock {
+        reason: Option<String>,
+    },
     CheckPin {
         pin: String,
         respond: oneshot::Sender<Result<(), CardReaderError>>,
     },
-    #[allow(dead_code)]
     Sign {
-        signing_cert: CertObject,
         data: Vec<u8>,
-        pin: Option<String>,
-        respond: oneshot::Sender<Result<(Vec<u8>, X509), CardReaderError>>,
+        pin: String,
+        respond: oneshot::Sender<Result<(Vec<u8>, X509), cacvote_server_client::Error>>,
     },
 }
+
+/// Returns the session data for a session that has just been locked. A card
+/// with a PIN unlocks it by entering the PIN again, but a card without one has
+/// no PIN to enter, so it must be removed and inserted again instead.
+fn locked_session_data(card_has_pin: bool, reason: Option<String>) -> cacvote::SessionData {
+    if card_has_pin {
+        cacvote::SessionData::Authenticating {
+            auth_error: reason,
+            locked_until: None,
+        }
+    } else {
+        cacvote::SessionData::Unauthenticated {
+            has_smartcard: true,
+        }
+    }
+}
+
+fn log_pin_entry(user: &User, disposition: Disposition, message: &str) {
+    vx_logging::log!(
+        event_id: EventId::AuthPinEntry,
+        event_type: EventType::UserAction,
+        user: log::vx_user(user),
+        disposition: disposition,
+        message: message.to_owned()
+    );
+}
+
+fn log_pin_lockout(user: &User, num_incorrect_pin_attempts: u8, locked_until: OffsetDateTime) {
+    vx_logging::log!(
+        event_id: EventId::AuthPinEntryLockout,
+        event_type: EventType::UserAction,
+        user: log::vx_user(user),
+        disposition: Disposition::Failure,
+        message: format!(
+            "Card locked out until {locked_until} after {num_incorrect_pin_attempts} incorrect PIN attempts."
+        )
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use auth_rs::{async_card::AsyncCard, tlv::Tlv};
+    use openssl::{
+        ecdsa::EcdsaSig,
+        pkey::{PKey, Private},
+    };
+    use types_rs::{auth::SystemAdministratorUser, cacvote::JurisdictionCode};
+
+    use super::*;
+
+    const PIN: &str = "123456";
+
+    /// A simulated card answering just enough of the OpenFIPS201 applet's
+    /// APDUs to check its PIN and sign with it.
+    struct MockCard {
+        cert: X509,
+        private_key: PKey<Private>,
+        num_incorrect_pin_attempts: u8,
+    }
+
+    impl MockCard {
+        fn transmit(&mut self, apdu: &[u8]) -> Vec<u8> {
+            let [_cla, ins, _p1, _p2, data @ ..] = apdu else {
+                return vec![0x67, 0x00];
+            };
+            let data = data.get(1..).unwrap_or_default();
+
+            match ins {
+                // SELECT
+                0xa4 => vec![0x90, 0x00],
+
+                // VERIFY, or check the PIN attempts without any data
+                0x20 if data.is_empty() => match self.num_incorrect_pin_attempts {
+                    0 => vec![0x90, 0x00],
+                    n => vec![0x63, 0xc0 | n],
+                },
+                0x20 => {
+                    let pin_length = data.iter().position(|&b| b == 0xff).unwrap_or(data.len());
+                    if &data[..pin_length] == PIN.as_bytes() {
+                        self.num_incorrect_pin_attempts = 0;
+                        vec![0x90, 0x00]
+                    } else {
+                        self.num_incorrect_pin_attempts += 1;
+                        vec![0x63, 0xc0 | self.num_incorrect_pin_attempts]
+                    }
+                }
+
+                // GET DATA, returning the same cert for every object
+                0xcb => {
+                    let cert_info = [
+                        tlv(0x70, self.cert.to_der().unwrap()),
+                        tlv(0x71, vec![0x00]),
+                        tlv(0xfe, vec![]),
+                    ]
+                    .concat();
+                    [tlv(0x53, cert_info), vec![0x90, 0x00]].concat()
+                }
+
+                // GENERAL AUTHENTICATE, signing the hash in the challenge
+                0x87 => {
+                    let template = Tlv::parse_partial(0x7c, data).unwrap().1;
+                    let challenge = Tlv::parse_partial(0x81, template.value()).unwrap().1;
+                    let signature =
+                        EcdsaSig::sign(challenge.value(), &self.private_key.ec_key().unwrap())
+                            .unwrap()
+                            .to_der()
+                            .unwrap();
+                    [tlv(0x7c, tlv(0x82, signature)), vec![0x90, 0x00]].concat()
+                }
+
+                _ => vec![0x6d, 0x00],
+            }
+        }
+    }
+
+    fn tlv(tag: u8, value: Vec<u8>) -> Vec<u8> {
+        Tlv::new(tag, value).into_bytes().unwrap()
+    }
+
+    fn load_cert() -> X509 {
+        X509::from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))
+        .unwrap()
+    }
+
+    fn mock_vx_card() -> (Arc<VxCard>, Arc<Mutex<MockCard>>) {
+        let mock_card = Arc::new(Mutex::new(MockCard {
+            cert: load_cert(),
+            private_key: PKey::private_key_from_pem(include_bytes!(
+                "../../../../libs/auth/certs/dev/vx-admin-private-key.pem"
+            ))
+            .unwrap(),
+            num_incorrect_pin_attempts: 0,
+        }));
+        let async_card = AsyncCard::from_transmit({
+            let mock_card = Arc::clone(&mock_card);
+            move |apdu| Ok(mock_card.lock().unwrap().transmit(apdu))
+        });
+        let vx_cert_authority_cert = X509::from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-cert-authority-cert.pem"
+        ))
+        .unwrap();
+        let card = VxCard::new(vx_cert_authority_cert, load_cert(), async_card);
+        (Arc::new(card), mock_card)
+    }
+
+    fn system_administrator() -> User {
+        User::SystemAdministrator(SystemAdministratorUser::new(
+            JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+        ))
+    }
+
+    #[test]
+    fn test_lockout_duration() {
+        let policy = SessionPolicy {
+            inactivity_timeout: None,
+            max_incorrect_pin_attempts: 5,
+            pin_lockout_duration: Duration::from_secs(15),
+        };
+
+        assert_eq!(policy.lockout_duration(0), None);
+        assert_eq!(policy.lockout_duration(4), None);
+        assert_eq!(policy.lockout_duration(5), Some(Duration::from_secs(15)));
+        assert_eq!(policy.lockout_duration(6), Some(Duration::from_secs(30)));
+        assert_eq!(policy.lockout_duration(7), Some(Duration::from_secs(60)));
+        assert_eq!(
+            policy.lockout_duration(u8::MAX),
+            Some(MAX_PIN_LOCKOUT_DURATION)
+        );
+    }
+
+    #[test]
+    fn test_locked_session_data() {
+        let reason = Some("session locked due to inactivity".to_owned());
+
+        assert_eq!(
+            locked_session_data(true, reason.clone()),
+            cacvote::SessionData::Authenticating {
+                auth_error: reason.clone(),
+                locked_until: None,
+            }
+        );
+
+        // a card without a PIN has to be reinserted
+        assert_eq!(
+            locked_session_data(false, reason),
+            cacvote::SessionData::Unauthenticated {
+                has_smartcard: true,
+            }
+        );
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_pin_attempts_while_signing(pool: sqlx::PgPool) {
+        vx_logging::set_app_name(log::APP_NAME);
+        let policy = SessionPolicy {
+            inactivity_timeout: None,
+            max_incorrect_pin_attempts: 2,
+            pin_lockout_duration: Duration::from_secs(60),
+        };
+        let (card, mock_card) = mock_vx_card();
+        let mut pin_attempts = PinAttempts::new(
+            policy,
+            pool.clone(),
+            &load_cert(),
+            system_administrator(),
+            0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(pin_attempts.locked_until(), None);
+
+        // incorrect PINs count the same whether checking the PIN or signing
+        let e = pin_attempts.check_pin(&card, "000000").await.unwrap_err();
+        assert!(e.is_incorrect_pin_error(), "{e:?}");
+        assert_eq!(pin_attempts.locked_until(), None);
+
+        match pin_attempts
+            .sign(&card, b"payload", "000000".to_owned())
+            .await
+        {
+            Err(cacvote_server_client::Error::Card(e)) => {
+                assert!(e.is_incorrect_pin_error(), "{e:?}");
+            }
+            result => panic!("expected an incorrect PIN error, got {result:?}"),
+        }
+        let locked_until = pin_attempts.locked_until().expect("card is locked out");
+        assert_eq!(mock_card.lock().unwrap().num_incorrect_pin_attempts, 2);
+
+        // while locked out, not even the correct PIN reaches the card
+        match pin_attempts.sign(&card, b"payload", PIN.to_owned()).await {
+            Err(cacvote_server_client::Error::Card(CardReaderError::Other(message))) => {
+                assert!(message.contains("locked out"), "{message}");
+            }
+            result => panic!("expected a lockout error, got {result:?}"),
+        }
+        assert_eq!(mock_card.lock().unwrap().num_incorrect_pin_attempts, 2);
+
+        // reinserting the card does not end the lockout early
+        let pin_attempts = PinAttempts::new(
+            policy,
+            pool.clone(),
+            &load_cert(),
+            system_administrator(),
+            2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            pin_attempts
+                .locked_until()
+                .map(OffsetDateTime::unix_timestamp),
+            Some(locked_until.unix_timestamp())
+        );
+
+        // resetting the card's PIN attempts does
+        let mut pin_attempts = PinAttempts::new(
+            policy,
+            pool.clone(),
+            &load_cert(),
+            system_administrator(),
+            0,
+        )
+        .await
+        .unwrap();
+        mock_card.lock().unwrap().num_incorrect_pin_attempts = 0;
+        assert_eq!(pin_attempts.locked_until(), None);
+        let mut connection = pool.acquire().await.unwrap();
+        assert_eq!(
+            db::get_pin_lockout(&mut connection, &pin_attempts.card_fingerprint)
+                .await
+                .unwrap(),
+            None
+        );
+
+        pin_attempts
+            .sign(&card, b"payload", PIN.to_owned())
+            .await
+            .unwrap();
+    }
+}
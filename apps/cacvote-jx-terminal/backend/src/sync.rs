@@ -1,9 +1,14 @@
 //! CACvote Server synchronization utilities.
 
 use cacvote_server_client::Client;
+use color_eyre::eyre::bail;
 use openssl::x509;
+use sqlx::Connection;
 use tokio::time::sleep;
-use types_rs::cacvote::JurisdictionCode;
+use types_rs::cacvote::{
+    CacCaStore, JurisdictionCode, RejectedObject, SignedTransferBundle, TransferBundle,
+    TransferReceipt,
+};
 
 use crate::{
     config::{Config, SYNC_INTERVAL},
@@ -24,7 +29,7 @@ pub(crate) async fn sync_periodically(pool: &sqlx::PgPool, config: Config) {
     let mut client = Client::new(
         config.cacvote_url.clone(),
         config.machine_cert().expect("invalid MACHINE_CERT"),
-        config.signer().expect("invalid signer"),
+        config.async_signer().expect("invalid signer"),
     );
 
     tokio::spawn({
@@ -50,6 +55,21 @@ pub(crate) async fn sync_periodically(pool: &sqlx::PgPool, config: Config) {
                         tracing::error!("Failed to sync with CACvote Server: {e}");
                     }
                 }
+
+                // elections certified on other machines arrive by syncing
+                match db::delete_private_keys_for_certified_elections(&mut connection).await {
+                    Ok(election_ids) if !election_ids.is_empty() => {
+                        tracing::info!(
+                            "Deleted private keys for certified elections: {election_ids:?}"
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to delete private keys for certified elections: {e}"
+                        );
+                    }
+                }
                 sleep(SYNC_INTERVAL).await;
             }
         }
@@ -65,7 +85,7 @@ pub(crate) async fn sync(
     client: &mut Client,
     jurisdiction_code: &JurisdictionCode,
     machine_cert: &x509::X509,
-    cac_root_ca_store: &x509::store::X509Store,
+    cac_root_ca_store: &CacCaStore,
 ) -> color_eyre::eyre::Result<()> {
     client.check_status().await?;
 
@@ -114,15 +134,15 @@ async fn pull_objects(
     executor: &mut sqlx::PgConnection,
     client: &mut Client,
     machine_cert: &x509::X509,
-    cac_root_ca_store: &x509::store::X509Store,
+    cac_root_ca_store: &CacCaStore,
 ) -> color_eyre::eyre::Result<()> {
     let journal_entries = db::get_journal_entries_for_objects_to_pull(executor).await?;
     for journal_entry in journal_entries {
         match client.get_object_by_id(journal_entry.object_id).await? {
             Some(object) => {
-                if !object.verify(machine_cert, cac_root_ca_store)? {
+                if let Err(e) = object.verify(machine_cert, cac_root_ca_store) {
                     tracing::warn!(
-                        "Object with id {} failed verification",
+                        "Object with id {} failed verification: {e}",
                         journal_entry.object_id
                     );
                     continue;
@@ -142,14 +162,112 @@ async fn pull_objects(
     Ok(())
 }
 
+/// Exports all objects not yet synced with the CACvote Server to a signed
+/// bundle, for sites without a reliable network connection. Objects are not
+/// marked as synced until they come back in a bundle from the server.
+pub(crate) async fn export_bundle(
+    executor: &mut sqlx::PgConnection,
+    config: &Config,
+) -> color_eyre::eyre::Result<SignedTransferBundle> {
+    let objects = db::get_unsynced_objects(executor).await?;
+    let bundle = TransferBundle::new(config.jurisdiction_code()?, vec![], objects);
+    tracing::info!(
+        "exporting {count} unsynced objects to transfer bundle {id}",
+        count = bundle.objects.len(),
+        id = bundle.id
+    );
+
+    let bundle = serde_json::to_vec(&bundle)?;
+    let (signature, certificate) = config.sign(&bundle)?;
+
+    Ok(SignedTransferBundle {
+        bundle,
+        certificate: certificate.to_pem()?,
+        signature,
+    })
+}
+
+/// Imports a bundle exported by the CACvote Server. Objects that already exist
+/// locally are marked as synced and otherwise left alone, so importing the
+/// same bundle more than once is harmless.
+#[tracing::instrument(skip_all, name = "Import transfer bundle")]
+pub(crate) async fn import_bundle(
+    executor: &mut sqlx::PgConnection,
+    signed_bundle: &SignedTransferBundle,
+    config: &Config,
+) -> color_eyre::eyre::Result<TransferReceipt> {
+    if let Err(e) = signed_bundle.verify(&config.vx_cert_authority_cert()?) {
+        bail!("transfer bundle verification failed: {e}");
+    }
+
+    let jurisdiction_code = config.jurisdiction_code()?;
+    let machine_cert = config.machine_cert()?;
+    let cac_root_ca_store = config.cac_root_ca_store()?;
+
+    let bundle = signed_bundle.try_to_inner()?;
+    if bundle.jurisdiction_code != jurisdiction_code {
+        bail!(
+            "transfer bundle is for jurisdiction {}, expected {jurisdiction_code}",
+            bundle.jurisdiction_code
+        );
+    }
+
+    let mut receipt = TransferReceipt::new(bundle.id);
+    let mut txn = executor.begin().await?;
+
+    receipt.journal_entries_imported =
+        db::add_journal_entries(&mut txn, bundle.journal_entries).await?;
+
+    for object in bundle.objects {
+        match db::get_object_sync_status(&mut txn, object.id).await? {
+            Some(is_synced) => {
+                if !is_synced {
+                    db::mark_object_synced(&mut txn, object.id).await?;
+                }
+                receipt.skipped_object_ids.push(object.id);
+            }
+            None => {
+                if object.jurisdiction_code().as_ref() != Some(&jurisdiction_code) {
+                    tracing::warn!(
+                        "Object with id {} is not for jurisdiction {jurisdiction_code}",
+                        object.id
+                    );
+                    receipt.rejected_objects.push(RejectedObject {
+                        id: object.id,
+                        reason: format!("object is not for jurisdiction {jurisdiction_code}"),
+                    });
+                    continue;
+                }
+
+                if let Err(e) = object.verify(&machine_cert, &cac_root_ca_store) {
+                    tracing::warn!("Object with id {} failed verification: {e}", object.id);
+                    receipt.rejected_objects.push(RejectedObject {
+                        id: object.id,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+
+                db::add_object_from_server(&mut txn, &object).await?;
+                receipt.imported_object_ids.push(object.id);
+            }
+        }
+    }
+
+    txn.commit().await?;
+
+    Ok(receipt)
+}
+
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
-    use cacvote_server_client::{signer, PrivateKeySigner};
+    use cacvote_server_client::{sealed::SealedSecret, signer, PrivateKeySigner};
     use openssl::{pkey::PKey, x509::X509};
     use reqwest::Url;
     use tracing::Level;
+    use types_rs::cacvote::{Payload, RegistrationRequest, SignedObject};
 
     use crate::app;
 
@@ -157,6 +275,72 @@ mod tests {
 
     const JURISDICTION_CODE: &str = "st.test-jurisdiction";
 
+    /// The jurisdiction of the dev machine certificate.
+    const DEV_JURISDICTION_CODE: &str = "st.dev-jurisdiction";
+
+    fn dev_cert_path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../libs/auth/certs/dev")
+            .join(name)
+    }
+
+    /// A config for a JX whose machine certificate and signing key are the dev
+    /// VxAdmin ones, which also act as the CAC root for registration requests.
+    fn dev_config() -> Config {
+        Config {
+            cacvote_url: "http://localhost:0".parse().unwrap(),
+            database_url: "".to_owned(),
+            machine_id: "".to_owned(),
+            port: 0,
+            public_dir: None,
+            log_level: Level::DEBUG,
+            machine_cert: dev_cert_path("vx-admin-cert-authority-cert.pem"),
+            vx_cert_authority_cert: dev_cert_path("vx-cert-authority-cert.pem"),
+            cac_root_ca_certs: vec![dev_cert_path("vx-cert-authority-cert.pem")],
+            cac_intermediate_ca_certs_dir: None,
+            signer: signer::Description::File(dev_cert_path("vx-admin-private-key.pem")),
+            eg_classpath: PathBuf::from("/not/real/path"),
+            eg_key_encryption_key: SealedSecret::Tpm(0x8100_0002),
+            eg_retired_key_encryption_keys: vec![],
+            inactivity_timeout_seconds: 0,
+            max_incorrect_pin_attempts: 5,
+            pin_lockout_seconds: 15,
+            registration_request_expiry_days: 0,
+        }
+    }
+
+    fn registration_request(jurisdiction_code: &str) -> color_eyre::Result<SignedObject> {
+        let certificate = X509::from_pem(&std::fs::read(dev_cert_path(
+            "vx-admin-cert-authority-cert.pem",
+        ))?)?;
+        let private_key =
+            PKey::private_key_from_pem(&std::fs::read(dev_cert_path("vx-admin-private-key.pem"))?)?;
+        SignedObject::from_payload(
+            &Payload::RegistrationRequest(RegistrationRequest {
+                jurisdiction_code: JurisdictionCode::try_from(jurisdiction_code).unwrap(),
+                common_access_card_id: "1234567890".to_owned(),
+                family_name: "Smith".to_owned(),
+                given_name: "John".to_owned(),
+                address: None,
+            }),
+            certificate,
+            &private_key,
+        )
+    }
+
+    fn sign_bundle(
+        bundle: &TransferBundle,
+        config: &Config,
+    ) -> color_eyre::Result<SignedTransferBundle> {
+        let bundle = serde_json::to_vec(bundle)?;
+        let (signature, certificate) = config.sign(&bundle)?;
+        Ok(SignedTransferBundle {
+            bundle,
+            certificate: certificate.to_pem()?,
+            signature,
+        })
+    }
+
     async fn setup(pool: sqlx::PgPool) -> color_eyre::Result<Client> {
         let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await?;
         let addr = listener.local_addr()?;
@@ -171,8 +355,15 @@ mod tests {
             machine_cert: PathBuf::from("/not/real/path"),
             vx_cert_authority_cert: PathBuf::from("/not/real/path"),
             cac_root_ca_certs: vec![PathBuf::from("/not/real/path")],
+            cac_intermediate_ca_certs_dir: None,
             signer: signer::Description::File(PathBuf::from("/not/real/path")),
             eg_classpath: PathBuf::from("/not/real/path"),
+            eg_key_encryption_key: SealedSecret::Tpm(0x8100_0002),
+            eg_retired_key_encryption_keys: vec![],
+            inactivity_timeout_seconds: 0,
+            max_incorrect_pin_attempts: 5,
+            pin_lockout_seconds: 15,
+            registration_request_expiry_days: 0,
         };
 
         tokio::spawn(async move {
@@ -206,10 +397,126 @@ mod tests {
             &X509::from_pem(include_bytes!(
                 "../../../../libs/auth/certs/dev/vx-cert-authority-cert.pem"
             ))?,
-            &x509::store::X509StoreBuilder::new()?.build(),
+            &types_rs::cacvote::build_cac_ca_store([], [])?,
         )
         .await;
 
         Ok(())
     }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_export_bundle(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let config = dev_config();
+
+        let unsynced_object = registration_request(DEV_JURISDICTION_CODE)?;
+        let unsynced_object_id = unsynced_object.id;
+        db::add_object(&mut connection, &unsynced_object).await?;
+        let synced_object = registration_request(DEV_JURISDICTION_CODE)?;
+        db::add_object_from_server(&mut connection, &synced_object).await?;
+
+        let signed_bundle = export_bundle(&mut connection, &config).await?;
+        signed_bundle.verify(&config.vx_cert_authority_cert()?)?;
+
+        let bundle = signed_bundle.try_to_inner()?;
+        assert_eq!(
+            bundle.jurisdiction_code,
+            JurisdictionCode::try_from(DEV_JURISDICTION_CODE).unwrap()
+        );
+        assert_eq!(
+            bundle
+                .objects
+                .iter()
+                .map(|object| object.id)
+                .collect::<Vec<_>>(),
+            vec![unsynced_object_id],
+            "only unsynced objects are exported"
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let config = dev_config();
+
+        let object = registration_request(DEV_JURISDICTION_CODE)?;
+        let object_id = object.id;
+        let other_jurisdiction_object = registration_request(JURISDICTION_CODE)?;
+        let other_jurisdiction_object_id = other_jurisdiction_object.id;
+        let mut unverifiable_object = registration_request(DEV_JURISDICTION_CODE)?;
+        unverifiable_object.signature = vec![];
+        let unverifiable_object_id = unverifiable_object.id;
+
+        let bundle = TransferBundle::new(
+            JurisdictionCode::try_from(DEV_JURISDICTION_CODE).unwrap(),
+            vec![],
+            vec![object, other_jurisdiction_object, unverifiable_object],
+        );
+        let signed_bundle = sign_bundle(&bundle, &config)?;
+
+        let receipt = import_bundle(&mut connection, &signed_bundle, &config).await?;
+        assert_eq!(receipt.bundle_id, bundle.id);
+        assert_eq!(receipt.imported_object_ids, vec![object_id]);
+        assert_eq!(receipt.skipped_object_ids, vec![]);
+        assert_eq!(
+            receipt
+                .rejected_objects
+                .iter()
+                .map(|rejected| rejected.id)
+                .collect::<Vec<_>>(),
+            vec![other_jurisdiction_object_id, unverifiable_object_id]
+        );
+        assert_eq!(
+            db::get_object_sync_status(&mut connection, object_id).await?,
+            Some(true)
+        );
+        assert_eq!(
+            db::get_object_sync_status(&mut connection, other_jurisdiction_object_id).await?,
+            None
+        );
+
+        // importing again skips the object that was already imported
+        let receipt = import_bundle(&mut connection, &signed_bundle, &config).await?;
+        assert_eq!(receipt.imported_object_ids, vec![]);
+        assert_eq!(receipt.skipped_object_ids, vec![object_id]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle_rejects_invalid_bundle(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let config = dev_config();
+
+        // tampered with after signing
+        let bundle = TransferBundle::new(
+            JurisdictionCode::try_from(DEV_JURISDICTION_CODE).unwrap(),
+            vec![],
+            vec![registration_request(DEV_JURISDICTION_CODE)?],
+        );
+        let mut signed_bundle = sign_bundle(&bundle, &config)?;
+        signed_bundle.bundle.push(b' ');
+        assert!(import_bundle(&mut connection, &signed_bundle, &config)
+            .await
+            .is_err());
+
+        // for another jurisdiction
+        let bundle = TransferBundle::new(
+            JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+            vec![],
+            vec![registration_request(JURISDICTION_CODE)?],
+        );
+        let signed_bundle = sign_bundle(&bundle, &config)?;
+        assert!(import_bundle(&mut connection, &signed_bundle, &config)
+            .await
+            .is_err());
+
+        assert!(db::get_unsynced_objects(&mut connection).await?.is_empty());
+
+        Ok(())
+    }
 }
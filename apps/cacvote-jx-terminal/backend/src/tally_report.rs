@@ -0,0 +1,99 @@
+//! Exporting an election's tally report.
+//!
+//! The report itself is built by [`electionguard_rs::tally_report`] from the
+//! decrypted tally. As CSV, each contest has a row per candidate or yes/no
+//! option followed by rows for its overvotes and undervotes, like the CSV
+//! tally reports exported by VxAdmin.
+
+use electionguard_rs::tally_report::TallyReport;
+use serde::{Deserialize, Serialize};
+use types_rs::election::ContestId;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TallyReportRow {
+    pub(crate) contest_id: ContestId,
+    pub(crate) contest_title: String,
+    pub(crate) selection_id: String,
+    pub(crate) selection: String,
+    pub(crate) votes: u64,
+}
+
+impl TallyReportRow {
+    pub(crate) fn from_report(report: &TallyReport) -> Vec<Self> {
+        report
+            .contests
+            .iter()
+            .flat_map(|contest| {
+                let row = |selection_id: &str, selection: &str, votes: u64| Self {
+                    contest_id: contest.contest_id.clone(),
+                    contest_title: contest.title.clone(),
+                    selection_id: selection_id.to_owned(),
+                    selection: selection.to_owned(),
+                    votes,
+                };
+                contest
+                    .options
+                    .iter()
+                    .map(move |option| row(&option.option_id, &option.label, option.votes))
+                    .chain([
+                        row("overvotes", "Overvotes", contest.overvotes),
+                        row("undervotes", "Undervotes", contest.undervotes),
+                    ])
+            })
+            .collect()
+    }
+}
+
+/// Writes the rows as CSV with a header row.
+pub(crate) fn export_csv(rows: &[TallyReportRow]) -> color_eyre::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use electionguard_rs::tally_report::{ContestResults, OptionResults};
+
+    use super::*;
+
+    #[test]
+    fn test_export_csv() {
+        let report = TallyReport {
+            ballot_count: 3,
+            contests: vec![ContestResults {
+                contest_id: ContestId::from("mayor".to_owned()),
+                title: "Mayor".to_owned(),
+                votes_allowed: 1,
+                ballot_count: 3,
+                overvotes: 0,
+                undervotes: 1,
+                options: vec![
+                    OptionResults {
+                        option_id: "sherlock-holmes".to_owned(),
+                        label: "Sherlock Holmes".to_owned(),
+                        votes: 2,
+                    },
+                    OptionResults {
+                        option_id: "thomas-edison".to_owned(),
+                        label: "Thomas Edison".to_owned(),
+                        votes: 0,
+                    },
+                ],
+            }],
+        };
+
+        let csv = export_csv(&TallyReportRow::from_report(&report)).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "contestId,contestTitle,selectionId,selection,votes\n\
+             mayor,Mayor,sherlock-holmes,Sherlock Holmes,2\n\
+             mayor,Mayor,thomas-edison,Thomas Edison,0\n\
+             mayor,Mayor,overvotes,Overvotes,0\n\
+             mayor,Mayor,undervotes,Undervotes,1\n"
+        );
+    }
+}
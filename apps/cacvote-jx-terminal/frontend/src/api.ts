@@ -14,7 +14,13 @@ import { QUERY_CLIENT_DEFAULT_OPTIONS } from '@votingworks/ui';
 import { Buffer } from 'buffer';
 import { useEffect } from 'react';
 import { SessionData, SessionDataSchema } from './cacvote-server/session_data';
-import { JurisdictionCode, Uuid } from './cacvote-server/types';
+import {
+  ElectionLifecycleAction,
+  JobStatus,
+  JurisdictionCode,
+  RegistrationRejectionReason,
+  Uuid,
+} from './cacvote-server/types';
 
 export function createQueryClient(): QueryClient {
   return new QueryClient({ defaultOptions: QUERY_CLIENT_DEFAULT_OPTIONS });
@@ -64,12 +70,30 @@ export interface CreateElectionRequest {
   jurisdictionCode: JurisdictionCode;
   electionDefinition: ElectionDefinition;
   mailingAddress: string;
+  /** ISO 8601 timestamp of when voters may start casting ballots. */
+  votingStartsAt?: string;
+  /** ISO 8601 timestamp of when voters may no longer cast ballots. */
+  votingEndsAt?: string;
+  /** ISO 8601 timestamp after which mailed ballots are too late to count. */
+  ballotReceiptDeadline?: string;
+  /** Sign with the official's card instead of the machine key. */
+  pin?: string;
 }
 
 export interface CreateElectionResponse {
   id: Uuid;
 }
 
+/**
+ * The JX rejected the election definition. `problems` lists everything wrong
+ * with it so they can all be fixed at once.
+ */
+export class InvalidElectionDefinitionError extends Error {
+  constructor(readonly problems: readonly string[]) {
+    super(`Invalid election definition: ${problems.join('; ')}`);
+  }
+}
+
 export const createElection = {
   /**
    * Create a new election.
@@ -89,6 +113,13 @@ export const createElection = {
         }),
       });
 
+      if (response.status === 400) {
+        const body = (await response.json()) as { problems?: string[] };
+        if (body.problems) {
+          throw new InvalidElectionDefinitionError(body.problems);
+        }
+      }
+
       if (!response.ok) {
         throw new Error(`Failed to create election: ${response.statusText}`);
       }
@@ -101,8 +132,12 @@ export const createElection = {
 export interface CreateRegistrationRequest {
   registrationRequestId: Uuid;
   electionId: Uuid;
-  ballotStyleId: BallotStyleId;
-  precinctId: PrecinctId;
+  /** Defaults to the only ballot style used in the precinct. */
+  ballotStyleId?: BallotStyleId;
+  /** Defaults to the precinct found from the voter's address. */
+  precinctId?: PrecinctId;
+  /** Sign with the official's card instead of the machine key. */
+  pin?: string;
 }
 
 export interface CreateRegistrationResponse {
@@ -129,12 +164,85 @@ export const registerVoter = {
   },
 } as const;
 
+export interface RejectRegistrationRequest {
+  registrationRequestId: Uuid;
+  reason: RegistrationRejectionReason;
+  message?: string;
+  /** Sign with the official's card instead of the machine key. */
+  pin?: string;
+}
+
+export interface RejectRegistrationResponse {
+  id: Uuid;
+}
+
+export const rejectRegistration = {
+  useMutation() {
+    return useMutation(async (request: RejectRegistrationRequest) => {
+      const response = await fetch('/api/registration-rejections', {
+        method: 'POST',
+        headers: {
+          'Content-Type': 'application/json',
+        },
+        body: JSON.stringify(request),
+      });
+
+      if (!response.ok) {
+        throw new Error(
+          `Failed to reject registration request: ${response.statusText}`
+        );
+      }
+
+      return (await response.json()) as RejectRegistrationResponse;
+    });
+  },
+} as const;
+
+export interface ChangeElectionLifecycleRequest {
+  electionId: Uuid;
+  action: ElectionLifecycleAction;
+  /** Sign with the official's card instead of the machine key. */
+  pin?: string;
+}
+
+export interface ChangeElectionLifecycleResponse {
+  id: Uuid;
+}
+
+export const changeElectionLifecycle = {
+  useMutation() {
+    return useMutation(
+      async ({ electionId, action, pin }: ChangeElectionLifecycleRequest) => {
+        const response = await fetch(`/api/elections/${electionId}/lifecycle`, {
+          method: 'POST',
+          headers: {
+            'Content-Type': 'application/json',
+          },
+          body: JSON.stringify({ action, pin }),
+        });
+
+        if (!response.ok) {
+          throw new Error(
+            `Failed to change election lifecycle: ${response.statusText}`
+          );
+        }
+
+        return (await response.json()) as ChangeElectionLifecycleResponse;
+      }
+    );
+  },
+} as const;
+
 export interface CreateEncryptedElectionTallyRequest {
   electionId: Uuid;
 }
 
+/**
+ * The tally is generated by a background job, whose progress is reported in
+ * the session data.
+ */
 export interface CreateEncryptedElectionTallyResponse {
-  id: Uuid;
+  jobId: Uuid;
 }
 
 export const generateEncryptedElectionTally = {
@@ -158,19 +266,31 @@ export const generateEncryptedElectionTally = {
 
 export interface DecryptEncryptedElectionTallyRequest {
   electionId: Uuid;
+  /** PIN of the official's card, required to unwrap the election key. */
+  pin: string;
 }
 
+/**
+ * The tally is decrypted by a background job, whose progress is reported in
+ * the session data.
+ */
 export interface DecryptEncryptedElectionTallyResponse {
-  id: Uuid;
+  jobId: Uuid;
 }
 
 export const decryptEncryptedElectionTally = {
   useMutation() {
     return useMutation(
-      async ({ electionId }: DecryptEncryptedElectionTallyRequest) => {
+      async ({ electionId, pin }: DecryptEncryptedElectionTallyRequest) => {
         const response = await fetch(
           `/api/elections/${electionId}/decrypted-tally`,
-          { method: 'POST' }
+          {
+            method: 'POST',
+            headers: {
+              'Content-Type': 'application/json',
+            },
+            body: JSON.stringify({ pin }),
+          }
         );
 
         if (!response.ok) {
@@ -185,13 +305,92 @@ export const decryptEncryptedElectionTally = {
   },
 } as const;
 
+export interface TallyReportOption {
+  /** The ID of the candidate or yes/no option. */
+  optionId: string;
+  /** The candidate's name or the yes/no option's label. */
+  label: string;
+  votes: number;
+}
+
+export interface TallyReportContest {
+  contestId: string;
+  title: string;
+  votesAllowed: number;
+  /** How many of the tallied ballots included the contest. */
+  ballotCount: number;
+  /** Always 0, since ballots are marked on a BMD. */
+  overvotes: number;
+  undervotes: number;
+  options: TallyReportOption[];
+}
+
+/**
+ * The results of each contest of an election, from its decrypted tally.
+ */
+export interface TallyReport {
+  ballotCount: number;
+  contests: TallyReportContest[];
+}
+
+export const tallyReport = {
+  queryKey: (electionId: Uuid) => ['tallyReport', electionId],
+
+  /**
+   * Returns `null` for elections whose tally has not been decrypted.
+   */
+  useQuery(electionId: Uuid, { enabled }: { enabled: boolean }) {
+    return useQuery(
+      this.queryKey(electionId),
+      async () => {
+        const response = await fetch(
+          `/api/elections/${electionId}/tally-report`
+        );
+        if (response.status === 409) {
+          return null;
+        }
+        if (!response.ok) {
+          throw new Error(
+            `Failed to fetch tally report: ${response.statusText}`
+          );
+        }
+
+        return (await response.json()) as TallyReport;
+      },
+      { enabled }
+    );
+  },
+} as const;
+
+/**
+ * Gets the tally report for the given election as CSV. We don't parse it here
+ * because we're just going to download it.
+ */
+export async function getTallyReportCsvRaw(
+  electionId: Uuid
+): Promise<Uint8Array> {
+  const response = await fetch(
+    `/api/elections/${electionId}/tally-report?format=csv`
+  );
+  if (!response.ok) {
+    throw new Error(`Failed to fetch tally report: ${response.statusText}`);
+  }
+
+  const blob = await response.blob();
+  return new Uint8Array(await blob.arrayBuffer());
+}
+
 export interface ShuffleEncryptedBallotsRequest {
   electionId: Uuid;
   phases: number;
 }
 
+/**
+ * The ballots are shuffled by a background job, whose progress is reported in
+ * the session data.
+ */
 export interface ShuffleEncryptedBallotsResponse {
-  id: Uuid;
+  jobId: Uuid;
 }
 
 export const shuffleEncryptedBallots = {
@@ -219,6 +418,118 @@ export const shuffleEncryptedBallots = {
   },
 } as const;
 
+export interface VerifyMixedBallotsRequest {
+  electionId: Uuid;
+}
+
+/**
+ * The proofs of shuffle are checked by a background job, whose progress is
+ * reported in the session data.
+ */
+export interface VerifyMixedBallotsResponse {
+  jobId: Uuid;
+}
+
+export const verifyMixedBallots = {
+  useMutation() {
+    return useMutation(async ({ electionId }: VerifyMixedBallotsRequest) => {
+      const response = await fetch(
+        `/api/elections/${electionId}/mixed-ballots/verification`,
+        { method: 'POST' }
+      );
+
+      if (!response.ok) {
+        throw new Error(
+          `Failed to verify mixed ballots: ${response.statusText}`
+        );
+      }
+
+      return (await response.json()) as VerifyMixedBallotsResponse;
+    });
+  },
+} as const;
+
+export interface DecryptMixedBallotsRequest {
+  electionId: Uuid;
+
+  /**
+   * PIN of the official's card, required to unwrap the election key held by
+   * this JX.
+   */
+  pin: string;
+}
+
+/**
+ * The mixed ballots are decrypted by a background job, whose progress is
+ * reported in the session data.
+ */
+export interface DecryptMixedBallotsResponse {
+  jobId: Uuid;
+}
+
+/**
+ * The JX will not decrypt the mixed ballots in their current state, e.g.
+ * because it is not the JX that holds the election's private key. The message
+ * says why.
+ */
+export class DecryptMixedBallotsConflictError extends Error {}
+
+export const decryptMixedBallots = {
+  useMutation() {
+    return useMutation(
+      async ({ electionId, pin }: DecryptMixedBallotsRequest) => {
+        const response = await fetch(
+          `/api/elections/${electionId}/mixed-ballots/decryption`,
+          {
+            method: 'POST',
+            headers: {
+              'Content-Type': 'application/json',
+            },
+            body: JSON.stringify({ pin }),
+          }
+        );
+
+        if (response.status === 409) {
+          const body = (await response.json()) as { error: string };
+          throw new DecryptMixedBallotsConflictError(body.error);
+        }
+
+        if (!response.ok) {
+          throw new Error(
+            `Failed to decrypt mixed ballots: ${response.statusText}`
+          );
+        }
+
+        return (await response.json()) as DecryptMixedBallotsResponse;
+      }
+    );
+  },
+} as const;
+
+export interface CancelJobResponse {
+  id: Uuid;
+  status: JobStatus;
+}
+
+/**
+ * Cancels a background job. A running job stops at its next progress report.
+ */
+export const cancelJob = {
+  useMutation() {
+    return useMutation(async ({ jobId }: { jobId: Uuid }) => {
+      const response = await fetch(`/api/jobs/${jobId}/cancel`, {
+        method: 'POST',
+      });
+
+      if (!response.ok) {
+        throw new Error(`Failed to cancel job: ${response.statusText}`);
+      }
+
+      return (await response.json()) as CancelJobResponse;
+    });
+  },
+} as const;
+
 export const authenticate = {
   useMutation() {
     return useMutation(async (pin: string) => {
@@ -235,6 +546,15 @@ export const authenticate = {
   },
 } as const;
 
+export const lock = {
+  useMutation() {
+    return useMutation(async () => {
+      const response = await fetch('/api/lock', { method: 'POST' });
+      return response.ok;
+    });
+  },
+} as const;
+
 /**
  * Gets the raw mailing label data for the given election. We don't parse it
  * here because we're just going to download it.
@@ -254,3 +574,30 @@ export async function getScannedMailingLabelsRawByElection(
   const blob = await response.blob();
   return new Uint8Array(await blob.arrayBuffer());
 }
+
+/**
+ * The parts of a scanned mailing label needed to reconcile received ballots.
+ */
+export interface ScannedMailingLabelSummary {
+  createdAt: string;
+  receivedAfterDeadline: boolean;
+}
+
+export const scannedMailingLabels = {
+  queryKey: (electionId: Uuid) => ['scannedMailingLabels', electionId],
+
+  useQuery(electionId: Uuid) {
+    return useQuery(this.queryKey(electionId), async () => {
+      const response = await fetch(
+        `/api/elections/${electionId}/scanned-mailing-labels`
+      );
+      if (!response.ok) {
+        throw new Error(
+          `Failed to fetch scanned mailing labels: ${response.statusText}`
+        );
+      }
+
+      return (await response.json()) as ScannedMailingLabelSummary[];
+    });
+  },
+} as const;
@@ -45,6 +45,7 @@ test('authenticated render', async () => {
     ],
     [],
     [],
+    [],
     []
   );
 
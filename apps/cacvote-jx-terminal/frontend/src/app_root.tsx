@@ -24,8 +24,8 @@ export function AppRoot(): JSX.Element {
   const authenticationFailed = authenticateMutation.data === false;
   const isUnauthenticated =
     sessionData && sessionData instanceof UnauthenticatedSessionData;
-  const isAuthenticating =
-    sessionData && sessionData instanceof AuthenticatingSessionData;
+  const authenticatingSessionData =
+    sessionData instanceof AuthenticatingSessionData ? sessionData : undefined;
 
   useEffect(() => {
     if (isUnauthenticated) {
@@ -33,13 +33,16 @@ export function AppRoot(): JSX.Element {
     }
   }, [isUnauthenticated, history]);
 
-  if (isAuthenticating) {
+  if (authenticatingSessionData) {
     return (
       <PinPadModal
         isAuthenticating={authenticateMutation.isLoading}
         error={
-          authenticationFailed ? 'Could not log in. Invalid PIN?' : undefined
+          authenticationFailed
+            ? 'Could not log in. Invalid PIN?'
+            : authenticatingSessionData.getAuthError()
         }
+        lockedUntil={authenticatingSessionData.getLockedUntil()}
         onEnter={(pin) => {
           authenticateMutation.mutate(pin);
         }}
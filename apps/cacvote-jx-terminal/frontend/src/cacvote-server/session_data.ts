@@ -12,7 +12,13 @@ import { Buffer } from 'buffer';
 import { DateTime } from 'luxon';
 import { z } from 'zod';
 import {
+  ElectionLifecycleState,
+  ElectionLifecycleStateSchema,
   Iso8601DateSchema,
+  JobKind,
+  JobKindSchema,
+  JobStatus,
+  JobStatusSchema,
   JurisdictionCode,
   JurisdictionCodeSchema,
   Uuid,
@@ -34,11 +40,13 @@ export const VerificationStatusSuccessSchema: z.ZodSchema<VerificationStatusSucc
 
 export interface VerificationStatusFailure {
   type: 'failure';
+  reason: string;
 }
 
 export const VerificationStatusFailureSchema: z.ZodSchema<VerificationStatusFailure> =
   z.object({
     type: z.literal('failure'),
+    reason: z.string(),
   });
 
 export interface VerificationStatusError {
@@ -79,6 +87,7 @@ export interface EncryptedElectionTallyStruct {
   jurisdictionCode: string;
   electionObjectId: string;
   electionguardEncryptedTally: string;
+  castBallotIds?: string[];
 }
 
 export const EncryptedElectionTallyStructSchema: z.ZodSchema<EncryptedElectionTallyStruct> =
@@ -86,13 +95,15 @@ export const EncryptedElectionTallyStructSchema: z.ZodSchema<EncryptedElectionTa
     jurisdictionCode: z.string(),
     electionObjectId: z.string(),
     electionguardEncryptedTally: z.string(),
+    castBallotIds: z.array(z.string()).optional(),
   });
 
 export class EncryptedElectionTally {
   constructor(
     private readonly jurisdictionCode: JurisdictionCode,
     private readonly electionObjectId: Uuid,
-    private readonly electionguardEncryptedTally: Buffer
+    private readonly electionguardEncryptedTally: Buffer,
+    private readonly castBallotIds: Uuid[]
   ) {}
 
   getJurisdictionCode(): JurisdictionCode {
@@ -107,12 +118,17 @@ export class EncryptedElectionTally {
     return this.electionguardEncryptedTally;
   }
 
+  getCastBallotIds(): Uuid[] {
+    return this.castBallotIds;
+  }
+
   toJSON(): EncryptedElectionTallyStruct {
     return {
       jurisdictionCode: this.jurisdictionCode,
       electionObjectId: this.electionObjectId,
       electionguardEncryptedTally:
         this.electionguardEncryptedTally.toString('base64'),
+      castBallotIds: this.castBallotIds,
     };
   }
 }
@@ -123,7 +139,8 @@ export const EncryptedElectionTallySchema: z.ZodSchema<EncryptedElectionTally> =
       new EncryptedElectionTally(
         JurisdictionCodeSchema.parse(struct.jurisdictionCode),
         UuidSchema.parse(struct.electionObjectId),
-        Buffer.from(struct.electionguardEncryptedTally, 'base64')
+        Buffer.from(struct.electionguardEncryptedTally, 'base64'),
+        (struct.castBallotIds ?? []).map((id) => UuidSchema.parse(id))
       )
   ) as unknown as z.ZodSchema<EncryptedElectionTally>;
 
@@ -351,11 +368,258 @@ export const ShuffledEncryptedCastBallotsPresenterSchema: z.ZodSchema<ShuffledEn
       )
   ) as unknown as z.ZodSchema<ShuffledEncryptedCastBallotsPresenter>;
 
+export interface MixPhaseVerification {
+  mixName: string;
+  ballotCount: number;
+  error?: string;
+}
+
+export const MixPhaseVerificationSchema: z.ZodSchema<MixPhaseVerification> =
+  z.object({
+    mixName: z.string(),
+    ballotCount: z.number().int().nonnegative(),
+    error: z.string().optional(),
+  });
+
+export interface ShuffledEncryptedCastBallotsVerificationPresenterStruct {
+  jurisdictionCode: string;
+  electionObjectId: string;
+  shuffledEncryptedCastBallotsObjectId: string;
+  castBallotCount: number;
+  phases: MixPhaseVerification[];
+  createdAt: string;
+  syncedAt?: string;
+}
+
+export const ShuffledEncryptedCastBallotsVerificationPresenterStructSchema: z.ZodSchema<ShuffledEncryptedCastBallotsVerificationPresenterStruct> =
+  z.object({
+    jurisdictionCode: z.string(),
+    electionObjectId: z.string(),
+    shuffledEncryptedCastBallotsObjectId: z.string(),
+    castBallotCount: z.number().int().nonnegative(),
+    phases: z.array(MixPhaseVerificationSchema),
+    createdAt: z.string(),
+    syncedAt: z.string().optional(),
+  });
+
+/**
+ * The result of checking the proof of shuffle of every phase of an election's
+ * shuffled ballots.
+ */
+export class ShuffledEncryptedCastBallotsVerificationPresenter {
+  constructor(
+    private readonly jurisdictionCode: JurisdictionCode,
+    private readonly electionObjectId: Uuid,
+    private readonly shuffledEncryptedCastBallotsObjectId: Uuid,
+    private readonly castBallotCount: number,
+    private readonly phases: MixPhaseVerification[],
+    private readonly createdAt: DateTime,
+    private readonly syncedAt?: DateTime
+  ) {}
+
+  getJurisdictionCode(): JurisdictionCode {
+    return this.jurisdictionCode;
+  }
+
+  getElectionObjectId(): Uuid {
+    return this.electionObjectId;
+  }
+
+  getShuffledEncryptedCastBallotsObjectId(): Uuid {
+    return this.shuffledEncryptedCastBallotsObjectId;
+  }
+
+  getCastBallotCount(): number {
+    return this.castBallotCount;
+  }
+
+  getPhases(): MixPhaseVerification[] {
+    return this.phases;
+  }
+
+  /**
+   * Whether every phase's proof verified and every phase output as many
+   * ballots as were cast.
+   */
+  isVerified(): boolean {
+    return (
+      this.phases.length > 0 &&
+      this.phases.every(
+        (phase) =>
+          phase.error === undefined &&
+          phase.ballotCount === this.castBallotCount
+      )
+    );
+  }
+
+  getCreatedAt(): DateTime {
+    return this.createdAt;
+  }
+
+  getSyncedAt(): Optional<DateTime> {
+    return this.syncedAt;
+  }
+
+  toJSON(): ShuffledEncryptedCastBallotsVerificationPresenterStruct {
+    return {
+      jurisdictionCode: this.jurisdictionCode,
+      electionObjectId: this.electionObjectId,
+      shuffledEncryptedCastBallotsObjectId:
+        this.shuffledEncryptedCastBallotsObjectId,
+      castBallotCount: this.castBallotCount,
+      phases: this.phases,
+      createdAt: this.createdAt.toISO(),
+      syncedAt: this.syncedAt?.toISO(),
+    };
+  }
+}
+
+export const ShuffledEncryptedCastBallotsVerificationPresenterSchema: z.ZodSchema<ShuffledEncryptedCastBallotsVerificationPresenter> =
+  ShuffledEncryptedCastBallotsVerificationPresenterStructSchema.transform(
+    (struct) =>
+      new ShuffledEncryptedCastBallotsVerificationPresenter(
+        JurisdictionCodeSchema.parse(struct.jurisdictionCode),
+        UuidSchema.parse(struct.electionObjectId),
+        UuidSchema.parse(struct.shuffledEncryptedCastBallotsObjectId),
+        struct.castBallotCount,
+        struct.phases,
+        DateTime.fromISO(struct.createdAt),
+        struct.syncedAt ? DateTime.fromISO(struct.syncedAt) : undefined
+      )
+  ) as unknown as z.ZodSchema<ShuffledEncryptedCastBallotsVerificationPresenter>;
+
+export interface AnonymousBallotSelection {
+  optionId: string;
+  vote: number;
+}
+
+export interface AnonymousBallotContest {
+  contestId: string;
+  selections: AnonymousBallotSelection[];
+}
+
+/**
+ * A decrypted ballot that cannot be linked to the voter who cast it.
+ */
+export interface AnonymousBallot {
+  contests: AnonymousBallotContest[];
+}
+
+export const AnonymousBallotSchema: z.ZodSchema<AnonymousBallot> = z.object({
+  contests: z.array(
+    z.object({
+      contestId: z.string(),
+      selections: z.array(
+        z.object({
+          optionId: z.string(),
+          vote: z.number().int().nonnegative(),
+        })
+      ),
+    })
+  ),
+});
+
+export interface DecryptedShuffledBallotsPresenterStruct {
+  jurisdictionCode: string;
+  electionObjectId: string;
+  shuffledEncryptedCastBallotsObjectId: string;
+  ballots: AnonymousBallot[];
+  electionguardDecryptedBallots: string;
+  createdAt: string;
+  syncedAt?: string;
+}
+
+export const DecryptedShuffledBallotsPresenterStructSchema: z.ZodSchema<DecryptedShuffledBallotsPresenterStruct> =
+  z.object({
+    jurisdictionCode: z.string(),
+    electionObjectId: z.string(),
+    shuffledEncryptedCastBallotsObjectId: z.string(),
+    ballots: z.array(AnonymousBallotSchema),
+    electionguardDecryptedBallots: z.string(),
+    createdAt: z.string(),
+    syncedAt: z.string().optional(),
+  });
+
+/**
+ * The ballots output by the final shuffling phase, decrypted into anonymous
+ * ballots.
+ */
+export class DecryptedShuffledBallotsPresenter {
+  constructor(
+    private readonly jurisdictionCode: JurisdictionCode,
+    private readonly electionObjectId: Uuid,
+    private readonly shuffledEncryptedCastBallotsObjectId: Uuid,
+    private readonly ballots: AnonymousBallot[],
+    private readonly electionguardDecryptedBallots: Buffer,
+    private readonly createdAt: DateTime,
+    private readonly syncedAt?: DateTime
+  ) {}
+
+  getJurisdictionCode(): JurisdictionCode {
+    return this.jurisdictionCode;
+  }
+
+  getElectionObjectId(): Uuid {
+    return this.electionObjectId;
+  }
+
+  getShuffledEncryptedCastBallotsObjectId(): Uuid {
+    return this.shuffledEncryptedCastBallotsObjectId;
+  }
+
+  getBallots(): AnonymousBallot[] {
+    return this.ballots;
+  }
+
+  getElectionguardDecryptedBallots(): Buffer {
+    return this.electionguardDecryptedBallots;
+  }
+
+  getCreatedAt(): DateTime {
+    return this.createdAt;
+  }
+
+  getSyncedAt(): Optional<DateTime> {
+    return this.syncedAt;
+  }
+
+  toJSON(): DecryptedShuffledBallotsPresenterStruct {
+    return {
+      jurisdictionCode: this.jurisdictionCode,
+      electionObjectId: this.electionObjectId,
+      shuffledEncryptedCastBallotsObjectId:
+        this.shuffledEncryptedCastBallotsObjectId,
+      ballots: this.ballots,
+      electionguardDecryptedBallots:
+        this.electionguardDecryptedBallots.toString('base64'),
+      createdAt: this.createdAt.toISO(),
+      syncedAt: this.syncedAt?.toISO(),
+    };
+  }
+}
+
+export const DecryptedShuffledBallotsPresenterSchema: z.ZodSchema<DecryptedShuffledBallotsPresenter> =
+  DecryptedShuffledBallotsPresenterStructSchema.transform(
+    (struct) =>
+      new DecryptedShuffledBallotsPresenter(
+        JurisdictionCodeSchema.parse(struct.jurisdictionCode),
+        UuidSchema.parse(struct.electionObjectId),
+        UuidSchema.parse(struct.shuffledEncryptedCastBallotsObjectId),
+        struct.ballots,
+        Buffer.from(struct.electionguardDecryptedBallots, 'base64'),
+        DateTime.fromISO(struct.createdAt),
+        struct.syncedAt ? DateTime.fromISO(struct.syncedAt) : undefined
+      )
+  ) as unknown as z.ZodSchema<DecryptedShuffledBallotsPresenter>;
+
 export interface ElectionInfoStruct {
   jurisdictionCode: string;
   electionDefinition: string;
   mailingAddress: string;
   electionguardElectionMetadataBlob: string;
+  votingStartsAt?: string;
+  votingEndsAt?: string;
+  ballotReceiptDeadline?: string;
 }
 
 export const ElectionInfoStructSchema: z.ZodSchema<ElectionInfoStruct> =
@@ -364,6 +628,9 @@ export const ElectionInfoStructSchema: z.ZodSchema<ElectionInfoStruct> =
     electionDefinition: z.string(),
     mailingAddress: z.string(),
     electionguardElectionMetadataBlob: z.string(),
+    votingStartsAt: z.string().optional(),
+    votingEndsAt: z.string().optional(),
+    ballotReceiptDeadline: z.string().optional(),
   });
 
 export class ElectionInfo {
@@ -371,7 +638,10 @@ export class ElectionInfo {
     private readonly jurisdictionCode: JurisdictionCode,
     private readonly electionDefinition: ElectionDefinition,
     private readonly mailingAddress: string,
-    private readonly electionguardElectionMetadataBlob: Buffer
+    private readonly electionguardElectionMetadataBlob: Buffer,
+    private readonly votingStartsAt?: DateTime,
+    private readonly votingEndsAt?: DateTime,
+    private readonly ballotReceiptDeadline?: DateTime
   ) {}
 
   getJurisdictionCode(): JurisdictionCode {
@@ -390,6 +660,18 @@ export class ElectionInfo {
     return this.electionguardElectionMetadataBlob;
   }
 
+  getVotingStartsAt(): Optional<DateTime> {
+    return this.votingStartsAt;
+  }
+
+  getVotingEndsAt(): Optional<DateTime> {
+    return this.votingEndsAt;
+  }
+
+  getBallotReceiptDeadline(): Optional<DateTime> {
+    return this.ballotReceiptDeadline;
+  }
+
   toJSON(): ElectionInfoStruct {
     return {
       jurisdictionCode: this.jurisdictionCode,
@@ -399,6 +681,9 @@ export class ElectionInfo {
       mailingAddress: this.mailingAddress,
       electionguardElectionMetadataBlob:
         this.electionguardElectionMetadataBlob.toString('base64'),
+      votingStartsAt: this.votingStartsAt?.toISO(),
+      votingEndsAt: this.votingEndsAt?.toISO(),
+      ballotReceiptDeadline: this.ballotReceiptDeadline?.toISO(),
     };
   }
 }
@@ -412,7 +697,14 @@ export const ElectionInfoSchema: z.ZodSchema<ElectionInfo> =
           Buffer.from(struct.electionDefinition, 'base64').toString('utf-8')
         ).unsafeUnwrap(),
         struct.mailingAddress,
-        Buffer.from(struct.electionguardElectionMetadataBlob, 'base64')
+        Buffer.from(struct.electionguardElectionMetadataBlob, 'base64'),
+        struct.votingStartsAt
+          ? DateTime.fromISO(struct.votingStartsAt)
+          : undefined,
+        struct.votingEndsAt ? DateTime.fromISO(struct.votingEndsAt) : undefined,
+        struct.ballotReceiptDeadline
+          ? DateTime.fromISO(struct.ballotReceiptDeadline)
+          : undefined
       )
   ) as unknown as z.ZodSchema<ElectionInfo>;
 
@@ -422,6 +714,9 @@ export interface ElectionPresenterStruct {
   encryptedTally?: EncryptedElectionTallyPresenterStruct;
   decryptedTally?: DecryptedElectionTallyPresenterStruct;
   shuffledEncryptedCastBallots?: ShuffledEncryptedCastBallotsPresenterStruct;
+  shuffledEncryptedCastBallotsVerification?: ShuffledEncryptedCastBallotsVerificationPresenterStruct;
+  decryptedShuffledBallots?: DecryptedShuffledBallotsPresenterStruct;
+  lifecycleState?: ElectionLifecycleState;
 }
 
 export const ElectionPresenterStructSchema: z.ZodSchema<ElectionPresenterStruct> =
@@ -432,6 +727,11 @@ export const ElectionPresenterStructSchema: z.ZodSchema<ElectionPresenterStruct>
     decryptedTally: DecryptedElectionTallyPresenterStructSchema.optional(),
     shuffledEncryptedCastBallots:
       ShuffledEncryptedCastBallotsPresenterStructSchema.optional(),
+    shuffledEncryptedCastBallotsVerification:
+      ShuffledEncryptedCastBallotsVerificationPresenterStructSchema.optional(),
+    decryptedShuffledBallots:
+      DecryptedShuffledBallotsPresenterStructSchema.optional(),
+    lifecycleState: ElectionLifecycleStateSchema.optional(),
   });
 
 export class ElectionPresenter {
@@ -440,7 +740,10 @@ export class ElectionPresenter {
     private readonly election: ElectionInfo,
     private readonly encryptedTally?: EncryptedElectionTallyPresenter,
     private readonly decryptedTally?: DecryptedElectionTallyPresenter,
-    private readonly shuffledEncryptedCastBallots?: ShuffledEncryptedCastBallotsPresenter
+    private readonly shuffledEncryptedCastBallots?: ShuffledEncryptedCastBallotsPresenter,
+    private readonly lifecycleState: ElectionLifecycleState = 'created',
+    private readonly shuffledEncryptedCastBallotsVerification?: ShuffledEncryptedCastBallotsVerificationPresenter,
+    private readonly decryptedShuffledBallots?: DecryptedShuffledBallotsPresenter
   ) {}
 
   getId(): Uuid {
@@ -463,6 +766,18 @@ export class ElectionPresenter {
     return this.shuffledEncryptedCastBallots;
   }
 
+  getShuffledEncryptedCastBallotsVerification(): Optional<ShuffledEncryptedCastBallotsVerificationPresenter> {
+    return this.shuffledEncryptedCastBallotsVerification;
+  }
+
+  getDecryptedShuffledBallots(): Optional<DecryptedShuffledBallotsPresenter> {
+    return this.decryptedShuffledBallots;
+  }
+
+  getLifecycleState(): ElectionLifecycleState {
+    return this.lifecycleState;
+  }
+
   toJSON(): ElectionPresenterStruct {
     return {
       id: this.id,
@@ -470,6 +785,10 @@ export class ElectionPresenter {
       encryptedTally: this.encryptedTally?.toJSON(),
       decryptedTally: this.decryptedTally?.toJSON(),
       shuffledEncryptedCastBallots: this.shuffledEncryptedCastBallots?.toJSON(),
+      shuffledEncryptedCastBallotsVerification:
+        this.shuffledEncryptedCastBallotsVerification?.toJSON(),
+      decryptedShuffledBallots: this.decryptedShuffledBallots?.toJSON(),
+      lifecycleState: this.lifecycleState,
     };
   }
 }
@@ -487,15 +806,51 @@ export const ElectionPresenterSchema: z.ZodSchema<ElectionPresenter> =
         struct.shuffledEncryptedCastBallots &&
           ShuffledEncryptedCastBallotsPresenterSchema.parse(
             struct.shuffledEncryptedCastBallots
+          ),
+        struct.lifecycleState,
+        struct.shuffledEncryptedCastBallotsVerification &&
+          ShuffledEncryptedCastBallotsVerificationPresenterSchema.parse(
+            struct.shuffledEncryptedCastBallotsVerification
+          ),
+        struct.decryptedShuffledBallots &&
+          DecryptedShuffledBallotsPresenterSchema.parse(
+            struct.decryptedShuffledBallots
           )
       )
   ) as unknown as z.ZodSchema<ElectionPresenter>;
 
+export interface VoterAddress {
+  houseNumber: number;
+  streetName: string;
+  unit?: string;
+  city: string;
+  zipCode: string;
+}
+
+export const VoterAddressSchema: z.ZodSchema<VoterAddress> = z.object({
+  houseNumber: z.number().int().nonnegative(),
+  streetName: z.string(),
+  unit: z.string().optional(),
+  city: z.string(),
+  zipCode: z.string(),
+});
+
+/**
+ * Formats an address on one line, e.g. "12 Main St Apt 4, Springfield 12345".
+ */
+export function formatVoterAddress(address: VoterAddress): string {
+  const street = [address.houseNumber, address.streetName, address.unit]
+    .filter((part) => part !== undefined)
+    .join(' ');
+  return `${street}, ${address.city} ${address.zipCode}`;
+}
+
 export interface RegistrationRequestStruct {
   commonAccessCardId: string;
   jurisdictionCode: string;
   givenName: string;
   familyName: string;
+  address?: VoterAddress;
 }
 
 export const RegistrationRequestStructSchema: z.ZodSchema<RegistrationRequestStruct> =
@@ -504,6 +859,7 @@ export const RegistrationRequestStructSchema: z.ZodSchema<RegistrationRequestStr
     jurisdictionCode: z.string(),
     givenName: z.string(),
     familyName: z.string(),
+    address: VoterAddressSchema.optional(),
   });
 
 export class RegistrationRequest {
@@ -511,7 +867,8 @@ export class RegistrationRequest {
     private readonly commonAccessCardId: string,
     private readonly jurisdictionCode: JurisdictionCode,
     private readonly givenName: string,
-    private readonly familyName: string
+    private readonly familyName: string,
+    private readonly address?: VoterAddress
   ) {}
 
   getCommonAccessCardId(): string {
@@ -530,12 +887,17 @@ export class RegistrationRequest {
     return this.familyName;
   }
 
+  getAddress(): Optional<VoterAddress> {
+    return this.address;
+  }
+
   toJSON(): RegistrationRequestStruct {
     return {
       commonAccessCardId: this.commonAccessCardId,
       jurisdictionCode: this.jurisdictionCode,
       givenName: this.givenName,
       familyName: this.familyName,
+      address: this.address,
     };
   }
 }
@@ -547,15 +909,34 @@ export const RegistrationRequestSchema: z.ZodSchema<RegistrationRequest> =
         struct.commonAccessCardId,
         JurisdictionCodeSchema.parse(struct.jurisdictionCode),
         struct.givenName,
-        struct.familyName
+        struct.familyName,
+        struct.address
       )
   ) as unknown as z.ZodSchema<RegistrationRequest>;
 
+/**
+ * A precinct and ballot style the JX suggests for a registration request in
+ * an election, based on the voter's address.
+ */
+export interface SuggestedAssignment {
+  electionId: Uuid;
+  precinctId: PrecinctId;
+  ballotStyleId: BallotStyleId;
+}
+
+export const SuggestedAssignmentSchema: z.ZodSchema<SuggestedAssignment> =
+  z.object({
+    electionId: UuidSchema,
+    precinctId: PrecinctIdSchema,
+    ballotStyleId: BallotStyleIdSchema,
+  });
+
 export interface RegistrationRequestPresenter {
   id: Uuid;
   displayName: string;
   registrationRequest: RegistrationRequestStruct;
   createdAt: DateTime;
+  suggestedAssignments: SuggestedAssignment[];
 }
 
 export const RegistrationRequestPresenterSchema: z.ZodSchema<RegistrationRequestPresenter> =
@@ -564,6 +945,7 @@ export const RegistrationRequestPresenterSchema: z.ZodSchema<RegistrationRequest
     displayName: z.string(),
     registrationRequest: RegistrationRequestStructSchema,
     createdAt: Iso8601DateSchema,
+    suggestedAssignments: z.array(SuggestedAssignmentSchema).default([]),
   });
 
 export interface RegistrationStruct {
@@ -860,39 +1242,91 @@ export const UnauthenticatedSessionDataSchema: z.ZodSchema<UnauthenticatedSessio
 
 export interface AuthenticatingSessionDataStruct {
   type: 'authenticating';
-  authorizationError?: string;
+  authError?: string;
+  lockedUntil?: string;
 }
 
 export const AuthenticatingSessionDataStructSchema: z.ZodSchema<AuthenticatingSessionDataStruct> =
   z.object({
     type: z.literal('authenticating'),
-    authorizationError: z.string().optional(),
+    authError: z.string().optional(),
+    lockedUntil: z.string().optional(),
   });
 
 export class AuthenticatingSessionData {
-  constructor(private readonly authorizationError?: string) {}
+  constructor(
+    private readonly authError?: string,
+    private readonly lockedUntil?: DateTime
+  ) {}
 
   get type(): 'authenticating' {
     return 'authenticating';
   }
 
-  getAuthorizationError(): Optional<string> {
-    return this.authorizationError;
+  getAuthError(): Optional<string> {
+    return this.authError;
+  }
+
+  /**
+   * When the card's PIN lockout ends, if it is locked out after too many
+   * incorrect PIN attempts.
+   */
+  getLockedUntil(): Optional<DateTime> {
+    return this.lockedUntil;
   }
 
   toJSON(): AuthenticatingSessionDataStruct {
     return {
       type: 'authenticating',
-      authorizationError: this.authorizationError,
+      authError: this.authError,
+      lockedUntil: this.lockedUntil?.toISO() ?? undefined,
     };
   }
 }
 
 export const AuthenticatingSessionDataSchema: z.ZodSchema<AuthenticatingSessionData> =
   AuthenticatingSessionDataStructSchema.transform(
-    (struct) => new AuthenticatingSessionData(struct.authorizationError)
+    (struct) =>
+      new AuthenticatingSessionData(
+        struct.authError,
+        struct.lockedUntil ? DateTime.fromISO(struct.lockedUntil) : undefined
+      )
   ) as unknown as z.ZodSchema<AuthenticatingSessionData>;
 
+/**
+ * A long-running ElectionGuard operation the JX runs in the background.
+ */
+export interface Job {
+  id: Uuid;
+  kind: JobKind;
+  electionObjectId: Uuid;
+  status: JobStatus;
+  /** What the job is doing now, while it runs. */
+  progress?: string;
+  cancelRequested: boolean;
+  attempts: number;
+  resultObjectId?: Uuid;
+  error?: string;
+  createdAt: string;
+  startedAt?: string;
+  finishedAt?: string;
+}
+
+export const JobSchema: z.ZodSchema<Job> = z.object({
+  id: UuidSchema,
+  kind: JobKindSchema,
+  electionObjectId: UuidSchema,
+  status: JobStatusSchema,
+  progress: z.string().optional(),
+  cancelRequested: z.boolean(),
+  attempts: z.number(),
+  resultObjectId: UuidSchema.optional(),
+  error: z.string().optional(),
+  createdAt: z.string(),
+  startedAt: z.string().optional(),
+  finishedAt: z.string().optional(),
+});
+
 export interface AuthenticatedSessionDataStruct {
   type: 'authenticated';
   jurisdictionCode: JurisdictionCode;
@@ -900,6 +1334,7 @@ export interface AuthenticatedSessionDataStruct {
   pendingRegistrationRequests: RegistrationRequestPresenter[];
   registrations: RegistrationPresenterStruct[];
   castBallots: CastBallotPresenterStruct[];
+  jobs: Job[];
 }
 
 export const AuthenticatedSessionDataStructSchema: z.ZodSchema<AuthenticatedSessionDataStruct> =
@@ -910,6 +1345,7 @@ export const AuthenticatedSessionDataStructSchema: z.ZodSchema<AuthenticatedSess
     pendingRegistrationRequests: z.array(RegistrationRequestPresenterSchema),
     registrations: z.array(RegistrationPresenterStructSchema),
     castBallots: z.array(CastBallotPresenterStructSchema),
+    jobs: z.array(JobSchema),
   });
 
 export class AuthenticatedSessionData {
@@ -918,7 +1354,8 @@ export class AuthenticatedSessionData {
     private readonly elections: ElectionPresenter[],
     private readonly pendingRegistrationRequests: RegistrationRequestPresenter[],
     private readonly registrations: RegistrationPresenter[],
-    private readonly castBallots: CastBallotPresenter[]
+    private readonly castBallots: CastBallotPresenter[],
+    private readonly jobs: Job[]
   ) {}
 
   get type(): 'authenticated' {
@@ -945,6 +1382,13 @@ export class AuthenticatedSessionData {
     return this.castBallots;
   }
 
+  /**
+   * Background jobs, newest first.
+   */
+  getJobs(): Job[] {
+    return this.jobs;
+  }
+
   toJSON(): AuthenticatedSessionDataStruct {
     return {
       type: 'authenticated',
@@ -953,6 +1397,7 @@ export class AuthenticatedSessionData {
       pendingRegistrationRequests: this.pendingRegistrationRequests,
       registrations: this.registrations.map((r) => r.toJSON()),
       castBallots: this.castBallots.map((c) => c.toJSON()),
+      jobs: this.jobs,
     };
   }
 }
@@ -965,7 +1410,8 @@ export const AuthenticatedSessionDataSchema: z.ZodSchema<AuthenticatedSessionDat
         struct.elections.map((e) => ElectionPresenterSchema.parse(e)),
         struct.pendingRegistrationRequests,
         struct.registrations.map((r) => RegistrationPresenterSchema.parse(r)),
-        struct.castBallots.map((c) => CastBallotPresenterSchema.parse(c))
+        struct.castBallots.map((c) => CastBallotPresenterSchema.parse(c)),
+        struct.jobs
       )
   ) as unknown as z.ZodSchema<AuthenticatedSessionData>;
 
@@ -34,3 +34,64 @@ export const Base64StringSchema: z.ZodSchema<Buffer> = z
 export const Iso8601DateSchema: z.ZodSchema<DateTime> = z
   .string()
   .transform((s) => DateTime.fromISO(s)) as unknown as z.ZodSchema<DateTime>;
+
+export type RegistrationRejectionReason =
+  | 'notEligible'
+  | 'identityNotVerified'
+  | 'duplicate'
+  | 'expired'
+  | 'other';
+
+export type ElectionLifecycleState = 'created' | 'open' | 'closed' | 'certified';
+
+export const ElectionLifecycleStateSchema: z.ZodSchema<ElectionLifecycleState> =
+  z.enum(['created', 'open', 'closed', 'certified']);
+
+export type ElectionLifecycleAction =
+  | 'openVoting'
+  | 'closeVoting'
+  | 'certifyResults';
+
+/**
+ * Whether voting has ended, so the ballots may be tallied and mixed.
+ */
+export function isVotingClosed(state: ElectionLifecycleState): boolean {
+  return state === 'closed' || state === 'certified';
+}
+
+export type JobKind =
+  | 'generateEncryptedTally'
+  | 'decryptTally'
+  | 'mixBallots'
+  | 'verifyMixedBallots'
+  | 'decryptMixedBallots';
+
+export const JobKindSchema: z.ZodSchema<JobKind> = z.enum([
+  'generateEncryptedTally',
+  'decryptTally',
+  'mixBallots',
+  'verifyMixedBallots',
+  'decryptMixedBallots',
+]);
+
+export type JobStatus =
+  | 'pending'
+  | 'running'
+  | 'succeeded'
+  | 'failed'
+  | 'cancelled';
+
+export const JobStatusSchema: z.ZodSchema<JobStatus> = z.enum([
+  'pending',
+  'running',
+  'succeeded',
+  'failed',
+  'cancelled',
+]);
+
+/**
+ * Whether a job is waiting to run or running.
+ */
+export function isJobActive(status: JobStatus): boolean {
+  return status === 'pending' || status === 'running';
+}
@@ -13,26 +13,46 @@ const Label = styled.label`
   font-weight: ${(p) => p.theme.sizes.fontWeight.semiBold};
 `;
 
+/**
+ * Converts the value of a `datetime-local` input, which is in local time, to
+ * an ISO 8601 timestamp.
+ */
+function toIsoTimestamp(value: string): string | undefined {
+  return value ? new Date(value).toISOString() : undefined;
+}
+
 export interface CreateElectionModalProps {
   onCreate: ({
     mailingAddress,
     electionDefinition,
+    votingStartsAt,
+    votingEndsAt,
+    ballotReceiptDeadline,
   }: {
     mailingAddress: string;
     electionDefinition: ElectionDefinition;
+    votingStartsAt?: string;
+    votingEndsAt?: string;
+    ballotReceiptDeadline?: string;
   }) => void;
   onClose: () => void;
   isCreating?: boolean;
+  /** Problems the JX found with the last election definition submitted. */
+  problems?: readonly string[];
 }
 
 export function CreateElectionModal({
   onCreate,
   onClose,
   isCreating,
+  problems,
 }: CreateElectionModalProps): JSX.Element {
   const [mailingAddress, setMailingAddress] = useState('');
   const [electionDefinition, setElectionDefinition] =
     useState<ElectionDefinition>();
+  const [votingStartsAt, setVotingStartsAt] = useState('');
+  const [votingEndsAt, setVotingEndsAt] = useState('');
+  const [ballotReceiptDeadline, setBallotReceiptDeadline] = useState('');
   const isReadyToCreate =
     !isCreating &&
     mailingAddress.length > 0 &&
@@ -41,8 +61,21 @@ export function CreateElectionModal({
   const onPressCreate = useCallback(() => {
     assert(electionDefinition);
     assert(mailingAddress.length > 0);
-    onCreate({ mailingAddress, electionDefinition });
-  }, [electionDefinition, mailingAddress, onCreate]);
+    onCreate({
+      mailingAddress,
+      electionDefinition,
+      votingStartsAt: toIsoTimestamp(votingStartsAt),
+      votingEndsAt: toIsoTimestamp(votingEndsAt),
+      ballotReceiptDeadline: toIsoTimestamp(ballotReceiptDeadline),
+    });
+  }, [
+    electionDefinition,
+    mailingAddress,
+    votingStartsAt,
+    votingEndsAt,
+    ballotReceiptDeadline,
+    onCreate,
+  ]);
 
   const onKeyboardEnter = useCallback(() => {
     if (isReadyToCreate) {
@@ -72,6 +105,35 @@ export function CreateElectionModal({
               onInput={(event) => setMailingAddress(event.currentTarget.value)}
             />
           </Label>
+          <Label>
+            Voting Starts (optional)
+            <br />
+            <input
+              type="datetime-local"
+              value={votingStartsAt}
+              onChange={(event) => setVotingStartsAt(event.currentTarget.value)}
+            />
+          </Label>
+          <Label>
+            Voting Ends (optional)
+            <br />
+            <input
+              type="datetime-local"
+              value={votingEndsAt}
+              onChange={(event) => setVotingEndsAt(event.currentTarget.value)}
+            />
+          </Label>
+          <Label>
+            Ballot Receipt Deadline (optional)
+            <br />
+            <input
+              type="datetime-local"
+              value={ballotReceiptDeadline}
+              onChange={(event) =>
+                setBallotReceiptDeadline(event.currentTarget.value)
+              }
+            />
+          </Label>
           <Label>
             Election Definition
             <br />
@@ -98,6 +160,16 @@ export function CreateElectionModal({
                 : 'Choose Election'}
             </FileInputButton>
           </Label>
+          {problems && problems.length > 0 && (
+            <React.Fragment>
+              <P>This election definition cannot be used:</P>
+              <ul>
+                {problems.map((problem) => (
+                  <li key={problem}>{problem}</li>
+                ))}
+              </ul>
+            </React.Fragment>
+          )}
         </React.Fragment>
       }
       actions={
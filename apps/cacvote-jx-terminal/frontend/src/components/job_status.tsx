@@ -0,0 +1,53 @@
+import { Button, P } from '@votingworks/ui';
+import * as api from '../api';
+import { Job } from '../cacvote-server/session_data';
+
+export interface JobStatusProps {
+  job?: Job;
+}
+
+/**
+ * Shows how far a background job has got, letting the operator cancel it
+ * while it is in progress, or why it stopped if it did not succeed.
+ */
+export function JobStatus({ job }: JobStatusProps): JSX.Element | null {
+  const cancelJobMutation = api.cancelJob.useMutation();
+
+  if (!job || job.status === 'succeeded') {
+    return null;
+  }
+
+  if (job.status === 'failed') {
+    return (
+      <P>
+        <strong>Failed:</strong> {job.error ?? 'unknown error'}
+      </P>
+    );
+  }
+
+  if (job.status === 'cancelled') {
+    return (
+      <P>
+        <strong>Cancelled</strong>
+      </P>
+    );
+  }
+
+  return (
+    <P>
+      <strong>In progress:</strong>{' '}
+      {job.status === 'pending'
+        ? 'Waiting to start'
+        : job.progress ?? 'Starting'}
+      {job.attempts > 1 && ` (attempt ${job.attempts})`}
+      <br />
+      <Button
+        icon="Delete"
+        disabled={job.cancelRequested || cancelJobMutation.isLoading}
+        onPress={() => cancelJobMutation.mutate({ jobId: job.id })}
+      >
+        {job.cancelRequested ? 'Cancelling…' : 'Cancel'}
+      </Button>
+    </P>
+  );
+}
@@ -1,11 +1,13 @@
 import {
+  Button,
   Modal,
   NumberPad,
   PinLength,
   Text,
   usePinEntry,
 } from '@votingworks/ui';
-import React, { useEffect } from 'react';
+import { DateTime } from 'luxon';
+import React, { useEffect, useState } from 'react';
 import styled from 'styled-components';
 
 const NumberPadWrapper = styled.div`
@@ -34,6 +36,27 @@ export interface PinPadModalProps {
   title?: string;
   isAuthenticating?: boolean;
   error?: string;
+  lockedUntil?: DateTime;
+  onCancel?: () => void;
+}
+
+/**
+ * Returns the number of whole seconds until `lockedUntil`, updating every
+ * second, or 0 once it has passed.
+ */
+function useLockoutSecondsRemaining(lockedUntil?: DateTime): number {
+  const [now, setNow] = useState(() => DateTime.now());
+
+  useEffect(() => {
+    const interval = setInterval(() => setNow(DateTime.now()), 1000);
+    return () => clearInterval(interval);
+  }, []);
+
+  if (!lockedUntil) {
+    return 0;
+  }
+
+  return Math.max(0, Math.ceil(lockedUntil.diff(now).as('seconds')));
 }
 
 export function PinPadModal({
@@ -41,9 +64,13 @@ export function PinPadModal({
   title = 'Enter Your PIN',
   isAuthenticating,
   error,
+  lockedUntil,
+  onCancel,
 }: PinPadModalProps): JSX.Element {
   const pinLength = PinLength.exactly(6);
   const pinEntry = usePinEntry({ pinLength });
+  const lockoutSecondsRemaining = useLockoutSecondsRemaining(lockedUntil);
+  const isLockedOut = lockoutSecondsRemaining > 0;
 
   useEffect(() => {
     if (pinEntry.current.length === pinLength.max) {
@@ -59,10 +86,16 @@ export function PinPadModal({
       content={
         <React.Fragment>
           {error && <Text error>Error: {error}</Text>}
+          {isLockedOut && (
+            <Text error>
+              Card locked due to too many incorrect PIN attempts. Try again in{' '}
+              {lockoutSecondsRemaining} seconds.
+            </Text>
+          )}
           <EnteredCode>{pinEntry.display}</EnteredCode>
           <NumberPadWrapper>
             <NumberPad
-              disabled={isAuthenticating}
+              disabled={isAuthenticating || isLockedOut}
               onButtonPress={pinEntry.handleDigit}
               onBackspace={pinEntry.handleBackspace}
               onClear={pinEntry.reset}
@@ -70,6 +103,13 @@ export function PinPadModal({
           </NumberPadWrapper>
         </React.Fragment>
       }
+      actions={
+        onCancel && (
+          <Button onPress={onCancel} disabled={isAuthenticating}>
+            Cancel
+          </Button>
+        )
+      }
     />
   );
 }
@@ -0,0 +1,34 @@
+import { SearchSelect, SelectOption } from '@votingworks/ui';
+import { RegistrationRejectionReason } from '../cacvote-server/types';
+
+const REJECTION_REASON_OPTIONS: Array<
+  SelectOption<RegistrationRejectionReason>
+> = [
+  { value: 'notEligible', label: 'Not eligible' },
+  { value: 'identityNotVerified', label: 'Identity not verified' },
+  { value: 'duplicate', label: 'Duplicate request' },
+  { value: 'expired', label: 'Expired' },
+  { value: 'other', label: 'Other' },
+];
+
+export interface RegistrationRejectionReasonSelectProps {
+  value?: RegistrationRejectionReason;
+  onChange: (reason?: RegistrationRejectionReason) => void;
+  disabled?: boolean;
+}
+
+export function RegistrationRejectionReasonSelect({
+  value,
+  onChange,
+  disabled,
+}: RegistrationRejectionReasonSelectProps): JSX.Element {
+  return (
+    <SearchSelect
+      placeholder="Reject with reason"
+      value={value}
+      disabled={disabled}
+      options={REJECTION_REASON_OPTIONS}
+      onChange={onChange}
+    />
+  );
+}
@@ -0,0 +1,55 @@
+import { TD, TH, Table } from '@votingworks/ui';
+import { format } from '@votingworks/utils';
+import React from 'react';
+import { TallyReport } from '../api';
+
+export interface TallyReportTableProps {
+  tallyReport: TallyReport;
+}
+
+/**
+ * Shows the votes for each option of each contest, followed by the contest's
+ * overvotes and undervotes.
+ */
+export function TallyReportTable({
+  tallyReport,
+}: TallyReportTableProps): JSX.Element {
+  return (
+    <Table>
+      <thead>
+        <tr>
+          <TH>Contest</TH>
+          <TH>Selection</TH>
+          <TH>Votes</TH>
+        </tr>
+      </thead>
+      <tbody>
+        {tallyReport.contests.map((contest) => (
+          <React.Fragment key={contest.contestId}>
+            {contest.options.map((option) => (
+              <tr key={option.optionId}>
+                <TD>{contest.title}</TD>
+                <TD>{option.label}</TD>
+                <TD>{format.count(option.votes)}</TD>
+              </tr>
+            ))}
+            <tr>
+              <TD>{contest.title}</TD>
+              <TD>
+                <em>Overvotes</em>
+              </TD>
+              <TD>{format.count(contest.overvotes)}</TD>
+            </tr>
+            <tr>
+              <TD>{contest.title}</TD>
+              <TD>
+                <em>Undervotes</em>
+              </TD>
+              <TD>{format.count(contest.undervotes)}</TD>
+            </tr>
+          </React.Fragment>
+        ))}
+      </tbody>
+    </Table>
+  );
+}
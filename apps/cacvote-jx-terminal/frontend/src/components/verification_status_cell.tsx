@@ -0,0 +1,56 @@
+import { throwIllegalValue } from '@votingworks/basics';
+import { Icons, P, TD } from '@votingworks/ui';
+import { VerificationStatus } from '../cacvote-server/session_data';
+
+export interface VerificationStatusCellProps {
+  verificationStatus: VerificationStatus;
+}
+
+export function VerificationStatusCell({
+  verificationStatus,
+}: VerificationStatusCellProps): JSX.Element {
+  switch (verificationStatus.type) {
+    case 'success':
+      return (
+        <TD>
+          <P>
+            <Icons.Done color="success" /> Signed by{' '}
+            {verificationStatus.displayName}
+          </P>
+        </TD>
+      );
+
+    case 'failure':
+      return (
+        <TD>
+          <P>
+            <Icons.Danger color="danger" /> Verification failed
+          </P>
+          <P>{verificationStatus.reason}</P>
+        </TD>
+      );
+
+    case 'error':
+      return (
+        <TD>
+          <P>
+            <Icons.Warning color="warning" /> Unable to verify
+          </P>
+          <P>{verificationStatus.message}</P>
+        </TD>
+      );
+
+    case 'unknown':
+      return (
+        <TD>
+          <P>
+            <Icons.Question /> Not verified
+          </P>
+        </TD>
+      );
+
+    /* istanbul ignore next */
+    default:
+      throwIllegalValue(verificationStatus);
+  }
+}
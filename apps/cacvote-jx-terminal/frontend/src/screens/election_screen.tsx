@@ -1,6 +1,7 @@
 import { assertDefined } from '@votingworks/basics';
+import { DateTime } from 'luxon';
 import { Button, H2, P, TD, TH, Table } from '@votingworks/ui';
-import React from 'react';
+import React, { useState } from 'react';
 import { useHistory, useParams } from 'react-router-dom';
 import * as api from '../api';
 import {
@@ -8,10 +9,15 @@ import {
   CastBallotPresenter,
   RegistrationPresenter,
 } from '../cacvote-server/session_data';
-import { Uuid } from '../cacvote-server/types';
+import {
+  ElectionLifecycleAction,
+  ElectionLifecycleState,
+  Uuid,
+} from '../cacvote-server/types';
 import { DateTimeCell } from '../components/date_time_cell';
 import { ElectionCard } from '../components/election_card';
 import { RegistrationConfigurationCell } from '../components/registration_configuration_cell';
+import { VerificationStatusCell } from '../components/verification_status_cell';
 import { VoterInfoCell } from '../components/voter_info_cell';
 import { NavigationScreen } from './navigation_screen';
 
@@ -38,6 +44,7 @@ function VotersAndBallotsTable({
           <TH>Election Configuration</TH>
           <TH>Registered</TH>
           <TH>Ballot Cast</TH>
+          <TH>Signature</TH>
         </tr>
       </thead>
       <tbody>
@@ -56,9 +63,14 @@ function VotersAndBallotsTable({
               />
               <DateTimeCell dateTime={r.getCreatedAt()} />
               {castBallot ? (
-                <DateTimeCell dateTime={castBallot.getCreatedAt()} />
+                <React.Fragment>
+                  <DateTimeCell dateTime={castBallot.getCreatedAt()} />
+                  <VerificationStatusCell
+                    verificationStatus={castBallot.getVerificationStatus()}
+                  />
+                </React.Fragment>
               ) : (
-                <TD>
+                <TD colSpan={2}>
                   <P>No ballot cast</P>
                 </TD>
               )}
@@ -70,6 +82,23 @@ function VotersAndBallotsTable({
   );
 }
 
+const LIFECYCLE_STATE_DESCRIPTIONS: Record<ElectionLifecycleState, string> = {
+  created: 'Voting has not opened yet.',
+  open: 'Voting is open. Ballots are being accepted.',
+  closed: 'Voting is closed. The ballots may be tallied.',
+  certified: 'The results have been certified.',
+};
+
+const NEXT_LIFECYCLE_ACTIONS: Record<
+  ElectionLifecycleState,
+  { action: ElectionLifecycleAction; label: string } | undefined
+> = {
+  created: { action: 'openVoting', label: 'Open Voting' },
+  open: { action: 'closeVoting', label: 'Close Voting' },
+  closed: { action: 'certifyResults', label: 'Certify Results' },
+  certified: undefined,
+};
+
 export interface ElectionScreenParams {
   electionId: string;
 }
@@ -81,6 +110,9 @@ export function ElectionScreen(): JSX.Element | null {
     sessionData && sessionData instanceof AuthenticatedSessionData;
   const { electionId } = useParams<ElectionScreenParams>();
   const history = useHistory();
+  const changeElectionLifecycleMutation =
+    api.changeElectionLifecycle.useMutation();
+  const [lifecycleError, setLifecycleError] = useState<string>();
 
   if (!isAuthenticated || !electionId) {
     return null;
@@ -102,6 +134,18 @@ export function ElectionScreen(): JSX.Element | null {
     history.push(`/elections/${electionId}/tally`);
   }
 
+  const electionInfo = electionPresenter.getElection();
+  const lifecycleState = electionPresenter.getLifecycleState();
+  const nextLifecycleAction = NEXT_LIFECYCLE_ACTIONS[lifecycleState];
+
+  function onPressLifecycleButton(action: ElectionLifecycleAction) {
+    setLifecycleError(undefined);
+    changeElectionLifecycleMutation.mutate(
+      { electionId, action },
+      { onError: (error) => setLifecycleError((error as Error).message) }
+    );
+  }
+
   return (
     <NavigationScreen
       title={
@@ -116,6 +160,37 @@ export function ElectionScreen(): JSX.Element | null {
         }
       />
 
+      <H2>Voting</H2>
+      <P>{LIFECYCLE_STATE_DESCRIPTIONS[lifecycleState]}</P>
+      <P>
+        <strong>Voting starts:</strong>{' '}
+        {electionInfo
+          .getVotingStartsAt()
+          ?.toLocaleString(DateTime.DATETIME_SHORT) ?? 'when voting is opened'}
+        <br />
+        <strong>Voting ends:</strong>{' '}
+        {electionInfo
+          .getVotingEndsAt()
+          ?.toLocaleString(DateTime.DATETIME_SHORT) ?? 'when voting is closed'}
+        <br />
+        <strong>Ballot receipt deadline:</strong>{' '}
+        {electionInfo
+          .getBallotReceiptDeadline()
+          ?.toLocaleString(DateTime.DATETIME_SHORT) ?? 'none'}
+      </P>
+      {nextLifecycleAction && (
+        <P>
+          <Button
+            onPress={onPressLifecycleButton}
+            value={nextLifecycleAction.action}
+            disabled={changeElectionLifecycleMutation.isLoading}
+          >
+            {nextLifecycleAction.label}
+          </Button>
+        </P>
+      )}
+      {lifecycleError && <P>{lifecycleError}</P>}
+
       <H2>Voters &amp; Ballots</H2>
       {registrations.length > 0 ? (
         <React.Fragment>
@@ -36,12 +36,27 @@ export function ElectionsScreen(): JSX.Element | null {
       {isShowingAddElectionModal && (
         <CreateElectionModal
           isCreating={createElectionMutation.isLoading}
-          onCreate={({ mailingAddress, electionDefinition }) =>
+          problems={
+            createElectionMutation.error instanceof
+            api.InvalidElectionDefinitionError
+              ? createElectionMutation.error.problems
+              : undefined
+          }
+          onCreate={({
+            mailingAddress,
+            electionDefinition,
+            votingStartsAt,
+            votingEndsAt,
+            ballotReceiptDeadline,
+          }) =>
             createElectionMutation.mutate(
               {
                 jurisdictionCode: sessionData.getJurisdictionCode(),
                 electionDefinition,
                 mailingAddress,
+                votingStartsAt,
+                votingEndsAt,
+                ballotReceiptDeadline,
               },
               {
                 onSuccess: () => {
@@ -34,7 +34,7 @@ export function InsertCardScreen(): JSX.Element {
           <H1 align="center">
             {sessionData instanceof UnauthenticatedSessionData &&
             sessionData.getHasSmartcard()
-              ? 'Invalid Card'
+              ? 'Remove and Reinsert Your Card'
               : 'Insert Your Card'}
           </H1>
         </FullScreenIconWrapper>
@@ -1,5 +1,6 @@
 import React from 'react';
 import {
+  Button,
   Main,
   Screen,
   H1,
@@ -43,6 +44,7 @@ export function NavigationScreen({
   const sessionData = sessionDataQuery.data;
   const isUnauthenticated =
     sessionData && sessionData instanceof UnauthenticatedSessionData;
+  const lockMutation = api.lock.useMutation();
 
   if (isUnauthenticated) {
     return null;
@@ -71,7 +73,12 @@ export function NavigationScreen({
               </React.Fragment>
             )}
           </div>
-          {headerActions}
+          <HeaderActions>
+            {headerActions}
+            <Button icon="Lock" onPress={() => lockMutation.mutate()}>
+              Lock
+            </Button>
+          </HeaderActions>
         </Header>
         <MainContent>{children}</MainContent>
       </Main>
@@ -5,8 +5,16 @@ import { useState } from 'react';
 import { useParams } from 'react-router-dom';
 import { DateTime } from 'luxon';
 import * as api from '../api';
-import { Uuid } from '../cacvote-server/types';
+import {
+  isJobActive,
+  isVotingClosed,
+  JobKind,
+  Uuid,
+} from '../cacvote-server/types';
 import { GenerateEncryptedTallyModal } from '../components/generate_encrypted_tally_modal';
+import { JobStatus } from '../components/job_status';
+import { PinPadModal } from '../components/pin_pad_modal';
+import { TallyReportTable } from '../components/tally_report_table';
 import { NavigationScreen } from './navigation_screen';
 import { downloadData } from '../utils/download';
 import { AuthenticatedSessionData } from '../cacvote-server/session_data';
@@ -48,11 +56,28 @@ export function TallyScreen(): JSX.Element | null {
     api.generateEncryptedElectionTally.useMutation();
   const decryptEncryptedElectionTallyMutation =
     api.decryptEncryptedElectionTally.useMutation();
+  const [isEnteringUnwrapPin, setIsEnteringUnwrapPin] = useState(false);
   const shuffleEncryptedBallotsMutation =
     api.shuffleEncryptedBallots.useMutation();
+  const verifyMixedBallotsMutation = api.verifyMixedBallots.useMutation();
+  const decryptMixedBallotsMutation = api.decryptMixedBallots.useMutation();
+  const [isEnteringMixedBallotsUnwrapPin, setIsEnteringMixedBallotsUnwrapPin] =
+    useState(false);
 
   const [isDownloadingBallotManifest, setIsDownloadingBallotManifest] =
     useState(false);
+  const scannedMailingLabelsQuery =
+    api.scannedMailingLabels.useQuery(electionId);
+  // the report is only available once the tally is decrypted
+  const tallyReportQuery = api.tallyReport.useQuery(electionId, {
+    enabled: Boolean(
+      isAuthenticated &&
+        sessionData
+          .getElections()
+          .find((e) => e.getId() === electionId)
+          ?.getDecryptedTally()
+    ),
+  });
 
   if (!isAuthenticated || !electionId) {
     return null;
@@ -70,6 +95,37 @@ export function TallyScreen(): JSX.Element | null {
     .filter((b) => b.getRegistration().getElectionObjectId() === electionId)
     .count();
 
+  // jobs are newest first, so this finds the latest of each kind
+  const jobs = sessionData.getJobs();
+  function findLatestJob(kind: JobKind) {
+    return jobs.find(
+      (job) => job.electionObjectId === electionId && job.kind === kind
+    );
+  }
+  const generateEncryptedTallyJob = findLatestJob('generateEncryptedTally');
+  const decryptTallyJob = findLatestJob('decryptTally');
+  const mixBallotsJob = findLatestJob('mixBallots');
+  const verifyMixedBallotsJob = findLatestJob('verifyMixedBallots');
+  const decryptMixedBallotsJob = findLatestJob('decryptMixedBallots');
+  const isGeneratingEncryptedTally =
+    generateEncryptedElectionTallyMutation.isLoading ||
+    (generateEncryptedTallyJob !== undefined &&
+      isJobActive(generateEncryptedTallyJob.status));
+  const isDecryptingElectionTally =
+    decryptEncryptedElectionTallyMutation.isLoading ||
+    (decryptTallyJob !== undefined && isJobActive(decryptTallyJob.status));
+  const isShufflingBallots =
+    shuffleEncryptedBallotsMutation.isLoading ||
+    (mixBallotsJob !== undefined && isJobActive(mixBallotsJob.status));
+  const isVerifyingMixedBallots =
+    verifyMixedBallotsMutation.isLoading ||
+    (verifyMixedBallotsJob !== undefined &&
+      isJobActive(verifyMixedBallotsJob.status));
+  const isDecryptingMixedBallots =
+    decryptMixedBallotsMutation.isLoading ||
+    (decryptMixedBallotsJob !== undefined &&
+      isJobActive(decryptMixedBallotsJob.status));
+
   const isEncryptedElectionTallyPresent = Boolean(
     electionPresenter.getEncryptedTally()
   );
@@ -78,13 +134,39 @@ export function TallyScreen(): JSX.Element | null {
   );
   const areShuffledBallotsPresent =
     typeof electionPresenter.getShuffledEncryptedCastBallots() !== 'undefined';
-  const isReadyToGenerateEncryptedTally = !isEncryptedElectionTallyPresent;
+  const isVotingClosedForElection = isVotingClosed(
+    electionPresenter.getLifecycleState()
+  );
+  const isReadyToGenerateEncryptedTally =
+    isVotingClosedForElection &&
+    !isEncryptedElectionTallyPresent &&
+    !isGeneratingEncryptedTally;
+  const tallyReport = tallyReportQuery.data;
   const isReadyToDecryptElectionTally =
     isEncryptedElectionTallyPresent && !isDecryptedElectionTallyPresent;
   const isReadyToShuffleBallots =
+    isVotingClosedForElection &&
     castBallotCount > 0 &&
     isDecryptedElectionTallyPresent &&
     !areShuffledBallotsPresent;
+  const mixVerification =
+    electionPresenter.getShuffledEncryptedCastBallotsVerification();
+  const decryptedShuffledBallots =
+    electionPresenter.getDecryptedShuffledBallots();
+  // only a verified mix of the current shuffled ballots may be decrypted
+  const isReadyToDecryptMixedBallots =
+    areShuffledBallotsPresent &&
+    mixVerification !== undefined &&
+    mixVerification.isVerified() &&
+    !decryptedShuffledBallots;
+
+  const scannedMailingLabels = scannedMailingLabelsQuery.data ?? [];
+  const lateMailingLabelCount = scannedMailingLabels.filter(
+    (label) => label.receivedAfterDeadline
+  ).length;
+  const ballotReceiptDeadline = electionPresenter
+    .getElection()
+    .getBallotReceiptDeadline();
 
   function onGenerateEncryptedTallyPressed() {
     setIsShowingGenerateEncryptedTallyModal(true);
@@ -106,7 +188,16 @@ export function TallyScreen(): JSX.Element | null {
   }
 
   function onDecryptElectionTallyPressed() {
-    decryptEncryptedElectionTallyMutation.mutate({ electionId });
+    // an official must enter their PIN to unwrap the election key
+    decryptEncryptedElectionTallyMutation.reset();
+    setIsEnteringUnwrapPin(true);
+  }
+
+  function onUnwrapPinEntered(pin: string) {
+    decryptEncryptedElectionTallyMutation.mutate(
+      { electionId, pin },
+      { onSuccess: () => setIsEnteringUnwrapPin(false) }
+    );
   }
 
   async function onSaveDecryptedTallyPressed() {
@@ -120,6 +211,47 @@ export function TallyScreen(): JSX.Element | null {
     );
   }
 
+  async function onSaveTallyReportPressed() {
+    await downloadData(
+      await api.getTallyReportCsvRaw(electionId),
+      `tally-report-${electionId}.csv`
+    );
+  }
+
+  function onVerifyMixedBallotsPressed() {
+    verifyMixedBallotsMutation.mutate({ electionId });
+  }
+
+  function onDecryptMixedBallotsPressed() {
+    decryptMixedBallotsMutation.reset();
+    setIsEnteringMixedBallotsUnwrapPin(true);
+  }
+
+  function onMixedBallotsUnwrapPinEntered(pin: string) {
+    decryptMixedBallotsMutation.mutate(
+      { electionId, pin },
+      { onSuccess: () => setIsEnteringMixedBallotsUnwrapPin(false) }
+    );
+  }
+
+  async function onSaveDecryptedBallotsPressed() {
+    assert(decryptedShuffledBallots);
+    await downloadData(
+      Buffer.from(
+        JSON.stringify(decryptedShuffledBallots.getBallots(), null, 2)
+      ),
+      `decrypted-ballots-${electionId}.json`
+    );
+  }
+
+  async function onSaveDecryptionProofsPressed() {
+    assert(decryptedShuffledBallots);
+    await downloadData(
+      decryptedShuffledBallots.getElectionguardDecryptedBallots(),
+      `decrypted-ballots-${electionId}.zip`
+    );
+  }
+
   function onShuffleBallotsPressed() {
     shuffleEncryptedBallotsMutation.mutate({
       electionId,
@@ -158,15 +290,23 @@ export function TallyScreen(): JSX.Element | null {
         this election. This operation may only be performed once, and should be
         performed only once all ballots to be counted are present in the system.
       </P>
+      {!isVotingClosedForElection && (
+        <P>Voting must be closed before the ballots can be tallied or mixed.</P>
+      )}
       <P>
-        <Button
-          icon="Add"
-          onPress={onGenerateEncryptedTallyPressed}
-          disabled={!isReadyToGenerateEncryptedTally}
-        >
-          Generate Encrypted Tally
-        </Button>
+        {isGeneratingEncryptedTally ? (
+          <LoadingButton>Generating Encrypted Tally…</LoadingButton>
+        ) : (
+          <Button
+            icon="Add"
+            onPress={onGenerateEncryptedTallyPressed}
+            disabled={!isReadyToGenerateEncryptedTally}
+          >
+            Generate Encrypted Tally
+          </Button>
+        )}
       </P>
+      <JobStatus job={generateEncryptedTallyJob} />
       <P>
         <Button
           icon="Export"
@@ -189,7 +329,7 @@ export function TallyScreen(): JSX.Element | null {
         decrypted tally can be saved below.
       </P>
       <P>
-        {decryptEncryptedElectionTallyMutation.isLoading ? (
+        {isDecryptingElectionTally ? (
           <LoadingButton>Decrypting Election Tally…</LoadingButton>
         ) : (
           <Button
@@ -201,6 +341,7 @@ export function TallyScreen(): JSX.Element | null {
           </Button>
         )}
       </P>
+      <JobStatus job={decryptTallyJob} />
       <P>
         <Button
           icon="Export"
@@ -208,8 +349,29 @@ export function TallyScreen(): JSX.Element | null {
           disabled={!isDecryptedElectionTallyPresent}
         >
           Save Decrypted Tally
+        </Button>{' '}
+        <Button
+          icon="Export"
+          onPress={onSaveTallyReportPressed}
+          disabled={!tallyReport}
+        >
+          Save Tally Report
         </Button>
       </P>
+      {tallyReport && <TallyReportTable tallyReport={tallyReport} />}
+      {isEnteringUnwrapPin && (
+        <PinPadModal
+          title="Enter PIN to Unwrap Election Key"
+          isAuthenticating={decryptEncryptedElectionTallyMutation.isLoading}
+          error={
+            decryptEncryptedElectionTallyMutation.isError
+              ? 'Could not unwrap the election key. Invalid PIN?'
+              : undefined
+          }
+          onEnter={onUnwrapPinEntered}
+          onCancel={() => setIsEnteringUnwrapPin(false)}
+        />
+      )}
       {isShowingGenerateEncryptedTallyModal && (
         <GenerateEncryptedTallyModal
           isGenerating={generateEncryptedElectionTallyMutation.isLoading}
@@ -235,7 +397,7 @@ export function TallyScreen(): JSX.Element | null {
         ballots, posting the shuffled ballots to the bulletin board.
       </P>
       <P>
-        {shuffleEncryptedBallotsMutation.isLoading ? (
+        {isShufflingBallots ? (
           <LoadingButton>Shuffling Ballots…</LoadingButton>
         ) : (
           <Button
@@ -247,12 +409,138 @@ export function TallyScreen(): JSX.Element | null {
           </Button>
         )}
       </P>
+      <JobStatus job={mixBallotsJob} />
+      <P>
+        Checks the proof of shuffle of every shuffling phase against the cast
+        ballots, posting the result to the bulletin board for auditors.
+      </P>
+      {mixVerification && (
+        <P>
+          <strong>Verification:</strong>{' '}
+          {mixVerification.isVerified()
+            ? `all ${mixVerification.getPhases().length} phases verified`
+            : 'FAILED'}{' '}
+          (
+          {mixVerification
+            .getCreatedAt()
+            .toLocaleString(DateTime.DATETIME_SHORT)}
+          )
+          {mixVerification
+            .getPhases()
+            .filter((phase) => phase.error !== undefined)
+            .map((phase) => (
+              <span key={phase.mixName}>
+                <br />
+                {phase.mixName}: {phase.error}
+              </span>
+            ))}
+        </P>
+      )}
+      <P>
+        {isVerifyingMixedBallots ? (
+          <LoadingButton>Verifying Shuffled Ballots…</LoadingButton>
+        ) : (
+          <Button
+            icon="Checkmark"
+            onPress={onVerifyMixedBallotsPressed}
+            disabled={!areShuffledBallotsPresent}
+          >
+            Verify Shuffled Ballots
+          </Button>
+        )}
+      </P>
+      <JobStatus job={verifyMixedBallotsJob} />
+
+      <H2>Decrypted Ballots</H2>
+      <ObjectStatus
+        createdAt={decryptedShuffledBallots?.getCreatedAt()}
+        postedAt={decryptedShuffledBallots?.getSyncedAt()}
+      />
+      <P>
+        Decrypts each ballot output by the final shuffling phase, which can no
+        longer be linked to the voter who cast it. The shuffled ballots must be
+        verified first. The decrypted ballots are posted to the bulletin board
+        along with the proofs that each was decrypted correctly.
+      </P>
+      {decryptedShuffledBallots && (
+        <P>
+          <strong>Decrypted ballot count:</strong>{' '}
+          {format.count(decryptedShuffledBallots.getBallots().length)}
+        </P>
+      )}
+      <P>
+        {isDecryptingMixedBallots ? (
+          <LoadingButton>Decrypting Ballots…</LoadingButton>
+        ) : (
+          <Button
+            icon="Unlock"
+            onPress={onDecryptMixedBallotsPressed}
+            disabled={!isReadyToDecryptMixedBallots}
+          >
+            Decrypt Ballots
+          </Button>
+        )}
+      </P>
+      <JobStatus job={decryptMixedBallotsJob} />
+      <P>
+        <Button
+          icon="Export"
+          onPress={onSaveDecryptedBallotsPressed}
+          disabled={!decryptedShuffledBallots}
+        >
+          Save Decrypted Ballots
+        </Button>{' '}
+        <Button
+          icon="Export"
+          onPress={onSaveDecryptionProofsPressed}
+          disabled={!decryptedShuffledBallots}
+        >
+          Save Decryption Proofs
+        </Button>
+      </P>
+      {isEnteringMixedBallotsUnwrapPin && (
+        <PinPadModal
+          title="Enter PIN to Unwrap Election Key"
+          isAuthenticating={decryptMixedBallotsMutation.isLoading}
+          error={
+            decryptMixedBallotsMutation.error instanceof
+            api.DecryptMixedBallotsConflictError
+              ? `Could not decrypt the ballots: ${decryptMixedBallotsMutation.error.message}.`
+              : decryptMixedBallotsMutation.isError
+              ? 'Could not unwrap the election key. Invalid PIN?'
+              : undefined
+          }
+          onEnter={onMixedBallotsUnwrapPinEntered}
+          onCancel={() => setIsEnteringMixedBallotsUnwrapPin(false)}
+        />
+      )}
+
       <H2>Ballot Manifest</H2>
       <P>
         Save the manifest of validated paper ballots received for this election.
         The manifest contains the voter’s Common Access Card number and
         encrypted ballot signature hash for each ballot cast.
       </P>
+      <P>
+        <strong>Mailing labels scanned:</strong>{' '}
+        {scannedMailingLabelsQuery.isSuccess
+          ? format.count(scannedMailingLabels.length)
+          : 'n/a'}
+        <br />
+        <strong>Ballot receipt deadline:</strong>{' '}
+        {ballotReceiptDeadline?.toLocaleString(DateTime.DATETIME_SHORT) ??
+          'none'}
+      </P>
+      {lateMailingLabelCount > 0 && (
+        <P>
+          <strong>
+            {format.count(lateMailingLabelCount)} of the scanned mailing labels
+            were received after the ballot receipt deadline.
+          </strong>{' '}
+          These ballots are flagged in the manifest with{' '}
+          <code>receivedAfterDeadline</code>.
+        </P>
+      )}
       <P>
         <Button
           icon="Export"
@@ -1,17 +1,22 @@
-import { H2, P, TD, TH, Table } from '@votingworks/ui';
-import { useState } from 'react';
+import { Button, H2, P, TD, TH, Table } from '@votingworks/ui';
+import React, { useState } from 'react';
 import * as api from '../api';
 import {
   AuthenticatedSessionData,
   RegistrationPresenter,
+  formatVoterAddress,
 } from '../cacvote-server/session_data';
-import { Uuid } from '../cacvote-server/types';
+import {
+  RegistrationRejectionReason,
+  Uuid,
+} from '../cacvote-server/types';
 import { DateTimeCell } from '../components/date_time_cell';
 import {
   ElectionConfiguration,
   ElectionConfigurationSelect,
 } from '../components/election_configuration_select';
 import { RegistrationConfigurationCell } from '../components/registration_configuration_cell';
+import { RegistrationRejectionReasonSelect } from '../components/registration_rejection_reason_select';
 import { VoterInfoCell } from '../components/voter_info_cell';
 import { NavigationScreen } from './navigation_screen';
 
@@ -62,6 +67,11 @@ export function VotersScreen(): JSX.Element | null {
     registrationRequestId: Uuid;
     electionConfiguration: ElectionConfiguration;
   }>();
+  const rejectRegistrationMutation = api.rejectRegistration.useMutation();
+  const [pendingRejectionData, setPendingRejectionData] = useState<{
+    registrationRequestId: Uuid;
+    reason: RegistrationRejectionReason;
+  }>();
 
   if (!isAuthenticated) {
     return null;
@@ -76,6 +86,7 @@ export function VotersScreen(): JSX.Element | null {
             <tr>
               <TH>Voter</TH>
               <TH>Election Configuration</TH>
+              <TH>Reject</TH>
             </tr>
           </thead>
           <tbody>
@@ -86,9 +97,47 @@ export function VotersScreen(): JSX.Element | null {
                     {rr.displayName}
                     <br />
                     <em>CAC:</em> {rr.registrationRequest.commonAccessCardId}
+                    {rr.registrationRequest.address && (
+                      <React.Fragment>
+                        <br />
+                        <em>Address:</em>{' '}
+                        {formatVoterAddress(rr.registrationRequest.address)}
+                      </React.Fragment>
+                    )}
                   </P>
                 </TD>
                 <TD>
+                  {rr.suggestedAssignments.map((suggestion) => {
+                    const election = sessionData
+                      .getElections()
+                      .find((e) => e.getId() === suggestion.electionId);
+                    if (!election) {
+                      return null;
+                    }
+                    return (
+                      <P key={suggestion.electionId}>
+                        <Button
+                          onPress={() =>
+                            registerVoterMutation.mutate({
+                              registrationRequestId: rr.id,
+                              electionId: suggestion.electionId,
+                              ballotStyleId: suggestion.ballotStyleId,
+                              precinctId: suggestion.precinctId,
+                            })
+                          }
+                          disabled={registerVoterMutation.isLoading}
+                        >
+                          Register for{' '}
+                          {
+                            election.getElection().getElectionDefinition()
+                              .election.title
+                          }{' '}
+                          (Precinct {suggestion.precinctId}, Ballot Style{' '}
+                          {suggestion.ballotStyleId})
+                        </Button>
+                      </P>
+                    );
+                  })}
                   <ElectionConfigurationSelect
                     elections={sessionData.getElections().map((e) => ({
                       id: e.getId(),
@@ -118,6 +167,29 @@ export function VotersScreen(): JSX.Element | null {
                     disabled={registerVoterMutation.isLoading}
                   />
                 </TD>
+                <TD>
+                  <RegistrationRejectionReasonSelect
+                    value={
+                      pendingRejectionData?.registrationRequestId === rr.id
+                        ? pendingRejectionData.reason
+                        : undefined
+                    }
+                    onChange={(reason) => {
+                      if (reason) {
+                        setPendingRejectionData({
+                          registrationRequestId: rr.id,
+                          reason,
+                        });
+
+                        rejectRegistrationMutation.mutate({
+                          registrationRequestId: rr.id,
+                          reason,
+                        });
+                      }
+                    }}
+                    disabled={rejectRegistrationMutation.isLoading}
+                  />
+                </TD>
               </tr>
             ))}
           </tbody>
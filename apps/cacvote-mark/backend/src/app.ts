@@ -40,9 +40,11 @@ import {
   ElectionObjectType,
   JurisdictionCode,
   Payload,
+  RegistrationRejectionReason,
   RegistrationRequest,
   SignedObject,
   Uuid,
+  VoterAddress,
 } from './cacvote-server/types';
 import { createEncryptedBallotPayload } from './electionguard';
 import * as mailLabel from './mail-label';
@@ -53,6 +55,7 @@ import { Workspace } from './workspace';
 export type VoterStatus =
   | 'unregistered'
   | 'registration_pending'
+  | 'registration_rejected'
   | 'registered'
   | 'voted';
 
@@ -82,7 +85,12 @@ function buildApi({
       return store.getJurisdictionCodes();
     },
 
-    async getVoterStatus(): Promise<Optional<{ status: VoterStatus }>> {
+    async getVoterStatus(): Promise<
+      Optional<{
+        status: VoterStatus;
+        rejection?: { reason: RegistrationRejectionReason; message?: string };
+      }>
+    > {
       const authStatus: AuthStatus = await getAuthStatus();
 
       if (authStatus.status !== 'has_card') {
@@ -108,6 +116,23 @@ function buildApi({
         .first();
 
       if (!registration) {
+        const rejection = await store
+          .forEachRegistrationRejection({
+            commonAccessCardId,
+            registrationRequestObjectId: registrationRequest.object.getId(),
+          })
+          .first();
+
+        if (rejection) {
+          return {
+            status: 'registration_rejected',
+            rejection: {
+              reason: rejection.registrationRejection.getReason(),
+              message: rejection.registrationRejection.getMessage(),
+            },
+          };
+        }
+
         return { status: 'registration_pending' };
       }
 
@@ -140,6 +165,7 @@ function buildApi({
       jurisdictionCode: JurisdictionCode;
       givenName: string;
       familyName: string;
+      address?: VoterAddress;
       pin: string;
     }): Promise<
       Result<
@@ -162,7 +188,8 @@ function buildApi({
         input.jurisdictionCode,
         input.givenName,
         input.familyName,
-        DateTime.now()
+        DateTime.now(),
+        input.address
       );
 
       const payload =
@@ -27,11 +27,13 @@ import { ZodError, z } from 'zod';
 export const ElectionObjectType = 'Election';
 export const RegistrationRequestObjectType = 'RegistrationRequest';
 export const RegistrationObjectType = 'Registration';
+export const RegistrationRejectionObjectType = 'RegistrationRejection';
 export const CastBallotObjectType = 'CastBallot';
 export type PayloadObjectType =
   | typeof ElectionObjectType
   | typeof RegistrationRequestObjectType
   | typeof RegistrationObjectType
+  | typeof RegistrationRejectionObjectType
   | typeof CastBallotObjectType;
 export type Uuid = NewType<string, 'Uuid'>;
 
@@ -261,13 +263,33 @@ export const ElectionSchema = ElectionStructSchema.transform(
     )
 );
 
+/**
+ * A voter's residential address. The JX uses it to find the voter's precinct.
+ */
+export interface VoterAddress {
+  houseNumber: number;
+  streetName: string;
+  unit?: string;
+  city: string;
+  zipCode: string;
+}
+
+export const VoterAddressSchema = z.object({
+  houseNumber: z.number().int().nonnegative(),
+  streetName: z.string(),
+  unit: z.string().optional(),
+  city: z.string(),
+  zipCode: z.string(),
+}) satisfies z.ZodSchema<VoterAddress>;
+
 export class RegistrationRequest {
   constructor(
     private readonly commonAccessCardId: string,
     private readonly jurisdictionCode: JurisdictionCode,
     private readonly givenName: string,
     private readonly familyName: string,
-    private readonly createdAt: DateTime
+    private readonly createdAt: DateTime,
+    private readonly address?: VoterAddress
   ) {}
 
   getCommonAccessCardId(): string {
@@ -290,6 +312,10 @@ export class RegistrationRequest {
     return this.createdAt;
   }
 
+  getAddress(): VoterAddress | undefined {
+    return this.address;
+  }
+
   toJSON(): RegistrationRequestStruct {
     return {
       commonAccessCardId: this.commonAccessCardId,
@@ -297,6 +323,7 @@ export class RegistrationRequest {
       givenName: this.givenName,
       familyName: this.familyName,
       createdAt: this.createdAt.toISO(),
+      address: this.address,
     };
   }
 }
@@ -307,6 +334,7 @@ interface RegistrationRequestStruct {
   givenName: string;
   familyName: string;
   createdAt: string;
+  address?: VoterAddress;
 }
 
 const RegistrationRequestStructSchema = z.object({
@@ -315,6 +343,7 @@ const RegistrationRequestStructSchema = z.object({
   givenName: z.string(),
   familyName: z.string(),
   createdAt: z.string(),
+  address: VoterAddressSchema.optional(),
 }) satisfies z.ZodSchema<RegistrationRequestStruct>;
 
 export const RegistrationRequestSchema =
@@ -325,7 +354,8 @@ export const RegistrationRequestSchema =
         JurisdictionCodeSchema.parse(o.jurisdictionCode),
         o.givenName,
         o.familyName,
-        DateTime.fromISO(o.createdAt)
+        DateTime.fromISO(o.createdAt),
+        o.address
       )
   );
 
@@ -406,6 +436,85 @@ export const RegistrationSchema: z.ZodSchema<Registration> =
       )
   ) as unknown as z.ZodSchema<Registration>;
 
+export const REGISTRATION_REJECTION_REASONS = [
+  'notEligible',
+  'identityNotVerified',
+  'duplicate',
+  'expired',
+  'other',
+] as const;
+
+export type RegistrationRejectionReason =
+  (typeof REGISTRATION_REJECTION_REASONS)[number];
+
+export class RegistrationRejection {
+  constructor(
+    private readonly commonAccessCardId: string,
+    private readonly jurisdictionCode: JurisdictionCode,
+    private readonly registrationRequestObjectId: Uuid,
+    private readonly reason: RegistrationRejectionReason,
+    private readonly message?: string
+  ) {}
+
+  getCommonAccessCardId(): string {
+    return this.commonAccessCardId;
+  }
+
+  getJurisdictionCode(): JurisdictionCode {
+    return this.jurisdictionCode;
+  }
+
+  getRegistrationRequestObjectId(): Uuid {
+    return this.registrationRequestObjectId;
+  }
+
+  getReason(): RegistrationRejectionReason {
+    return this.reason;
+  }
+
+  getMessage(): string | undefined {
+    return this.message;
+  }
+
+  toJSON(): RegistrationRejectionStruct {
+    return {
+      commonAccessCardId: this.commonAccessCardId,
+      jurisdictionCode: this.jurisdictionCode,
+      registrationRequestObjectId: this.registrationRequestObjectId.toString(),
+      reason: this.reason,
+      message: this.message,
+    };
+  }
+}
+
+export interface RegistrationRejectionStruct {
+  commonAccessCardId: string;
+  jurisdictionCode: string;
+  registrationRequestObjectId: string;
+  reason: RegistrationRejectionReason;
+  message?: string;
+}
+
+const RegistrationRejectionStructSchema = z.object({
+  commonAccessCardId: z.string(),
+  jurisdictionCode: JurisdictionCodeSchema,
+  registrationRequestObjectId: UuidSchema,
+  reason: z.enum(REGISTRATION_REJECTION_REASONS),
+  message: z.string().optional(),
+}) satisfies z.ZodSchema<RegistrationRejectionStruct>;
+
+export const RegistrationRejectionSchema: z.ZodSchema<RegistrationRejection> =
+  RegistrationRejectionStructSchema.transform(
+    (o) =>
+      new RegistrationRejection(
+        o.commonAccessCardId,
+        o.jurisdictionCode,
+        o.registrationRequestObjectId,
+        o.reason,
+        o.message
+      )
+  ) as unknown as z.ZodSchema<RegistrationRejection>;
+
 export class CastBallot {
   constructor(
     private readonly commonAccessCardId: string,
@@ -507,6 +616,7 @@ export const CastBallotSchema = CastBallotStructSchema.transform(
 export type PayloadInner =
   | Election
   | Registration
+  | RegistrationRejection
   | RegistrationRequest
   | CastBallot;
 
@@ -544,6 +654,12 @@ export class Payload<T extends PayloadInner = PayloadInner> {
     return new Payload(RegistrationObjectType, data);
   }
 
+  static RegistrationRejection(
+    data: RegistrationRejection
+  ): Payload<RegistrationRejection> {
+    return new Payload(RegistrationRejectionObjectType, data);
+  }
+
   static RegistrationRequest(
     data: RegistrationRequest
   ): Payload<RegistrationRequest> {
@@ -563,6 +679,9 @@ export const PayloadSchema: z.ZodSchema<Payload> = z
     z
       .object({ objectType: z.literal(RegistrationObjectType) })
       .merge(RegistrationStructSchema),
+    z
+      .object({ objectType: z.literal(RegistrationRejectionObjectType) })
+      .merge(RegistrationRejectionStructSchema),
     z
       .object({ objectType: z.literal(RegistrationRequestObjectType) })
       .merge(RegistrationRequestStructSchema),
@@ -580,6 +699,12 @@ export const PayloadSchema: z.ZodSchema<Payload> = z
         return Payload.Registration(RegistrationSchema.parse(o));
       }
 
+      case RegistrationRejectionObjectType: {
+        return Payload.RegistrationRejection(
+          RegistrationRejectionSchema.parse(o)
+        );
+      }
+
       case RegistrationRequestObjectType: {
         return Payload.RegistrationRequest(RegistrationRequestSchema.parse(o));
       }
@@ -628,6 +753,9 @@ export class SignedObject {
   getPayloadAsObjectType(
     objectType: typeof RegistrationObjectType
   ): Result<Payload<Registration>, ZodError | SyntaxError>;
+  getPayloadAsObjectType(
+    objectType: typeof RegistrationRejectionObjectType
+  ): Result<Payload<RegistrationRejection>, ZodError | SyntaxError>;
   getPayloadAsObjectType(
     objectType: typeof CastBallotObjectType
   ): Result<Payload<CastBallot>, ZodError | SyntaxError>;
@@ -11,7 +11,12 @@ import { UsabilityTestStore } from './usability-test/usability_test_store';
 
 export type { Api, VoterStatus } from './app';
 export type { AuthStatus } from './types/auth';
-export type { JurisdictionCode, Uuid } from './cacvote-server/types';
+export type {
+  JurisdictionCode,
+  RegistrationRejectionReason,
+  Uuid,
+  VoterAddress,
+} from './cacvote-server/types';
 
 const logger = new Logger(LogSource.VxMarkBackend, () =>
   Promise.resolve('system')
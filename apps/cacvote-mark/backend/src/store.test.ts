@@ -12,6 +12,7 @@ import {
   JurisdictionCode,
   JurisdictionCodeSchema,
   Payload,
+  RegistrationRejection,
   RegistrationRequest,
   SignedObject,
   Uuid,
@@ -156,3 +157,49 @@ test('forEachRegistrationRequest', async () => {
       .isEmpty()
   ).toBeTruthy();
 });
+
+test('forEachRegistrationRejection', async () => {
+  const store = Store.memoryStore();
+  const commonAccessCardId = '1234567890';
+  const registrationRequestObjectId = Uuid();
+
+  const registrationRejection = new RegistrationRejection(
+    commonAccessCardId,
+    JurisdictionCodeSchema.parse('st.test-jurisdiction'),
+    registrationRequestObjectId,
+    'notEligible',
+    'Contact the election office'
+  );
+  const object = new SignedObject(
+    Uuid(),
+    undefined,
+    Payload.RegistrationRejection(registrationRejection).toBuffer(),
+    await getCertificates(),
+    Buffer.from('signature')
+  );
+
+  expect(
+    await store
+      .forEachRegistrationRejection({ commonAccessCardId })
+      .isEmpty()
+  ).toBeTruthy();
+
+  (await store.addObject(object)).unsafeUnwrap();
+
+  expect(
+    await store
+      .forEachRegistrationRejection({
+        commonAccessCardId,
+        registrationRequestObjectId,
+      })
+      .first()
+  ).toEqual({ object, registrationRejection });
+  expect(
+    await store
+      .forEachRegistrationRejection({
+        commonAccessCardId,
+        registrationRequestObjectId: Uuid(),
+      })
+      .isEmpty()
+  ).toBeTruthy();
+});
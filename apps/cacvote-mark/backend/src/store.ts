@@ -22,6 +22,8 @@ import {
   JurisdictionCodeSchema,
   Registration,
   RegistrationObjectType,
+  RegistrationRejection,
+  RegistrationRejectionObjectType,
   RegistrationRequest,
   RegistrationRequestObjectType,
   SignedObject,
@@ -296,6 +298,7 @@ export class Store {
     const objectTypesToPull = [
       RegistrationRequestObjectType,
       RegistrationObjectType,
+      RegistrationRejectionObjectType,
       ElectionObjectType,
     ];
     const action = 'create';
@@ -439,6 +442,39 @@ export class Store {
     );
   }
 
+  forEachRegistrationRejection({
+    commonAccessCardId,
+    registrationRequestObjectId,
+  }: {
+    commonAccessCardId: string;
+    registrationRequestObjectId?: Uuid;
+  }): AsyncIteratorPlus<{
+    object: SignedObject;
+    registrationRejection: RegistrationRejection;
+  }> {
+    return this.forEachObjectOfType(RegistrationRejectionObjectType).filterMap(
+      (object) => {
+        const registrationRejection = object
+          .getPayload()
+          .unsafeUnwrap()
+          .getData();
+        assert(
+          registrationRejection instanceof RegistrationRejection,
+          'payload matches object type because we used forEachObjectType'
+        );
+        if (
+          registrationRejection.getCommonAccessCardId() ===
+            commonAccessCardId &&
+          (!registrationRequestObjectId ||
+            registrationRequestObjectId ===
+              registrationRejection.getRegistrationRequestObjectId())
+        ) {
+          return { object, registrationRejection };
+        }
+      }
+    );
+  }
+
   forEachCastBallot({
     commonAccessCardId,
     electionObjectId,
@@ -1,2 +1,3 @@
+export * from './rejected_screen';
 export * from './start_screen';
 export * from './status_screen';
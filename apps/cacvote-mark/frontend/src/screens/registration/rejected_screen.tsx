@@ -0,0 +1,42 @@
+import { throwIllegalValue } from '@votingworks/basics';
+import type { RegistrationRejectionReason } from '@votingworks/cacvote-mark-backend';
+import { H1, Main, P, Screen } from '@votingworks/ui';
+
+function describeReason(reason: RegistrationRejectionReason): string {
+  switch (reason) {
+    case 'notEligible':
+      return 'You are not eligible to vote in this jurisdiction.';
+    case 'identityNotVerified':
+      return 'Your identity could not be verified.';
+    case 'duplicate':
+      return 'You already have a registration request on file.';
+    case 'expired':
+      return 'Your registration request expired before it was reviewed.';
+    case 'other':
+      return 'Your registration request could not be approved.';
+    /* istanbul ignore next */
+    default:
+      throwIllegalValue(reason);
+  }
+}
+
+export interface RejectedScreenProps {
+  reason: RegistrationRejectionReason;
+  message?: string;
+}
+
+export function RejectedScreen({
+  reason,
+  message,
+}: RejectedScreenProps): JSX.Element {
+  return (
+    <Screen>
+      <Main centerChild padded>
+        <H1>Registration Rejected</H1>
+        <P>{describeReason(reason)}</P>
+        {message && <P>{message}</P>}
+        <P>Contact your election office for help.</P>
+      </Main>
+    </Screen>
+  );
+}
@@ -1,7 +1,10 @@
 import { extractErrorMessage } from '@votingworks/basics';
 import { Button, H1, Main, P, Screen, Select } from '@votingworks/ui';
 import { useState } from 'react';
-import { JurisdictionCode } from '@votingworks/cacvote-mark-backend';
+import {
+  JurisdictionCode,
+  VoterAddress,
+} from '@votingworks/cacvote-mark-backend';
 import {
   createVoterRegistration,
   getAuthStatus,
@@ -21,6 +24,11 @@ export function StartScreen(): JSX.Element {
   const [jurisdictionCode, setJurisdictionCode] = useState<JurisdictionCode>();
   const [givenName, setGivenName] = useState(cardDetails?.givenName ?? '');
   const [familyName, setFamilyName] = useState(cardDetails?.familyName ?? '');
+  const [houseNumber, setHouseNumber] = useState('');
+  const [streetName, setStreetName] = useState('');
+  const [unit, setUnit] = useState('');
+  const [city, setCity] = useState('');
+  const [zipCode, setZipCode] = useState('');
   const [isShowingPinModal, setIsShowingPinModal] = useState(false);
   const createVoterRegistrationMutation = createVoterRegistration.useMutation();
   const error = createVoterRegistrationMutation.data?.err();
@@ -33,11 +41,31 @@ export function StartScreen(): JSX.Element {
     setIsShowingPinModal(true);
   }
 
+  // the address is optional, but if given must be complete enough to look up
+  const parsedHouseNumber = Number.parseInt(houseNumber, 10);
+  const address: VoterAddress | undefined =
+    Number.isSafeInteger(parsedHouseNumber) &&
+    parsedHouseNumber >= 0 &&
+    streetName.trim() &&
+    city.trim() &&
+    zipCode.trim()
+      ? {
+          houseNumber: parsedHouseNumber,
+          streetName: streetName.trim(),
+          unit: unit.trim() || undefined,
+          city: city.trim(),
+          zipCode: zipCode.trim(),
+        }
+      : undefined;
+  const isAddressIncomplete =
+    !address && [houseNumber, streetName, unit, city, zipCode].some(Boolean);
+
   function onEnterPin(pin: string, code: JurisdictionCode) {
     createVoterRegistrationMutation.mutate({
       jurisdictionCode: code,
       givenName,
       familyName,
+      address,
       pin,
     });
   }
@@ -64,6 +92,46 @@ export function StartScreen(): JSX.Element {
               setFamilyName(newValue);
             }}
           />
+          <TextInput
+            label="House Number"
+            data-testid="house-number"
+            value={houseNumber}
+            onChange={(newValue) => {
+              setHouseNumber(newValue);
+            }}
+          />
+          <TextInput
+            label="Street"
+            data-testid="street-name"
+            value={streetName}
+            onChange={(newValue) => {
+              setStreetName(newValue);
+            }}
+          />
+          <TextInput
+            label="Unit (optional)"
+            data-testid="unit"
+            value={unit}
+            onChange={(newValue) => {
+              setUnit(newValue);
+            }}
+          />
+          <TextInput
+            label="City"
+            data-testid="city"
+            value={city}
+            onChange={(newValue) => {
+              setCity(newValue);
+            }}
+          />
+          <TextInput
+            label="ZIP Code"
+            data-testid="zip-code"
+            value={zipCode}
+            onChange={(newValue) => {
+              setZipCode(newValue);
+            }}
+          />
           <Select value={jurisdictionCode} onChange={onChangeJurisdictionId}>
             <option disabled selected={!jurisdictionCode}>
               Select your jurisdiction
@@ -76,7 +144,7 @@ export function StartScreen(): JSX.Element {
           </Select>
           <Button
             onPress={onSubmitRegistrationForm}
-            disabled={!jurisdictionCode}
+            disabled={!jurisdictionCode || isAddressIncomplete}
           >
             Submit
           </Button>
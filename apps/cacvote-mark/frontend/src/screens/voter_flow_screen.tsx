@@ -587,6 +587,15 @@ export function VoterFlowScreen({
     case 'registration_pending':
       return <Registration.StatusScreen />;
 
+    case 'registration_rejected':
+      assert(getVoterStatusQuery.data?.rejection);
+      return (
+        <Registration.RejectedScreen
+          reason={getVoterStatusQuery.data.rejection.reason}
+          message={getVoterStatusQuery.data.rejection.message}
+        />
+      );
+
     case 'registered':
     case 'voted':
     case undefined:
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use auth_rs::{card_details::extract_field_value, certs::VX_CUSTOM_CERT_FIELD_JURISDICTION};
 use cacvote_server::client::Client;
-use cacvote_server_client::{signer, AnySigner, PrivateKeySigner};
+use cacvote_server_client::{signer, AnyAsyncSigner, AnySigner, PrivateKeySigner};
 use clap::Parser;
 use color_eyre::eyre::eyre;
 use openssl::asn1::Asn1Time;
@@ -59,8 +59,8 @@ impl GlobalOpts {
             .map_err(|e| eyre!("invalid jurisdiction code: {e}"))
     }
 
-    fn signer(&self) -> color_eyre::Result<AnySigner> {
-        AnySigner::try_from(&self.signer)
+    fn signer(&self) -> color_eyre::Result<AnyAsyncSigner> {
+        AnyAsyncSigner::try_from(&self.signer)
     }
 
     fn client(&self) -> color_eyre::Result<Client> {
@@ -267,6 +267,7 @@ async fn create_object(
             given_name: create_object_opts.given_name,
             family_name: create_object_opts.family_name,
             jurisdiction_code: create_object_opts.jurisdiction_code,
+            address: None,
         });
 
     let payload = serde_json::to_vec(&payload)?;
@@ -277,6 +278,7 @@ async fn create_object(
         election_id: None,
         payload,
         certificate: card_cert.to_pem()?,
+        certificate_chain: None,
         signature,
     };
 
@@ -0,0 +1,131 @@
+//! `cacvote-transfer` moves objects between the CACvote Server and JX terminals
+//! that cannot reach it over the network, using signed transfer bundles
+//! carried on removable media.
+
+use std::path::PathBuf;
+
+use auth_rs::{card_details::extract_field_value, certs::VX_CUSTOM_CERT_FIELD_JURISDICTION};
+use cacvote_server::{config::Config, db, transfer};
+use cacvote_server_client::{signer, AnySigner};
+use clap::Parser;
+use color_eyre::eyre::eyre;
+use openssl::x509::X509;
+use types_rs::cacvote::{JurisdictionCode, SignedTransferBundle};
+
+#[derive(Parser)]
+struct App {
+    #[clap(flatten)]
+    config: Config,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Parser)]
+enum Command {
+    /// Import a bundle exported by a JX terminal and write a receipt.
+    Import(ImportOpts),
+
+    /// Export the journal and objects for a jurisdiction to a bundle for
+    /// import by a JX terminal.
+    Export(ExportOpts),
+}
+
+#[derive(Parser)]
+struct ImportOpts {
+    /// Path to the signed bundle to import.
+    bundle: PathBuf,
+
+    /// Where to write the receipt. Defaults to the bundle path with a
+    /// `.receipt.json` extension.
+    #[clap(long)]
+    receipt: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+struct ExportOpts {
+    /// Where to write the signed bundle.
+    output: PathBuf,
+
+    /// Certificate used to sign the bundle. Its jurisdiction determines which
+    /// objects are exported.
+    #[clap(long, env = "SIGNING_CERT")]
+    signing_cert: PathBuf,
+
+    #[clap(long, env = "SIGNER")]
+    signer: signer::Description,
+}
+
+impl ExportOpts {
+    fn signing_cert(&self) -> color_eyre::Result<X509> {
+        let pem = std::fs::read(&self.signing_cert)?;
+        Ok(X509::from_pem(&pem)?)
+    }
+
+    fn jurisdiction_code(&self) -> color_eyre::Result<JurisdictionCode> {
+        let jurisdiction_code =
+            extract_field_value(&self.signing_cert()?, VX_CUSTOM_CERT_FIELD_JURISDICTION)?
+                .ok_or_else(|| eyre!("signing certificate does not have a jurisdiction code"))?;
+        JurisdictionCode::try_from(jurisdiction_code)
+            .map_err(|e| eyre!("invalid jurisdiction code: {e}"))
+    }
+
+    fn signer(&self) -> color_eyre::Result<AnySigner> {
+        AnySigner::try_from(&self.signer)
+    }
+}
+
+#[tokio::main]
+async fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    dotenvy::dotenv()?;
+
+    let app = App::parse();
+    let pool = db::setup(&app.config).await?;
+    let mut connection = pool.acquire().await?;
+
+    match app.command {
+        Command::Import(import_opts) => {
+            let signed_bundle: SignedTransferBundle =
+                serde_json::from_slice(&std::fs::read(&import_opts.bundle)?)?;
+            let receipt = transfer::import_bundle(
+                &mut connection,
+                &signed_bundle,
+                &app.config.vx_root_ca_cert()?,
+                &app.config.cac_root_ca_store()?,
+            )
+            .await?;
+            let receipt_path = import_opts
+                .receipt
+                .unwrap_or_else(|| import_opts.bundle.with_extension("receipt.json"));
+            std::fs::write(&receipt_path, serde_json::to_vec_pretty(&receipt)?)?;
+            println!(
+                "imported {} objects, skipped {}, rejected {}; receipt written to {}",
+                receipt.imported_object_ids.len(),
+                receipt.skipped_object_ids.len(),
+                receipt.rejected_objects.len(),
+                receipt_path.display()
+            );
+        }
+        Command::Export(export_opts) => {
+            let bundle =
+                transfer::export_bundle(&mut connection, export_opts.jurisdiction_code()?).await?;
+            let bundle_bytes = serde_json::to_vec(&bundle)?;
+            let signature = export_opts.signer()?.sign(&bundle_bytes)?;
+            let signed_bundle = SignedTransferBundle {
+                bundle: bundle_bytes,
+                certificate: export_opts.signing_cert()?.to_pem()?,
+                signature,
+            };
+            std::fs::write(&export_opts.output, serde_json::to_vec(&signed_bundle)?)?;
+            println!(
+                "exported {} journal entries and {} objects to {}",
+                bundle.journal_entries.len(),
+                bundle.objects.len(),
+                export_opts.output.display()
+            );
+        }
+    }
+
+    Ok(())
+}
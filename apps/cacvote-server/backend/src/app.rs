@@ -16,12 +16,12 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
-use base64_serde::base64_serde_type;
 use cacvote_server_client::{
+    CreateMachineRequest, CreateMachineResponse, CreateScannedMailingLabelResponse,
     CreateSessionRequest, CreateSessionRequestPayload, CreateSessionResponse,
+    GetJournalEntriesQuery, SearchQuery, SearchResult,
 };
 use openssl::{hash::MessageDigest, sign::Verifier, x509};
-use serde::Deserialize;
 use serde_json::json;
 use sqlx::PgPool;
 use tokio::sync::Mutex;
@@ -38,8 +38,6 @@ use crate::{
     state::AppState,
 };
 
-base64_serde_type!(Base64Standard, base64::engine::general_purpose::STANDARD);
-
 /// Prepares the application to be run within an HTTP server.
 ///
 /// Requires a [`PgPool`] from [`db::setup`]. Run the application with [`run`]
@@ -47,7 +45,7 @@ base64_serde_type!(Base64Standard, base64::engine::general_purpose::STANDARD);
 pub async fn setup(
     pool: PgPool,
     vx_root_ca_cert: x509::X509,
-    cac_root_ca_store: x509::store::X509Store,
+    cac_root_ca_store: cacvote::CacCaStore,
 ) -> Router {
     let _entered = tracing::span!(Level::DEBUG, "Setting up application").entered();
     Router::new()
@@ -86,6 +84,14 @@ pub async fn setup(
             "/api/elections/:election_id/shuffled-ballots",
             get(list_shuffled_ballots_by_election),
         )
+        .route(
+            "/api/elections/:election_id/shuffled-ballots/verifications",
+            get(list_shuffled_ballots_verifications_by_election),
+        )
+        .route(
+            "/api/elections/:election_id/decrypted-shuffled-ballots",
+            get(get_decrypted_shuffled_ballots_by_election),
+        )
         .route("/api/search", post(search))
         .layer(DefaultBodyLimit::max(MAX_REQUEST_SIZE))
         .layer(TraceLayer::new_for_http())
@@ -134,9 +140,11 @@ async fn create_session(
         tracing::error!("Failed to extract public key from certificate: {e}");
         Error::Other(e.into())
     })?;
-    if !verify_cert_single_ca(&vx_root_ca_cert, &certificate).unwrap_or(false) {
-        tracing::error!("Failed to verify certificate");
-        return Err(Error::BadRequest("Failed to verify certificate".to_owned()));
+    if let Err(e) = verify_cert_single_ca(&vx_root_ca_cert, &certificate) {
+        tracing::error!("Failed to verify certificate: {e}");
+        return Err(Error::BadRequest(format!(
+            "Failed to verify certificate: {e}"
+        )));
     }
 
     // verify "signature" signed by "signed TPM public key"
@@ -197,33 +205,162 @@ async fn create_object(
     }): State<AppState>,
     object: Json<cacvote::SignedObject>,
 ) -> Result<impl IntoResponse, Error> {
-    match object.verify(&vx_root_ca_cert, &cac_root_ca_store) {
-        Ok(true) => (),
-        Ok(false) => {
-            tracing::error!("Signature verification failed");
-            return Err(Error::BadRequest(
-                "Signature verification failed".to_owned(),
-            ));
+    verify_signed_object(&object, &vx_root_ca_cert, &cac_root_ca_store)
+        .map_err(Error::BadRequest)?;
+    let mut txn = pool.begin().await?;
+    check_election_lifecycle(&mut txn, &object)
+        .await?
+        .map_err(Error::BadRequest)?;
+    check_shuffled_ballots_reference(&mut txn, &object)
+        .await?
+        .map_err(Error::BadRequest)?;
+    let object_id = db::create_object(&mut txn, &object).await?;
+    txn.commit().await?;
+    Ok((StatusCode::CREATED, object_id.to_string()))
+}
+
+/// Verifies an object's signature and certificate chain before it is stored.
+/// Used both for objects posted directly and for objects imported from a
+/// transfer bundle.
+pub(crate) fn verify_signed_object(
+    object: &cacvote::SignedObject,
+    vx_root_ca_cert: &x509::X509,
+    cac_root_ca_store: &cacvote::CacCaStore,
+) -> Result<(), String> {
+    object
+        .verify(vx_root_ca_cert, cac_root_ca_store)
+        .map_err(|e| {
+            tracing::error!("Object verification failed: {e}");
+            format!("Object verification failed: {e}")
+        })
+}
+
+/// Checks an object against the lifecycle of the election it belongs to:
+/// ballots are only accepted while voting is open and within the election's
+/// voting window, and lifecycle transitions must be for an election in the
+/// same jurisdiction and follow from its current state. The outer result is
+/// for database errors, the inner one for objects that are not allowed.
+///
+/// The election stays locked until the end of the transaction `connection`
+/// is in, which must also store the object so that a concurrent transition
+/// cannot change the state it was checked against.
+pub(crate) async fn check_election_lifecycle(
+    connection: &mut sqlx::PgConnection,
+    object: &cacvote::SignedObject,
+) -> color_eyre::Result<Result<(), String>> {
+    let Ok(payload) = object.try_to_inner() else {
+        return Ok(Ok(()));
+    };
+
+    match payload {
+        cacvote::Payload::CastBallot(cast_ballot) => {
+            let Some(election) = db::lock_election(
+                connection,
+                cast_ballot.election_object_id,
+                db::ElectionLock::Shared,
+            )
+            .await?
+            else {
+                return Ok(Err("Ballot is for an unknown election".to_owned()));
+            };
+
+            let state =
+                db::get_election_lifecycle_state(connection, cast_ballot.election_object_id)
+                    .await?;
+            if !state.is_accepting_ballots() {
+                tracing::error!(
+                    "Rejecting ballot {} for election {}: election is {state}",
+                    object.id,
+                    cast_ballot.election_object_id
+                );
+                return Ok(Err(format!(
+                    "Ballots are not accepted while the election is {state}"
+                )));
+            }
+
+            if let Err(e) = election.check_voting_window(time::OffsetDateTime::now_utc()) {
+                tracing::error!(
+                    "Rejecting ballot {} for election {}: {e}",
+                    object.id,
+                    cast_ballot.election_object_id
+                );
+                return Ok(Err(format!("Ballots are not accepted: {e}")));
+            }
         }
-        Err(e) => {
-            tracing::error!("Error verifying object signature: {e}");
-            return Err(Error::BadRequest(format!(
-                "Error verifying object signature: {e}"
-            )));
+        cacvote::Payload::ElectionLifecycleTransition(transition) => {
+            let Some(election) = db::lock_election(
+                connection,
+                transition.election_object_id,
+                db::ElectionLock::Exclusive,
+            )
+            .await?
+            else {
+                return Ok(Err(
+                    "Lifecycle transition is for an unknown election".to_owned()
+                ));
+            };
+
+            if election.jurisdiction_code != transition.jurisdiction_code {
+                tracing::error!(
+                    "Rejecting lifecycle transition {}: election {} is in jurisdiction {}",
+                    object.id,
+                    transition.election_object_id,
+                    election.jurisdiction_code
+                );
+                return Ok(Err(format!(
+                    "Lifecycle transition is for jurisdiction {}, but the election is in {}",
+                    transition.jurisdiction_code, election.jurisdiction_code
+                )));
+            }
+
+            let state =
+                db::get_election_lifecycle_state(connection, transition.election_object_id).await?;
+            if let Err(e) = state.apply(transition.action) {
+                tracing::error!("Rejecting lifecycle transition {}: {e}", object.id);
+                return Ok(Err(e));
+            }
         }
+        _ => {}
     }
-    let mut conn = pool.acquire().await?;
-    let object_id = db::create_object(&mut conn, &object).await?;
-    Ok((StatusCode::CREATED, object_id.to_string()))
-}
 
-#[derive(Debug, Deserialize)]
-struct GetJournalEntriesQuery {
-    #[serde(rename = "since")]
-    since_journal_entry_id: Option<Uuid>,
+    Ok(Ok(()))
+}
 
-    #[serde(rename = "jurisdiction")]
-    jurisdiction_code: Option<cacvote::JurisdictionCode>,
+/// Checks that a record of verifying or decrypting an election's shuffled
+/// ballots refers to the shuffled ballots of that same election. The outer
+/// result is for database errors, the inner one for objects that are not
+/// allowed.
+pub(crate) async fn check_shuffled_ballots_reference(
+    connection: &mut sqlx::PgConnection,
+    object: &cacvote::SignedObject,
+) -> color_eyre::Result<Result<(), String>> {
+    let (election_object_id, shuffled_ballots_id) = match object.try_to_inner() {
+        Ok(cacvote::Payload::ShuffledEncryptedCastBallotsVerification(verification)) => (
+            verification.election_object_id,
+            verification.shuffled_encrypted_cast_ballots_object_id,
+        ),
+        Ok(cacvote::Payload::DecryptedShuffledBallots(decrypted_shuffled_ballots)) => (
+            decrypted_shuffled_ballots.election_object_id,
+            decrypted_shuffled_ballots.shuffled_encrypted_cast_ballots_object_id,
+        ),
+        _ => return Ok(Ok(())),
+    };
+
+    let shuffled_ballots = match db::get_object_by_id(connection, shuffled_ballots_id).await? {
+        Some(shuffled_ballots) => shuffled_ballots.try_to_inner().ok(),
+        None => None,
+    };
+
+    match shuffled_ballots {
+        Some(cacvote::Payload::ShuffledEncryptedCastBallots(shuffled_ballots))
+            if shuffled_ballots.election_object_id == election_object_id =>
+        {
+            Ok(Ok(()))
+        }
+        _ => Ok(Err(format!(
+            "Election {election_object_id} has no shuffled ballots with ID {shuffled_ballots_id}"
+        ))),
+    }
 }
 
 async fn get_journal_entries(
@@ -255,15 +392,6 @@ async fn get_object_by_id(
     }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct CreateMachineRequest {
-    machine_identifier: String,
-
-    #[serde(with = "Base64Standard")]
-    certificate: Vec<u8>,
-}
-
 async fn create_machine(
     State(AppState {
         vx_root_ca_cert,
@@ -281,7 +409,12 @@ async fn create_machine(
         db::get_machine_by_identifier(&mut conn, &machine_identifier).await?
     {
         if existing_machine.certificate == certificate_bytes {
-            return Ok((StatusCode::OK, Json(json!({ "id": existing_machine.id }))));
+            return Ok((
+                StatusCode::OK,
+                Json(CreateMachineResponse {
+                    id: existing_machine.id,
+                }),
+            ));
         } else {
             tracing::error!("Machine already exists with different certificate");
             return Err(Error::BadRequest(
@@ -295,9 +428,11 @@ async fn create_machine(
         Error::BadRequest(format!("Failed to parse certificate: {e}"))
     })?;
 
-    if !verify_cert_single_ca(&vx_root_ca_cert, &certificate).unwrap_or(false) {
-        tracing::error!("Failed to verify certificate");
-        return Err(Error::BadRequest("Failed to verify certificate".to_owned()));
+    if let Err(e) = verify_cert_single_ca(&vx_root_ca_cert, &certificate) {
+        tracing::error!("Failed to verify certificate: {e}");
+        return Err(Error::BadRequest(format!(
+            "Failed to verify certificate: {e}"
+        )));
     }
 
     // TODO: Extract the machine ID from the certificate rather than having it
@@ -305,7 +440,10 @@ async fn create_machine(
     // machine ID.
     let machine = db::create_machine(&mut conn, &machine_identifier, &certificate_bytes).await?;
 
-    Ok((StatusCode::CREATED, Json(json!({ "id": machine.id }))))
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateMachineResponse { id: machine.id }),
+    ))
 }
 
 async fn scanned_create_mailing_label(
@@ -316,7 +454,10 @@ async fn scanned_create_mailing_label(
 
     let id = db::create_scanned_mailing_label_code(&mut conn, &scanned_mailing_label_code).await?;
 
-    Ok((StatusCode::CREATED, Json(json!({ "id": id }))))
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateScannedMailingLabelResponse { id }),
+    ))
 }
 
 async fn list_elections(
@@ -430,16 +571,44 @@ async fn list_shuffled_ballots_by_election(
     }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct SearchQuery {
-    common_access_card_id: String,
+async fn list_shuffled_ballots_verifications_by_election(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(election_id): Path<Uuid>,
+) -> Result<Json<Vec<cacvote::SignedObject>>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    Ok(Json(
+        db::get_objects_by_election_id_and_type(
+            &mut conn,
+            election_id,
+            cacvote::Payload::shuffled_encrypted_cast_ballots_verification_object_type(),
+        )
+        .await?,
+    ))
+}
+
+async fn get_decrypted_shuffled_ballots_by_election(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(election_id): Path<Uuid>,
+) -> Result<Json<cacvote::SignedObject>, Error> {
+    let mut conn = pool.acquire().await?;
+
+    match db::get_object_by_election_id_and_type(
+        &mut conn,
+        election_id,
+        cacvote::Payload::decrypted_shuffled_ballots_object_type(),
+    )
+    .await?
+    {
+        Some(object) => Ok(Json(object)),
+        None => Err(Error::NotFound),
+    }
 }
 
 async fn search(
     State(AppState { pool, .. }): State<AppState>,
     query: Query<SearchQuery>,
-) -> Result<Json<Vec<db::SearchResult>>, Error> {
+) -> Result<Json<Vec<SearchResult>>, Error> {
     let mut conn = pool.acquire().await?;
 
     Ok(Json(
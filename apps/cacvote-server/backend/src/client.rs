@@ -6,8 +6,10 @@ mod tests {
     use openssl::{pkey::PKey, x509};
     use sqlx::PgPool;
     use types_rs::cacvote::{
-        JournalEntryAction, JurisdictionCode, Payload, RegistrationRequest, SignedObject,
+        build_cac_ca_store, CacCaStore, Election, JournalEntryAction, JurisdictionCode, Payload,
+        RegistrationRequest, SignedObject,
     };
+    use types_rs::election::ElectionDefinition;
     use uuid::Uuid;
 
     use super::*;
@@ -16,7 +18,7 @@ mod tests {
     async fn setup(
         pool: PgPool,
         vx_root_ca_cert: x509::X509,
-        cac_root_ca_store: x509::store::X509Store,
+        cac_root_ca_store: CacCaStore,
     ) -> color_eyre::Result<Client> {
         let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await?;
         let addr = listener.local_addr()?;
@@ -43,8 +45,7 @@ mod tests {
         ))
     }
 
-    fn load_cryptographic_info(
-    ) -> color_eyre::Result<(x509::X509, x509::store::X509Store, PrivateKeySigner)> {
+    fn load_cryptographic_info() -> color_eyre::Result<(x509::X509, CacCaStore, PrivateKeySigner)> {
         let vx_root_ca_cert = x509::X509::from_pem(include_bytes!(
             "../../../../libs/auth/certs/dev/vx-cert-authority-cert.pem"
         ))?;
@@ -58,13 +59,7 @@ mod tests {
                 "../../../../libs/auth/certs/dev/vx-cert-authority-cert.pem"
             ))?,
         ];
-        let mut cac_root_ca_store_builder = x509::store::X509StoreBuilder::new()?;
-
-        for cac_root_ca_cert in cac_root_ca_certs {
-            cac_root_ca_store_builder.add_cert(cac_root_ca_cert.clone())?;
-        }
-
-        let cac_root_ca_store = cac_root_ca_store_builder.build();
+        let cac_root_ca_store = build_cac_ca_store(cac_root_ca_certs, [])?;
 
         let private_key_pem = include_bytes!("../../../../libs/auth/certs/dev/vx-private-key.pem");
         let private_key = PKey::private_key_from_pem(private_key_pem)?;
@@ -87,6 +82,7 @@ mod tests {
             common_access_card_id: "1234567890".to_owned(),
             given_name: "John".to_owned(),
             family_name: "Doe".to_owned(),
+            address: None,
             jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
         });
         let payload = serde_json::to_vec(&payload)?;
@@ -99,6 +95,7 @@ mod tests {
                 election_id: None,
                 payload,
                 certificate: vx_root_ca_cert.to_pem()?,
+                certificate_chain: None,
                 signature: signature.clone(),
             })
             .await?;
@@ -174,6 +171,7 @@ mod tests {
             common_access_card_id: "1234567890".to_owned(),
             given_name: "John".to_owned(),
             family_name: "Doe".to_owned(),
+            address: None,
             jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
         });
         let payload = serde_json::to_vec(&payload)?;
@@ -185,6 +183,7 @@ mod tests {
                 election_id: None,
                 // invalid certificate and signature
                 certificate: vec![],
+                certificate_chain: None,
                 signature: vec![],
             })
             .await
@@ -195,4 +194,103 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_bulletin_board(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let (vx_root_ca_cert, cac_root_ca_store, object_signer) = load_cryptographic_info()?;
+        let mut client = setup(pool, vx_root_ca_cert.clone(), cac_root_ca_store).await?;
+
+        assert_eq!(client.list_elections().await?, vec![]);
+
+        let election_definition = ElectionDefinition::try_from(
+            &include_bytes!(
+                "../../../cacvote-jx-terminal/backend/tests/fixtures/electionFamousNames2021.json"
+            )[..],
+        )?;
+        let payload = serde_json::to_vec(&Payload::Election(Election {
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            election_definition,
+            mailing_address: "123 Main St".to_owned(),
+            electionguard_election_metadata_blob: vec![],
+            voting_starts_at: None,
+            voting_ends_at: None,
+            ballot_receipt_deadline: None,
+        }))?;
+        let signature = object_signer.sign(&payload)?;
+        let election_id = client
+            .create_object(SignedObject {
+                id: Uuid::new_v4(),
+                election_id: None,
+                payload,
+                certificate: vx_root_ca_cert.to_pem()?,
+                certificate_chain: None,
+                signature,
+            })
+            .await?;
+
+        let elections = client.list_elections().await?;
+        assert_eq!(
+            elections,
+            vec![cacvote_server_client::bulletin_board::Election::new(
+                election_id
+            )]
+        );
+        assert_eq!(
+            client.list_cast_ballots_by_election(election_id).await?,
+            vec![]
+        );
+        assert_eq!(
+            client
+                .list_scanned_mailing_labels_by_election(election_id)
+                .await?
+                .len(),
+            0
+        );
+        assert!(client
+            .get_cast_ballot_by_id(election_id, Uuid::new_v4())
+            .await?
+            .is_none());
+        assert!(client
+            .get_encrypted_tally_by_election(election_id)
+            .await?
+            .is_none());
+        assert!(client
+            .get_decrypted_tally_by_election(election_id)
+            .await?
+            .is_none());
+        assert!(client
+            .get_shuffled_ballots_by_election(election_id)
+            .await?
+            .is_none());
+        assert_eq!(client.search("1234567890").await?.len(), 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_machines_and_scanned_mailing_labels(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let (vx_root_ca_cert, cac_root_ca_store, _) = load_cryptographic_info()?;
+        let client = setup(pool, vx_root_ca_cert, cac_root_ca_store).await?;
+
+        let machine_cert = x509::X509::from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-mark-cert.pem"
+        ))?;
+        let machine_id = client.create_machine("mark-1", &machine_cert).await?;
+
+        // registering again with the same certificate is a no-op
+        assert_eq!(
+            client.create_machine("mark-1", &machine_cert).await?,
+            machine_id
+        );
+
+        // a label that is not a valid signed ballot verification payload
+        assert!(client
+            .create_scanned_mailing_label(b"not a mailing label")
+            .await
+            .is_err());
+
+        Ok(())
+    }
 }
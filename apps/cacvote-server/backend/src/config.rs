@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 
 use clap::Parser;
 use color_eyre::eyre::Context;
+use types_rs::cacvote::{build_cac_ca_store, CacCaStore};
 
 const TEN_MB: usize = 10 * 1024 * 1024;
 
@@ -29,20 +30,30 @@ pub struct Config {
     #[arg(long, env = "CAC_ROOT_CA_CERTS", value_delimiter = ',')]
     pub cac_root_ca_certs: Vec<PathBuf>,
 
+    /// Directory of intermediate certificate authority certificates, e.g. the
+    /// DoD email CAs, used to link CAC certificates up to `CAC_ROOT_CA_CERTS`.
+    /// Each file may hold any number of PEM certificates or a single DER one.
+    #[arg(long, env = "CAC_INTERMEDIATE_CA_CERTS_DIR")]
+    pub cac_intermediate_ca_certs_dir: Option<PathBuf>,
+
     /// Certificate authority used to sign the machine certificates.
     #[arg(long, env = "ROOT_CA_CERT")]
     pub vx_root_ca_cert: PathBuf,
 }
 
 impl Config {
-    pub fn cac_root_ca_store(&self) -> color_eyre::Result<openssl::x509::store::X509Store> {
-        let mut builder = openssl::x509::store::X509StoreBuilder::new()?;
-
-        for ca_cert in &self.cac_root_ca_certs {
-            builder.add_cert(load_cert(ca_cert)?)?;
-        }
+    pub fn cac_root_ca_store(&self) -> color_eyre::Result<CacCaStore> {
+        let root_certs = self
+            .cac_root_ca_certs
+            .iter()
+            .map(load_cert)
+            .collect::<color_eyre::Result<Vec<_>>>()?;
+        let intermediate_certs = match &self.cac_intermediate_ca_certs_dir {
+            Some(dir) => load_certs_in_dir(dir)?,
+            None => vec![],
+        };
 
-        Ok(builder.build())
+        Ok(build_cac_ca_store(root_certs, intermediate_certs)?)
     }
 
     pub fn vx_root_ca_cert(&self) -> color_eyre::Result<openssl::x509::X509> {
@@ -60,3 +71,34 @@ where
     Ok(openssl::x509::X509::from_pem(&ca_cert)
         .or_else(|_| openssl::x509::X509::from_der(&ca_cert))?)
 }
+
+/// Loads every certificate in the directory. Files may hold any number of PEM
+/// certificates or a single DER one.
+fn load_certs_in_dir<P>(dir: P) -> color_eyre::Result<Vec<openssl::x509::X509>>
+where
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    let mut certs = Vec::new();
+
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read certificate directory {dir:?}"))?
+    {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let contents = std::fs::read(&path)
+            .with_context(|| format!("failed to read certificate at {path:?}"))?;
+        match openssl::x509::X509::stack_from_pem(&contents) {
+            Ok(pem_certs) if !pem_certs.is_empty() => certs.extend(pem_certs),
+            _ => certs.push(
+                openssl::x509::X509::from_der(&contents)
+                    .with_context(|| format!("failed to parse certificate at {path:?}"))?,
+            ),
+        }
+    }
+
+    Ok(certs)
+}
@@ -10,14 +10,14 @@
 use std::time::Duration;
 
 use base64_serde::base64_serde_type;
+use cacvote_server_client::SearchResult;
 use color_eyre::eyre::bail;
 use openssl::x509;
-use serde::Serialize;
 use sqlx::{self, postgres::PgPoolOptions, Connection, PgPool};
 use tracing::Level;
 use types_rs::cacvote::{
-    self, BallotVerificationPayload, JournalEntry, JournalEntryAction, JurisdictionCode,
-    ScannedMailingLabel, SignedBuffer, SignedObject,
+    self, BallotVerificationPayload, ElectionLifecycleState, JournalEntry, JournalEntryAction,
+    JurisdictionCode, ScannedMailingLabel, SignedBuffer, SignedObject,
 };
 use uuid::Uuid;
 
@@ -57,8 +57,8 @@ pub async fn create_object(
 
     match sqlx::query!(
         r#"
-        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificate, signature)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO objects (id, election_id, jurisdiction, object_type, payload, certificate, certificate_chain, signature)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
         "#,
         &object.id,
         object.election_id,
@@ -66,6 +66,7 @@ pub async fn create_object(
         object_type,
         &object.payload,
         &object.certificate,
+        object.certificate_chain.as_deref(),
         &object.signature
     )
     .execute(&mut *txn)
@@ -235,7 +236,7 @@ pub async fn get_object_by_id(
     let object = sqlx::query_as!(
         cacvote::SignedObject,
         r#"
-        SELECT id, election_id, payload, certificate, signature
+        SELECT id, election_id, payload, certificate, certificate_chain, signature
         FROM objects
         WHERE id = $1
         "#,
@@ -296,6 +297,130 @@ pub async fn get_cast_ballot_ids_by_election(
     Ok(records.into_iter().map(|record| record.id).collect())
 }
 
+/// How [`lock_election`] locks an election.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElectionLock {
+    /// Allows other shared locks, so ballots can be cast concurrently.
+    Shared,
+
+    /// Waits for and blocks all other locks, so nothing is accepted for the
+    /// election based on the state a lifecycle transition is changing.
+    Exclusive,
+}
+
+/// Gets an election, locking it until the end of the current transaction so
+/// that checks made against its lifecycle still hold when the object being
+/// checked is stored.
+pub async fn lock_election(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+    lock: ElectionLock,
+) -> color_eyre::Result<Option<cacvote::Election>> {
+    let object = match lock {
+        ElectionLock::Shared => {
+            sqlx::query_as!(
+                cacvote::SignedObject,
+                r#"
+                SELECT id, election_id, payload, certificate, certificate_chain, signature
+                FROM objects
+                WHERE id = $1
+                  AND object_type = $2
+                FOR SHARE
+                "#,
+                election_id,
+                cacvote::Payload::election_object_type()
+            )
+            .fetch_optional(connection)
+            .await?
+        }
+        ElectionLock::Exclusive => {
+            sqlx::query_as!(
+                cacvote::SignedObject,
+                r#"
+                SELECT id, election_id, payload, certificate, certificate_chain, signature
+                FROM objects
+                WHERE id = $1
+                  AND object_type = $2
+                FOR UPDATE
+                "#,
+                election_id,
+                cacvote::Payload::election_object_type()
+            )
+            .fetch_optional(connection)
+            .await?
+        }
+    };
+
+    object
+        .map(|object| match object.try_to_inner()? {
+            cacvote::Payload::Election(election) => Ok(election),
+            payload => bail!("unexpected payload for election: {payload:?}"),
+        })
+        .transpose()
+}
+
+/// Replays the lifecycle transitions recorded for an election, in the order
+/// they were created, to find its current state. Elections that existed before
+/// transitions were recorded start out open.
+pub async fn get_election_lifecycle_state(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+) -> color_eyre::Result<ElectionLifecycleState> {
+    let initial_state = if is_legacy_open_election(connection, election_id).await? {
+        ElectionLifecycleState::Open
+    } else {
+        ElectionLifecycleState::Created
+    };
+
+    let objects = sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+        SELECT id, election_id, payload, certificate, certificate_chain, signature
+        FROM objects
+        WHERE object_type = $1
+          AND election_id = $2
+        ORDER BY created_at ASC, id ASC
+        "#,
+        cacvote::Payload::election_lifecycle_transition_object_type(),
+        election_id
+    )
+    .fetch_all(connection)
+    .await?;
+
+    let actions = objects
+        .iter()
+        .map(|object| match object.try_to_inner()? {
+            cacvote::Payload::ElectionLifecycleTransition(transition) => Ok(transition.action),
+            payload => bail!("unexpected payload for lifecycle transition: {payload:?}"),
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    match initial_state.replay_from(actions) {
+        Ok(state) => Ok(state),
+        Err(e) => bail!("invalid lifecycle for election {election_id}: {e}"),
+    }
+}
+
+/// Whether the election existed before lifecycle transitions were recorded,
+/// when elections were open for voting from the start.
+async fn is_legacy_open_election(
+    connection: &mut sqlx::PgConnection,
+    election_id: Uuid,
+) -> color_eyre::Result<bool> {
+    let record = sqlx::query!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM legacy_open_elections WHERE election_id = $1
+        ) AS "exists!"
+        "#,
+        election_id
+    )
+    .fetch_one(connection)
+    .await?;
+
+    Ok(record.exists)
+}
+
 pub async fn get_object_by_election_id_and_type(
     conn: &mut sqlx::PgConnection,
     election_id: Uuid,
@@ -304,7 +429,7 @@ pub async fn get_object_by_election_id_and_type(
     Ok(sqlx::query_as!(
         cacvote::SignedObject,
         r#"
-            SELECT id, election_id, payload, certificate, signature
+            SELECT id, election_id, payload, certificate, certificate_chain, signature
             FROM objects
             WHERE election_id = $1
               AND object_type = $2
@@ -316,6 +441,28 @@ pub async fn get_object_by_election_id_and_type(
     .await?)
 }
 
+/// Returns every object of `object_type` for the election, oldest first.
+pub async fn get_objects_by_election_id_and_type(
+    conn: &mut sqlx::PgConnection,
+    election_id: Uuid,
+    object_type: &str,
+) -> color_eyre::Result<Vec<SignedObject>> {
+    Ok(sqlx::query_as!(
+        cacvote::SignedObject,
+        r#"
+            SELECT id, election_id, payload, certificate, certificate_chain, signature
+            FROM objects
+            WHERE election_id = $1
+              AND object_type = $2
+            ORDER BY created_at ASC, id ASC
+            "#,
+        election_id,
+        object_type,
+    )
+    .fetch_all(conn)
+    .await?)
+}
+
 #[derive(Debug)]
 pub struct Machine {
     pub id: Uuid,
@@ -423,9 +570,10 @@ pub async fn get_scanned_mailing_label_codes(
 ) -> color_eyre::Result<Vec<ScannedMailingLabel>> {
     let records = sqlx::query!(
         r#"
-        SELECT original_payload
+        SELECT original_payload, created_at
         FROM scanned_mailing_label_codes
         WHERE election_id = $1
+        ORDER BY created_at
         "#,
         election_id,
     )
@@ -444,38 +592,12 @@ pub async fn get_scanned_mailing_label_codes(
                 original_payload,
                 signed_buffer,
                 ballot_verification_payload,
+                record.created_at,
             ))
         })
         .collect::<color_eyre::Result<Vec<_>>>()
 }
 
-#[derive(Debug, Serialize)]
-#[serde(tag = "type", rename_all = "camelCase")]
-pub enum SearchResult {
-    #[serde(rename_all = "camelCase")]
-    CastBallot {
-        #[serde(flatten)]
-        cast_ballot: cacvote::CastBallot,
-
-        #[serde(skip_serializing_if = "Option::is_none")]
-        election: Option<String>,
-
-        #[serde(with = "time::serde::iso8601")]
-        created_at: time::OffsetDateTime,
-    },
-    #[serde(rename_all = "camelCase")]
-    ScannedMailingLabel {
-        #[serde(flatten)]
-        ballot_verification: BallotVerificationPayload,
-
-        #[serde(skip_serializing_if = "Option::is_none")]
-        election: Option<String>,
-
-        #[serde(with = "time::serde::iso8601")]
-        created_at: time::OffsetDateTime,
-    },
-}
-
 pub async fn search(
     conn: &mut sqlx::PgConnection,
     common_access_card_id: &str,
@@ -493,6 +615,7 @@ pub async fn search(
             FROM objects AS e WHERE e.id = objects.election_id) AS election,
             payload,
             certificate,
+            certificate_chain,
             signature,
             created_at
         FROM objects
@@ -513,6 +636,7 @@ pub async fn search(
                 election_id: record.election_id,
                 payload: record.payload,
                 certificate: record.certificate,
+                certificate_chain: record.certificate_chain,
                 signature: record.signature,
             };
 
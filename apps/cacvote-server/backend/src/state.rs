@@ -16,7 +16,7 @@ pub(crate) struct AppState {
 
     /// Certificate authority certificate store, used to validate the client
     /// certificates containing a CAC's public key.
-    pub cac_root_ca_store: Arc<openssl::x509::store::X509Store>,
+    pub cac_root_ca_store: Arc<types_rs::cacvote::CacCaStore>,
 
     /// In-memory session manager.
     pub sessions: Arc<Mutex<SessionManager>>,
@@ -0,0 +1,913 @@
+//! Offline synchronization with JX terminals via signed transfer bundles.
+//!
+//! JX terminals without a reliable network connection export their unsynced
+//! objects to a [`SignedTransferBundle`] on removable media, which is imported
+//! here with [`import_bundle`]. The reverse direction is covered by
+//! [`export_bundle`], which collects the journal and objects for a
+//! jurisdiction so the JX can import them. Importing is idempotent: objects
+//! that already exist are skipped rather than duplicated.
+
+use std::collections::HashSet;
+
+use color_eyre::eyre::bail;
+use openssl::x509;
+use sqlx::Connection;
+use types_rs::cacvote::{
+    CacCaStore, JournalEntryAction, JurisdictionCode, RejectedObject, SignedTransferBundle,
+    TransferBundle, TransferReceipt,
+};
+
+use crate::{app, db};
+
+/// Imports the objects in a bundle exported by a JX terminal, applying the
+/// same verification as objects posted to `/api/objects`.
+#[tracing::instrument(skip_all)]
+pub async fn import_bundle(
+    connection: &mut sqlx::PgConnection,
+    signed_bundle: &SignedTransferBundle,
+    vx_root_ca_cert: &x509::X509,
+    cac_root_ca_store: &CacCaStore,
+) -> color_eyre::Result<TransferReceipt> {
+    if let Err(e) = signed_bundle.verify(vx_root_ca_cert) {
+        bail!("Transfer bundle verification failed: {e}");
+    }
+
+    let bundle = signed_bundle.try_to_inner()?;
+    let mut receipt = TransferReceipt::new(bundle.id);
+
+    tracing::info!(
+        "Importing {} objects from transfer bundle {} for jurisdiction {}",
+        bundle.objects.len(),
+        bundle.id,
+        bundle.jurisdiction_code
+    );
+
+    for object in bundle.objects {
+        if db::get_object_by_id(connection, object.id).await?.is_some() {
+            tracing::debug!("Object {} already exists, skipping", object.id);
+            receipt.skipped_object_ids.push(object.id);
+            continue;
+        }
+
+        if object.jurisdiction_code().as_ref() != Some(&bundle.jurisdiction_code) {
+            receipt.rejected_objects.push(RejectedObject {
+                id: object.id,
+                reason: format!(
+                    "Object is not for the bundle's jurisdiction {}",
+                    bundle.jurisdiction_code
+                ),
+            });
+            continue;
+        }
+
+        if let Err(reason) = app::verify_signed_object(&object, vx_root_ca_cert, cac_root_ca_store)
+        {
+            receipt.rejected_objects.push(RejectedObject {
+                id: object.id,
+                reason,
+            });
+            continue;
+        }
+
+        // checked and stored together so the election's lifecycle cannot
+        // change in between; rejected objects roll back when `txn` drops
+        let mut txn = connection.begin().await?;
+
+        if let Err(reason) = app::check_election_lifecycle(&mut txn, &object).await? {
+            receipt.rejected_objects.push(RejectedObject {
+                id: object.id,
+                reason,
+            });
+            continue;
+        }
+
+        if let Err(reason) = app::check_shuffled_ballots_reference(&mut txn, &object).await? {
+            receipt.rejected_objects.push(RejectedObject {
+                id: object.id,
+                reason,
+            });
+            continue;
+        }
+
+        match db::create_object(&mut txn, &object).await {
+            Ok(object_id) => {
+                txn.commit().await?;
+                receipt.imported_object_ids.push(object_id);
+            }
+            Err(e) => {
+                tracing::error!("Failed to import object {}: {e}", object.id);
+                receipt.rejected_objects.push(RejectedObject {
+                    id: object.id,
+                    reason: e.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(receipt)
+}
+
+/// Collects the journal entries and objects for a jurisdiction into a bundle
+/// for import on a JX terminal. The caller is responsible for signing it.
+#[tracing::instrument(skip(connection))]
+pub async fn export_bundle(
+    connection: &mut sqlx::PgConnection,
+    jurisdiction_code: JurisdictionCode,
+) -> color_eyre::Result<TransferBundle> {
+    let journal_entries =
+        db::get_journal_entries(connection, None, Some(jurisdiction_code.clone())).await?;
+
+    let deleted_object_ids: HashSet<_> = journal_entries
+        .iter()
+        .filter(|entry| entry.action == JournalEntryAction::Delete)
+        .map(|entry| entry.object_id)
+        .collect();
+
+    let mut objects = Vec::new();
+    for entry in &journal_entries {
+        if entry.action != JournalEntryAction::Create
+            || deleted_object_ids.contains(&entry.object_id)
+        {
+            continue;
+        }
+
+        match db::get_object_by_id(connection, entry.object_id).await? {
+            Some(object) => objects.push(object),
+            None => {
+                tracing::warn!(
+                    "Object {} referenced by journal entry {} not found",
+                    entry.object_id,
+                    entry.id
+                );
+            }
+        }
+    }
+
+    Ok(TransferBundle::new(
+        jurisdiction_code,
+        journal_entries,
+        objects,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use cacvote_server_client::{PrivateKeySigner, Signer};
+    use openssl::pkey::PKey;
+    use types_rs::cacvote::{
+        build_cac_ca_store, CacCaStore, CastBallot, DecryptedShuffledBallots, Election,
+        ElectionLifecycleAction, ElectionLifecycleState, ElectionLifecycleTransition,
+        MixPhaseVerification, Payload, RegistrationRequest, ShuffledEncryptedCastBallots,
+        ShuffledEncryptedCastBallotsVerification, SignedObject,
+    };
+    use types_rs::election::ElectionDefinition;
+    use uuid::Uuid;
+
+    use super::*;
+
+    const JURISDICTION_CODE: &str = "st.dev-jurisdiction";
+
+    fn load_cryptographic_info() -> color_eyre::Result<(x509::X509, CacCaStore, PrivateKeySigner)> {
+        let vx_root_ca_cert = x509::X509::from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-cert-authority-cert.pem"
+        ))?;
+        let cac_root_ca_store = build_cac_ca_store([vx_root_ca_cert.clone()], [])?;
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../../../libs/auth/certs/dev/vx-private-key.pem"
+        ))?;
+
+        Ok((
+            vx_root_ca_cert,
+            cac_root_ca_store,
+            PrivateKeySigner::new(private_key),
+        ))
+    }
+
+    fn sign_bundle(
+        bundle: &TransferBundle,
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedTransferBundle> {
+        let bundle = serde_json::to_vec(bundle)?;
+        let signature = signer.sign(&bundle)?;
+        Ok(SignedTransferBundle {
+            bundle,
+            certificate: certificate.to_pem()?,
+            signature,
+        })
+    }
+
+    fn sign_object(
+        payload: &Payload,
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedObject> {
+        let election_id = payload.election_id();
+        let payload = serde_json::to_vec(payload)?;
+        let signature = signer.sign(&payload)?;
+        Ok(SignedObject {
+            id: Uuid::new_v4(),
+            election_id,
+            payload,
+            certificate: certificate.to_pem()?,
+            certificate_chain: None,
+            signature,
+        })
+    }
+
+    fn registration_request(
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedObject> {
+        sign_object(
+            &Payload::RegistrationRequest(RegistrationRequest {
+                common_access_card_id: "1234567890".to_owned(),
+                given_name: "John".to_owned(),
+                family_name: "Doe".to_owned(),
+                address: None,
+                jurisdiction_code: JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+            }),
+            certificate,
+            signer,
+        )
+    }
+
+    fn election(
+        voting_ends_at: Option<time::OffsetDateTime>,
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedObject> {
+        let election_definition = ElectionDefinition::try_from(
+            &include_bytes!(
+                "../../../cacvote-jx-terminal/backend/tests/fixtures/electionFamousNames2021.json"
+            )[..],
+        )?;
+        sign_object(
+            &Payload::Election(Election {
+                jurisdiction_code: JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+                election_definition,
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![],
+                voting_starts_at: None,
+                voting_ends_at,
+                ballot_receipt_deadline: None,
+            }),
+            certificate,
+            signer,
+        )
+    }
+
+    fn shuffled_ballots(
+        election_object_id: Uuid,
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedObject> {
+        sign_object(
+            &Payload::ShuffledEncryptedCastBallots(ShuffledEncryptedCastBallots {
+                jurisdiction_code: JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+                election_object_id,
+                electionguard_shuffled_ballots: vec![],
+            }),
+            certificate,
+            signer,
+        )
+    }
+
+    fn mix_verification(
+        election_object_id: Uuid,
+        shuffled_encrypted_cast_ballots_object_id: Uuid,
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedObject> {
+        sign_object(
+            &Payload::ShuffledEncryptedCastBallotsVerification(
+                ShuffledEncryptedCastBallotsVerification {
+                    jurisdiction_code: JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+                    election_object_id,
+                    shuffled_encrypted_cast_ballots_object_id,
+                    cast_ballot_count: 0,
+                    phases: vec![MixPhaseVerification {
+                        mix_name: "mix1".to_owned(),
+                        ballot_count: 0,
+                        error: None,
+                    }],
+                },
+            ),
+            certificate,
+            signer,
+        )
+    }
+
+    fn decrypted_shuffled_ballots(
+        election_object_id: Uuid,
+        shuffled_encrypted_cast_ballots_object_id: Uuid,
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedObject> {
+        sign_object(
+            &Payload::DecryptedShuffledBallots(DecryptedShuffledBallots {
+                jurisdiction_code: JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+                election_object_id,
+                shuffled_encrypted_cast_ballots_object_id,
+                ballots: vec![],
+                electionguard_decrypted_ballots: vec![],
+            }),
+            certificate,
+            signer,
+        )
+    }
+
+    fn lifecycle_transition(
+        election_object_id: Uuid,
+        action: ElectionLifecycleAction,
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedObject> {
+        sign_object(
+            &Payload::ElectionLifecycleTransition(ElectionLifecycleTransition {
+                jurisdiction_code: JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+                election_object_id,
+                action,
+            }),
+            certificate,
+            signer,
+        )
+    }
+
+    fn cast_ballot(
+        election_object_id: Uuid,
+        certificate: &x509::X509,
+        signer: &PrivateKeySigner,
+    ) -> color_eyre::Result<SignedObject> {
+        sign_object(
+            &Payload::CastBallot(CastBallot {
+                common_access_card_id: "1234567890".to_owned(),
+                jurisdiction_code: JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+                registration_request_object_id: Uuid::new_v4(),
+                registration_object_id: Uuid::new_v4(),
+                election_object_id,
+                electionguard_encrypted_ballot: vec![],
+            }),
+            certificate,
+            signer,
+        )
+    }
+
+    async fn import_objects(
+        connection: &mut sqlx::PgConnection,
+        objects: Vec<SignedObject>,
+        (vx_root_ca_cert, cac_root_ca_store, signer): &(x509::X509, CacCaStore, PrivateKeySigner),
+    ) -> color_eyre::Result<TransferReceipt> {
+        let bundle = TransferBundle::new(
+            JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+            vec![],
+            objects,
+        );
+        let signed_bundle = sign_bundle(&bundle, vx_root_ca_cert, signer)?;
+        import_bundle(
+            connection,
+            &signed_bundle,
+            vx_root_ca_cert,
+            cac_root_ca_store,
+        )
+        .await
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle_is_idempotent(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let (vx_root_ca_cert, cac_root_ca_store, signer) = load_cryptographic_info()?;
+
+        let valid_object = registration_request(&vx_root_ca_cert, &signer)?;
+        let valid_object_id = valid_object.id;
+        let mut invalid_object = registration_request(&vx_root_ca_cert, &signer)?;
+        invalid_object.signature = vec![];
+        let invalid_object_id = invalid_object.id;
+
+        let bundle = TransferBundle::new(
+            JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+            vec![],
+            vec![valid_object, invalid_object],
+        );
+        let signed_bundle = sign_bundle(&bundle, &vx_root_ca_cert, &signer)?;
+
+        let receipt = import_bundle(
+            &mut connection,
+            &signed_bundle,
+            &vx_root_ca_cert,
+            &cac_root_ca_store,
+        )
+        .await?;
+        assert_eq!(receipt.bundle_id, bundle.id);
+        assert_eq!(receipt.imported_object_ids, vec![valid_object_id]);
+        assert_eq!(receipt.skipped_object_ids, vec![]);
+        assert_eq!(
+            receipt
+                .rejected_objects
+                .iter()
+                .map(|rejected| rejected.id)
+                .collect::<Vec<_>>(),
+            vec![invalid_object_id]
+        );
+
+        // importing again skips the object that was already imported
+        let receipt = import_bundle(
+            &mut connection,
+            &signed_bundle,
+            &vx_root_ca_cert,
+            &cac_root_ca_store,
+        )
+        .await?;
+        assert_eq!(receipt.imported_object_ids, vec![]);
+        assert_eq!(receipt.skipped_object_ids, vec![valid_object_id]);
+        assert_eq!(
+            db::get_journal_entries(&mut connection, None, None)
+                .await?
+                .len(),
+            1
+        );
+
+        // the object shows up in an export for its jurisdiction
+        let exported = export_bundle(
+            &mut connection,
+            JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+        )
+        .await?;
+        assert_eq!(exported.journal_entries.len(), 1);
+        assert_eq!(
+            exported
+                .objects
+                .iter()
+                .map(|object| object.id)
+                .collect::<Vec<_>>(),
+            vec![valid_object_id]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle_rejects_bad_signature(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let (vx_root_ca_cert, cac_root_ca_store, signer) = load_cryptographic_info()?;
+
+        let bundle = TransferBundle::new(
+            JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+            vec![],
+            vec![registration_request(&vx_root_ca_cert, &signer)?],
+        );
+        let mut signed_bundle = sign_bundle(&bundle, &vx_root_ca_cert, &signer)?;
+        signed_bundle.bundle.push(b' ');
+
+        assert!(import_bundle(
+            &mut connection,
+            &signed_bundle,
+            &vx_root_ca_cert,
+            &cac_root_ca_store,
+        )
+        .await
+        .is_err());
+        assert_eq!(
+            db::get_journal_entries(&mut connection, None, None).await?,
+            vec![]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle_rejects_other_jurisdiction(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let (vx_root_ca_cert, cac_root_ca_store, signer) = load_cryptographic_info()?;
+
+        let object = registration_request(&vx_root_ca_cert, &signer)?;
+        let object_id = object.id;
+        let other_jurisdiction_object = sign_object(
+            &Payload::RegistrationRequest(RegistrationRequest {
+                common_access_card_id: "0987654321".to_owned(),
+                given_name: "Jane".to_owned(),
+                family_name: "Doe".to_owned(),
+                address: None,
+                jurisdiction_code: JurisdictionCode::try_from("st.other-jurisdiction").unwrap(),
+            }),
+            &vx_root_ca_cert,
+            &signer,
+        )?;
+        let other_jurisdiction_object_id = other_jurisdiction_object.id;
+
+        let bundle = TransferBundle::new(
+            JurisdictionCode::try_from(JURISDICTION_CODE).unwrap(),
+            vec![],
+            vec![object, other_jurisdiction_object],
+        );
+        let signed_bundle = sign_bundle(&bundle, &vx_root_ca_cert, &signer)?;
+
+        let receipt = import_bundle(
+            &mut connection,
+            &signed_bundle,
+            &vx_root_ca_cert,
+            &cac_root_ca_store,
+        )
+        .await?;
+        assert_eq!(receipt.imported_object_ids, vec![object_id]);
+        assert_eq!(
+            receipt
+                .rejected_objects
+                .iter()
+                .map(|rejected| rejected.id)
+                .collect::<Vec<_>>(),
+            vec![other_jurisdiction_object_id]
+        );
+        assert!(
+            db::get_object_by_id(&mut connection, other_jurisdiction_object_id)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle_enforces_election_lifecycle(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let info = load_cryptographic_info()?;
+        let (vx_root_ca_cert, _, signer) = &info;
+        let election = election(None, vx_root_ca_cert, signer)?;
+        let election_object_id = election.id;
+        import_objects(&mut connection, vec![election], &info).await?;
+
+        // ballots and out-of-order transitions are rejected before voting opens
+        let early_ballot = cast_ballot(election_object_id, vx_root_ca_cert, signer)?;
+        let early_close = lifecycle_transition(
+            election_object_id,
+            ElectionLifecycleAction::CloseVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        let receipt =
+            import_objects(&mut connection, vec![early_ballot, early_close], &info).await?;
+        assert_eq!(receipt.imported_object_ids, vec![]);
+        assert_eq!(
+            receipt
+                .rejected_objects
+                .iter()
+                .map(|rejected| rejected.reason.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Ballots are not accepted while the election is created",
+                "cannot close voting while the election is created; it must be open",
+            ]
+        );
+
+        // once open, ballots are accepted
+        let open = lifecycle_transition(
+            election_object_id,
+            ElectionLifecycleAction::OpenVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        let ballot = cast_ballot(election_object_id, vx_root_ca_cert, signer)?;
+        let expected_ids = vec![open.id, ballot.id];
+        let receipt = import_objects(&mut connection, vec![open, ballot], &info).await?;
+        assert_eq!(receipt.imported_object_ids, expected_ids);
+        assert_eq!(
+            db::get_election_lifecycle_state(&mut connection, election_object_id).await?,
+            ElectionLifecycleState::Open
+        );
+
+        // after closing, ballots are rejected again
+        let close = lifecycle_transition(
+            election_object_id,
+            ElectionLifecycleAction::CloseVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        let close_id = close.id;
+        let late_ballot = cast_ballot(election_object_id, vx_root_ca_cert, signer)?;
+        let late_ballot_id = late_ballot.id;
+        let receipt = import_objects(&mut connection, vec![close, late_ballot], &info).await?;
+        assert_eq!(receipt.imported_object_ids, vec![close_id]);
+        assert_eq!(
+            receipt
+                .rejected_objects
+                .iter()
+                .map(|rejected| rejected.id)
+                .collect::<Vec<_>>(),
+            vec![late_ballot_id]
+        );
+        assert_eq!(
+            db::get_election_lifecycle_state(&mut connection, election_object_id).await?,
+            ElectionLifecycleState::Closed
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn test_legacy_election_stays_open(pool: sqlx::PgPool) -> color_eyre::Result<()> {
+        const LEGACY_OPEN_ELECTIONS_MIGRATION: i64 = 20241106120000;
+
+        // an election stored before lifecycle transitions were recorded
+        let mut migrator = sqlx::migrate!("db/migrations");
+        migrator
+            .migrations
+            .to_mut()
+            .retain(|migration| migration.version < LEGACY_OPEN_ELECTIONS_MIGRATION);
+        migrator.run(&pool).await?;
+
+        let mut connection = pool.acquire().await?;
+        let info = load_cryptographic_info()?;
+        let (vx_root_ca_cert, _, signer) = &info;
+        let legacy_election = election(None, vx_root_ca_cert, signer)?;
+        let election_object_id = legacy_election.id;
+        db::create_object(&mut connection, &legacy_election).await?;
+
+        sqlx::migrate!("db/migrations").run(&pool).await?;
+        assert_eq!(
+            db::get_election_lifecycle_state(&mut connection, election_object_id).await?,
+            ElectionLifecycleState::Open
+        );
+
+        // it keeps accepting ballots until voting is closed
+        let open = lifecycle_transition(
+            election_object_id,
+            ElectionLifecycleAction::OpenVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        let ballot = cast_ballot(election_object_id, vx_root_ca_cert, signer)?;
+        let close = lifecycle_transition(
+            election_object_id,
+            ElectionLifecycleAction::CloseVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        let expected_ids = vec![ballot.id, close.id];
+        let receipt = import_objects(&mut connection, vec![open, ballot, close], &info).await?;
+        assert_eq!(receipt.imported_object_ids, expected_ids);
+        assert_eq!(
+            receipt
+                .rejected_objects
+                .iter()
+                .map(|rejected| rejected.reason.as_str())
+                .collect::<Vec<_>>(),
+            vec!["cannot open voting while the election is open; it must be created"]
+        );
+        assert_eq!(
+            db::get_election_lifecycle_state(&mut connection, election_object_id).await?,
+            ElectionLifecycleState::Closed
+        );
+
+        // elections created since start out created
+        let new_election = election(None, vx_root_ca_cert, signer)?;
+        let new_election_object_id = new_election.id;
+        db::create_object(&mut connection, &new_election).await?;
+        assert_eq!(
+            db::get_election_lifecycle_state(&mut connection, new_election_object_id).await?,
+            ElectionLifecycleState::Created
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle_rejects_transition_for_other_jurisdiction(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let info = load_cryptographic_info()?;
+        let (vx_root_ca_cert, _, signer) = &info;
+
+        // an election in another jurisdiction, stored directly since bundles
+        // only carry objects for their own jurisdiction
+        let Payload::Election(mut other_election) =
+            election(None, vx_root_ca_cert, signer)?.try_to_inner()?
+        else {
+            unreachable!();
+        };
+        other_election.jurisdiction_code =
+            JurisdictionCode::try_from("st.other-jurisdiction").unwrap();
+        let other_election =
+            sign_object(&Payload::Election(other_election), vx_root_ca_cert, signer)?;
+        db::create_object(&mut connection, &other_election).await?;
+
+        let open = lifecycle_transition(
+            other_election.id,
+            ElectionLifecycleAction::OpenVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        let receipt = import_objects(&mut connection, vec![open], &info).await?;
+        assert_eq!(receipt.imported_object_ids, vec![]);
+        assert_eq!(
+            receipt
+                .rejected_objects
+                .iter()
+                .map(|rejected| rejected.reason.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Lifecycle transition is for jurisdiction st.dev-jurisdiction, but the election is in st.other-jurisdiction"
+            ]
+        );
+        assert_eq!(
+            db::get_election_lifecycle_state(&mut connection, other_election.id).await?,
+            ElectionLifecycleState::Created
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_election_lifecycle_check_locks_election(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let info = load_cryptographic_info()?;
+        let (vx_root_ca_cert, _, signer) = &info;
+        let election = election(None, vx_root_ca_cert, signer)?;
+        let election_object_id = election.id;
+        let open = lifecycle_transition(
+            election_object_id,
+            ElectionLifecycleAction::OpenVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        import_objects(&mut connection, vec![election, open], &info).await?;
+
+        // a transition being checked holds the election until it is stored...
+        let mut transition_txn = pool.begin().await?;
+        let close = lifecycle_transition(
+            election_object_id,
+            ElectionLifecycleAction::CloseVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        assert_eq!(
+            app::check_election_lifecycle(&mut transition_txn, &close).await?,
+            Ok(())
+        );
+
+        // ...so a ballot checked meanwhile waits rather than being accepted
+        // by an election that is about to close
+        let mut ballot_txn = pool.begin().await?;
+        sqlx::query("SET LOCAL lock_timeout = '100ms'")
+            .execute(&mut *ballot_txn)
+            .await?;
+        let ballot = cast_ballot(election_object_id, vx_root_ca_cert, signer)?;
+        assert!(app::check_election_lifecycle(&mut ballot_txn, &ballot)
+            .await
+            .is_err());
+        ballot_txn.rollback().await?;
+
+        db::create_object(&mut transition_txn, &close).await?;
+        transition_txn.commit().await?;
+
+        let mut ballot_txn = pool.begin().await?;
+        assert_eq!(
+            app::check_election_lifecycle(&mut ballot_txn, &ballot).await?,
+            Err("Ballots are not accepted while the election is closed".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle_enforces_voting_window(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let info = load_cryptographic_info()?;
+        let (vx_root_ca_cert, _, signer) = &info;
+
+        let election = election(
+            Some(time::OffsetDateTime::now_utc() - time::Duration::days(1)),
+            vx_root_ca_cert,
+            signer,
+        )?;
+        let election_object_id = election.id;
+        let open = lifecycle_transition(
+            election_object_id,
+            ElectionLifecycleAction::OpenVoting,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        import_objects(&mut connection, vec![election, open], &info).await?;
+
+        // the election is open, but its voting window has already ended
+        let ballot = cast_ballot(election_object_id, vx_root_ca_cert, signer)?;
+        let receipt = import_objects(&mut connection, vec![ballot], &info).await?;
+        assert_eq!(receipt.imported_object_ids, vec![]);
+        match receipt.rejected_objects.as_slice() {
+            [rejected] => assert!(
+                rejected
+                    .reason
+                    .starts_with("Ballots are not accepted: voting ended at"),
+                "unexpected reason: {}",
+                rejected.reason
+            ),
+            rejected => panic!("Expected one rejected object, got {rejected:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "db/migrations")]
+    async fn test_import_bundle_checks_shuffled_ballots_reference(
+        pool: sqlx::PgPool,
+    ) -> color_eyre::Result<()> {
+        let mut connection = pool.acquire().await?;
+        let info = load_cryptographic_info()?;
+        let (vx_root_ca_cert, _, signer) = &info;
+
+        let election_1 = election(None, vx_root_ca_cert, signer)?;
+        let election_1_id = election_1.id;
+        let election_2 = election(None, vx_root_ca_cert, signer)?;
+        let election_2_id = election_2.id;
+        let shuffled_ballots = shuffled_ballots(election_1_id, vx_root_ca_cert, signer)?;
+        let shuffled_ballots_id = shuffled_ballots.id;
+        import_objects(
+            &mut connection,
+            vec![election_1, election_2, shuffled_ballots],
+            &info,
+        )
+        .await?;
+
+        let unknown_shuffled_ballots_id = Uuid::new_v4();
+        let verification =
+            mix_verification(election_1_id, shuffled_ballots_id, vx_root_ca_cert, signer)?;
+        let verification_id = verification.id;
+        let decrypted = decrypted_shuffled_ballots(
+            election_1_id,
+            shuffled_ballots_id,
+            vx_root_ca_cert,
+            signer,
+        )?;
+        let decrypted_id = decrypted.id;
+        let receipt = import_objects(
+            &mut connection,
+            vec![
+                mix_verification(
+                    election_1_id,
+                    unknown_shuffled_ballots_id,
+                    vx_root_ca_cert,
+                    signer,
+                )?,
+                mix_verification(election_2_id, shuffled_ballots_id, vx_root_ca_cert, signer)?,
+                verification,
+                decrypted_shuffled_ballots(
+                    election_2_id,
+                    shuffled_ballots_id,
+                    vx_root_ca_cert,
+                    signer,
+                )?,
+                decrypted,
+            ],
+            &info,
+        )
+        .await?;
+        assert_eq!(
+            receipt.imported_object_ids,
+            vec![verification_id, decrypted_id]
+        );
+        assert_eq!(
+            receipt
+                .rejected_objects
+                .iter()
+                .map(|rejected| rejected.reason.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                format!(
+                    "Election {election_1_id} has no shuffled ballots with ID {unknown_shuffled_ballots_id}"
+                ),
+                format!(
+                    "Election {election_2_id} has no shuffled ballots with ID {shuffled_ballots_id}"
+                ),
+                format!(
+                    "Election {election_2_id} has no shuffled ballots with ID {shuffled_ballots_id}"
+                ),
+            ]
+        );
+
+        let verifications = db::get_objects_by_election_id_and_type(
+            &mut connection,
+            election_1_id,
+            Payload::shuffled_encrypted_cast_ballots_verification_object_type(),
+        )
+        .await?;
+        assert_eq!(
+            verifications
+                .iter()
+                .map(|object| object.id)
+                .collect::<Vec<_>>(),
+            vec![verification_id]
+        );
+
+        Ok(())
+    }
+}
@@ -22,20 +22,29 @@ impl AsyncCard {
 
     /// Create an [`AsyncCard`] from a [`pcsc::Card`].
     pub fn from_card(card: Card) -> Result<Self, pcsc::Error> {
+        let mut receive_buffer = [0; 1024];
+        Ok(Self::from_transmit(move |data| {
+            card.transmit(data, &mut receive_buffer)
+                .map(|response| response.to_vec())
+        }))
+    }
+
+    /// Create an [`AsyncCard`] that sends each APDU to `transmit` and responds
+    /// with whatever it returns. Useful for talking to a simulated card.
+    pub fn from_transmit<F>(mut transmit: F) -> Self
+    where
+        F: FnMut(&[u8]) -> Result<Vec<u8>, pcsc::Error> + Send + 'static,
+    {
         let (tx, mut rx) = mpsc::unbounded_channel::<CardOperation>();
 
         // Spawn a blocking Tokio task to handle the card operations
         let task_handle = tokio::task::spawn_blocking(move || {
-            let mut receive_buffer = [0; 1024];
             tracing::debug!("starting async card loop");
             while let Some(op) = rx.blocking_recv() {
                 tracing::debug!("received operation: {:?}", op);
                 match op {
                     CardOperation::Transmit(data, response) => {
-                        let result = card
-                            .transmit(&data, &mut receive_buffer)
-                            .map(|response| response.to_vec());
-                        let _ = response.send(result);
+                        let _ = response.send(transmit(&data));
                     }
                     CardOperation::Disconnect => {
                         tracing::debug!("received disconnect request");
@@ -46,10 +55,10 @@ impl AsyncCard {
             tracing::debug!("exiting async card loop");
         });
 
-        Ok(Self {
+        Self {
             sender: tx,
             task_handle: Some(task_handle),
-        })
+        }
     }
 
     /// Transmit data to the card and return the response.
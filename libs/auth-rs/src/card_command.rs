@@ -40,12 +40,15 @@ pub struct CardCommand {
 }
 
 impl CardCommand {
+    /// Splits the command into APDUs small enough to send, chaining them if
+    /// there is more than one. A command without data is still sent, as a
+    /// single APDU.
     #[must_use]
     pub fn to_command_apdus(&self) -> Vec<CommandApdu> {
         let mut command_apdus = vec![];
 
         let mut data = self.data.clone();
-        while !data.is_empty() {
+        loop {
             let chunk = data.split_off(usize::min(MAX_COMMAND_APDU_DATA_LENGTH, data.len()));
             let is_last = chunk.is_empty();
             let command_apdu = CommandApdu::new(
@@ -62,6 +65,9 @@ impl CardCommand {
             )
             .expect("data is less than MAX_COMMAND_APDU_DATA_LENGTH");
             command_apdus.push(command_apdu);
+            if is_last {
+                break;
+            }
             data = chunk;
         }
 
@@ -153,4 +159,42 @@ mod tests {
             ]
         );
     }
+    #[test]
+    fn test_get_num_incorrect_pin_attempts() {
+        let command = CardCommand::get_num_incorrect_pin_attempts();
+        let apdus = command.to_command_apdus();
+        assert_eq!(
+            apdus,
+            vec![CommandApdu::new(Cla::Standard, 0x20, 0x00, 0x80, Vec::new()).unwrap()]
+        );
+        assert_eq!(apdus[0].to_bytes(), vec![0x00, 0x20, 0x00, 0x80, 0x00]);
+    }
+
+    #[test]
+    fn test_chained() {
+        let data = (0..=u8::MAX).collect::<Vec<_>>();
+        let command = CardCommand::new(0xdb, 0x3f, 0xff, data.clone());
+        let apdus = command.to_command_apdus();
+        assert_eq!(
+            apdus,
+            vec![
+                CommandApdu::new(
+                    Cla::Chained,
+                    0xdb,
+                    0x3f,
+                    0xff,
+                    data[..MAX_COMMAND_APDU_DATA_LENGTH].to_vec()
+                )
+                .unwrap(),
+                CommandApdu::new(
+                    Cla::Standard,
+                    0xdb,
+                    0x3f,
+                    0xff,
+                    data[MAX_COMMAND_APDU_DATA_LENGTH..].to_vec()
+                )
+                .unwrap(),
+            ]
+        );
+    }
 }
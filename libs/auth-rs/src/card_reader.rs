@@ -10,6 +10,7 @@ pub(crate) const OPEN_FIPS_201_AID: [u8; 11] = [
     0xa0, 0x00, 0x00, 0x03, 0x08, 0x00, 0x00, 0x10, 0x00, 0x01, 0x00,
 ];
 
+#[derive(Clone, Copy)]
 pub struct CertObject {
     pub private_key_id: u8,
 }
@@ -152,7 +152,8 @@ impl VxCard {
         ))
     }
 
-    async fn get_num_incorrect_pin_attempts(&self) -> Result<u8, CardReaderError> {
+    /// Reads the number of incorrect PIN attempts since the last correct one.
+    pub async fn get_num_incorrect_pin_attempts(&self) -> Result<u8, CardReaderError> {
         let command = CardCommand::get_num_incorrect_pin_attempts();
         match self.transmit(command).await {
             Ok(_) => Ok(0),
@@ -0,0 +1,56 @@
+//! Public bulletin board listings served by the CACvote Server. Each entry
+//! links to the related resources so that anyone can walk the board without
+//! knowing the URL layout.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Election {
+    pub id: Uuid,
+    pub cast_ballots_url: String,
+    pub encrypted_tally_url: String,
+    pub decrypted_tally_url: String,
+    pub shuffled_ballots_url: String,
+    pub shuffled_ballots_verifications_url: String,
+    pub decrypted_shuffled_ballots_url: String,
+}
+
+impl Election {
+    #[must_use]
+    pub fn new(id: Uuid) -> Self {
+        Self {
+            id,
+            cast_ballots_url: format!("/api/elections/{id}/cast-ballots"),
+            encrypted_tally_url: format!("/api/elections/{id}/encrypted-tally"),
+            decrypted_tally_url: format!("/api/elections/{id}/decrypted-tally"),
+            shuffled_ballots_url: format!("/api/elections/{id}/shuffled-ballots"),
+            shuffled_ballots_verifications_url: format!(
+                "/api/elections/{id}/shuffled-ballots/verifications"
+            ),
+            decrypted_shuffled_ballots_url: format!(
+                "/api/elections/{id}/decrypted-shuffled-ballots"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastBallot {
+    pub id: Uuid,
+    pub election_id: Uuid,
+    pub url: String,
+}
+
+impl CastBallot {
+    #[must_use]
+    pub fn new(id: Uuid, election_id: Uuid) -> Self {
+        Self {
+            id,
+            election_id,
+            url: format!("/api/elections/{election_id}/cast-ballots/{id}"),
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use auth_rs::{
+    vx_card::{VxCard, CARD_VX_CERT},
+    CertObject,
+};
+use openssl::{hash::MessageDigest, sign::Verifier, x509::X509};
+use types_rs::cacvote::verify_cert_single_ca;
+use uuid::Uuid;
+
+use crate::{
+    result::{Error, Result},
+    signer::AsyncSigner,
+};
+
+/// An [`AsyncSigner`] that signs with the private key on a connected VxCard,
+/// verifying the card's PIN before each signature.
+///
+/// By default it signs with the card's VotingWorks-issued cert, which chains
+/// directly to the VX root CA just like a machine cert does.
+#[must_use]
+pub struct CardSigner {
+    card: Arc<VxCard>,
+    signing_cert: CertObject,
+    pin: String,
+}
+
+impl CardSigner {
+    pub fn new(card: Arc<VxCard>, pin: impl Into<String>) -> Self {
+        Self {
+            card,
+            signing_cert: CARD_VX_CERT,
+            pin: pin.into(),
+        }
+    }
+
+    /// Sign with the key for the given cert object rather than the default.
+    pub fn with_signing_cert(mut self, signing_cert: CertObject) -> Self {
+        self.signing_cert = signing_cert;
+        self
+    }
+
+    /// Signs the payload, returning the signature along with the card's
+    /// certificate for the signing key. An incorrect PIN fails with
+    /// [`CardReaderError::ApduResponse`][auth_rs::CardReaderError::ApduResponse]
+    /// and counts against the card's PIN attempts.
+    pub async fn sign_with_certificate(&self, payload: &[u8]) -> Result<(Vec<u8>, X509)> {
+        Ok(self
+            .card
+            .sign(self.signing_cert, payload, Some(&self.pin))
+            .await?)
+    }
+}
+
+impl Debug for CardSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CardSigner")
+            .field("signing_cert", &self.signing_cert)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl AsyncSigner for CardSigner {
+    async fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let (signature, _) = self.sign_with_certificate(payload).await?;
+        Ok(signature)
+    }
+}
+
+/// The statement an election official's card signs to authorize unwrapping
+/// the ElectionGuard private key for an election.
+pub fn unwrap_authorization_statement(election_id: Uuid) -> Vec<u8> {
+    format!("unwrap ElectionGuard private key for {election_id}").into_bytes()
+}
+
+/// Verifies that `signature` is over the unwrap authorization statement for
+/// `election_id` and was made with the key for `cert`, which must be issued by
+/// `vx_cert_authority_cert`. Since a card only signs given its PIN, this shows
+/// that the official holding a genuine card was present.
+pub fn verify_unwrap_authorization(
+    vx_cert_authority_cert: &X509,
+    election_id: Uuid,
+    signature: &[u8],
+    cert: &X509,
+) -> Result<()> {
+    verify_cert_single_ca(vx_cert_authority_cert, cert)
+        .map_err(|e| Error::Signature(format!("card certificate is not trusted: {e}")))?;
+
+    let signature_matches = cert
+        .public_key()
+        .and_then(|public_key| {
+            let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key)?;
+            verifier.update(&unwrap_authorization_statement(election_id))?;
+            verifier.verify(signature)
+        })
+        .map_err(|e| Error::Signature(format!("error verifying card signature: {e}")))?;
+
+    if !signature_matches {
+        return Err(Error::Signature(format!(
+            "card signature does not authorize unwrapping the private key for {election_id}"
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use auth_rs::{async_card::AsyncCard, tlv::Tlv, CardReaderError};
+    use openssl::{
+        ecdsa::EcdsaSig,
+        pkey::{PKey, Private},
+    };
+
+    use super::*;
+    use crate::Error;
+
+    const PIN: &str = "123456";
+
+    /// A simulated card holding one key, answering just enough of the
+    /// OpenFIPS201 applet's APDUs to sign with it.
+    struct MockCard {
+        cert: X509,
+        private_key: PKey<Private>,
+        num_incorrect_pin_attempts: u8,
+    }
+
+    impl MockCard {
+        fn transmit(&mut self, apdu: &[u8]) -> Vec<u8> {
+            let [_cla, ins, _p1, _p2, data @ ..] = apdu else {
+                return vec![0x67, 0x00];
+            };
+            let data = data.get(1..).unwrap_or_default();
+
+            match ins {
+                // SELECT
+                0xa4 => vec![0x90, 0x00],
+
+                // VERIFY, or check the PIN attempts without any data
+                0x20 if data.is_empty() => match self.num_incorrect_pin_attempts {
+                    0 => vec![0x90, 0x00],
+                    n => vec![0x63, 0xc0 | n],
+                },
+                0x20 => {
+                    let pin_length = data.iter().position(|&b| b == 0xff).unwrap_or(data.len());
+                    if &data[..pin_length] == PIN.as_bytes() {
+                        self.num_incorrect_pin_attempts = 0;
+                        vec![0x90, 0x00]
+                    } else {
+                        self.num_incorrect_pin_attempts += 1;
+                        vec![0x63, 0xc0 | self.num_incorrect_pin_attempts]
+                    }
+                }
+
+                // GET DATA, only for the signing cert
+                0xcb => {
+                    let cert_info = [
+                        tlv(0x70, self.cert.to_der().unwrap()),
+                        tlv(0x71, vec![0x00]),
+                        tlv(0xfe, vec![]),
+                    ]
+                    .concat();
+                    [tlv(0x53, cert_info), vec![0x90, 0x00]].concat()
+                }
+
+                // GENERAL AUTHENTICATE, signing the hash in the challenge
+                0x87 => {
+                    let template = Tlv::parse_partial(0x7c, data).unwrap().1;
+                    let challenge = Tlv::parse_partial(0x81, template.value()).unwrap().1;
+                    let signature =
+                        EcdsaSig::sign(challenge.value(), &self.private_key.ec_key().unwrap())
+                            .unwrap()
+                            .to_der()
+                            .unwrap();
+                    [tlv(0x7c, tlv(0x82, signature)), vec![0x90, 0x00]].concat()
+                }
+
+                _ => vec![0x6d, 0x00],
+            }
+        }
+    }
+
+    fn tlv(tag: u8, value: Vec<u8>) -> Vec<u8> {
+        Tlv::new(tag, value).into_bytes().unwrap()
+    }
+
+    fn load_cert() -> X509 {
+        X509::from_pem(include_bytes!(
+            "../../auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))
+        .unwrap()
+    }
+
+    fn load_vx_cert_authority_cert() -> X509 {
+        X509::from_pem(include_bytes!(
+            "../../auth/certs/dev/vx-cert-authority-cert.pem"
+        ))
+        .unwrap()
+    }
+
+    fn vx_card(async_card: AsyncCard) -> Arc<VxCard> {
+        Arc::new(VxCard::new(
+            load_vx_cert_authority_cert(),
+            load_cert(),
+            async_card,
+        ))
+    }
+
+    fn mock_vx_card() -> (Arc<VxCard>, Arc<Mutex<MockCard>>) {
+        let mock_card = Arc::new(Mutex::new(MockCard {
+            cert: load_cert(),
+            private_key: PKey::private_key_from_pem(include_bytes!(
+                "../../auth/certs/dev/vx-admin-private-key.pem"
+            ))
+            .unwrap(),
+            num_incorrect_pin_attempts: 0,
+        }));
+        let async_card = AsyncCard::from_transmit({
+            let mock_card = Arc::clone(&mock_card);
+            move |apdu| Ok(mock_card.lock().unwrap().transmit(apdu))
+        });
+        (vx_card(async_card), mock_card)
+    }
+
+    #[tokio::test]
+    async fn test_sign_with_correct_pin() {
+        let (card, _) = mock_vx_card();
+        let signer = CardSigner::new(card, PIN);
+
+        let (signature, certificate) = signer.sign_with_certificate(b"payload").await.unwrap();
+        assert_eq!(certificate.to_der().unwrap(), load_cert().to_der().unwrap());
+
+        let public_key = certificate.public_key().unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key).unwrap();
+        verifier.update(b"payload").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+
+        // the `AsyncSigner` impl produces the same kind of signature
+        let signature = AsyncSigner::sign(&signer, b"other payload").await.unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key).unwrap();
+        verifier.update(b"other payload").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sign_with_incorrect_pin() {
+        let (card, mock_card) = mock_vx_card();
+        let signer = CardSigner::new(Arc::clone(&card), "000000");
+
+        match signer.sign_with_certificate(b"payload").await {
+            Err(Error::Card(e)) => assert!(e.is_incorrect_pin_error(), "{e:?}"),
+            result => panic!("expected an incorrect PIN error, got {result:?}"),
+        }
+        assert_eq!(mock_card.lock().unwrap().num_incorrect_pin_attempts, 1);
+
+        // a correct PIN afterwards resets the count
+        CardSigner::new(Arc::clone(&card), PIN)
+            .sign_with_certificate(b"payload")
+            .await
+            .unwrap();
+        assert_eq!(mock_card.lock().unwrap().num_incorrect_pin_attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn test_sign_without_card() {
+        let card = vx_card(AsyncCard::from_transmit(|_| Err(pcsc::Error::NoSmartcard)));
+        let signer = CardSigner::new(card, PIN);
+
+        match signer.sign_with_certificate(b"payload").await {
+            Err(Error::Card(CardReaderError::AsyncCard(_))) => {}
+            result => panic!("expected a card error, got {result:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_unwrap_authorization() {
+        let (card, _) = mock_vx_card();
+        let election_id = Uuid::new_v4();
+        let (signature, certificate) = CardSigner::new(card, PIN)
+            .sign_with_certificate(&unwrap_authorization_statement(election_id))
+            .await
+            .unwrap();
+        let vx_cert_authority_cert = load_vx_cert_authority_cert();
+
+        verify_unwrap_authorization(
+            &vx_cert_authority_cert,
+            election_id,
+            &signature,
+            &certificate,
+        )
+        .unwrap();
+
+        // the signature only authorizes unwrapping the key it names
+        assert!(matches!(
+            verify_unwrap_authorization(
+                &vx_cert_authority_cert,
+                Uuid::new_v4(),
+                &signature,
+                &certificate,
+            ),
+            Err(Error::Signature(_))
+        ));
+
+        // and only counts if the card's cert was issued by the VotingWorks CA
+        let other_ca_cert =
+            X509::from_pem(include_bytes!("../../auth/certs/dev/vx-mark-cert.pem")).unwrap();
+        assert!(matches!(
+            verify_unwrap_authorization(&other_ca_cert, election_id, &signature, &certificate),
+            Err(Error::Signature(_))
+        ));
+    }
+}
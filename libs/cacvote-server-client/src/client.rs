@@ -1,16 +1,26 @@
 use std::fmt::Debug;
+use std::time::Duration;
 
 use base64_serde::base64_serde_type;
 use openssl::x509::X509;
-use serde::{Deserialize, Serialize};
-use types_rs::cacvote::{JournalEntry, JurisdictionCode, SignedObject};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use types_rs::cacvote::{
+    self, BallotVerificationPayload, JournalEntry, JurisdictionCode, ScannedMailingLabel,
+    SignedObject,
+};
 use uuid::Uuid;
 
+use crate::bulletin_board;
 use crate::result::{Error, Result};
-use crate::signer::AnySigner;
+use crate::retry::RetryPolicy;
+use crate::signer::AnyAsyncSigner;
 
 base64_serde_type!(Base64Standard, base64::engine::general_purpose::STANDARD);
 
+/// How long to wait for a response before giving up on a request, unless
+/// configured otherwise with [`Client::with_timeout`].
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// A client for the CACvote server.
 pub struct Client {
     base_url: reqwest::Url,
@@ -19,20 +29,61 @@ pub struct Client {
     signing_cert: X509,
 
     /// The signer used to sign payloads.
-    signer: AnySigner,
+    signer: AnyAsyncSigner,
 
     /// The bearer token for the current session.
     bearer_token: Option<String>,
+
+    /// How to retry requests that fail for transient reasons.
+    retry_policy: RetryPolicy,
+
+    /// How long to wait for each request to complete.
+    timeout: Duration,
 }
 
 impl Debug for Client {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Client")
             .field("base_url", &self.base_url)
+            .field("retry_policy", &self.retry_policy)
+            .field("timeout", &self.timeout)
             .finish()
     }
 }
 
+/// Whether a request may be sent again after a failure that might have
+/// happened after the server received it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Idempotency {
+    Idempotent,
+    NonIdempotent,
+}
+
+#[derive(Debug, Clone)]
+enum RequestBody {
+    Json(Vec<u8>),
+    Bytes(Vec<u8>),
+}
+
+impl RequestBody {
+    fn json(body: &impl Serialize) -> Result<Self> {
+        Ok(Self::Json(serde_json::to_vec(body)?))
+    }
+
+    const fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json(_) => "application/json",
+            Self::Bytes(_) => "application/octet-stream",
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            Self::Json(bytes) | Self::Bytes(bytes) => bytes,
+        }
+    }
+}
+
 impl Client {
     /// Create a new client with the given base URL.
     ///
@@ -45,17 +96,35 @@ impl Client {
     /// let base_url = "http://localhost:8000".parse().unwrap();
     /// let client = Client::new(base_url, signing_cert, signer);
     /// ```
-    pub const fn new(base_url: reqwest::Url, signing_cert: X509, signer: AnySigner) -> Self {
+    pub const fn new(base_url: reqwest::Url, signing_cert: X509, signer: AnyAsyncSigner) -> Self {
         Self {
             base_url,
             signing_cert,
             signer,
             bearer_token: None,
+            retry_policy: RetryPolicy::DEFAULT,
+            timeout: DEFAULT_TIMEOUT,
         }
     }
 
+    /// Use the given policy to retry requests that fail for transient
+    /// reasons. Defaults to [`RetryPolicy::DEFAULT`].
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Give up on each request attempt after the given duration. Defaults to
+    /// [`DEFAULT_TIMEOUT`].
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
     /// Create a new client to connect to the server running on localhost.
-    pub fn localhost(signing_cert: X509, signer: AnySigner) -> Self {
+    pub fn localhost(signing_cert: X509, signer: AnyAsyncSigner) -> Self {
         Self::new(
             "http://localhost:8000"
                 .parse()
@@ -67,7 +136,14 @@ impl Client {
 
     /// Check that the server is responding.
     pub async fn check_status(&self) -> Result<()> {
-        let response = self.get("/api/status").await?;
+        let response = self
+            .send(
+                reqwest::Method::GET,
+                "/api/status",
+                None,
+                Idempotency::Idempotent,
+            )
+            .await?;
         response.error_for_status()?;
         Ok(())
     }
@@ -86,13 +162,23 @@ impl Client {
         let signature = self
             .signer
             .sign(payload.as_bytes())
+            .await
             .map_err(|e| Error::Signature(format!("failed to sign payload: {e}")))?;
         let request = CreateSessionRequest {
             certificate,
             payload,
             signature,
         };
-        let response = self.post_json("/api/sessions", &request).await?;
+        // creating a session has no side effects other than the new session,
+        // so it is safe to retry
+        let response = self
+            .send(
+                reqwest::Method::POST,
+                "/api/sessions",
+                Some(RequestBody::json(&request)?),
+                Idempotency::Idempotent,
+            )
+            .await?;
         let response: CreateSessionResponse = response.error_for_status()?.json().await?;
         self.bearer_token = Some(response.bearer_token);
         Ok(())
@@ -100,27 +186,19 @@ impl Client {
 
     /// Create an object on the server.
     pub async fn create_object(&mut self, signed_object: SignedObject) -> Result<Uuid> {
-        loop {
-            self.authenticate_if_needed().await?;
-            let response = self.post_json("/api/objects", &signed_object).await?;
-
-            if let reqwest::StatusCode::UNAUTHORIZED = response.status() {
-                self.bearer_token = None;
-                continue;
-            }
-            let status_code = response.status();
-            let text = response.text().await?;
-
-            if !status_code.is_success() {
-                return Err(Error::Http {
-                    status_code,
-                    text,
-                    context: format!("failed to create object with ID {:?}", signed_object.id),
-                });
-            }
-
-            return Ok(Uuid::try_parse(&text)?);
+        let response = self.post_json("/api/objects", &signed_object).await?;
+        let status_code = response.status();
+        let text = response.text().await?;
+
+        if !status_code.is_success() {
+            return Err(Error::Http {
+                status_code,
+                text,
+                context: format!("failed to create object with ID {:?}", signed_object.id),
+            });
         }
+
+        Ok(Uuid::try_parse(&text)?)
     }
 
     /// Get an object by its ID.
@@ -139,27 +217,16 @@ impl Client {
     /// ```
     pub async fn get_object_by_id(&mut self, object_id: Uuid) -> Result<Option<SignedObject>> {
         let path = format!("/api/objects/{object_id}");
-        loop {
-            self.authenticate_if_needed().await?;
-            let response = self.get(&path).await?;
-
-            match response.status() {
-                reqwest::StatusCode::NOT_FOUND => return Ok(None),
-                reqwest::StatusCode::UNAUTHORIZED => {
-                    self.bearer_token = None;
-                    continue;
-                }
-                status_code if status_code.is_success() => {
-                    return Ok(Some(response.json().await?));
-                }
-                status_code => {
-                    return Err(Error::Http {
-                        status_code,
-                        text: response.text().await?,
-                        context: format!("failed to get object by ID {object_id:?}"),
-                    });
-                }
-            }
+        let response = self.get(&path).await?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(None),
+            status_code if status_code.is_success() => Ok(Some(response.json().await?)),
+            status_code => Err(Error::Http {
+                status_code,
+                text: response.text().await?,
+                context: format!("failed to get object by ID {object_id:?}"),
+            }),
         }
     }
 
@@ -193,44 +260,204 @@ impl Client {
         since: Option<&Uuid>,
         jurisdiction_code: Option<&JurisdictionCode>,
     ) -> Result<Vec<JournalEntry>> {
-        let mut params = Vec::new();
+        let query = GetJournalEntriesQuery {
+            since_journal_entry_id: since.copied(),
+            jurisdiction_code: jurisdiction_code.cloned(),
+        };
+        let url = self.url_with_query("/api/journal-entries", &query)?;
+        let response = self.get(url.as_str()).await?;
+        let response = check_success(
+            response,
+            format!(
+                "failed to get journal entries since={since:?} jurisdiction={jurisdiction_code:?}"
+            ),
+        )
+        .await?;
+        Ok(response.json().await?)
+    }
 
-        if let Some(since) = since {
-            params.push(("since", since.to_string()));
-        }
+    /// Register a machine with the server by its identifier and certificate.
+    /// Registering the same machine again with the same certificate returns
+    /// the existing ID.
+    pub async fn create_machine(
+        &self,
+        machine_identifier: &str,
+        certificate: &X509,
+    ) -> Result<Uuid> {
+        let certificate = certificate
+            .to_pem()
+            .map_err(|e| Error::Signature(format!("failed to serialize certificate: {e}")))?;
+        let request = CreateMachineRequest {
+            machine_identifier: machine_identifier.to_owned(),
+            certificate,
+        };
+        let response = self
+            .send(
+                reqwest::Method::POST,
+                "/api/machines",
+                Some(RequestBody::json(&request)?),
+                Idempotency::Idempotent,
+            )
+            .await?;
+        let response = check_success(
+            response,
+            format!("failed to create machine {machine_identifier:?}"),
+        )
+        .await?;
+        let response: CreateMachineResponse = response.json().await?;
+        Ok(response.id)
+    }
 
-        if let Some(jurisdiction_code) = jurisdiction_code {
-            params.push(("jurisdiction", jurisdiction_code.to_string()));
-        }
+    /// Submit the contents of a scanned mailing label, i.e. a TLV-encoded
+    /// [`SignedBuffer`][types_rs::cacvote::SignedBuffer] wrapping a
+    /// [`BallotVerificationPayload`].
+    pub async fn create_scanned_mailing_label(&self, code: &[u8]) -> Result<Uuid> {
+        let response = self
+            .send(
+                reqwest::Method::POST,
+                "/api/scanned-mailing-label",
+                Some(RequestBody::Bytes(code.to_vec())),
+                Idempotency::NonIdempotent,
+            )
+            .await?;
+        let response = check_success(
+            response,
+            "failed to create scanned mailing label".to_owned(),
+        )
+        .await?;
+        let response: CreateScannedMailingLabelResponse = response.json().await?;
+        Ok(response.id)
+    }
 
-        let url = reqwest::Url::parse_with_params(
-            self.base_url.join("/api/journal-entries")?.as_str(),
-            params,
-        )?;
+    /// List all elections on the bulletin board.
+    pub async fn list_elections(&self) -> Result<Vec<bulletin_board::Election>> {
+        self.get_public_json("/api/elections", "failed to list elections".to_owned())
+            .await
+    }
 
-        loop {
-            self.authenticate_if_needed().await?;
-            let response = self.get(url.as_str()).await?;
+    /// List the cast ballots for an election on the bulletin board.
+    pub async fn list_cast_ballots_by_election(
+        &self,
+        election_id: Uuid,
+    ) -> Result<Vec<bulletin_board::CastBallot>> {
+        self.get_public_json(
+            &format!("/api/elections/{election_id}/cast-ballots"),
+            format!("failed to list cast ballots for election {election_id:?}"),
+        )
+        .await
+    }
 
-            match response.status() {
-                reqwest::StatusCode::UNAUTHORIZED => {
-                    self.bearer_token = None;
-                    continue;
-                }
-                status_code if status_code.is_success() => {
-                    return Ok(response.json().await?);
-                }
-                status_code => {
-                    return Err(Error::Http {
-                        status_code,
-                        text: response.text().await?,
-                        context: format!(
-                            "failed to get journal entries since={since:?} jurisdiction={jurisdiction_code:?}"
-                        )
-                    });
-                }
-            }
-        }
+    /// List the mailing labels scanned for an election.
+    pub async fn list_scanned_mailing_labels_by_election(
+        &self,
+        election_id: Uuid,
+    ) -> Result<Vec<ScannedMailingLabel>> {
+        self.get_public_json(
+            &format!("/api/elections/{election_id}/scanned-mailing-labels"),
+            format!("failed to list scanned mailing labels for election {election_id:?}"),
+        )
+        .await
+    }
+
+    /// Get a cast ballot by its ID, if it belongs to the given election.
+    pub async fn get_cast_ballot_by_id(
+        &self,
+        election_id: Uuid,
+        cast_ballot_id: Uuid,
+    ) -> Result<Option<SignedObject>> {
+        self.get_public_optional_json(
+            &format!("/api/elections/{election_id}/cast-ballots/{cast_ballot_id}"),
+            format!("failed to get cast ballot {cast_ballot_id:?} for election {election_id:?}"),
+        )
+        .await
+    }
+
+    /// Get the encrypted tally for an election, if there is one yet.
+    pub async fn get_encrypted_tally_by_election(
+        &self,
+        election_id: Uuid,
+    ) -> Result<Option<SignedObject>> {
+        self.get_public_optional_json(
+            &format!("/api/elections/{election_id}/encrypted-tally"),
+            format!("failed to get encrypted tally for election {election_id:?}"),
+        )
+        .await
+    }
+
+    /// Get the decrypted tally for an election, if there is one yet.
+    pub async fn get_decrypted_tally_by_election(
+        &self,
+        election_id: Uuid,
+    ) -> Result<Option<SignedObject>> {
+        self.get_public_optional_json(
+            &format!("/api/elections/{election_id}/decrypted-tally"),
+            format!("failed to get decrypted tally for election {election_id:?}"),
+        )
+        .await
+    }
+
+    /// Get the shuffled encrypted cast ballots for an election, if they have
+    /// been mixed yet.
+    pub async fn get_shuffled_ballots_by_election(
+        &self,
+        election_id: Uuid,
+    ) -> Result<Option<SignedObject>> {
+        self.get_public_optional_json(
+            &format!("/api/elections/{election_id}/shuffled-ballots"),
+            format!("failed to get shuffled ballots for election {election_id:?}"),
+        )
+        .await
+    }
+
+    /// List the recorded checks of the proofs of shuffle of an election's
+    /// shuffled ballots, oldest first.
+    pub async fn list_shuffled_ballots_verifications_by_election(
+        &self,
+        election_id: Uuid,
+    ) -> Result<Vec<SignedObject>> {
+        self.get_public_json(
+            &format!("/api/elections/{election_id}/shuffled-ballots/verifications"),
+            format!("failed to list shuffled ballots verifications for election {election_id:?}"),
+        )
+        .await
+    }
+
+    /// Get the decrypted mixed ballots for an election, if they have been
+    /// decrypted yet.
+    pub async fn get_decrypted_shuffled_ballots_by_election(
+        &self,
+        election_id: Uuid,
+    ) -> Result<Option<SignedObject>> {
+        self.get_public_optional_json(
+            &format!("/api/elections/{election_id}/decrypted-shuffled-ballots"),
+            format!("failed to get decrypted shuffled ballots for election {election_id:?}"),
+        )
+        .await
+    }
+
+    /// Search for cast ballots and scanned mailing labels by CAC ID.
+    pub async fn search(&self, common_access_card_id: &str) -> Result<Vec<SearchResult>> {
+        let query = SearchQuery {
+            common_access_card_id: common_access_card_id.to_owned(),
+        };
+        let url = self.url_with_query("/api/search", &query)?;
+        // searching is read-only, so it is safe to retry despite being a POST
+        let response = self
+            .send(
+                reqwest::Method::POST,
+                url.as_str(),
+                None,
+                Idempotency::Idempotent,
+            )
+            .await?;
+        let response = check_success(response, "failed to search".to_owned()).await?;
+        Ok(response.json().await?)
+    }
+
+    fn url_with_query(&self, path: &str, query: &impl Serialize) -> Result<reqwest::Url> {
+        let mut url = self.normalize_url(path)?;
+        url.set_query(Some(&serde_urlencoded::to_string(query)?));
+        Ok(url)
     }
 
     fn normalize_url(&self, path: &str) -> Result<reqwest::Url> {
@@ -248,25 +475,140 @@ impl Client {
         Ok(())
     }
 
-    async fn get(&self, path: &str) -> Result<reqwest::Response> {
-        let request = self.make_request(reqwest::Method::GET, path)?;
-        Ok(request.send().await?)
+    async fn get(&mut self, path: &str) -> Result<reqwest::Response> {
+        self.send_authenticated(reqwest::Method::GET, path, None, Idempotency::Idempotent)
+            .await
+    }
+
+    async fn post_json(&mut self, path: &str, body: &impl Serialize) -> Result<reqwest::Response> {
+        self.send_authenticated(
+            reqwest::Method::POST,
+            path,
+            Some(RequestBody::json(body)?),
+            Idempotency::NonIdempotent,
+        )
+        .await
+    }
+
+    /// Gets a JSON resource that does not require a session.
+    async fn get_public_json<T: DeserializeOwned>(&self, path: &str, context: String) -> Result<T> {
+        let response = self
+            .send(reqwest::Method::GET, path, None, Idempotency::Idempotent)
+            .await?;
+        Ok(check_success(response, context).await?.json().await?)
     }
 
-    async fn post_json(&self, path: &str, body: &impl Serialize) -> Result<reqwest::Response> {
-        let request = self.make_request(reqwest::Method::POST, path)?;
-        Ok(request.json(body).send().await?)
+    /// Gets a JSON resource that does not require a session, returning `None`
+    /// if it does not exist.
+    async fn get_public_optional_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        context: String,
+    ) -> Result<Option<T>> {
+        let response = self
+            .send(reqwest::Method::GET, path, None, Idempotency::Idempotent)
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(check_success(response, context).await?.json().await?))
     }
 
-    fn make_request(&self, method: reqwest::Method, path: &str) -> Result<reqwest::RequestBuilder> {
-        let mut request = reqwest::Client::new().request(method, self.normalize_url(path)?);
+    /// Sends a request that requires a session, authenticating first if
+    /// needed. If the server rejects the session, e.g. because it expired, the
+    /// client re-authenticates and tries once more.
+    async fn send_authenticated(
+        &mut self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<RequestBody>,
+        idempotency: Idempotency,
+    ) -> Result<reqwest::Response> {
+        self.authenticate_if_needed().await?;
+        let response = self
+            .send(method.clone(), path, body.clone(), idempotency)
+            .await?;
+
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        self.bearer_token = None;
+        self.authenticate().await?;
+        self.send(method, path, body, idempotency).await
+    }
+
+    /// Sends a request, retrying transient failures according to the
+    /// configured [`RetryPolicy`].
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<RequestBody>,
+        idempotency: Idempotency,
+    ) -> Result<reqwest::Response> {
+        let url = self.normalize_url(path)?;
+        let mut retry = 0;
+
+        loop {
+            let mut request = self.make_request(method.clone(), url.clone());
+            if let Some(body) = &body {
+                request = request
+                    .header(reqwest::header::CONTENT_TYPE, body.content_type())
+                    .body(body.clone().into_bytes());
+            }
+
+            let can_retry = retry < self.retry_policy.max_retries;
+            match request.send().await {
+                Ok(response)
+                    if can_retry
+                        && idempotency == Idempotency::Idempotent
+                        && response.status().is_server_error() => {}
+                Ok(response) => return Ok(response),
+                Err(e) if can_retry && is_transient(&e, idempotency) => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            tokio::time::sleep(self.retry_policy.backoff(retry)).await;
+            retry += 1;
+        }
+    }
+
+    fn make_request(&self, method: reqwest::Method, url: reqwest::Url) -> reqwest::RequestBuilder {
+        let mut request = reqwest::Client::new()
+            .request(method, url)
+            .timeout(self.timeout);
 
         if let Some(ref bearer_token) = self.bearer_token {
             request = request.bearer_auth(bearer_token);
         }
 
-        Ok(request)
+        request
+    }
+}
+
+/// Converts an unsuccessful response into an [`Error::Http`].
+async fn check_success(response: reqwest::Response, context: String) -> Result<reqwest::Response> {
+    let status_code = response.status();
+    if status_code.is_success() {
+        return Ok(response);
+    }
+
+    Err(Error::Http {
+        status_code,
+        text: response.text().await?,
+        context,
+    })
+}
+
+/// Determines whether a failed request may succeed if sent again. Requests
+/// that may have reached the server are only retried if they are idempotent.
+fn is_transient(error: &reqwest::Error, idempotency: Idempotency) -> bool {
+    if error.is_connect() {
+        return true;
     }
+
+    idempotency == Idempotency::Idempotent && (error.is_timeout() || error.is_request())
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -299,3 +641,229 @@ pub struct CreateSessionRequestPayload {
 pub struct CreateSessionResponse {
     pub bearer_token: String,
 }
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GetJournalEntriesQuery {
+    #[serde(rename = "since", skip_serializing_if = "Option::is_none")]
+    pub since_journal_entry_id: Option<Uuid>,
+
+    #[serde(rename = "jurisdiction", skip_serializing_if = "Option::is_none")]
+    pub jurisdiction_code: Option<JurisdictionCode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMachineRequest {
+    pub machine_identifier: String,
+
+    /// A PEM-encoded X.509 certificate for the machine, signed by the CA given
+    /// in the cacvote-server configuration.
+    #[serde(with = "Base64Standard")]
+    pub certificate: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMachineResponse {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateScannedMailingLabelResponse {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub common_access_card_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SearchResult {
+    #[serde(rename_all = "camelCase")]
+    CastBallot {
+        #[serde(flatten)]
+        cast_ballot: cacvote::CastBallot,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        election: Option<String>,
+
+        #[serde(with = "time::serde::iso8601")]
+        created_at: time::OffsetDateTime,
+    },
+    #[serde(rename_all = "camelCase")]
+    ScannedMailingLabel {
+        #[serde(flatten)]
+        ballot_verification: BallotVerificationPayload,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        election: Option<String>,
+
+        #[serde(with = "time::serde::iso8601")]
+        created_at: time::OffsetDateTime,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::{get, post},
+        Json, Router,
+    };
+    use openssl::pkey::PKey;
+    use serde_json::json;
+
+    use super::*;
+    use crate::PrivateKeySigner;
+
+    const FAST_RETRY_POLICY: RetryPolicy = RetryPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(1),
+    };
+
+    #[derive(Clone, Default)]
+    struct Counters {
+        sessions: Arc<AtomicUsize>,
+        requests: Arc<AtomicUsize>,
+    }
+
+    async fn serve(router: Router<Counters>) -> (Client, Counters) {
+        let counters = Counters::default();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = router
+            .route(
+                "/api/sessions",
+                post(|State(counters): State<Counters>| async move {
+                    let session = counters.sessions.fetch_add(1, Ordering::SeqCst) + 1;
+                    Json(json!({ "bearerToken": format!("token-{session}") }))
+                }),
+            )
+            .with_state(counters.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let signing_cert = X509::from_pem(include_bytes!(
+            "../../auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))
+        .unwrap();
+        let signer = PrivateKeySigner::new(
+            PKey::private_key_from_pem(include_bytes!(
+                "../../auth/certs/dev/vx-admin-private-key.pem"
+            ))
+            .unwrap(),
+        );
+        let client = Client::new(
+            format!("http://{addr}").parse().unwrap(),
+            signing_cert,
+            Box::new(signer),
+        )
+        .with_retry_policy(FAST_RETRY_POLICY);
+
+        (client, counters)
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let (client, counters) = serve(Router::new().route(
+            "/api/status",
+            get(|State(counters): State<Counters>| async move {
+                match counters.requests.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => StatusCode::SERVICE_UNAVAILABLE,
+                    _ => StatusCode::OK,
+                }
+            }),
+        ))
+        .await;
+
+        client.check_status().await.unwrap();
+        assert_eq!(counters.requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let (client, counters) = serve(Router::new().route(
+            "/api/status",
+            get(|State(counters): State<Counters>| async move {
+                counters.requests.fetch_add(1, Ordering::SeqCst);
+                StatusCode::SERVICE_UNAVAILABLE
+            }),
+        ))
+        .await;
+
+        client.check_status().await.unwrap_err();
+        assert_eq!(
+            counters.requests.load(Ordering::SeqCst),
+            FAST_RETRY_POLICY.max_retries as usize + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_non_idempotent_requests() {
+        let (mut client, counters) = serve(Router::new().route(
+            "/api/objects",
+            post(|State(counters): State<Counters>| async move {
+                counters.requests.fetch_add(1, Ordering::SeqCst);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }),
+        ))
+        .await;
+
+        let result = client
+            .create_object(SignedObject {
+                id: Uuid::new_v4(),
+                election_id: None,
+                payload: vec![],
+                certificate: vec![],
+                certificate_chain: None,
+                signature: vec![],
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Http {
+                status_code: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+                ..
+            })
+        ));
+        assert_eq!(counters.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reauthenticates_on_unauthorized() {
+        let (mut client, counters) = serve(Router::new().route(
+            "/api/journal-entries",
+            get(
+                |State(counters): State<Counters>, headers: HeaderMap| async move {
+                    counters.requests.fetch_add(1, Ordering::SeqCst);
+                    // only the second session is accepted, as if the first expired
+                    match headers.get("authorization") {
+                        Some(value) if value == "Bearer token-2" => {
+                            (StatusCode::OK, Json(json!([])))
+                        }
+                        _ => (StatusCode::UNAUTHORIZED, Json(json!({}))),
+                    }
+                },
+            ),
+        ))
+        .await;
+
+        assert_eq!(
+            client.get_journal_entries(None, None).await.unwrap(),
+            vec![]
+        );
+        assert_eq!(counters.sessions.load(Ordering::SeqCst), 2);
+        assert_eq!(counters.requests.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,9 +1,27 @@
+pub mod bulletin_board;
+#[cfg(feature = "vx-card")]
+mod card_signer;
 mod client;
+pub mod pkcs11;
 mod result;
+mod retry;
+pub mod sealed;
 pub mod signer;
+#[cfg(feature = "tpm")]
+pub mod tpm;
 
 pub use client::{
-    Client, CreateSessionRequest, CreateSessionRequestPayload, CreateSessionResponse,
+    Client, CreateMachineRequest, CreateMachineResponse, CreateScannedMailingLabelResponse,
+    CreateSessionRequest, CreateSessionRequestPayload, CreateSessionResponse,
+    GetJournalEntriesQuery, SearchQuery, SearchResult, DEFAULT_TIMEOUT,
 };
 pub use result::{Error, Result};
-pub use signer::{AnySigner, PrivateKeySigner, Signer, TpmSigner};
+pub use retry::RetryPolicy;
+#[cfg(feature = "tpm")]
+pub use signer::TpmSigner;
+pub use signer::{
+    AnyAsyncSigner, AnySigner, AsyncSigner, BlockingSigner, Pkcs11Signer, PrivateKeySigner, Signer,
+};
+
+#[cfg(feature = "vx-card")]
+pub use card_signer::{unwrap_authorization_statement, verify_unwrap_authorization, CardSigner};
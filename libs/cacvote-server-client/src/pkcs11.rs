@@ -0,0 +1,297 @@
+//! A minimal PKCS#11 client, covering just what [`Pkcs11Signer`] needs:
+//! logging in to a token, finding a private key by label, and signing with
+//! it.
+//!
+//! The PKCS#11 module, e.g. SoftHSM's `libsofthsm2.so` or a vendor HSM
+//! library, is loaded at runtime, so nothing HSM-specific is linked in.
+//!
+//! [`Pkcs11Signer`]: crate::Pkcs11Signer
+
+use std::{
+    fmt::{self, Debug},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use cryptoki::{
+    context::{CInitializeArgs, Pkcs11},
+    error::RvError,
+    mechanism::Mechanism,
+    object::{Attribute, AttributeType, KeyType as Pkcs11KeyType, ObjectClass, ObjectHandle},
+    session::UserType,
+    slot::Slot,
+    types::AuthPin,
+};
+use openssl::{bn::BigNum, ecdsa::EcdsaSig};
+
+/// The environment variable holding the user PIN for the token. The PIN is
+/// deliberately not part of [`Pkcs11Description`] so that it does not end
+/// up in configuration files or logs.
+pub const PIN_ENV_VAR: &str = "PKCS11_PIN";
+
+/// DER encoding of the `prime256v1` OID, as found in `CKA_EC_PARAMS`.
+const P256_EC_PARAMS: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("PKCS#11 module {path}: {source}")]
+    Module {
+        path: PathBuf,
+        source: cryptoki::error::Error,
+    },
+
+    #[error("{0}")]
+    Pkcs11(#[from] cryptoki::error::Error),
+
+    #[error("no private key labeled {0:?}")]
+    KeyNotFound(String),
+
+    #[error("unsupported PKCS#11 key: {0}")]
+    UnsupportedKey(String),
+
+    #[error("invalid PKCS#11 signer description {0:?}: {1}")]
+    InvalidDescription(String, &'static str),
+
+    #[error("openssl error: {0}")]
+    OpenSsl(#[from] openssl::error::ErrorStack),
+}
+
+/// Where to find a PKCS#11 key, parsed from e.g.
+/// `pkcs11:module=/usr/lib/softhsm/libsofthsm2.so;slot=0;label=cacvote`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pkcs11Description {
+    /// Path of the PKCS#11 module to load.
+    pub module: PathBuf,
+
+    /// ID of the slot holding the token with the key.
+    pub slot: u64,
+
+    /// `CKA_LABEL` of the private key.
+    pub label: String,
+}
+
+impl FromStr for Pkcs11Description {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason| Error::InvalidDescription(s.to_owned(), reason);
+        let attributes = s
+            .strip_prefix("pkcs11:")
+            .ok_or_else(|| invalid("must start with \"pkcs11:\""))?;
+
+        let mut module = None;
+        let mut slot = None;
+        let mut label = None;
+        for attribute in attributes.split(';').filter(|a| !a.is_empty()) {
+            match attribute.split_once('=') {
+                Some(("module", value)) => module = Some(PathBuf::from(value)),
+                Some(("slot", value)) => {
+                    slot = Some(
+                        value
+                            .parse()
+                            .map_err(|_| invalid("slot must be a number"))?,
+                    );
+                }
+                Some(("label", value)) => label = Some(value.to_owned()),
+                _ => return Err(invalid("expected module=, slot=, or label=")),
+            }
+        }
+
+        Ok(Self {
+            module: module.ok_or_else(|| invalid("missing module="))?,
+            slot: slot.ok_or_else(|| invalid("missing slot="))?,
+            label: label.ok_or_else(|| invalid("missing label="))?,
+        })
+    }
+}
+
+/// The kinds of key we can sign with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    Rsa,
+    EcdsaP256,
+}
+
+/// A logged-in session on a token with a private key to sign with. Keeps
+/// the module loaded for as long as it is open.
+pub struct Session {
+    session: cryptoki::session::Session,
+    key: ObjectHandle,
+    key_type: KeyType,
+}
+
+impl Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("key_type", &self.key_type)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Session {
+    /// Loads the module, opens a session on the slot, logs in with `pin` if
+    /// given, and finds the private key.
+    pub fn open(description: &Pkcs11Description, pin: Option<&str>) -> Result<Self> {
+        let module_error = |source| Error::Module {
+            path: description.module.clone(),
+            source,
+        };
+        let pkcs11 = Pkcs11::new(&description.module).map_err(module_error)?;
+        match pkcs11.initialize(CInitializeArgs::OsThreads) {
+            // someone else in this process already initialized the module
+            Ok(())
+            | Err(cryptoki::error::Error::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) => {}
+            Err(e) => return Err(module_error(e)),
+        }
+
+        let session = pkcs11.open_ro_session(Slot::try_from(description.slot)?)?;
+        if let Some(pin) = pin {
+            match session.login(UserType::User, Some(&AuthPin::new(pin.to_owned()))) {
+                Ok(()) | Err(cryptoki::error::Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let key = find_private_key(&session, &description.label)?;
+        let key_type = read_key_type(&session, key)?;
+        Ok(Self {
+            session,
+            key,
+            key_type,
+        })
+    }
+
+    pub const fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    /// Signs `payload` with SHA-256, returning the signature in the same
+    /// format as OpenSSL: DER for ECDSA and raw PKCS#1 v1.5 for RSA.
+    pub fn sign(&mut self, payload: &[u8]) -> Result<Vec<u8>> {
+        match self.key_type {
+            KeyType::EcdsaP256 => {
+                // plain `CKM_ECDSA` over our own digest is the most widely
+                // supported ECDSA mechanism
+                let digest = openssl::sha::sha256(payload);
+                let signature = self.session.sign(&Mechanism::Ecdsa, self.key, &digest)?;
+                ecdsa_signature_to_der(&signature)
+            }
+            KeyType::Rsa => Ok(self
+                .session
+                .sign(&Mechanism::Sha256RsaPkcs, self.key, payload)?),
+        }
+    }
+}
+
+fn find_private_key(session: &cryptoki::session::Session, label: &str) -> Result<ObjectHandle> {
+    let keys = session.find_objects(&[
+        Attribute::Class(ObjectClass::PRIVATE_KEY),
+        Attribute::Label(label.as_bytes().to_vec()),
+    ])?;
+
+    match keys.as_slice() {
+        [] => Err(Error::KeyNotFound(label.to_owned())),
+        [key] => Ok(*key),
+        _ => Err(Error::UnsupportedKey(format!(
+            "more than one private key is labeled {label:?}"
+        ))),
+    }
+}
+
+fn read_key_type(session: &cryptoki::session::Session, key: ObjectHandle) -> Result<KeyType> {
+    let attributes =
+        session.get_attributes(key, &[AttributeType::KeyType, AttributeType::EcParams])?;
+    let key_type = attributes.iter().find_map(|attribute| match attribute {
+        Attribute::KeyType(key_type) => Some(*key_type),
+        _ => None,
+    });
+    let ec_params = attributes.iter().find_map(|attribute| match attribute {
+        Attribute::EcParams(ec_params) => Some(ec_params.as_slice()),
+        _ => None,
+    });
+
+    match key_type {
+        Some(Pkcs11KeyType::RSA) => Ok(KeyType::Rsa),
+        Some(Pkcs11KeyType::EC) if ec_params == Some(P256_EC_PARAMS) => Ok(KeyType::EcdsaP256),
+        Some(Pkcs11KeyType::EC) => Err(Error::UnsupportedKey(
+            "only P-256 EC keys are supported".to_owned(),
+        )),
+        Some(key_type) => Err(Error::UnsupportedKey(format!(
+            "unsupported key type {key_type}"
+        ))),
+        None => Err(Error::UnsupportedKey("missing CKA_KEY_TYPE".to_owned())),
+    }
+}
+
+/// Converts a PKCS#11 ECDSA signature, the big-endian `r` and `s` of equal
+/// length concatenated, to DER.
+fn ecdsa_signature_to_der(signature: &[u8]) -> Result<Vec<u8>> {
+    let (r, s) = signature.split_at(signature.len() / 2);
+    if r.is_empty() || r.len() != s.len() {
+        return Err(Error::UnsupportedKey(format!(
+            "unexpected ECDSA signature length {}",
+            signature.len()
+        )));
+    }
+    let signature =
+        EcdsaSig::from_private_components(BigNum::from_slice(r)?, BigNum::from_slice(s)?)?;
+    Ok(signature.to_der()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::PKey,
+        sign::Verifier,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_parse_description() {
+        assert_eq!(
+            "pkcs11:module=/usr/lib/softhsm/libsofthsm2.so;slot=1234;label=cacvote"
+                .parse::<Pkcs11Description>()
+                .unwrap(),
+            Pkcs11Description {
+                module: PathBuf::from("/usr/lib/softhsm/libsofthsm2.so"),
+                slot: 1234,
+                label: "cacvote".to_owned(),
+            }
+        );
+        assert!("pkcs11:module=/lib.so;slot=1"
+            .parse::<Pkcs11Description>()
+            .is_err());
+        assert!("pkcs11:module=/lib.so;slot=one;label=cacvote"
+            .parse::<Pkcs11Description>()
+            .is_err());
+        assert!("pkcs11:module=/lib.so;slot=1;label=cacvote;pin=1234"
+            .parse::<Pkcs11Description>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_ecdsa_signature_to_der() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = EcKey::generate(&group).unwrap();
+        let digest = openssl::sha::sha256(b"payload");
+        let signature = EcdsaSig::sign(&digest, &key).unwrap();
+
+        // how a PKCS#11 module returns it
+        let mut raw = signature.r().to_vec_padded(32).unwrap();
+        raw.extend(signature.s().to_vec_padded(32).unwrap());
+
+        let der = ecdsa_signature_to_der(&raw).unwrap();
+        let public_key = PKey::from_ec_key(key).unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key).unwrap();
+        verifier.update(b"payload").unwrap();
+        assert!(verifier.verify(&der).unwrap());
+
+        assert!(ecdsa_signature_to_der(&raw[1..]).is_err());
+    }
+}
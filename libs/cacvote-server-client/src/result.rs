@@ -18,6 +18,9 @@ pub enum Error {
     #[error("url error: {0}")]
     Url(#[from] url::ParseError),
 
+    #[error("query string error: {0}")]
+    Query(#[from] serde_urlencoded::ser::Error),
+
     #[error("json error: {0}")]
     Json(#[from] serde_json::Error),
 
@@ -26,4 +29,15 @@ pub enum Error {
 
     #[error("signature error: {0}")]
     Signature(String),
+
+    #[cfg(feature = "tpm")]
+    #[error("TPM error: {0}")]
+    Tpm(#[from] crate::tpm::Error),
+
+    #[error("PKCS#11 error: {0}")]
+    Pkcs11(#[from] crate::pkcs11::Error),
+
+    #[cfg(feature = "vx-card")]
+    #[error("card error: {0}")]
+    Card(#[from] auth_rs::CardReaderError),
 }
@@ -0,0 +1,105 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// Controls how the client retries requests that fail for transient reasons,
+/// i.e. network errors and 5xx responses.
+///
+/// Requests that are safe to repeat (`GET`) are retried on any transient
+/// failure. Other requests are only retried when the connection could not be
+/// established, since the server may otherwise have already acted on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of times to retry a failed request. Zero disables retries.
+    pub max_retries: u32,
+
+    /// Delay before the first retry. Each subsequent retry doubles the delay.
+    pub initial_backoff: Duration,
+
+    /// Upper bound on the delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub const NONE: Self = Self {
+        max_retries: 0,
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    };
+
+    /// The policy used by [`Client`][crate::Client] unless configured
+    /// otherwise.
+    pub const DEFAULT: Self = Self {
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(250),
+        max_backoff: Duration::from_secs(5),
+    };
+
+    /// Returns the delay to wait before the given retry, starting at 0 for the
+    /// first retry. Uses "full jitter", i.e. a random delay between zero and
+    /// the exponential backoff, so that many clients failing at once do not
+    /// retry in lockstep.
+    #[must_use]
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let ceiling = self.backoff_ceiling(retry);
+        let ceiling_millis = u64::try_from(ceiling.as_millis()).unwrap_or(u64::MAX);
+        if ceiling_millis == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(random_u64() % (ceiling_millis + 1))
+    }
+
+    /// Returns the maximum delay before the given retry, without jitter.
+    #[must_use]
+    pub fn backoff_ceiling(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .checked_mul(2u32.saturating_pow(retry))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Returns a random number without pulling in a dedicated RNG dependency.
+/// Each `RandomState` is seeded randomly, which is plenty for jitter.
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_ceiling_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+
+        assert_eq!(policy.backoff_ceiling(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff_ceiling(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff_ceiling(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff_ceiling(3), Duration::from_millis(800));
+        assert_eq!(policy.backoff_ceiling(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff_ceiling(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_backoff_is_within_ceiling() {
+        let policy = RetryPolicy::DEFAULT;
+
+        for retry in 0..10 {
+            assert!(policy.backoff(retry) <= policy.backoff_ceiling(retry));
+        }
+        assert_eq!(RetryPolicy::NONE.backoff(0), Duration::ZERO);
+    }
+}
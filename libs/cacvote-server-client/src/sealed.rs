@@ -0,0 +1,87 @@
+//! Secrets that only the machine they are sealed to can read, such as the
+//! key-encryption key for ElectionGuard private keys on a JX terminal.
+
+#[cfg(feature = "tpm")]
+use crate::tpm::{self, Tpm};
+
+/// Where a sealed secret is held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SealedSecret {
+    /// The handle of a persistent TPM sealed data object. The TPM itself is
+    /// chosen by the `TCTI` environment variable. Only usable when built
+    /// with the `tpm` feature.
+    Tpm(u32),
+}
+
+impl SealedSecret {
+    /// Reads the secret, which fails on any machine other than the one it
+    /// was sealed to.
+    pub fn unseal(&self) -> color_eyre::Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "tpm")]
+            Self::Tpm(handle) => Ok(Tpm::open(tpm::tcti_from_environment()?)?.unseal(*handle)?),
+            #[cfg(not(feature = "tpm"))]
+            Self::Tpm(_) => Err(color_eyre::eyre::eyre!(
+                "TPM sealed secrets require building with the `tpm` feature"
+            )),
+        }
+    }
+}
+
+impl std::str::FromStr for SealedSecret {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("tpm:") {
+            Some(handle) => crate::signer::parse_tpm_handle(handle).map(Self::Tpm),
+            None => Err(format!(
+                "sealed secrets must be held in the TPM, e.g. tpm:0x81000002, not {s:?}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sealed_secret() {
+        assert_eq!(
+            "tpm:0x81000002".parse::<SealedSecret>(),
+            Ok(SealedSecret::Tpm(0x8100_0002))
+        );
+        assert!("/path/to/key-encryption-key"
+            .parse::<SealedSecret>()
+            .is_err());
+        assert!("tpm:0xnope".parse::<SealedSecret>().is_err());
+    }
+
+    /// Runs against a software TPM, set up in CI with a sealed data object
+    /// by `script/setup-ci-devices`. To run it locally:
+    ///
+    /// ```sh
+    /// swtpm socket --tpm2 --daemon --server type=tcp,port=2321 \
+    ///   --ctrl type=tcp,port=2322 --tpmstate dir=/tmp/swtpm \
+    ///   --flags not-need-init,startup-clear
+    /// export TPM2TOOLS_TCTI=swtpm:port=2321
+    /// tpm2_createprimary -C o -c primary.ctx
+    /// echo -n secret | tpm2_create -C primary.ctx -i - -u sealed.pub -r sealed.priv
+    /// tpm2_load -C primary.ctx -u sealed.pub -r sealed.priv -c sealed.ctx
+    /// tpm2_evictcontrol -C o -c sealed.ctx 0x81000002
+    /// TCTI=swtpm:port=2321 TPM_SEALED_SECRET=tpm:0x81000002 TPM_SEALED_VALUE=secret \
+    ///   cargo test -p cacvote-server-client --features tpm -- --ignored test_swtpm_unseal
+    /// ```
+    #[cfg(feature = "tpm")]
+    #[test]
+    #[ignore = "requires a software TPM, see doc comment"]
+    fn test_swtpm_unseal() {
+        let sealed_secret: SealedSecret = std::env::var("TPM_SEALED_SECRET")
+            .expect("TPM_SEALED_SECRET must be set")
+            .parse()
+            .unwrap();
+        let expected = std::env::var("TPM_SEALED_VALUE").expect("TPM_SEALED_VALUE must be set");
+
+        assert_eq!(sealed_secret.unseal().unwrap(), expected.into_bytes());
+    }
+}
@@ -1,16 +1,23 @@
 use std::{
     fmt::Debug,
-    io::Write,
     path::PathBuf,
-    process::{Command, Stdio},
+    sync::{Arc, Mutex},
 };
 
+use async_trait::async_trait;
 use openssl::{
     hash::MessageDigest,
     pkey::{PKey, Private},
 };
+#[cfg(feature = "tpm")]
+use openssl::{pkey::Public, x509::X509};
 
-use crate::result::{Error, Result};
+#[cfg(feature = "tpm")]
+use crate::tpm::{self, Tcti, Tpm};
+use crate::{
+    pkcs11::{self, Pkcs11Description},
+    result::{Error, Result},
+};
 
 pub trait Signer {
     fn sign(&self, payload: &[u8]) -> Result<Vec<u8>>;
@@ -18,6 +25,58 @@ pub trait Signer {
 
 pub type AnySigner = Box<dyn Signer + Send + Sync>;
 
+impl<S: Signer + ?Sized> Signer for Box<S> {
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        (**self).sign(payload)
+    }
+}
+
+/// Like [`Signer`], but for signers that need to wait on something else to
+/// produce a signature, e.g. a smartcard or a subprocess.
+#[async_trait]
+pub trait AsyncSigner {
+    async fn sign(&self, payload: &[u8]) -> Result<Vec<u8>>;
+}
+
+pub type AnyAsyncSigner = Box<dyn AsyncSigner + Send + Sync>;
+
+/// Adapts a [`Signer`] that may block, such as [`Pkcs11Signer`], to
+/// [`AsyncSigner`] by signing on Tokio's blocking thread pool.
+pub struct BlockingSigner<S> {
+    signer: Arc<S>,
+}
+
+impl<S> BlockingSigner<S> {
+    pub fn new(signer: S) -> Self {
+        Self {
+            signer: Arc::new(signer),
+        }
+    }
+}
+
+impl<S: Debug> Debug for BlockingSigner<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BlockingSigner").field(&self.signer).finish()
+    }
+}
+
+#[async_trait]
+impl<S: Signer + Send + Sync + 'static> AsyncSigner for BlockingSigner<S> {
+    async fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let signer = Arc::clone(&self.signer);
+        let payload = payload.to_vec();
+        tokio::task::spawn_blocking(move || signer.sign(&payload))
+            .await
+            .map_err(|e| Error::Signature(format!("signing task failed: {e}")))?
+    }
+}
+
+impl From<AnySigner> for AnyAsyncSigner {
+    fn from(signer: AnySigner) -> Self {
+        Box::new(BlockingSigner::new(signer))
+    }
+}
+
 /// A `Signer` that uses a private key to sign payloads.
 #[derive(Debug)]
 #[must_use]
@@ -44,17 +103,73 @@ impl Signer for PrivateKeySigner {
     }
 }
 
-/// A signer that uses the TPM to sign payloads.
+/// Signing with a private key in memory is fast enough to do inline.
+#[async_trait]
+impl AsyncSigner for PrivateKeySigner {
+    async fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        Signer::sign(self, payload)
+    }
+}
+
+/// A signer that uses a persistent key in the TPM to sign payloads. Talks to
+/// the TPM directly and keeps the connection open for its lifetime.
+///
+/// The user running the program must have access to the TPM, e.g. by being
+/// in the `tss` group. The key must not require a password.
+#[cfg(feature = "tpm")]
 pub struct TpmSigner {
     handle: u32,
+    public_key: tpm::PublicKey,
+    tpm: Mutex<Tpm>,
 }
 
+#[cfg(feature = "tpm")]
 impl TpmSigner {
-    pub const fn new(handle: u32) -> Self {
-        Self { handle }
+    /// Connects to the TPM described by `tcti` and loads the public part of
+    /// the key at `handle`.
+    pub fn open(tcti: Tcti, handle: u32) -> Result<Self> {
+        Self::with_tpm(Tpm::open(tcti)?, handle)
+    }
+
+    pub fn with_tpm(mut tpm: Tpm, handle: u32) -> Result<Self> {
+        let public_key = tpm.read_public(handle)?;
+        Ok(Self {
+            handle,
+            public_key,
+            tpm: Mutex::new(tpm),
+        })
+    }
+
+    /// The public key corresponding to the signing key.
+    pub fn public_key(&self) -> Result<PKey<Public>> {
+        Ok(self.public_key.to_pkey()?)
+    }
+
+    /// Reads the certificate for the signing key from the given NV index,
+    /// checking that it matches the key.
+    pub fn read_certificate(&self, nv_index: u32) -> Result<X509> {
+        let certificate = self.lock()?.read_certificate(nv_index)?;
+        let certificate_public_key = certificate
+            .public_key()
+            .map_err(|e| Error::Signature(e.to_string()))?;
+        let public_key = self.public_key()?;
+        if !certificate_public_key.public_eq(&public_key) {
+            return Err(Error::Signature(format!(
+                "certificate in NV index 0x{nv_index:08x} does not match TPM key 0x{:08x}",
+                self.handle
+            )));
+        }
+        Ok(certificate)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Tpm>> {
+        self.tpm
+            .lock()
+            .map_err(|_| Error::Signature("TPM connection lock poisoned".to_owned()))
     }
 }
 
+#[cfg(feature = "tpm")]
 impl Debug for TpmSigner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TpmSigner")
@@ -63,49 +178,46 @@ impl Debug for TpmSigner {
     }
 }
 
+#[cfg(feature = "tpm")]
 impl Signer for TpmSigner {
     fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
-        // NOTE: you must have the tpm2 OpenSSL provider installed for this to
-        // work, and the user running the program must have access to the TPM.
-        // Add the user to the `tss` group to allow access to the TPM.
-        let mut command = Command::new("openssl")
-            .arg("dgst")
-            .arg("-sha256")
-            .arg("-sign")
-            .arg(format!("handle:0x{:x}", self.handle))
-            .arg("-provider")
-            .arg("tpm2")
-            .arg("-provider")
-            .arg("default")
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .spawn()
-            .map_err(|e| Error::Signature(e.to_string()))?;
-
-        let Some(mut stdin) = command.stdin.take() else {
-            return Err(Error::Signature(
-                "openssl stdin is not available".to_string(),
-            ));
-        };
-        stdin
-            .write_all(payload)
-            .map_err(|e| Error::Signature(e.to_string()))?;
+        let digest = openssl::sha::sha256(payload);
+        Ok(self
+            .lock()?
+            .sign_digest(self.handle, &self.public_key, &digest)?)
+    }
+}
 
-        // close stdin so we can wait for output
-        drop(stdin);
+/// A signer that uses a private key held in an HSM, or anything else with a
+/// PKCS#11 module, to sign payloads. Supports ECDSA P-256 and RSA keys.
+///
+/// Keeps a logged-in session open for its lifetime.
+pub struct Pkcs11Signer {
+    session: Mutex<pkcs11::Session>,
+}
 
-        let output = command
-            .wait_with_output()
-            .map_err(|e| Error::Signature(e.to_string()))?;
+impl Pkcs11Signer {
+    /// Loads the module and logs in to the token with `pin`, if given.
+    pub fn open(description: &Pkcs11Description, pin: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            session: Mutex::new(pkcs11::Session::open(description, pin)?),
+        })
+    }
+}
 
-        if !output.status.success() {
-            return Err(Error::Signature(format!(
-                "openssl failed with exit code: {}",
-                output.status
-            )));
-        }
+impl Debug for Pkcs11Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Signer").finish_non_exhaustive()
+    }
+}
 
-        Ok(output.stdout)
+impl Signer for Pkcs11Signer {
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| Error::Signature("PKCS#11 session lock poisoned".to_owned()))?;
+        Ok(session.sign(payload)?)
     }
 }
 
@@ -115,30 +227,42 @@ pub enum Description {
     /// The path of a private key file.
     File(PathBuf),
 
-    /// The handle of a TPM key.
+    /// The handle of a persistent TPM key. The TPM itself is chosen by the
+    /// `TCTI` environment variable. Only usable when built with the `tpm`
+    /// feature.
     Tpm(u32),
+
+    /// A key accessed through a PKCS#11 module. The PIN, if needed, is read
+    /// from the `PKCS11_PIN` environment variable.
+    Pkcs11(Pkcs11Description),
 }
 
 impl std::str::FromStr for Description {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("pkcs11:") {
+            return s
+                .parse()
+                .map(Self::Pkcs11)
+                .map_err(|e: pkcs11::Error| e.to_string());
+        }
+
         match s.strip_prefix("tpm:") {
-            Some(handle) => match handle.strip_prefix("0x") {
-                Some(handle) => {
-                    let handle = u32::from_str_radix(handle, 16).map_err(|e| e.to_string())?;
-                    Ok(Self::Tpm(handle))
-                }
-                None => {
-                    let handle = handle.parse::<u32>().map_err(|e| e.to_string())?;
-                    Ok(Self::Tpm(handle))
-                }
-            },
+            Some(handle) => parse_tpm_handle(handle).map(Self::Tpm),
             None => Ok(Self::File(PathBuf::from(s))),
         }
     }
 }
 
+/// Parses a TPM handle given in hex with a `0x` prefix, or in decimal.
+pub(crate) fn parse_tpm_handle(handle: &str) -> Result<u32, String> {
+    match handle.strip_prefix("0x") {
+        Some(handle) => u32::from_str_radix(handle, 16).map_err(|e| e.to_string()),
+        None => handle.parse::<u32>().map_err(|e| e.to_string()),
+    }
+}
+
 impl TryFrom<&Description> for AnySigner {
     type Error = color_eyre::Report;
 
@@ -150,7 +274,187 @@ impl TryFrom<&Description> for AnySigner {
                     &pem,
                 )?)))
             }
-            Description::Tpm(handle) => Ok(Box::new(TpmSigner::new(*handle))),
+            #[cfg(feature = "tpm")]
+            Description::Tpm(handle) => Ok(Box::new(TpmSigner::open(
+                tpm::tcti_from_environment()?,
+                *handle,
+            )?)),
+            #[cfg(not(feature = "tpm"))]
+            Description::Tpm(_) => Err(tpm_feature_required()),
+            Description::Pkcs11(description) => Ok(Box::new(Pkcs11Signer::open(
+                description,
+                std::env::var(pkcs11::PIN_ENV_VAR).ok().as_deref(),
+            )?)),
+        }
+    }
+}
+
+impl TryFrom<&Description> for AnyAsyncSigner {
+    type Error = color_eyre::Report;
+
+    fn try_from(value: &Description) -> Result<Self, Self::Error> {
+        match value {
+            Description::File(path) => {
+                let pem = std::fs::read(path)?;
+                Ok(Box::new(PrivateKeySigner::new(PKey::private_key_from_pem(
+                    &pem,
+                )?)))
+            }
+            #[cfg(feature = "tpm")]
+            Description::Tpm(handle) => Ok(Box::new(BlockingSigner::new(TpmSigner::open(
+                tpm::tcti_from_environment()?,
+                *handle,
+            )?))),
+            #[cfg(not(feature = "tpm"))]
+            Description::Tpm(_) => Err(tpm_feature_required()),
+            Description::Pkcs11(description) => {
+                Ok(Box::new(BlockingSigner::new(Pkcs11Signer::open(
+                    description,
+                    std::env::var(pkcs11::PIN_ENV_VAR).ok().as_deref(),
+                )?)))
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "tpm"))]
+fn tpm_feature_required() -> color_eyre::Report {
+    color_eyre::eyre::eyre!("TPM signers require building with the `tpm` feature")
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{sign::Verifier, x509::X509};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blocking_signer() {
+        let cert = X509::from_pem(include_bytes!(
+            "../../auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))
+        .unwrap();
+        let private_key = PKey::private_key_from_pem(include_bytes!(
+            "../../auth/certs/dev/vx-admin-private-key.pem"
+        ))
+        .unwrap();
+        let signer: AnySigner = Box::new(PrivateKeySigner::new(private_key));
+        let signer = AnyAsyncSigner::from(signer);
+
+        let signature = signer.sign(b"payload").await.unwrap();
+
+        let public_key = cert.public_key().unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key).unwrap();
+        verifier.update(b"payload").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+    }
+
+    #[test]
+    fn test_parse_tpm_description() {
+        assert!(matches!(
+            "tpm:0x81000001".parse::<Description>(),
+            Ok(Description::Tpm(0x8100_0001))
+        ));
+        assert!(matches!(
+            "tpm:2164260865".parse::<Description>(),
+            Ok(Description::Tpm(0x8100_0001))
+        ));
+        assert!("tpm:0xnope".parse::<Description>().is_err());
+    }
+
+    #[test]
+    fn test_parse_pkcs11_description() {
+        match "pkcs11:module=/usr/lib/softhsm/libsofthsm2.so;slot=7;label=cacvote"
+            .parse::<Description>()
+        {
+            Ok(Description::Pkcs11(description)) => {
+                assert_eq!(description.slot, 7);
+                assert_eq!(description.label, "cacvote");
+            }
+            description => panic!("unexpected description: {description:?}"),
+        }
+        assert!("pkcs11:slot=7".parse::<Description>().is_err());
+    }
+
+    /// Runs against SoftHSM, set up in CI with an ECDSA P-256 key by
+    /// `script/setup-ci-devices`. To also check an RSA key, run it once for
+    /// each, e.g.:
+    ///
+    /// ```sh
+    /// export SOFTHSM2_CONF=/tmp/softhsm2.conf MODULE=/usr/lib/softhsm/libsofthsm2.so
+    /// echo "directories.tokendir = /tmp/softhsm-tokens" > $SOFTHSM2_CONF
+    /// mkdir -p /tmp/softhsm-tokens
+    /// softhsm2-util --init-token --free --label cacvote --so-pin 0000 --pin 1234
+    /// pkcs11-tool --module $MODULE --token-label cacvote --login --pin 1234 \
+    ///   --keypairgen --key-type EC:prime256v1 --label ec-key
+    /// pkcs11-tool --module $MODULE --token-label cacvote --read-object \
+    ///   --type pubkey --label ec-key -o /tmp/ec-key.der
+    /// SLOT=$(softhsm2-util --show-slots | awk '/^Slot [0-9]+$/ { print $2; exit }')
+    /// PKCS11_PIN=1234 PKCS11_PUBLIC_KEY=/tmp/ec-key.der \
+    ///   PKCS11_SIGNER="pkcs11:module=$MODULE;slot=$SLOT;label=ec-key" \
+    ///   cargo test -p cacvote-server-client -- --ignored test_softhsm_signer
+    /// ```
+    ///
+    /// and the same with `--key-type rsa:2048 --label rsa-key`.
+    #[test]
+    #[ignore = "requires SoftHSM, see doc comment"]
+    fn test_softhsm_signer() {
+        let description: Description = std::env::var("PKCS11_SIGNER")
+            .expect("PKCS11_SIGNER must be set")
+            .parse()
+            .unwrap();
+        let public_key = PKey::public_key_from_der(
+            &std::fs::read(std::env::var("PKCS11_PUBLIC_KEY").unwrap()).unwrap(),
+        )
+        .unwrap();
+        let signer = AnySigner::try_from(&description).unwrap();
+
+        let signature = signer.sign(b"payload").unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key).unwrap();
+        verifier.update(b"payload").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+    }
+
+    /// Runs against a software TPM, set up in CI by `script/setup-ci-devices`,
+    /// e.g.:
+    ///
+    /// ```sh
+    /// swtpm socket --tpm2 --server type=tcp,port=2321 \
+    ///   --ctrl type=tcp,port=2322 --tpmstate dir=/tmp/swtpm --flags startup-clear &
+    /// export TPM2TOOLS_TCTI=swtpm:port=2321
+    /// tpm2_createprimary -C o -c primary.ctx
+    /// tpm2_create -C primary.ctx -G ecc256 -u key.pub -r key.priv
+    /// tpm2_load -C primary.ctx -u key.pub -r key.priv -c key.ctx
+    /// tpm2_evictcontrol -C o -c key.ctx 0x81000001
+    /// TCTI=swtpm:port=2321 TPM_SIGNER=tpm:0x81000001 \
+    ///   cargo test -p cacvote-server-client --features tpm -- --ignored test_swtpm_signer
+    /// ```
+    ///
+    /// If `TPM_CERT_NV_INDEX` is set, also reads the key's certificate from
+    /// that NV index.
+    #[cfg(feature = "tpm")]
+    #[test]
+    #[ignore = "requires a software TPM, see doc comment"]
+    fn test_swtpm_signer() {
+        let description: Description = std::env::var("TPM_SIGNER")
+            .expect("TPM_SIGNER must be set")
+            .parse()
+            .unwrap();
+        let Description::Tpm(handle) = description else {
+            panic!("TPM_SIGNER must be a TPM signer");
+        };
+        let signer = TpmSigner::open(tpm::tcti_from_environment().unwrap(), handle).unwrap();
+
+        let signature = Signer::sign(&signer, b"payload").unwrap();
+        let public_key = signer.public_key().unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key).unwrap();
+        verifier.update(b"payload").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+
+        if let Ok(nv_index) = std::env::var("TPM_CERT_NV_INDEX") {
+            let nv_index = u32::from_str_radix(nv_index.trim_start_matches("0x"), 16).unwrap();
+            let certificate = signer.read_certificate(nv_index).unwrap();
+            assert!(certificate.public_key().unwrap().public_eq(&public_key));
         }
     }
 }
@@ -0,0 +1,296 @@
+//! TPM 2.0 access for [`TpmSigner`] and [`SealedSecret`], covering just what
+//! they need: signing with a persistent key, reading its public key and
+//! certificate, and unsealing persistent sealed data objects.
+//!
+//! Talks to the TPM through the TSS Enhanced System API, so the TSS
+//! libraries (e.g. `libtss2-dev`) must be installed to build with the `tpm`
+//! feature.
+//!
+//! [`TpmSigner`]: crate::TpmSigner
+//! [`SealedSecret`]: crate::sealed::SealedSecret
+
+use std::{fmt::Debug, str::FromStr};
+
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    ec::{EcGroup, EcKey, EcPoint},
+    ecdsa::EcdsaSig,
+    nid::Nid,
+    pkey::{PKey, Public},
+    rsa::Rsa,
+    x509::X509,
+};
+use tss_esapi::{
+    abstraction::nv,
+    constants::tss::{TPM2_RH_NULL, TPM2_ST_HASHCHECK},
+    handles::{KeyHandle, NvIndexTpmHandle, PersistentTpmHandle, TpmHandle},
+    interface_types::{algorithm::HashingAlgorithm, ecc::EccCurve, resource_handles::NvAuth},
+    structures::{
+        Digest, EccScheme, HashScheme, HashcheckTicket, Public as TpmPublic, RsaScheme, Signature,
+        SignatureScheme,
+    },
+    tss2_esys::TPMT_TK_HASHCHECK,
+    Context,
+};
+
+pub use tss_esapi::TctiNameConf as Tcti;
+
+/// The environment variable used to choose a TPM, using the same syntax as
+/// the TSS tools, e.g. `device:/dev/tpmrm0` or `swtpm:host=localhost,port=2321`.
+pub const TCTI_ENV_VAR: &str = "TCTI";
+
+const DEFAULT_TCTI: &str = "device:/dev/tpmrm0";
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("TSS error: {0}")]
+    Tss(#[from] tss_esapi::Error),
+
+    #[error("unsupported TPM key: {0}")]
+    UnsupportedKey(String),
+
+    #[error("openssl error: {0}")]
+    OpenSsl(#[from] openssl::error::ErrorStack),
+}
+
+/// Reads the TCTI from [`TCTI_ENV_VAR`], defaulting to the kernel resource
+/// manager.
+pub fn tcti_from_environment() -> Result<Tcti> {
+    let tcti = std::env::var(TCTI_ENV_VAR).unwrap_or_else(|_| DEFAULT_TCTI.to_owned());
+    Ok(Tcti::from_str(&tcti)?)
+}
+
+/// An open connection to a TPM.
+pub struct Tpm {
+    context: Context,
+}
+
+impl Debug for Tpm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tpm").finish_non_exhaustive()
+    }
+}
+
+impl Tpm {
+    /// Connects to the TPM described by `tcti`.
+    pub fn open(tcti: Tcti) -> Result<Self> {
+        Ok(Self {
+            context: Context::new(tcti)?,
+        })
+    }
+
+    /// Reads the public area of a persistent key.
+    pub fn read_public(&mut self, handle: u32) -> Result<PublicKey> {
+        let key_handle = self.key_handle(handle)?;
+        let (public, _, _) = self.context.read_public(key_handle)?;
+        PublicKey::new(public)
+    }
+
+    /// Signs a SHA-256 digest with the given unrestricted signing key, which
+    /// must not require a password.
+    pub fn sign_digest(&mut self, handle: u32, key: &PublicKey, digest: &[u8]) -> Result<Vec<u8>> {
+        let key_handle = self.key_handle(handle)?;
+        let digest = Digest::try_from(digest)?;
+        // a NULL hash check ticket, only acceptable for unrestricted keys
+        let validation = HashcheckTicket::try_from(TPMT_TK_HASHCHECK {
+            tag: TPM2_ST_HASHCHECK,
+            hierarchy: TPM2_RH_NULL,
+            digest: Default::default(),
+        })?;
+
+        let signature = self.context.execute_with_nullauth_session(|context| {
+            context.sign(key_handle, digest, key.signing_scheme(), validation)
+        })?;
+
+        match signature {
+            Signature::RsaSsa(signature) | Signature::RsaPss(signature) => {
+                Ok(signature.signature().value().to_vec())
+            }
+            Signature::EcDsa(signature) => {
+                // match the DER encoding produced by OpenSSL
+                let r = BigNum::from_slice(signature.signature_r().value())?;
+                let s = BigNum::from_slice(signature.signature_s().value())?;
+                Ok(EcdsaSig::from_private_components(r, s)?.to_der()?)
+            }
+            signature => Err(Error::UnsupportedKey(format!(
+                "unexpected signature algorithm {:?}",
+                signature.algorithm()
+            ))),
+        }
+    }
+
+    /// Unseals a persistent sealed data object, e.g. one created with
+    /// `tpm2_create -i` and made persistent with `tpm2_evictcontrol`, which
+    /// must not require a password.
+    pub fn unseal(&mut self, handle: u32) -> Result<Vec<u8>> {
+        let key_handle = self.key_handle(handle)?;
+        let sensitive_data = self
+            .context
+            .execute_with_nullauth_session(|context| context.unseal(key_handle.into()))?;
+        Ok(sensitive_data.value().to_vec())
+    }
+
+    /// Reads the entire contents of an NV index that can be read without a
+    /// password.
+    pub fn nv_read(&mut self, nv_index: u32) -> Result<Vec<u8>> {
+        let nv_index = NvIndexTpmHandle::new(nv_index)?;
+        let nv_index_handle = self
+            .context
+            .tr_from_tpm_public(TpmHandle::NvIndex(nv_index))?;
+        Ok(self.context.execute_with_nullauth_session(|context| {
+            nv::read_full(context, NvAuth::NvIndex(nv_index_handle.into()), nv_index)
+        })?)
+    }
+
+    /// Reads a DER- or PEM-encoded certificate from an NV index.
+    pub fn read_certificate(&mut self, nv_index: u32) -> Result<X509> {
+        let data = self.nv_read(nv_index)?;
+        // NV indices are often sized generously and padded with zeros or
+        // 0xff, which OpenSSL rejects as trailing data
+        let der = der_sequence_len(&data).map_or(&data[..], |len| &data[..len]);
+        Ok(X509::from_der(der).or_else(|_| X509::from_pem(&data))?)
+    }
+
+    fn key_handle(&mut self, handle: u32) -> Result<KeyHandle> {
+        let handle = PersistentTpmHandle::new(handle)?;
+        Ok(self
+            .context
+            .tr_from_tpm_public(TpmHandle::Persistent(handle))?
+            .into())
+    }
+}
+
+/// The public part of a TPM signing key.
+#[derive(Debug, Clone)]
+pub struct PublicKey(TpmPublic);
+
+impl PublicKey {
+    fn new(public: TpmPublic) -> Result<Self> {
+        match &public {
+            TpmPublic::Rsa { .. } | TpmPublic::Ecc { .. } => Ok(Self(public)),
+            _ => Err(Error::UnsupportedKey(
+                "key is neither an RSA nor an ECC key".to_owned(),
+            )),
+        }
+    }
+
+    /// Converts the key for use with OpenSSL, e.g. to check it matches a
+    /// certificate.
+    pub fn to_pkey(&self) -> Result<PKey<Public>> {
+        match &self.0 {
+            TpmPublic::Rsa {
+                parameters, unique, ..
+            } => {
+                // an exponent of zero means the default
+                let exponent = match parameters.exponent().value() {
+                    0 => 65537,
+                    exponent => exponent,
+                };
+                let rsa = Rsa::from_public_components(
+                    BigNum::from_slice(unique.value())?,
+                    BigNum::from_u32(exponent)?,
+                )?;
+                Ok(PKey::from_rsa(rsa)?)
+            }
+            TpmPublic::Ecc {
+                parameters, unique, ..
+            } => {
+                let nid = match parameters.ecc_curve() {
+                    EccCurve::NistP256 => Nid::X9_62_PRIME256V1,
+                    EccCurve::NistP384 => Nid::SECP384R1,
+                    curve => {
+                        return Err(Error::UnsupportedKey(format!(
+                            "unsupported ECC curve {curve:?}"
+                        )))
+                    }
+                };
+                let group = EcGroup::from_curve_name(nid)?;
+                let mut ctx = BigNumContext::new()?;
+                let x = BigNum::from_slice(unique.x().value())?;
+                let y = BigNum::from_slice(unique.y().value())?;
+                let mut point = EcPoint::new(&group)?;
+                point.set_affine_coordinates_gfp(&group, &x, &y, &mut ctx)?;
+                Ok(PKey::from_ec_key(EcKey::from_public_key(&group, &point)?)?)
+            }
+            _ => unreachable!("checked in PublicKey::new"),
+        }
+    }
+
+    /// The scheme to request from `TPM2_Sign`. Keys with their own scheme
+    /// must be asked for exactly that one.
+    fn signing_scheme(&self) -> SignatureScheme {
+        let sha256 = HashScheme::new(HashingAlgorithm::Sha256);
+        match &self.0 {
+            TpmPublic::Rsa { parameters, .. } => match parameters.rsa_scheme() {
+                RsaScheme::RsaPss(hash_scheme) => SignatureScheme::RsaPss { hash_scheme },
+                RsaScheme::RsaSsa(hash_scheme) => SignatureScheme::RsaSsa { hash_scheme },
+                _ => SignatureScheme::RsaSsa {
+                    hash_scheme: sha256,
+                },
+            },
+            TpmPublic::Ecc { parameters, .. } => match parameters.ecc_scheme() {
+                EccScheme::EcDsa(hash_scheme) => SignatureScheme::EcDsa { hash_scheme },
+                _ => SignatureScheme::EcDsa {
+                    hash_scheme: sha256,
+                },
+            },
+            _ => SignatureScheme::Null,
+        }
+    }
+}
+
+/// Returns the encoded length of the DER `SEQUENCE` at the start of `data`,
+/// if there is one and it fits.
+fn der_sequence_len(data: &[u8]) -> Option<usize> {
+    let (&tag, rest) = data.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    if tag != 0x30 {
+        return None;
+    }
+
+    let (header_len, content_len) = if first < 0x80 {
+        (2, usize::from(first))
+    } else {
+        let num_bytes = usize::from(first & 0x7f);
+        if num_bytes == 0 || num_bytes > 4 || rest.len() < num_bytes {
+            return None;
+        }
+        let content_len = rest[..num_bytes]
+            .iter()
+            .fold(0, |len, &byte| (len << 8) | usize::from(byte));
+        (2 + num_bytes, content_len)
+    };
+
+    let len = header_len + content_len;
+    (len <= data.len()).then_some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_der_sequence_len() {
+        let cert = X509::from_pem(include_bytes!(
+            "../../auth/certs/dev/vx-admin-cert-authority-cert.pem"
+        ))
+        .unwrap()
+        .to_der()
+        .unwrap();
+
+        let mut padded = cert.clone();
+        padded.extend_from_slice(&[0xff; 64]);
+        assert_eq!(der_sequence_len(&padded), Some(cert.len()));
+        assert_eq!(der_sequence_len(&cert[..cert.len() - 1]), None);
+        assert_eq!(der_sequence_len(b"-----BEGIN CERTIFICATE-----"), None);
+        assert_eq!(der_sequence_len(&[]), None);
+    }
+
+    #[test]
+    fn test_tcti_from_environment() {
+        std::env::remove_var(TCTI_ENV_VAR);
+        assert!(matches!(tcti_from_environment(), Ok(Tcti::Device(_))));
+    }
+}
@@ -1,35 +1,45 @@
-use std::{io, process::Command};
+use std::{
+    io,
+    process::{Command, Output},
+};
 
 #[tracing::instrument]
 pub fn run_electionguard_command(command: &mut Command) -> io::Result<()> {
-    command
-        .output()
-        .and_then(|output| {
-            if let Ok(stdout) = std::str::from_utf8(&output.stdout) {
-                if !stdout.is_empty() {
-                    tracing::debug!("stdout: {stdout}");
+    run_electionguard_command_for_output(command).and_then(|output| {
+        if !output.status.success() {
+            Err(command_failed_error(&output))
+        } else {
+            Ok(())
+        }
+    })
+}
 
-                }
+/// Runs the command and returns its output whether or not it succeeded, for
+/// callers that need to tell why it failed. Only fails if the command could not
+/// be run at all.
+#[tracing::instrument]
+pub fn run_electionguard_command_for_output(command: &mut Command) -> io::Result<Output> {
+    command.output().inspect(|output| {
+        if let Ok(stdout) = std::str::from_utf8(&output.stdout) {
+            if !stdout.is_empty() {
+                tracing::debug!("stdout: {stdout}");
             }
+        }
 
-            if let Ok(stderr) = std::str::from_utf8(&output.stderr) {
-                if !stderr.is_empty() {
-                    tracing::debug!("stderr: {stderr}");
-                }
+        if let Ok(stderr) = std::str::from_utf8(&output.stderr) {
+            if !stderr.is_empty() {
+                tracing::debug!("stderr: {stderr}");
             }
+        }
+    })
+}
 
-            if !output.status.success() {
-                Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!(
-                        "Java ElectionGuard CLI exited with code {status}\nstdout: {stdout}\nstderr: {stderr}",
-                        status = output.status.code().unwrap_or_default(),
-                        stdout = std::str::from_utf8(&output.stdout).unwrap_or_default(),
-                        stderr = std::str::from_utf8(&output.stderr).unwrap_or_default(),
-                    ),
-                ))
-            } else {
-                Ok(())
-            }
-        })
+/// The error for a command that exited unsuccessfully, including its output.
+pub fn command_failed_error(output: &Output) -> io::Error {
+    io::Error::other(format!(
+        "Java ElectionGuard CLI exited with code {status}\nstdout: {stdout}\nstderr: {stderr}",
+        status = output.status.code().unwrap_or_default(),
+        stdout = std::str::from_utf8(&output.stdout).unwrap_or_default(),
+        stderr = std::str::from_utf8(&output.stderr).unwrap_or_default(),
+    ))
 }
@@ -62,6 +62,13 @@ pub fn generate_election_config(
 /// Run the Java ElectionGuard CLI to create an election configuration. Expects
 /// to read and write files because the Java ElectionGuard implementation
 /// expects to work with files.
+///
+/// Elections have a single guardian. The ElectionGuard CLI only offers a
+/// trusted key ceremony, which generates every guardian's key share in one
+/// process, so splitting the key between several guardians here would still
+/// leave one machine that saw every share. A real n-of-m ceremony needs each
+/// guardian to generate its share and exchange proofs on its own machine,
+/// which the CLI does not support.
 pub fn run_create_election_config(
     classpath: &PathBuf,
     manifest_path: &PathBuf,
@@ -9,3 +9,6 @@ pub(crate) const ELECTION_INITIALIZED_FILE: &str = "election_initialized.json";
 pub(crate) const CONSTANTS_FILE: &str = "constants.json";
 pub(crate) const PLAINTEXT_BALLOT_PREFIX: &str = "pballot-";
 pub(crate) const ENCRYPTED_BALLOT_PREFIX: &str = "eballot-";
+pub(crate) const DECRYPTED_BALLOT_PREFIX: &str = "dballot-";
+pub(crate) const MIX_DIRECTORY_PREFIX: &str = "mix";
+pub(crate) const SHUFFLED_BALLOTS_FILE: &str = "ShuffledBallots.json";
@@ -0,0 +1,278 @@
+//! The plaintext that ElectionGuard decryption produces, and how it maps back
+//! to the VX election the manifest was converted from.
+//!
+//! ElectionGuard writes decrypted tallies and decrypted ballots in the same
+//! form: each contest with the decrypted value of each of its selections,
+//! along with proofs that the decryption was done correctly. Only the values
+//! are modeled here, so the proofs must be kept from the original JSON.
+
+use color_eyre::eyre::{bail, ensure};
+use serde::{Deserialize, Serialize};
+use types_rs::{
+    cacvote::{AnonymousBallot, AnonymousBallotContest, AnonymousBallotSelection},
+    election as vx_election,
+};
+
+use crate::manifest::ObjectId;
+
+/// A decrypted tally or ballot as written by the ElectionGuard CLI.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct DecryptedTallyOrBallot {
+    pub id: String,
+    pub contests: Vec<DecryptedContest>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct DecryptedContest {
+    pub contest_id: ObjectId, // matches ContestDescription.contestId
+    pub selections: Vec<DecryptedSelection>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct DecryptedSelection {
+    pub selection_id: ObjectId, // matches SelectionDescription.selectionId
+    pub tally: u64,             // the vote for a ballot, the sum of votes for a tally
+}
+
+/// Finds the VX contest whose converted manifest contest has `contest_id`,
+/// along with its position in the election.
+pub fn find_vx_contest<'a>(
+    election: &'a vx_election::Election,
+    contest_id: &ObjectId,
+) -> Option<(usize, &'a vx_election::Contest)> {
+    election
+        .contests
+        .iter()
+        .enumerate()
+        .find(|(_, contest)| &ObjectId::from(contest.id().clone()) == contest_id)
+}
+
+/// Lists the IDs of a VX contest's candidates or yes/no options in the order
+/// of the contest's ballot selections in the converted manifest, each with the
+/// ID of its ballot selection.
+pub fn vx_contest_options(contest: &vx_election::Contest) -> Vec<(ObjectId, String)> {
+    match contest {
+        vx_election::Contest::Candidate(contest) => contest
+            .candidates
+            .iter()
+            .map(|candidate| (candidate.id.clone().into(), candidate.id.to_string()))
+            .collect(),
+        vx_election::Contest::YesNo(contest) => [&contest.yes_option, &contest.no_option]
+            .into_iter()
+            .flatten()
+            .map(|option| (option.id.clone().into(), option.id.to_string()))
+            .collect(),
+    }
+}
+
+/// Maps a ballot decrypted by ElectionGuard back to the VX election, with its
+/// contests in election order and each contest's selections in ballot order.
+pub fn convert_eg_decrypted_ballot_to_vx(
+    ballot: &DecryptedTallyOrBallot,
+    election: &vx_election::Election,
+) -> color_eyre::Result<AnonymousBallot> {
+    let mut contests = Vec::with_capacity(ballot.contests.len());
+
+    for eg_contest in &ballot.contests {
+        let Some((contest_index, contest)) = find_vx_contest(election, &eg_contest.contest_id)
+        else {
+            bail!("Contest not found in election: {:?}", eg_contest.contest_id);
+        };
+        let options = vx_contest_options(contest);
+
+        let mut selections = Vec::with_capacity(eg_contest.selections.len());
+        for eg_selection in &eg_contest.selections {
+            let Some((option_index, (_, option_id))) = options
+                .iter()
+                .enumerate()
+                .find(|(_, (selection_id, _))| selection_id == &eg_selection.selection_id)
+            else {
+                bail!(
+                    "Selection not found in contest {}: {:?}",
+                    contest.id(),
+                    eg_selection.selection_id
+                );
+            };
+            let Ok(vote) = u32::try_from(eg_selection.tally) else {
+                bail!("Vote out of range for selection {option_id}");
+            };
+            selections.push((
+                option_index,
+                AnonymousBallotSelection {
+                    option_id: option_id.clone(),
+                    vote,
+                },
+            ));
+        }
+
+        selections.sort_by_key(|(option_index, _)| *option_index);
+        contests.push((
+            contest_index,
+            AnonymousBallotContest {
+                contest_id: contest.id().clone(),
+                selections: selections
+                    .into_iter()
+                    .map(|(_, selection)| selection)
+                    .collect(),
+            },
+        ));
+    }
+
+    contests.sort_by_key(|(contest_index, _)| *contest_index);
+    ensure!(
+        contests.windows(2).all(|pair| pair[0].0 != pair[1].0),
+        "Contest appears more than once in decrypted ballot {}",
+        ballot.id
+    );
+
+    Ok(AnonymousBallot {
+        contests: contests.into_iter().map(|(_, contest)| contest).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use types_rs::election::ElectionDefinition;
+
+    use super::*;
+
+    fn load_election() -> vx_election::Election {
+        ElectionDefinition::try_from(
+            &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+        )
+        .unwrap()
+        .election
+    }
+
+    fn selection(selection_id: &str, tally: u64) -> DecryptedSelection {
+        DecryptedSelection {
+            selection_id: ObjectId(selection_id.to_owned()),
+            tally,
+        }
+    }
+
+    #[test]
+    fn test_convert_eg_decrypted_ballot_to_vx() {
+        let election = load_election();
+        let first_contest = &election.contests[0];
+        let second_contest = &election.contests[1];
+        let first_options = vx_contest_options(first_contest);
+        let second_options = vx_contest_options(second_contest);
+
+        // ElectionGuard does not promise any order, so give it backwards
+        let ballot = DecryptedTallyOrBallot {
+            id: "ballot".to_owned(),
+            contests: vec![
+                DecryptedContest {
+                    contest_id: second_contest.id().clone().into(),
+                    selections: vec![selection(&second_options[0].0 .0, 1)],
+                },
+                DecryptedContest {
+                    contest_id: first_contest.id().clone().into(),
+                    selections: vec![
+                        selection(&first_options[1].0 .0, 0),
+                        selection(&first_options[0].0 .0, 1),
+                    ],
+                },
+            ],
+        };
+
+        assert_eq!(
+            convert_eg_decrypted_ballot_to_vx(&ballot, &election).unwrap(),
+            AnonymousBallot {
+                contests: vec![
+                    AnonymousBallotContest {
+                        contest_id: first_contest.id().clone(),
+                        selections: vec![
+                            AnonymousBallotSelection {
+                                option_id: first_options[0].1.clone(),
+                                vote: 1,
+                            },
+                            AnonymousBallotSelection {
+                                option_id: first_options[1].1.clone(),
+                                vote: 0,
+                            },
+                        ],
+                    },
+                    AnonymousBallotContest {
+                        contest_id: second_contest.id().clone(),
+                        selections: vec![AnonymousBallotSelection {
+                            option_id: second_options[0].1.clone(),
+                            vote: 1,
+                        }],
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_convert_eg_decrypted_ballot_to_vx_unknown_ids() {
+        let election = load_election();
+        let contest = &election.contests[0];
+
+        let unknown_contest = DecryptedTallyOrBallot {
+            id: "ballot".to_owned(),
+            contests: vec![DecryptedContest {
+                contest_id: ObjectId("contest-unknown".to_owned()),
+                selections: vec![],
+            }],
+        };
+        assert!(convert_eg_decrypted_ballot_to_vx(&unknown_contest, &election).is_err());
+
+        let unknown_selection = DecryptedTallyOrBallot {
+            id: "ballot".to_owned(),
+            contests: vec![DecryptedContest {
+                contest_id: contest.id().clone().into(),
+                selections: vec![selection("cand-unknown", 1)],
+            }],
+        };
+        assert!(convert_eg_decrypted_ballot_to_vx(&unknown_selection, &election).is_err());
+
+        let repeated_contest = DecryptedTallyOrBallot {
+            id: "ballot".to_owned(),
+            contests: vec![
+                DecryptedContest {
+                    contest_id: contest.id().clone().into(),
+                    selections: vec![],
+                },
+                DecryptedContest {
+                    contest_id: contest.id().clone().into(),
+                    selections: vec![],
+                },
+            ],
+        };
+        assert!(convert_eg_decrypted_ballot_to_vx(&repeated_contest, &election).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_ignores_proofs() {
+        let ballot: DecryptedTallyOrBallot = serde_json::from_value(serde_json::json!({
+            "id": "ballot",
+            "election_id": "0A",
+            "contests": [{
+                "contest_id": "contest-mayor",
+                "selections": [{
+                    "selection_id": "cand-sherlock-holmes",
+                    "tally": 1,
+                    "b_over_m": "0B",
+                    "encrypted_vote": { "pad": "0C", "data": "0D" },
+                    "proof": { "challenge": "0E", "response": "0F" },
+                }],
+                "decrypted_contest_data": null,
+            }],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            ballot,
+            DecryptedTallyOrBallot {
+                id: "ballot".to_owned(),
+                contests: vec![DecryptedContest {
+                    contest_id: ObjectId("contest-mayor".to_owned()),
+                    selections: vec![selection("cand-sherlock-holmes", 1)],
+                }],
+            }
+        );
+    }
+}
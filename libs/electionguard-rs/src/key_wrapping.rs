@@ -0,0 +1,261 @@
+//! Encryption of ElectionGuard private keys at rest.
+//!
+//! Private keys are wrapped with AES-256-GCM under a key-encryption key (KEK)
+//! that is never stored alongside them. The KEK is sealed to the machine's
+//! TPM and only unsealed when needed, so neither a copy of the database nor
+//! a copy of the disk is enough to decrypt ballots.
+//!
+//! Each wrapped key records the ID of the KEK that wrapped it. To rotate, make
+//! a new KEK current and keep the old one as a retired KEK until every key has
+//! been rewrapped with [`KeyEncryptionKeys::rewrap_key`]. Once the old KEK is
+//! destroyed, any stale copies of keys it wrapped, e.g. in backups or deleted
+//! database rows, can no longer be decrypted. This is the only way a deleted
+//! key is made unrecoverable: deleting it from the database does not erase it.
+
+use std::fmt;
+
+use color_eyre::eyre::{bail, Context};
+use openssl::{
+    rand::rand_bytes,
+    sha::sha256,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+/// A 256-bit AES key used to wrap private keys.
+pub struct KeyEncryptionKey {
+    id: String,
+    key: [u8; KEY_LENGTH],
+}
+
+impl KeyEncryptionKey {
+    /// Parses a KEK from 64 hex digits, e.g. the output of
+    /// `openssl rand -hex 32`. Surrounding whitespace is ignored.
+    pub fn from_hex(hex_key: &str) -> color_eyre::Result<Self> {
+        let bytes = hex::decode(hex_key.trim()).context("key-encryption key is not hex")?;
+        let Ok(key) = <[u8; KEY_LENGTH]>::try_from(bytes.as_slice()) else {
+            bail!(
+                "key-encryption key must be {KEY_LENGTH} bytes, got {}",
+                bytes.len()
+            );
+        };
+
+        // identify the KEK without revealing anything useful about it
+        let id = hex::encode(&sha256(&key)[..8]);
+        Ok(Self { id, key })
+    }
+
+    /// Parses a hex KEK as unsealed from the TPM.
+    pub fn from_unsealed(unsealed: &[u8]) -> color_eyre::Result<Self> {
+        let hex_key =
+            std::str::from_utf8(unsealed).context("unsealed key-encryption key is not hex")?;
+        Self::from_hex(hex_key)
+    }
+
+    /// A fingerprint of this KEK, stored with each key it wraps.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Encrypts `key` under this KEK. The same `context` must be given to
+    /// unwrap it, binding the wrapped key to e.g. the row it is stored in.
+    pub fn wrap_key(&self, key: &[u8], context: &[u8]) -> color_eyre::Result<WrappedKey> {
+        let mut nonce = [0; NONCE_LENGTH];
+        rand_bytes(&mut nonce)?;
+        let mut tag = [0; TAG_LENGTH];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(&nonce),
+            context,
+            key,
+            &mut tag,
+        )?;
+
+        Ok(WrappedKey {
+            key_encryption_key_id: self.id.clone(),
+            wrapped_key: [&nonce[..], &ciphertext, &tag].concat(),
+        })
+    }
+
+    /// Decrypts a key wrapped by [`Self::wrap_key`] with the same `context`.
+    pub fn unwrap_key(&self, wrapped: &WrappedKey, context: &[u8]) -> color_eyre::Result<Vec<u8>> {
+        if wrapped.key_encryption_key_id != self.id {
+            bail!(
+                "key was wrapped with key-encryption key {}, not {}",
+                wrapped.key_encryption_key_id,
+                self.id
+            );
+        }
+
+        if wrapped.wrapped_key.len() < NONCE_LENGTH + TAG_LENGTH {
+            bail!("wrapped key is too short");
+        }
+
+        let (nonce, rest) = wrapped.wrapped_key.split_at(NONCE_LENGTH);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LENGTH);
+        decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(nonce),
+            context,
+            ciphertext,
+            tag,
+        )
+        .context("failed to unwrap key; it may have been tampered with or moved")
+    }
+}
+
+impl fmt::Debug for KeyEncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyEncryptionKey")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A private key encrypted under a [`KeyEncryptionKey`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    /// ID of the KEK the key was wrapped with.
+    pub key_encryption_key_id: String,
+
+    /// The nonce, ciphertext, and authentication tag, in that order.
+    pub wrapped_key: Vec<u8>,
+}
+
+/// The current KEK, used to wrap new keys, and any retired KEKs that may still
+/// be needed to unwrap keys wrapped before a rotation.
+#[derive(Debug)]
+pub struct KeyEncryptionKeys {
+    current: KeyEncryptionKey,
+    retired: Vec<KeyEncryptionKey>,
+}
+
+impl KeyEncryptionKeys {
+    pub fn new(current: KeyEncryptionKey, retired: Vec<KeyEncryptionKey>) -> Self {
+        Self { current, retired }
+    }
+
+    pub fn current(&self) -> &KeyEncryptionKey {
+        &self.current
+    }
+
+    /// The current KEK followed by any retired ones.
+    pub fn iter(&self) -> impl Iterator<Item = &KeyEncryptionKey> {
+        std::iter::once(&self.current).chain(&self.retired)
+    }
+
+    fn find(&self, id: &str) -> Option<&KeyEncryptionKey> {
+        self.iter().find(|kek| kek.id() == id)
+    }
+
+    /// Wraps `key` under the current KEK.
+    pub fn wrap_key(&self, key: &[u8], context: &[u8]) -> color_eyre::Result<WrappedKey> {
+        self.current.wrap_key(key, context)
+    }
+
+    /// Unwraps `wrapped` with whichever KEK wrapped it.
+    pub fn unwrap_key(&self, wrapped: &WrappedKey, context: &[u8]) -> color_eyre::Result<Vec<u8>> {
+        match self.find(&wrapped.key_encryption_key_id) {
+            Some(kek) => kek.unwrap_key(wrapped, context),
+            None => bail!(
+                "key was wrapped with key-encryption key {}, which is not configured",
+                wrapped.key_encryption_key_id
+            ),
+        }
+    }
+
+    /// Rewraps `wrapped` under the current KEK, or returns `None` if it is
+    /// already wrapped by it.
+    pub fn rewrap_key(
+        &self,
+        wrapped: &WrappedKey,
+        context: &[u8],
+    ) -> color_eyre::Result<Option<WrappedKey>> {
+        if wrapped.key_encryption_key_id == self.current.id() {
+            return Ok(None);
+        }
+
+        let key = self.unwrap_key(wrapped, context)?;
+        Ok(Some(self.wrap_key(&key, context)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEK_1: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    const KEK_2: &str = "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100";
+
+    #[test]
+    fn test_from_hex() {
+        let kek = KeyEncryptionKey::from_hex(&format!("{KEK_1}\n")).unwrap();
+        assert_eq!(kek.id().len(), 16);
+        assert_ne!(kek.id(), KeyEncryptionKey::from_hex(KEK_2).unwrap().id());
+        assert!(!format!("{kek:?}").contains("0001020304"));
+
+        assert!(KeyEncryptionKey::from_hex("not hex").is_err());
+        assert!(KeyEncryptionKey::from_hex("0001").is_err());
+    }
+
+    #[test]
+    fn test_wrap_and_unwrap() {
+        let kek = KeyEncryptionKey::from_hex(KEK_1).unwrap();
+        let wrapped = kek.wrap_key(b"secret", b"election 1").unwrap();
+        assert_eq!(wrapped.key_encryption_key_id, kek.id());
+        assert!(!wrapped
+            .wrapped_key
+            .windows(b"secret".len())
+            .any(|window| window == b"secret"));
+
+        assert_eq!(kek.unwrap_key(&wrapped, b"election 1").unwrap(), b"secret");
+
+        // a wrapped key cannot be moved to another row
+        assert!(kek.unwrap_key(&wrapped, b"election 2").is_err());
+
+        // nor modified
+        let mut tampered = wrapped.clone();
+        tampered.wrapped_key[NONCE_LENGTH] ^= 1;
+        assert!(kek.unwrap_key(&tampered, b"election 1").is_err());
+
+        // nor unwrapped with another KEK
+        let other_kek = KeyEncryptionKey::from_hex(KEK_2).unwrap();
+        assert!(other_kek.unwrap_key(&wrapped, b"election 1").is_err());
+    }
+
+    #[test]
+    fn test_rotation() {
+        let old_keks = KeyEncryptionKeys::new(KeyEncryptionKey::from_hex(KEK_1).unwrap(), vec![]);
+        let wrapped = old_keks.wrap_key(b"secret", b"election 1").unwrap();
+        assert_eq!(old_keks.rewrap_key(&wrapped, b"election 1").unwrap(), None);
+
+        let new_keks = KeyEncryptionKeys::new(
+            KeyEncryptionKey::from_hex(KEK_2).unwrap(),
+            vec![KeyEncryptionKey::from_hex(KEK_1).unwrap()],
+        );
+        assert_eq!(
+            new_keks.unwrap_key(&wrapped, b"election 1").unwrap(),
+            b"secret"
+        );
+
+        let rewrapped = new_keks
+            .rewrap_key(&wrapped, b"election 1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(rewrapped.key_encryption_key_id, new_keks.current().id());
+
+        // once the old KEK is destroyed, only the rewrapped key can be unwrapped
+        let rotated_keks =
+            KeyEncryptionKeys::new(KeyEncryptionKey::from_hex(KEK_2).unwrap(), vec![]);
+        assert!(rotated_keks.unwrap_key(&wrapped, b"election 1").is_err());
+        assert_eq!(
+            rotated_keks.unwrap_key(&rewrapped, b"election 1").unwrap(),
+            b"secret"
+        );
+    }
+}
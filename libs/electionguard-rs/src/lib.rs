@@ -2,7 +2,10 @@ pub mod ballot;
 mod command;
 pub mod config;
 mod constants;
+pub mod decrypted;
+pub mod key_wrapping;
 pub mod manifest;
 pub mod mixnet;
 pub mod tally;
+pub mod tally_report;
 mod zip;
@@ -1,13 +1,22 @@
 use std::{
-    fs::{DirBuilder, File},
-    io::{self, Write},
+    fs::{read_dir, DirBuilder, File},
+    io::{self, Read, Write},
     num::NonZeroUsize,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+use types_rs::cacvote::MixPhaseVerification;
+
 use crate::{
-    command::run_electionguard_command,
-    constants::{ENCRYPTED_BALLOTS_DIRECTORY, ENCRYPTED_BALLOT_PREFIX},
+    command::{
+        command_failed_error, run_electionguard_command, run_electionguard_command_for_output,
+    },
+    config::ElectionConfig,
+    constants::{
+        DECRYPTED_BALLOT_PREFIX, ENCRYPTED_BALLOTS_DIRECTORY, ENCRYPTED_BALLOT_PREFIX,
+        MIX_DIRECTORY_PREFIX, SHUFFLED_BALLOTS_FILE,
+    },
+    decrypted::DecryptedTallyOrBallot,
     zip::{unzip_into_directory, zip_files_in_directory_to_buffer, UnzipLimits, ZipOptions},
 };
 
@@ -26,21 +35,7 @@ pub fn mix<'a>(
     let temp_dir_path = temp_dir.path();
 
     let input_directory = temp_dir_path.join("input");
-    DirBuilder::new().create(&input_directory)?;
-
-    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(public_metadata_blob))?;
-    unzip_into_directory(&mut zip, &input_directory, UnzipLimits::default())?;
-
-    let encrypted_ballots_directory = input_directory.join(ENCRYPTED_BALLOTS_DIRECTORY);
-    DirBuilder::new().create(&encrypted_ballots_directory)?;
-
-    for (index, encrypted_ballot) in encrypted_ballots.enumerate() {
-        let encrypted_ballot_path =
-            encrypted_ballots_directory.join(format!("{ENCRYPTED_BALLOT_PREFIX}{index}.json"));
-        let mut encrypted_ballot_file = File::create(&encrypted_ballot_path)?;
-        encrypted_ballot_file.write_all(encrypted_ballot)?;
-        encrypted_ballot_file.sync_all()?;
-    }
+    write_mix_input(&input_directory, public_metadata_blob, encrypted_ballots)?;
 
     let output_directory = temp_dir_path.join("output");
     DirBuilder::new().create(&output_directory)?;
@@ -48,7 +43,7 @@ pub fn mix<'a>(
     let mut input_mix_directory = None;
 
     for phase in 1..=phases.get() {
-        let mix_name = format!("mix{phase}");
+        let mix_name = format!("{MIX_DIRECTORY_PREFIX}{phase}");
         let mix_directory = output_directory.join(&mix_name);
         DirBuilder::new().create(&mix_directory)?;
         run_mixnet(
@@ -65,6 +60,245 @@ pub fn mix<'a>(
     zip_files_in_directory_to_buffer(&output_directory, ZipOptions { recursion_depth: 1 })
 }
 
+/// Checks the proof of shuffle of every phase in `shuffled_ballots`, a ZIP
+/// archive produced by [`mix`], against the phase's input: the encrypted
+/// ballots for the first phase, and the output of the phase before it for the
+/// others. `encrypted_ballots` must be given in the same order as they were to
+/// [`mix`]. Returns the result of each phase in order.
+///
+/// A phase whose proof does not hold is reported in its result rather than as
+/// an error, and the phases after it are still checked.
+///
+/// # Errors
+///
+/// Returns an error if the archive does not hold consecutively numbered mix
+/// directories starting with `mix1`, if a phase's shuffled ballots cannot be
+/// read, or if the verifier cannot be run or fails without reaching a verdict
+/// on the proof.
+pub fn verify_mix<'a>(
+    classpath: &PathBuf,
+    public_metadata_blob: &[u8],
+    encrypted_ballots: impl Iterator<Item = &'a [u8]>,
+    shuffled_ballots: &[u8],
+) -> io::Result<Vec<MixPhaseVerification>> {
+    let temp_dir = tempfile::tempdir()?;
+    let temp_dir_path = temp_dir.path();
+
+    let output_directory = temp_dir_path.join("output");
+    DirBuilder::new().create(&output_directory)?;
+
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(shuffled_ballots))?;
+    unzip_into_directory(&mut zip, &output_directory, UnzipLimits::default())?;
+    let mix_names = find_mix_names(&output_directory)?;
+
+    let input_directory = temp_dir_path.join("input");
+    write_mix_input(&input_directory, public_metadata_blob, encrypted_ballots)?;
+
+    let mut input_mix_directory = None;
+    let mut verifications = Vec::with_capacity(mix_names.len());
+
+    for mix_name in mix_names {
+        let mix_directory = output_directory.join(&mix_name);
+        let ballot_count = count_shuffled_ballots(&mix_directory)?;
+        let error = match run_verify_mixnet(
+            classpath,
+            &input_directory,
+            &mix_directory,
+            input_mix_directory.as_ref(),
+        ) {
+            Ok(()) => None,
+            Err(e @ VerifyMixnetError::ProofFailed(_)) => {
+                tracing::error!("{mix_name}: {e}");
+                Some(e.to_string())
+            }
+            Err(VerifyMixnetError::Io(e)) => return Err(e),
+        };
+
+        verifications.push(MixPhaseVerification {
+            mix_name,
+            ballot_count,
+            error,
+        });
+        input_mix_directory = Some(mix_directory);
+    }
+
+    Ok(verifications)
+}
+
+/// Decrypts the ballots output by the final phase of `shuffled_ballots`, a ZIP
+/// archive produced by [`mix`], using the election's trustee key. Returns a
+/// ZIP archive with one `dballot-*.json` file per ballot, each including the
+/// proofs that it was decrypted correctly. Use [`read_decrypted_ballots`] to
+/// read the plaintext from it.
+///
+/// The proofs of shuffle are not checked here, so [`verify_mix`] should be run
+/// first: decrypting ballots whose mix did not verify may reveal how voters
+/// voted.
+///
+/// # Errors
+///
+/// Returns an error if the archive does not hold consecutively numbered mix
+/// directories starting with `mix1`, or if the ballots cannot be decrypted.
+pub fn decrypt_mixed_ballots(
+    classpath: &PathBuf,
+    election_config: &ElectionConfig,
+    shuffled_ballots: &[u8],
+) -> io::Result<Vec<u8>> {
+    let temp_dir = tempfile::tempdir()?;
+    let temp_dir_path = temp_dir.path();
+
+    let mix_output_directory = temp_dir_path.join("mixes");
+    DirBuilder::new().create(&mix_output_directory)?;
+
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(shuffled_ballots))?;
+    unzip_into_directory(&mut zip, &mix_output_directory, UnzipLimits::default())?;
+    let mix_names = find_mix_names(&mix_output_directory)?;
+    let final_mix_directory = mix_output_directory.join(
+        mix_names
+            .last()
+            .expect("find_mix_names requires at least one phase"),
+    );
+
+    let input_directory = temp_dir_path.join("input");
+    DirBuilder::new().create(&input_directory)?;
+    let mut zip =
+        zip::ZipArchive::new(std::io::Cursor::new(&election_config.public_metadata_blob))?;
+    unzip_into_directory(&mut zip, &input_directory, UnzipLimits::default())?;
+
+    let trustees_directory = temp_dir_path.join("trustees");
+    DirBuilder::new().create(&trustees_directory)?;
+    let mut zip =
+        zip::ZipArchive::new(std::io::Cursor::new(&election_config.private_metadata_blob))?;
+    unzip_into_directory(&mut zip, &trustees_directory, UnzipLimits::default())?;
+
+    let output_directory = temp_dir_path.join("output");
+    DirBuilder::new().create(&output_directory)?;
+
+    run_decrypt_mixed_ballots(
+        classpath,
+        &input_directory,
+        &trustees_directory,
+        &final_mix_directory,
+        &output_directory,
+    )?;
+
+    zip_files_in_directory_to_buffer(&output_directory, ZipOptions { recursion_depth: 0 })
+}
+
+/// Reads the plaintext of the ballots in `decrypted_ballots`, a ZIP archive
+/// produced by [`decrypt_mixed_ballots`], ordered by file name.
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be read or holds a ballot that
+/// cannot be parsed.
+pub fn read_decrypted_ballots(decrypted_ballots: &[u8]) -> io::Result<Vec<DecryptedTallyOrBallot>> {
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(decrypted_ballots))?;
+
+    let mut file_names = zip
+        .file_names()
+        .filter(|name| name.starts_with(DECRYPTED_BALLOT_PREFIX))
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    file_names.sort();
+
+    let mut ballots = Vec::with_capacity(file_names.len());
+    for file_name in file_names {
+        let mut contents = Vec::new();
+        zip.by_name(&file_name)?.read_to_end(&mut contents)?;
+        ballots.push(serde_json::from_slice(&contents)?);
+    }
+
+    Ok(ballots)
+}
+
+/// Writes the election's public metadata and the encrypted ballots to be mixed
+/// into `input_directory` in the layout the mixnet expects.
+fn write_mix_input<'a>(
+    input_directory: &PathBuf,
+    public_metadata_blob: &[u8],
+    encrypted_ballots: impl Iterator<Item = &'a [u8]>,
+) -> io::Result<()> {
+    DirBuilder::new().create(input_directory)?;
+
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(public_metadata_blob))?;
+    unzip_into_directory(&mut zip, input_directory, UnzipLimits::default())?;
+
+    let encrypted_ballots_directory = input_directory.join(ENCRYPTED_BALLOTS_DIRECTORY);
+    DirBuilder::new().create(&encrypted_ballots_directory)?;
+
+    for (index, encrypted_ballot) in encrypted_ballots.enumerate() {
+        let encrypted_ballot_path =
+            encrypted_ballots_directory.join(format!("{ENCRYPTED_BALLOT_PREFIX}{index}.json"));
+        let mut encrypted_ballot_file = File::create(&encrypted_ballot_path)?;
+        encrypted_ballot_file.write_all(encrypted_ballot)?;
+        encrypted_ballot_file.sync_all()?;
+    }
+
+    Ok(())
+}
+
+/// Returns the names of the mix directories in `output_directory` in the order
+/// they were run, which fails unless they are named `mix1`, `mix2`, etc. with
+/// none missing.
+fn find_mix_names(output_directory: &Path) -> io::Result<Vec<String>> {
+    let mut phases = Vec::new();
+
+    for entry in read_dir(output_directory)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let phase = name
+            .to_str()
+            .and_then(|name| name.strip_prefix(MIX_DIRECTORY_PREFIX))
+            .and_then(|phase| phase.parse::<usize>().ok())
+            .filter(|_| entry.path().is_dir());
+        match phase {
+            Some(phase) => phases.push(phase),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected entry in mixnet output: {name:?}"),
+                ))
+            }
+        }
+    }
+
+    phases.sort_unstable();
+
+    if phases.is_empty()
+        || phases
+            .iter()
+            .zip(1..)
+            .any(|(phase, expected)| *phase != expected)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("mixnet output must have phases numbered from 1, found {phases:?}"),
+        ));
+    }
+
+    Ok(phases
+        .into_iter()
+        .map(|phase| format!("{MIX_DIRECTORY_PREFIX}{phase}"))
+        .collect())
+}
+
+/// Counts the ballots output by the phase in `mix_directory`.
+fn count_shuffled_ballots(mix_directory: &Path) -> io::Result<u64> {
+    let shuffled_ballots: serde_json::Value =
+        serde_json::from_reader(File::open(mix_directory.join(SHUFFLED_BALLOTS_FILE))?)?;
+
+    shuffled_ballots["rows"]
+        .as_array()
+        .map(|rows| rows.len() as u64)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{SHUFFLED_BALLOTS_FILE} in {mix_directory:?} has no rows"),
+            )
+        })
+}
+
 /// Run the mixnet command to shuffle the encrypted ballots.
 ///
 /// # Arguments
@@ -110,6 +344,150 @@ pub fn run_mixnet(
     run_electionguard_command(&mut command)
 }
 
+/// Run the mixnet verifier to check the proof of shuffle of one phase.
+///
+/// # Arguments
+///
+/// * `classpath` - The classpath to the ElectionGuard CLI JAR file.
+/// * `public_directory` - The directory containing the public election data,
+///   in particular the encrypted ballots.
+/// * `mix_directory` - The mix directory of the phase to check, as written by
+///   [`run_mixnet`].
+/// * `input_mix_directory` - The mix directory of the phase before it. If
+///   `None`, this is the first mix and the input is the public directory.
+///
+/// # Errors
+///
+/// Returns [`VerifyMixnetError::ProofFailed`] if the verifier reports that the
+/// proof does not hold, or [`VerifyMixnetError::Io`] if it could not be run or
+/// failed without reaching a verdict, e.g. because Java or the classpath is
+/// misconfigured.
+pub fn run_verify_mixnet(
+    classpath: &PathBuf,
+    public_directory: &PathBuf,
+    mix_directory: &PathBuf,
+    input_mix_directory: Option<&PathBuf>,
+) -> Result<(), VerifyMixnetError> {
+    let mut command = std::process::Command::new("java");
+
+    command
+        .arg("-classpath")
+        .arg(classpath)
+        .arg("org.cryptobiotic.mixnet.cli.RunVerifyMixnet")
+        .arg("-publicDir")
+        .arg(public_directory)
+        .arg("-out")
+        .arg(mix_directory);
+
+    if let Some(input_mix_directory) = input_mix_directory {
+        command.arg("-in").arg(input_mix_directory);
+    }
+
+    let output = run_electionguard_command_for_output(&mut command)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if let Some(verdict) = find_invalid_proof_verdict(&stdout, &stderr) {
+        return Err(VerifyMixnetError::ProofFailed(verdict.to_owned()));
+    }
+
+    if !output.status.success() {
+        return Err(VerifyMixnetError::Io(command_failed_error(&output)));
+    }
+
+    Ok(())
+}
+
+/// Why [`run_verify_mixnet`] did not verify a phase.
+#[derive(Debug)]
+pub enum VerifyMixnetError {
+    /// The verifier ran and found that the proof of shuffle does not hold,
+    /// with the line of its output saying so.
+    ProofFailed(String),
+
+    /// The verifier could not be run or did not reach a verdict.
+    Io(io::Error),
+}
+
+impl std::fmt::Display for VerifyMixnetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ProofFailed(verdict) => write!(f, "proof of shuffle did not verify: {verdict}"),
+            Self::Io(e) => write!(f, "unable to verify proof of shuffle: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyMixnetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ProofFailed(_) => None,
+            Self::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for VerifyMixnetError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Finds the verifier's verdict that a proof does not hold, e.g.
+/// `runVerifyMixnet valid = false`, among its output. Anything else, such as a
+/// stack trace from a missing class, is not a verdict on the proof.
+fn find_invalid_proof_verdict<'a>(stdout: &'a str, stderr: &'a str) -> Option<&'a str> {
+    stdout
+        .lines()
+        .chain(stderr.lines())
+        .map(str::trim)
+        .find(|line| {
+            let Some((name, value)) = line.rsplit_once('=') else {
+                return false;
+            };
+            let name = name.trim_end().to_ascii_lowercase();
+            value.trim() == "false" && (name.ends_with("valid") || name.ends_with("verify"))
+        })
+}
+
+/// Run the mixnet command to decrypt the ballots output by one phase.
+///
+/// # Arguments
+///
+/// * `classpath` - The classpath to the ElectionGuard CLI JAR file.
+/// * `public_directory` - The directory containing the public election data.
+/// * `trustees_directory` - The directory containing the trustee's private
+///   key.
+/// * `mix_directory` - The mix directory of the phase whose ballots to
+///   decrypt, as written by [`run_mixnet`].
+/// * `output_directory` - The directory to write one `dballot-*.json` file per
+///   decrypted ballot to.
+///
+/// # Errors
+///
+/// Returns an error if the decryption command fails.
+pub fn run_decrypt_mixed_ballots(
+    classpath: &PathBuf,
+    public_directory: &PathBuf,
+    trustees_directory: &PathBuf,
+    mix_directory: &PathBuf,
+    output_directory: &PathBuf,
+) -> io::Result<()> {
+    run_electionguard_command(
+        std::process::Command::new("java")
+            .arg("-classpath")
+            .arg(classpath)
+            .arg("org.cryptobiotic.mixnet.cli.RunDecryptBallots")
+            .arg("-publicDir")
+            .arg(public_directory)
+            .arg("-trustees")
+            .arg(trustees_directory)
+            .arg("-mix")
+            .arg(mix_directory)
+            .arg("-out")
+            .arg(output_directory),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use types_rs::election::ElectionDefinition;
@@ -129,6 +507,33 @@ mod tests {
         )?)
     }
 
+    #[test]
+    fn test_find_invalid_proof_verdict() {
+        assert_eq!(
+            find_invalid_proof_verdict("RunVerifyMixnet\n  runVerifyMixnet valid = false\n", ""),
+            Some("runVerifyMixnet valid = false")
+        );
+        assert_eq!(
+            find_invalid_proof_verdict("", "verify = false"),
+            Some("verify = false")
+        );
+        assert_eq!(
+            find_invalid_proof_verdict("  runVerifyMixnet valid = true\n", ""),
+            None
+        );
+        assert_eq!(
+            find_invalid_proof_verdict(
+                "",
+                "Error: Could not find or load main class org.cryptobiotic.mixnet.cli.RunVerifyMixnet"
+            ),
+            None
+        );
+        assert_eq!(
+            find_invalid_proof_verdict("", "Exception in thread \"main\" isTrusted = false"),
+            None
+        );
+    }
+
     #[test]
     fn test_mix1() {
         let Ok(classpath) = std::env::var("EG_CLASSPATH") else {
@@ -236,4 +641,199 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_verify_mix2() {
+        let Ok(classpath) = std::env::var("EG_CLASSPATH") else {
+            eprintln!("EG_CLASSPATH environment variable not set");
+            return;
+        };
+        let classpath = PathBuf::from(classpath);
+
+        let election_definition = load_election_definition().unwrap();
+        let manifest: Manifest = election_definition.election.clone().into();
+        let election_config =
+            generate_election_config(&classpath, election_definition.election.clone()).unwrap();
+
+        let encrypted_ballots = (1..=2)
+            .map(|sn| {
+                encrypt(
+                    &classpath,
+                    &election_config.public_metadata_blob,
+                    &PlaintextBallot {
+                        ballot_id: ObjectId(format!("ballot{sn}")),
+                        ballot_style: manifest.ballot_styles[0].object_id.clone(),
+                        contests: vec![],
+                        sn: Some(sn),
+                        errors: None,
+                    },
+                    "device1",
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mix = mix(
+            &classpath,
+            &election_config.public_metadata_blob,
+            encrypted_ballots.iter().map(Vec::as_slice),
+            NonZeroUsize::new(2).unwrap(),
+        )
+        .unwrap();
+
+        let verifications = verify_mix(
+            &classpath,
+            &election_config.public_metadata_blob,
+            encrypted_ballots.iter().map(Vec::as_slice),
+            &mix,
+        )
+        .unwrap();
+        assert_eq!(
+            verifications,
+            vec![
+                MixPhaseVerification {
+                    mix_name: "mix1".to_owned(),
+                    ballot_count: 2,
+                    error: None,
+                },
+                MixPhaseVerification {
+                    mix_name: "mix2".to_owned(),
+                    ballot_count: 2,
+                    error: None,
+                },
+            ]
+        );
+
+        // the proofs do not hold for different input ballots
+        let verifications = verify_mix(
+            &classpath,
+            &election_config.public_metadata_blob,
+            encrypted_ballots.iter().rev().take(1).map(Vec::as_slice),
+            &mix,
+        )
+        .unwrap();
+        assert!(!verifications[0].is_verified());
+    }
+
+    fn zip_mix_directories(mix_names: &[&str]) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut buffer));
+        for mix_name in mix_names {
+            zip.start_file(
+                format!("{mix_name}/{SHUFFLED_BALLOTS_FILE}"),
+                Default::default(),
+            )
+            .unwrap();
+            zip.write_all(br#"{"rows":[]}"#).unwrap();
+        }
+        zip.finish().unwrap();
+        drop(zip);
+        buffer
+    }
+
+    #[test]
+    fn test_decrypt_mixed_ballots() {
+        let Ok(classpath) = std::env::var("EG_CLASSPATH") else {
+            eprintln!("EG_CLASSPATH environment variable not set");
+            return;
+        };
+        let classpath = PathBuf::from(classpath);
+
+        let election_definition = load_election_definition().unwrap();
+        let manifest: Manifest = election_definition.election.clone().into();
+        let election_config =
+            generate_election_config(&classpath, election_definition.election.clone()).unwrap();
+
+        let encrypted_ballots = (1..=2)
+            .map(|sn| {
+                encrypt(
+                    &classpath,
+                    &election_config.public_metadata_blob,
+                    &PlaintextBallot {
+                        ballot_id: ObjectId(format!("ballot{sn}")),
+                        ballot_style: manifest.ballot_styles[0].object_id.clone(),
+                        contests: vec![],
+                        sn: Some(sn),
+                        errors: None,
+                    },
+                    "device1",
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mix = mix(
+            &classpath,
+            &election_config.public_metadata_blob,
+            encrypted_ballots.iter().map(Vec::as_slice),
+            NonZeroUsize::new(2).unwrap(),
+        )
+        .unwrap();
+
+        let decrypted_ballots = decrypt_mixed_ballots(&classpath, &election_config, &mix).unwrap();
+        let ballots = read_decrypted_ballots(&decrypted_ballots).unwrap();
+        assert_eq!(ballots.len(), 2);
+
+        // nothing was voted for
+        for ballot in ballots {
+            for contest in ballot.contests {
+                assert!(contest
+                    .selections
+                    .iter()
+                    .all(|selection| selection.tally == 0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_decrypted_ballots() {
+        let ballot = |id: &str| {
+            serde_json::json!({
+                "id": id,
+                "contests": [{
+                    "contest_id": "contest-mayor",
+                    "selections": [{ "selection_id": "cand-sherlock-holmes", "tally": 1 }],
+                }],
+            })
+        };
+
+        let mut buffer = Vec::new();
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut buffer));
+        for (file_name, contents) in [
+            (format!("{DECRYPTED_BALLOT_PREFIX}2.json"), ballot("b")),
+            ("other.json".to_owned(), serde_json::json!({})),
+            (format!("{DECRYPTED_BALLOT_PREFIX}1.json"), ballot("a")),
+        ] {
+            zip.start_file(file_name, Default::default()).unwrap();
+            serde_json::to_writer(&mut zip, &contents).unwrap();
+        }
+        zip.finish().unwrap();
+        drop(zip);
+
+        let ballots = read_decrypted_ballots(&buffer).unwrap();
+        assert_eq!(
+            ballots
+                .iter()
+                .map(|ballot| ballot.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(ballots[0].contests[0].selections[0].tally, 1);
+    }
+
+    #[test]
+    fn test_verify_mix_requires_consecutive_phases() {
+        // the phases are checked before the verifier is run, so no classpath
+        // is needed
+        let classpath = PathBuf::from("/nonexistent.jar");
+
+        for mix_names in [&[][..], &["mix2"], &["mix1", "mix3"], &["mix1", "other"]] {
+            let error = verify_mix(
+                &classpath,
+                &[],
+                std::iter::empty(),
+                &zip_mix_directories(mix_names),
+            )
+            .unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{mix_names:?}");
+        }
+    }
 }
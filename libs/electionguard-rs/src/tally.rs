@@ -55,6 +55,10 @@ pub fn accumulate<'a>(
 
 /// Decrypt the encrypted tally into a plaintext tally. Returns the plaintext
 /// tally file as a byte vector, but its format should be UTF-8 JSON.
+///
+/// Decrypts with the election's single guardian key in one step. Elections
+/// have no other guardians to share the decryption with; see
+/// [`crate::config::run_create_election_config`].
 pub fn decrypt(
     classpath: &PathBuf,
     election_config: &ElectionConfig,
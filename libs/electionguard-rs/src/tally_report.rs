@@ -0,0 +1,414 @@
+//! Per-contest results of a decrypted ElectionGuard tally, mapped back to the
+//! VX election the manifest was converted from.
+//!
+//! A tally only holds the sum of the votes for each selection, so how many
+//! ballots included each contest must come from elsewhere, i.e. the ballot
+//! styles the tallied ballots were encrypted with, see
+//! [`count_ballots_by_ballot_style`]. ElectionGuard counts no votes in an
+//! overvoted contest and records the overvote only in the ballot's encrypted
+//! contest data, which a tally does not include. CACvote ballots are marked on
+//! a BMD that does not allow overvotes, so the overvote count is always zero
+//! and is kept so the columns match VxAdmin's tally reports.
+
+use std::collections::HashMap;
+
+use color_eyre::eyre::{bail, ensure};
+use serde::{Deserialize, Serialize};
+use types_rs::election::{self as vx_election, BallotStyleId, ContestId};
+
+use crate::{
+    decrypted::{find_vx_contest, vx_contest_options, DecryptedTallyOrBallot},
+    manifest,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TallyReport {
+    /// How many ballots were tallied.
+    pub ballot_count: u64,
+
+    /// The results of every contest in election order.
+    pub contests: Vec<ContestResults>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContestResults {
+    pub contest_id: ContestId,
+    pub title: String,
+    pub votes_allowed: u32,
+
+    /// How many of the tallied ballots included the contest.
+    pub ballot_count: u64,
+
+    /// Always zero, since ballots are marked on a BMD. See the module docs.
+    pub overvotes: u64,
+    pub undervotes: u64,
+
+    /// The votes for each candidate or yes/no option in ballot order.
+    pub options: Vec<OptionResults>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionResults {
+    /// The ID of the candidate or yes/no option.
+    pub option_id: String,
+
+    /// The candidate's name or the yes/no option's label.
+    pub label: String,
+    pub votes: u64,
+}
+
+/// Parses the plaintext tally written by the ElectionGuard CLI, e.g.
+/// `DecryptedElectionTally.electionguard_decrypted_tally`.
+pub fn parse_decrypted_tally(
+    electionguard_decrypted_tally: &[u8],
+) -> serde_json::Result<DecryptedTallyOrBallot> {
+    serde_json::from_slice(electionguard_decrypted_tally)
+}
+
+/// The part of an encrypted ballot written by the ElectionGuard CLI, e.g.
+/// `CastBallot.electionguard_encrypted_ballot`, naming the manifest ballot
+/// style it was encrypted with.
+#[derive(Debug, Deserialize)]
+struct EncryptedBallotStyle {
+    ballot_style_id: manifest::ObjectId,
+}
+
+/// Counts `encrypted_ballots` by the ballot style of `election` each was
+/// encrypted with, for [`build_tally_report`]. These must be exactly the
+/// ballots the tally accumulated.
+pub fn count_ballots_by_ballot_style<'a>(
+    election: &vx_election::Election,
+    encrypted_ballots: impl IntoIterator<Item = &'a [u8]>,
+) -> color_eyre::Result<HashMap<BallotStyleId, u64>> {
+    let mut ballot_counts = HashMap::new();
+    for encrypted_ballot in encrypted_ballots {
+        let EncryptedBallotStyle { ballot_style_id } = serde_json::from_slice(encrypted_ballot)?;
+        let Some(ballot_style) = election.ballot_styles.iter().find(|ballot_style| {
+            manifest::ObjectId::from(ballot_style.id.clone()) == ballot_style_id
+        }) else {
+            bail!("Ballot style not found in election: {ballot_style_id:?}");
+        };
+        *ballot_counts.entry(ballot_style.id.clone()).or_default() += 1;
+    }
+    Ok(ballot_counts)
+}
+
+/// Builds the results of every contest in `election` from a decrypted tally of
+/// `ballot_counts[ballot_style_id]` ballots of each ballot style.
+pub fn build_tally_report(
+    tally: &DecryptedTallyOrBallot,
+    election: &vx_election::Election,
+    ballot_counts: &HashMap<BallotStyleId, u64>,
+) -> color_eyre::Result<TallyReport> {
+    let mut contest_ballot_counts: HashMap<&ContestId, u64> = HashMap::new();
+    for (ballot_style_id, ballot_count) in ballot_counts {
+        let Some(contests) = election.get_contests(ballot_style_id.clone()) else {
+            bail!("Ballot style not found in election: {ballot_style_id}");
+        };
+        for contest in contests {
+            *contest_ballot_counts.entry(contest.id()).or_default() += ballot_count;
+        }
+    }
+
+    let mut tallied_contests = vec![None; election.contests.len()];
+    for eg_contest in &tally.contests {
+        let Some((contest_index, _)) = find_vx_contest(election, &eg_contest.contest_id) else {
+            bail!("Contest not found in election: {:?}", eg_contest.contest_id);
+        };
+        ensure!(
+            tallied_contests[contest_index]
+                .replace(eg_contest)
+                .is_none(),
+            "Contest appears more than once in decrypted tally {}: {:?}",
+            tally.id,
+            eg_contest.contest_id
+        );
+    }
+
+    let mut contests = Vec::with_capacity(election.contests.len());
+    for (contest, eg_contest) in election.contests.iter().zip(tallied_contests) {
+        let Some(eg_contest) = eg_contest else {
+            bail!("Contest missing from decrypted tally: {}", contest.id());
+        };
+        let options = vx_contest_options(contest);
+
+        for eg_selection in &eg_contest.selections {
+            ensure!(
+                options
+                    .iter()
+                    .any(|(selection_id, _)| selection_id == &eg_selection.selection_id),
+                "Selection not found in contest {}: {:?}",
+                contest.id(),
+                eg_selection.selection_id
+            );
+        }
+
+        let mut option_results = Vec::with_capacity(options.len());
+        for (selection_id, option_id) in options {
+            let Some(eg_selection) = eg_contest
+                .selections
+                .iter()
+                .find(|eg_selection| eg_selection.selection_id == selection_id)
+            else {
+                bail!(
+                    "Selection missing from decrypted tally of contest {}: {option_id}",
+                    contest.id()
+                );
+            };
+            option_results.push(OptionResults {
+                label: option_label(contest, &option_id),
+                option_id,
+                votes: eg_selection.tally,
+            });
+        }
+
+        let votes_allowed = manifest::Contest::from(contest.clone()).votes_allowed;
+        let ballot_count = contest_ballot_counts
+            .get(contest.id())
+            .copied()
+            .unwrap_or_default();
+        let votes = option_results
+            .iter()
+            .map(|option_results| option_results.votes)
+            .sum::<u64>();
+        let Some(undervotes) = (ballot_count * u64::from(votes_allowed)).checked_sub(votes) else {
+            bail!(
+                "Contest {} has {votes} votes, more than its {ballot_count} ballots allow",
+                contest.id()
+            );
+        };
+
+        contests.push(ContestResults {
+            contest_id: contest.id().clone(),
+            title: contest_title(contest).to_owned(),
+            votes_allowed,
+            ballot_count,
+            overvotes: 0,
+            undervotes,
+            options: option_results,
+        });
+    }
+
+    Ok(TallyReport {
+        ballot_count: ballot_counts.values().sum(),
+        contests,
+    })
+}
+
+fn contest_title(contest: &vx_election::Contest) -> &str {
+    match contest {
+        vx_election::Contest::Candidate(contest) => &contest.title,
+        vx_election::Contest::YesNo(contest) => &contest.title,
+    }
+}
+
+fn option_label(contest: &vx_election::Contest, option_id: &str) -> String {
+    match contest {
+        vx_election::Contest::Candidate(contest) => contest
+            .candidates
+            .iter()
+            .find(|candidate| candidate.id.to_string() == option_id)
+            .map(|candidate| candidate.name.clone()),
+        vx_election::Contest::YesNo(contest) => [&contest.yes_option, &contest.no_option]
+            .into_iter()
+            .flatten()
+            .find(|option| option.id.to_string() == option_id)
+            .map(|option| option.label.clone()),
+    }
+    .unwrap_or_else(|| option_id.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use types_rs::election::ElectionDefinition;
+
+    use crate::decrypted::{DecryptedContest, DecryptedSelection};
+
+    use super::*;
+
+    fn load_election() -> vx_election::Election {
+        ElectionDefinition::try_from(
+            &include_bytes!("../tests/fixtures/electionFamousNames2021.json")[..],
+        )
+        .unwrap()
+        .election
+    }
+
+    /// A tally giving every option of every contest `votes` votes.
+    fn tally(election: &vx_election::Election, votes: u64) -> DecryptedTallyOrBallot {
+        DecryptedTallyOrBallot {
+            id: "tally".to_owned(),
+            contests: election
+                .contests
+                .iter()
+                .rev()
+                .map(|contest| DecryptedContest {
+                    contest_id: contest.id().clone().into(),
+                    selections: vx_contest_options(contest)
+                        .into_iter()
+                        .map(|(selection_id, _)| DecryptedSelection {
+                            selection_id,
+                            tally: votes,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_build_tally_report() {
+        let election = load_election();
+        let ballot_style_id = election.ballot_styles[0].id.clone();
+        let ballot_counts = HashMap::from([(ballot_style_id, 10)]);
+
+        let report = build_tally_report(&tally(&election, 2), &election, &ballot_counts).unwrap();
+
+        assert_eq!(report.ballot_count, 10);
+        assert_eq!(
+            report
+                .contests
+                .iter()
+                .map(|contest| &contest.contest_id)
+                .collect::<Vec<_>>(),
+            election
+                .contests
+                .iter()
+                .map(vx_election::Contest::id)
+                .collect::<Vec<_>>(),
+            "contests are in election order"
+        );
+
+        let vx_election::Contest::Candidate(mayor) = &election.contests[0] else {
+            panic!("expected a candidate contest");
+        };
+        assert_eq!(
+            report.contests[0],
+            ContestResults {
+                contest_id: mayor.id.clone(),
+                title: mayor.title.clone(),
+                votes_allowed: 1,
+                ballot_count: 10,
+                overvotes: 0,
+                // 10 ballots, 2 votes for each of 2 candidates
+                undervotes: 6,
+                options: mayor
+                    .candidates
+                    .iter()
+                    .map(|candidate| OptionResults {
+                        option_id: candidate.id.to_string(),
+                        label: candidate.name.clone(),
+                        votes: 2,
+                    })
+                    .collect(),
+            }
+        );
+
+        let city_council = report
+            .contests
+            .iter()
+            .find(|contest| contest.contest_id.to_string() == "city-council")
+            .unwrap();
+        assert_eq!(city_council.votes_allowed, 4);
+        // 10 ballots with 4 votes each, 2 votes for each of 9 candidates
+        assert_eq!(city_council.undervotes, 40 - 18);
+    }
+
+    #[test]
+    fn test_build_tally_report_mismatched_tally() {
+        let election = load_election();
+        let ballot_style_id = election.ballot_styles[0].id.clone();
+        let ballot_counts = HashMap::from([(ballot_style_id, 1)]);
+
+        // more votes than ballots
+        assert!(build_tally_report(&tally(&election, 2), &election, &ballot_counts).is_err());
+
+        // a contest is missing
+        let mut missing_contest = tally(&election, 0);
+        missing_contest.contests.pop();
+        assert!(build_tally_report(&missing_contest, &election, &ballot_counts).is_err());
+
+        // a selection is missing
+        let mut missing_selection = tally(&election, 0);
+        missing_selection.contests[0].selections.pop();
+        assert!(build_tally_report(&missing_selection, &election, &ballot_counts).is_err());
+
+        // a contest is not in the election
+        let mut unknown_contest = tally(&election, 0);
+        unknown_contest.contests[0].contest_id = manifest::ObjectId("unknown".to_owned());
+        assert!(build_tally_report(&unknown_contest, &election, &ballot_counts).is_err());
+
+        // a ballot style is not in the election
+        assert!(build_tally_report(
+            &tally(&election, 0),
+            &election,
+            &HashMap::from([(BallotStyleId::from("unknown".to_owned()), 1)])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_count_ballots_by_ballot_style() {
+        let election = load_election();
+        let encrypted_ballot = |ballot_style_id: manifest::ObjectId| {
+            serde_json::json!({
+                "ballot_id": "ballot",
+                "ballot_style_id": ballot_style_id,
+                "encrypting_device": "device",
+                "contests": [],
+            })
+            .to_string()
+            .into_bytes()
+        };
+        let ballot_style_id = election.ballot_styles[0].id.clone();
+        let encrypted_ballots = [
+            encrypted_ballot(ballot_style_id.clone().into()),
+            encrypted_ballot(ballot_style_id.clone().into()),
+        ];
+
+        assert_eq!(
+            count_ballots_by_ballot_style(&election, encrypted_ballots.iter().map(Vec::as_slice))
+                .unwrap(),
+            HashMap::from([(ballot_style_id, 2)])
+        );
+        assert!(count_ballots_by_ballot_style(&election, [])
+            .unwrap()
+            .is_empty());
+
+        // a ballot style is not in the election
+        let unknown_ballot_style =
+            encrypted_ballot(BallotStyleId::from("unknown".to_owned()).into());
+        assert!(
+            count_ballots_by_ballot_style(&election, [unknown_ballot_style.as_slice()]).is_err()
+        );
+
+        // not an encrypted ballot
+        assert!(count_ballots_by_ballot_style(&election, [b"{}".as_slice()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_decrypted_tally() {
+        let tally = parse_decrypted_tally(
+            br#"{
+                "id": "tally",
+                "election_id": "0A",
+                "contests": [{
+                    "contest_id": "mayor",
+                    "selections": [{
+                        "selection_id": "sherlock-holmes",
+                        "tally": 3,
+                        "b_over_m": "0B",
+                        "encrypted_vote": { "pad": "0C", "data": "0D" },
+                        "proof": { "challenge": "0E", "response": "0F" }
+                    }]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(tally.contests[0].selections[0].tally, 3);
+    }
+}
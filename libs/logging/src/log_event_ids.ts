@@ -140,6 +140,21 @@ export enum LogEventId {
   NoPid = 'no-pid',
   SignedHashValidationInit = 'signed-hash-validation-init',
   SignedHashValidationComplete = 'signed-hash-validation-complete',
+  CacvoteElectionCreated = 'cacvote-election-created',
+  CacvoteRegistrationCreated = 'cacvote-registration-created',
+  CacvoteRegistrationRejected = 'cacvote-registration-rejected',
+  CacvoteRegistrationsImported = 'cacvote-registrations-imported',
+  CacvoteRegistrationsExported = 'cacvote-registrations-exported',
+  CacvoteStreetSegmentsUpdated = 'cacvote-street-segments-updated',
+  CacvoteElectionLifecycleChanged = 'cacvote-election-lifecycle-changed',
+  CacvoteEncryptedTallyGenerated = 'cacvote-encrypted-tally-generated',
+  CacvoteTallyDecrypted = 'cacvote-tally-decrypted',
+  CacvoteEncryptedBallotsMixed = 'cacvote-encrypted-ballots-mixed',
+  CacvoteMixedBallotsVerified = 'cacvote-mixed-ballots-verified',
+  CacvoteMixedBallotsDecrypted = 'cacvote-mixed-ballots-decrypted',
+  CacvoteTransferBundleImported = 'cacvote-transfer-bundle-imported',
+  CacvoteTransferBundleExported = 'cacvote-transfer-bundle-exported',
+  CacvoteAuditLogExported = 'cacvote-audit-log-exported',
 }
 
 const ElectionConfigured: LogDetails = {
@@ -1091,6 +1106,111 @@ const SignedHashValidationComplete: LogDetails = {
   defaultMessage: 'Signed hash validation completed.',
 };
 
+const CacvoteElectionCreated: LogDetails = {
+  eventId: LogEventId.CacvoteElectionCreated,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user created and signed a new election on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteRegistrationCreated: LogDetails = {
+  eventId: LogEventId.CacvoteRegistrationCreated,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user approved a voter registration request on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteRegistrationRejected: LogDetails = {
+  eventId: LogEventId.CacvoteRegistrationRejected,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user rejected a voter registration request on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteRegistrationsImported: LogDetails = {
+  eventId: LogEventId.CacvoteRegistrationsImported,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user imported voter registrations in bulk on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteRegistrationsExported: LogDetails = {
+  eventId: LogEventId.CacvoteRegistrationsExported,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user exported the voter registrations on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteStreetSegmentsUpdated: LogDetails = {
+  eventId: LogEventId.CacvoteStreetSegmentsUpdated,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    "A user replaced the street segments used to find voters' precincts on the CACvote JX terminal. Success or failure indicated by disposition.",
+};
+
+const CacvoteElectionLifecycleChanged: LogDetails = {
+  eventId: LogEventId.CacvoteElectionLifecycleChanged,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user opened voting, closed voting or certified the results for an election on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteEncryptedTallyGenerated: LogDetails = {
+  eventId: LogEventId.CacvoteEncryptedTallyGenerated,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user generated the encrypted tally for an election on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteTallyDecrypted: LogDetails = {
+  eventId: LogEventId.CacvoteTallyDecrypted,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user decrypted the encrypted tally for an election on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteEncryptedBallotsMixed: LogDetails = {
+  eventId: LogEventId.CacvoteEncryptedBallotsMixed,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user mixed the encrypted ballots for an election on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteMixedBallotsVerified: LogDetails = {
+  eventId: LogEventId.CacvoteMixedBallotsVerified,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user verified the proofs of shuffle for the mixed ballots of an election on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteMixedBallotsDecrypted: LogDetails = {
+  eventId: LogEventId.CacvoteMixedBallotsDecrypted,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user decrypted the mixed ballots of an election on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteTransferBundleImported: LogDetails = {
+  eventId: LogEventId.CacvoteTransferBundleImported,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user imported a transfer bundle on the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteTransferBundleExported: LogDetails = {
+  eventId: LogEventId.CacvoteTransferBundleExported,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user exported a transfer bundle from the CACvote JX terminal. Success or failure indicated by disposition.',
+};
+
+const CacvoteAuditLogExported: LogDetails = {
+  eventId: LogEventId.CacvoteAuditLogExported,
+  eventType: LogEventType.UserAction,
+  documentationMessage:
+    'A user exported the operator audit log from the CACvote JX terminal.',
+};
+
 export function getDetailsForEventId(eventId: LogEventId): LogDetails {
   switch (eventId) {
     case LogEventId.ElectionConfigured:
@@ -1349,6 +1469,36 @@ export function getDetailsForEventId(eventId: LogEventId): LogDetails {
       return SignedHashValidationInit;
     case LogEventId.SignedHashValidationComplete:
       return SignedHashValidationComplete;
+    case LogEventId.CacvoteElectionCreated:
+      return CacvoteElectionCreated;
+    case LogEventId.CacvoteRegistrationCreated:
+      return CacvoteRegistrationCreated;
+    case LogEventId.CacvoteRegistrationRejected:
+      return CacvoteRegistrationRejected;
+    case LogEventId.CacvoteRegistrationsImported:
+      return CacvoteRegistrationsImported;
+    case LogEventId.CacvoteRegistrationsExported:
+      return CacvoteRegistrationsExported;
+    case LogEventId.CacvoteStreetSegmentsUpdated:
+      return CacvoteStreetSegmentsUpdated;
+    case LogEventId.CacvoteElectionLifecycleChanged:
+      return CacvoteElectionLifecycleChanged;
+    case LogEventId.CacvoteEncryptedTallyGenerated:
+      return CacvoteEncryptedTallyGenerated;
+    case LogEventId.CacvoteTallyDecrypted:
+      return CacvoteTallyDecrypted;
+    case LogEventId.CacvoteEncryptedBallotsMixed:
+      return CacvoteEncryptedBallotsMixed;
+    case LogEventId.CacvoteMixedBallotsVerified:
+      return CacvoteMixedBallotsVerified;
+    case LogEventId.CacvoteMixedBallotsDecrypted:
+      return CacvoteMixedBallotsDecrypted;
+    case LogEventId.CacvoteTransferBundleImported:
+      return CacvoteTransferBundleImported;
+    case LogEventId.CacvoteTransferBundleExported:
+      return CacvoteTransferBundleExported;
+    case LogEventId.CacvoteAuditLogExported:
+      return CacvoteAuditLogExported;
     /* istanbul ignore next - compile time check for completeness */
     default:
       throwIllegalValue(eventId);
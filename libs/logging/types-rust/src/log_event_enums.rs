@@ -265,4 +265,34 @@ pub enum EventId {
     SignedHashValidationInit,
     #[serde(rename = "signed-hash-validation-complete")]
     SignedHashValidationComplete,
+    #[serde(rename = "cacvote-election-created")]
+    CacvoteElectionCreated,
+    #[serde(rename = "cacvote-registration-created")]
+    CacvoteRegistrationCreated,
+    #[serde(rename = "cacvote-registration-rejected")]
+    CacvoteRegistrationRejected,
+    #[serde(rename = "cacvote-registrations-imported")]
+    CacvoteRegistrationsImported,
+    #[serde(rename = "cacvote-registrations-exported")]
+    CacvoteRegistrationsExported,
+    #[serde(rename = "cacvote-street-segments-updated")]
+    CacvoteStreetSegmentsUpdated,
+    #[serde(rename = "cacvote-election-lifecycle-changed")]
+    CacvoteElectionLifecycleChanged,
+    #[serde(rename = "cacvote-encrypted-tally-generated")]
+    CacvoteEncryptedTallyGenerated,
+    #[serde(rename = "cacvote-tally-decrypted")]
+    CacvoteTallyDecrypted,
+    #[serde(rename = "cacvote-encrypted-ballots-mixed")]
+    CacvoteEncryptedBallotsMixed,
+    #[serde(rename = "cacvote-mixed-ballots-verified")]
+    CacvoteMixedBallotsVerified,
+    #[serde(rename = "cacvote-mixed-ballots-decrypted")]
+    CacvoteMixedBallotsDecrypted,
+    #[serde(rename = "cacvote-transfer-bundle-imported")]
+    CacvoteTransferBundleImported,
+    #[serde(rename = "cacvote-transfer-bundle-exported")]
+    CacvoteTransferBundleExported,
+    #[serde(rename = "cacvote-audit-log-exported")]
+    CacvoteAuditLogExported,
 }
@@ -5,6 +5,12 @@ use crate::{log_event_enums::EventId, APP_NAME};
 
 #[derive(Serialize, Deserialize)]
 pub enum EventType {
+    #[serde(rename = "user-action")]
+    UserAction,
+    #[serde(rename = "application-status")]
+    ApplicationStatus,
+    #[serde(rename = "application-action")]
+    ApplicationAction,
     #[serde(rename = "system-action")]
     SystemAction,
     #[serde(rename = "system-status")]
@@ -44,6 +50,11 @@ pub enum User {
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
 pub struct Log {
+    /// When the logged event happened, as an ISO 8601 timestamp. Logs printed
+    /// as they happen leave this unset and are timestamped when written;
+    /// logs exported from a stored record set it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_log_written: Option<String>,
     pub source: String,
     pub event_id: EventId,
     pub message: String,
@@ -56,6 +67,7 @@ impl Default for Log {
     fn default() -> Self {
         let app_name = APP_NAME.get().expect("App name is not initialized").clone();
         Self {
+            time_log_written: None,
             source: app_name,
             user: User::System,
             event_id: EventId::Unspecified,
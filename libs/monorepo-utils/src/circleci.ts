@@ -62,11 +62,17 @@ function* generateTestJobForNodeJsPackage(
 }
 
 /* istanbul ignore next */
-function* generateTestJobForRustCrate(crate: CargoCrate): Iterable<string> {
+function* generateTestJobForRustCrate(
+  crate: CargoCrate,
+  ciConfig: CargoCiConfig = {}
+): Iterable<string> {
   const hasDatabase = existsSync(join(crate.absolutePath, 'db/migrations'));
   const databaseUrl = hasDatabase
     ? `postgresql://root@localhost:5432/${crate.name}`
     : '';
+  const featuresFlag = ciConfig.features?.length
+    ? ` --features ${ciConfig.features.join(',')}`
+    : '';
 
   yield `${jobIdForRustCrate(crate)}:\n`;
   yield `  executor: ${hasDatabase ? 'rust-db' : 'nodejs'}\n`;
@@ -85,6 +91,14 @@ function* generateTestJobForRustCrate(crate: CargoCrate): Iterable<string> {
     yield `          cargo sqlx database reset -y --source=db/migrations\n`;
   }
 
+  if (ciConfig.setup) {
+    // the setup script prints `export` lines for the environment it set up
+    yield `    - run:\n`;
+    yield `        name: Setup\n`;
+    yield `        command: |\n`;
+    yield `          '${join(crate.workspacePath, ciConfig.setup)}' >> $BASH_ENV\n`;
+  }
+
   yield `    - run:\n`;
   yield `        name: Build\n`;
   if (hasDatabase) {
@@ -92,7 +106,7 @@ function* generateTestJobForRustCrate(crate: CargoCrate): Iterable<string> {
     yield `          DATABASE_URL: ${databaseUrl}\n`;
   }
   yield `        command: |\n`;
-  yield `          cargo build -p ${crate.name}\n`;
+  yield `          cargo build -p ${crate.name}${featuresFlag}\n`;
   yield `    - run:\n`;
   yield `        name: Test\n`;
   if (hasDatabase) {
@@ -100,7 +114,16 @@ function* generateTestJobForRustCrate(crate: CargoCrate): Iterable<string> {
     yield `          DATABASE_URL: ${databaseUrl}\n`;
   }
   yield `        command: |\n`;
-  yield `          cargo test -p ${crate.name}\n`;
+  yield `          cargo test -p ${crate.name}${featuresFlag}\n`;
+
+  const ignoredTests = ciConfig['ignored-tests'] ?? [];
+  if (ignoredTests.length > 0) {
+    const testNames = ignoredTests.join(' ');
+    yield `    - run:\n`;
+    yield `        name: Test Ignored\n`;
+    yield `        command: |\n`;
+    yield `          cargo test -p ${crate.name}${featuresFlag} -- --ignored ${testNames}\n`;
+  }
 }
 
 function* generateTestJobForPackage(pkg: PnpmPackageInfo): Iterable<string> {
@@ -120,16 +143,31 @@ export const CIRCLECI_CONFIG_PATH = join(
   '../../../.circleci/config.yml'
 );
 
+const CargoCiConfigSchema = z.object({
+  /** Set to `false` to skip the crate in CI. */
+  enabled: z.boolean().optional(),
+  /** Features to build and test the crate with. */
+  features: z.array(z.string()).optional(),
+  /**
+   * Script, relative to the crate, that sets up anything the tests need and
+   * prints `export` lines for the environment the tests should run with.
+   */
+  setup: z.string().optional(),
+  /**
+   * Tests marked `#[ignore]` to run anyway, usually ones that need what
+   * `setup` provides.
+   */
+  'ignored-tests': z.array(z.string()).optional(),
+});
+
+type CargoCiConfig = z.infer<typeof CargoCiConfigSchema>;
+
 const CargoTomlSchema = z.object({
   package: z
     .object({
       metadata: z
         .object({
-          ci: z
-            .object({
-              enabled: z.boolean(),
-            })
-            .optional(),
+          ci: CargoCiConfigSchema.optional(),
         })
         .optional(),
     })
@@ -163,7 +201,10 @@ export function* generateConfig(
       return memo;
     }
 
-    const jobLines = generateTestJobForRustCrate(crate);
+    const jobLines = generateTestJobForRustCrate(
+      crate,
+      cargoToml.package?.metadata?.ci
+    );
     return memo.set(crate, jobLines);
   }, new Map<CargoCrate, Iterable<string>>());
   const jobIds = [
@@ -77,3 +77,16 @@ pub enum User {
     #[serde(rename = "cardless_voter")]
     CardlessVoter(CardlessVoterUser),
 }
+
+impl User {
+    /// The jurisdiction the user's card is for, if any.
+    #[must_use]
+    pub const fn jurisdiction_code(&self) -> Option<&JurisdictionCode> {
+        match self {
+            Self::SystemAdministrator(user) => Some(&user.jurisdiction),
+            Self::ElectionManager(user) => Some(&user.jurisdiction),
+            Self::PollWorker(user) => Some(&user.jurisdiction),
+            Self::CardlessVoter(_) => None,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Decrypting an election's mixed ballots into individual plaintext ballots.
+//!
+//! Once the cast ballots have been shuffled through the mixnet and the proofs
+//! of shuffle verified, the ballots output by the final phase can no longer be
+//! linked to the voters who cast them. Decrypting each of them with the
+//! election's trustee key gives [`AnonymousBallot`]s, which can be published
+//! for anyone to count without revealing how any voter voted.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::election::ContestId;
+
+use super::{Base64Standard, JurisdictionCode, JurisdictionScoped};
+
+/// A decrypted ballot that cannot be linked to the voter who cast it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnonymousBallot {
+    /// The contests on the ballot in election order.
+    pub contests: Vec<AnonymousBallotContest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnonymousBallotContest {
+    pub contest_id: ContestId,
+
+    /// Every selection in the contest in ballot order, voted for or not.
+    pub selections: Vec<AnonymousBallotSelection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnonymousBallotSelection {
+    /// The ID of the candidate or yes/no option.
+    pub option_id: String,
+    pub vote: u32,
+}
+
+/// The ballots output by the final phase of a [`ShuffledEncryptedCastBallots`]
+/// object, decrypted and mapped back to the election's contests.
+///
+/// [`ShuffledEncryptedCastBallots`]: super::ShuffledEncryptedCastBallots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptedShuffledBallots {
+    pub jurisdiction_code: JurisdictionCode,
+    pub election_object_id: Uuid,
+
+    /// The object whose ballots were decrypted.
+    pub shuffled_encrypted_cast_ballots_object_id: Uuid,
+
+    /// The decrypted ballots in the order the final phase output them.
+    pub ballots: Vec<AnonymousBallot>,
+
+    /// A ZIP archive of the ballots as decrypted by ElectionGuard, including
+    /// the proofs that each was decrypted correctly.
+    #[serde(with = "Base64Standard")]
+    pub electionguard_decrypted_ballots: Vec<u8>,
+}
+
+impl DecryptedShuffledBallots {
+    pub fn election_object_id_field_name() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `DecryptedShuffledBallots` struct.
+        "electionObjectId"
+    }
+}
+
+impl JurisdictionScoped for DecryptedShuffledBallots {
+    fn jurisdiction_code(&self) -> JurisdictionCode {
+        self.jurisdiction_code.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptedShuffledBallotsPresenter {
+    #[serde(flatten)]
+    pub decrypted_shuffled_ballots: DecryptedShuffledBallots,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub synced_at: Option<OffsetDateTime>,
+}
+
+/// Asks the JX to decrypt an election's mixed ballots.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptMixedBallotsRequest {
+    /// PIN of the official's card, required to unwrap the election's private
+    /// key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialization() {
+        let decrypted_shuffled_ballots = DecryptedShuffledBallots {
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            election_object_id: Uuid::new_v4(),
+            shuffled_encrypted_cast_ballots_object_id: Uuid::new_v4(),
+            ballots: vec![AnonymousBallot {
+                contests: vec![AnonymousBallotContest {
+                    contest_id: ContestId::from("mayor".to_owned()),
+                    selections: vec![AnonymousBallotSelection {
+                        option_id: "sherlock-holmes".to_owned(),
+                        vote: 1,
+                    }],
+                }],
+            }],
+            electionguard_decrypted_ballots: b"zip".to_vec(),
+        };
+
+        let json = serde_json::to_value(&decrypted_shuffled_ballots).unwrap();
+        assert_eq!(
+            json["ballots"][0],
+            serde_json::json!({
+                "contests": [{
+                    "contestId": "mayor",
+                    "selections": [{ "optionId": "sherlock-holmes", "vote": 1 }],
+                }],
+            })
+        );
+        assert_eq!(json["electionguardDecryptedBallots"], "emlw");
+        assert_eq!(
+            serde_json::from_value::<DecryptedShuffledBallots>(json).unwrap(),
+            decrypted_shuffled_ballots
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Certificate chain verification for signed objects.
+//!
+//! CAC certificates are issued by DoD intermediate CAs rather than directly by
+//! a root, so verifying one means building a chain through those
+//! intermediates up to a trusted root. Each link must be within its validity
+//! period and permitted to be used the way it is being used: CAs to issue
+//! certificates and the signing certificate to sign.
+
+use std::fmt;
+
+use openssl::{
+    error::ErrorStack,
+    stack::Stack,
+    x509::{
+        store::{X509Store, X509StoreBuilder, X509StoreRef},
+        X509PurposeId, X509Ref, X509StoreContext, X509StoreContextRef, X509,
+    },
+};
+
+/// Why a signed object or certificate failed verification.
+#[derive(Debug)]
+pub enum VerificationError {
+    /// The payload could not be decoded.
+    InvalidPayload(serde_json::Error),
+
+    /// The signature was not made with the signing certificate's key.
+    SignatureMismatch,
+
+    /// A link in the signing certificate's chain is invalid.
+    CertificateChain(CertificateChainError),
+
+    /// OpenSSL failed while performing the verification.
+    OpenSsl(ErrorStack),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPayload(e) => write!(f, "invalid payload: {e}"),
+            Self::SignatureMismatch => {
+                write!(f, "signature does not match the signing certificate")
+            }
+            Self::CertificateChain(e) => write!(f, "{e}"),
+            Self::OpenSsl(e) => write!(f, "error verifying signature: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidPayload(e) => Some(e),
+            Self::SignatureMismatch => None,
+            Self::CertificateChain(e) => Some(e),
+            Self::OpenSsl(e) => Some(e),
+        }
+    }
+}
+
+impl From<ErrorStack> for VerificationError {
+    fn from(e: ErrorStack) -> Self {
+        Self::OpenSsl(e)
+    }
+}
+
+impl From<CertificateChainError> for VerificationError {
+    fn from(e: CertificateChainError) -> Self {
+        Self::CertificateChain(e)
+    }
+}
+
+/// Describes the link in a certificate chain that failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateChainError {
+    /// Position of the failing certificate in the chain. The signing
+    /// certificate is at depth 0, its issuer at depth 1, and so on.
+    pub depth: u32,
+
+    /// Subject of the failing certificate, if OpenSSL got as far as finding
+    /// it.
+    pub subject: Option<String>,
+
+    /// Why the certificate was rejected, e.g. "certificate has expired".
+    pub reason: String,
+}
+
+impl CertificateChainError {
+    fn from_context(context: &X509StoreContextRef) -> Self {
+        Self {
+            depth: context.error_depth(),
+            subject: context.current_cert().map(describe_subject),
+            reason: context.error().error_string().to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for CertificateChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.depth {
+            0 => write!(f, "signing certificate")?,
+            depth => write!(f, "issuer certificate at depth {depth}")?,
+        }
+
+        if let Some(subject) = &self.subject {
+            write!(f, " ({subject})")?;
+        }
+
+        write!(f, " failed verification: {}", self.reason)
+    }
+}
+
+impl std::error::Error for CertificateChainError {}
+
+/// Formats a certificate's subject as comma-separated `name=value` pairs.
+fn describe_subject(certificate: &X509Ref) -> String {
+    certificate
+        .subject_name()
+        .entries()
+        .map(|entry| {
+            let name = entry.object().nid().short_name().map_or_else(
+                |_| entry.object().to_string(),
+                std::borrow::ToOwned::to_owned,
+            );
+            let value = entry
+                .data()
+                .as_utf8()
+                .map_or_else(|_| "?".to_owned(), |value| value.to_string());
+            format!("{name}={value}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The certificates used to verify certificates presented by CACs: trusted
+/// roots, and intermediates that may link a certificate to them.
+pub struct CacCaStore {
+    roots: X509Store,
+    intermediates: Vec<X509>,
+}
+
+impl fmt::Debug for CacCaStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacCaStore")
+            .field("intermediates", &self.intermediates.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds the store used to verify certificates presented by CACs.
+///
+/// Only `root_certs` are trusted. `intermediate_certs` are available for
+/// building chains up to them but are never trusted on their own. Signing
+/// certificates must permit digital signatures wherever they restrict key
+/// usage or extended key usage.
+pub fn build_cac_ca_store(
+    root_certs: impl IntoIterator<Item = X509>,
+    intermediate_certs: impl IntoIterator<Item = X509>,
+) -> Result<CacCaStore, ErrorStack> {
+    let mut builder = X509StoreBuilder::new()?;
+    builder.set_purpose(X509PurposeId::SMIME_SIGN)?;
+    for cert in root_certs {
+        builder.add_cert(cert)?;
+    }
+
+    Ok(CacCaStore {
+        roots: builder.build(),
+        intermediates: intermediate_certs.into_iter().collect(),
+    })
+}
+
+/// Verifies that `certificate` chains up to a trusted root in `ca_store`,
+/// using `intermediates` in addition to the store's own intermediates.
+pub fn verify_cert(
+    ca_store: &CacCaStore,
+    certificate: &X509Ref,
+    intermediates: &[X509],
+) -> Result<(), VerificationError> {
+    let intermediates = intermediates.iter().chain(&ca_store.intermediates);
+    verify_cert_with_store(&ca_store.roots, certificate, intermediates)
+}
+
+/// Verifies that `certificate` chains up to a certificate in `store`.
+/// `intermediates` are only used to build the chain and are never trusted.
+fn verify_cert_with_store<'a>(
+    store: &X509StoreRef,
+    certificate: &X509Ref,
+    intermediates: impl IntoIterator<Item = &'a X509>,
+) -> Result<(), VerificationError> {
+    let mut untrusted = Stack::new()?;
+    for intermediate in intermediates {
+        untrusted.push(intermediate.clone())?;
+    }
+
+    let mut context = X509StoreContext::new()?;
+    context.init(store, certificate, &untrusted, |context| {
+        Ok(if context.verify_cert()? {
+            Ok(())
+        } else {
+            Err(CertificateChainError::from_context(context))
+        })
+    })??;
+
+    Ok(())
+}
+
+/// Verifies that `certificate` was issued by `ca_cert`.
+pub fn verify_cert_single_ca(ca_cert: &X509, certificate: &X509) -> Result<(), VerificationError> {
+    let mut builder = X509StoreBuilder::new()?;
+    builder.add_cert(ca_cert.clone())?;
+    verify_cert_with_store(&builder.build(), certificate, [])
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{
+        asn1::Asn1Time,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{PKey, Private},
+        x509::{
+            extension::{BasicConstraints, ExtendedKeyUsage, KeyUsage},
+            X509Builder, X509NameBuilder,
+        },
+    };
+
+    use super::*;
+
+    struct Issued {
+        cert: X509,
+        key: PKey<Private>,
+    }
+
+    enum Kind {
+        Ca,
+        Signing,
+        ServerAuth,
+    }
+
+    fn issue(
+        common_name: &str,
+        kind: Kind,
+        issuer: Option<&Issued>,
+        not_after: &Asn1Time,
+    ) -> Issued {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, common_name)
+            .unwrap();
+        let name = name.build();
+
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::from_unix(0).unwrap())
+            .unwrap();
+        builder.set_not_after(not_after).unwrap();
+
+        match kind {
+            Kind::Ca => {
+                builder
+                    .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                    .unwrap();
+                builder
+                    .append_extension(
+                        KeyUsage::new()
+                            .critical()
+                            .key_cert_sign()
+                            .crl_sign()
+                            .build()
+                            .unwrap(),
+                    )
+                    .unwrap();
+            }
+            Kind::Signing => {
+                builder
+                    .append_extension(
+                        KeyUsage::new()
+                            .critical()
+                            .digital_signature()
+                            .non_repudiation()
+                            .build()
+                            .unwrap(),
+                    )
+                    .unwrap();
+                builder
+                    .append_extension(ExtendedKeyUsage::new().email_protection().build().unwrap())
+                    .unwrap();
+            }
+            Kind::ServerAuth => {
+                builder
+                    .append_extension(
+                        KeyUsage::new()
+                            .critical()
+                            .key_encipherment()
+                            .build()
+                            .unwrap(),
+                    )
+                    .unwrap();
+                builder
+                    .append_extension(ExtendedKeyUsage::new().server_auth().build().unwrap())
+                    .unwrap();
+            }
+        }
+
+        match issuer {
+            Some(issuer) => {
+                builder.set_issuer_name(issuer.cert.subject_name()).unwrap();
+                builder.sign(&issuer.key, MessageDigest::sha256()).unwrap();
+            }
+            None => {
+                builder.set_issuer_name(&name).unwrap();
+                builder.sign(&key, MessageDigest::sha256()).unwrap();
+            }
+        }
+
+        Issued {
+            cert: builder.build(),
+            key,
+        }
+    }
+
+    fn next_year() -> Asn1Time {
+        Asn1Time::days_from_now(365).unwrap()
+    }
+
+    fn last_year() -> Asn1Time {
+        Asn1Time::from_unix(time::OffsetDateTime::now_utc().unix_timestamp() - 365 * 24 * 60 * 60)
+            .unwrap()
+    }
+
+    fn chain_error(result: Result<(), VerificationError>) -> CertificateChainError {
+        match result {
+            Err(VerificationError::CertificateChain(e)) => e,
+            other => panic!("expected a certificate chain error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_verify_cert_through_intermediate() {
+        let root = issue("Root CA", Kind::Ca, None, &next_year());
+        let intermediate = issue("Email CA", Kind::Ca, Some(&root), &next_year());
+        let leaf = issue(
+            "DOE.JANE.QUINN.1234567890",
+            Kind::Signing,
+            Some(&intermediate),
+            &next_year(),
+        );
+
+        // chain provided by the signed object
+        let store = build_cac_ca_store([root.cert.clone()], []).unwrap();
+        verify_cert(&store, &leaf.cert, std::slice::from_ref(&intermediate.cert)).unwrap();
+
+        // chain built from the configured intermediates
+        let store = build_cac_ca_store([root.cert.clone()], [intermediate.cert.clone()]).unwrap();
+        verify_cert(&store, &leaf.cert, &[]).unwrap();
+
+        // chain cannot be built at all
+        let store = build_cac_ca_store([root.cert.clone()], []).unwrap();
+        let error = chain_error(verify_cert(&store, &leaf.cert, &[]));
+        assert_eq!(error.depth, 0);
+        assert_eq!(
+            error.subject.as_deref(),
+            Some("CN=DOE.JANE.QUINN.1234567890")
+        );
+        assert_eq!(
+            error.to_string(),
+            "signing certificate (CN=DOE.JANE.QUINN.1234567890) failed verification: unable to get local issuer certificate"
+        );
+    }
+
+    #[test]
+    fn test_intermediates_are_not_trusted() {
+        let root = issue("Root CA", Kind::Ca, None, &next_year());
+        let intermediate = issue("Email CA", Kind::Ca, Some(&root), &next_year());
+        let leaf = issue(
+            "DOE.JANE.QUINN.1234567890",
+            Kind::Signing,
+            Some(&intermediate),
+            &next_year(),
+        );
+
+        let store = build_cac_ca_store([], [intermediate.cert]).unwrap();
+        let error = chain_error(verify_cert(&store, &leaf.cert, &[]));
+        assert_eq!(error.depth, 1);
+        assert_eq!(error.subject.as_deref(), Some("CN=Email CA"));
+    }
+
+    #[test]
+    fn test_expired_intermediate() {
+        let root = issue("Root CA", Kind::Ca, None, &next_year());
+        let intermediate = issue("Email CA", Kind::Ca, Some(&root), &last_year());
+        let leaf = issue(
+            "DOE.JANE.QUINN.1234567890",
+            Kind::Signing,
+            Some(&intermediate),
+            &next_year(),
+        );
+
+        let store = build_cac_ca_store([root.cert], [intermediate.cert]).unwrap();
+        assert_eq!(
+            chain_error(verify_cert(&store, &leaf.cert, &[])).to_string(),
+            "issuer certificate at depth 1 (CN=Email CA) failed verification: certificate has expired"
+        );
+    }
+
+    #[test]
+    fn test_signing_certificate_usage() {
+        let root = issue("Root CA", Kind::Ca, None, &next_year());
+        let intermediate = issue("Email CA", Kind::Ca, Some(&root), &next_year());
+        let leaf = issue(
+            "www.example.com",
+            Kind::ServerAuth,
+            Some(&intermediate),
+            &next_year(),
+        );
+
+        let store = build_cac_ca_store([root.cert], [intermediate.cert]).unwrap();
+        let error = chain_error(verify_cert(&store, &leaf.cert, &[]));
+        assert_eq!(error.depth, 0);
+        assert_eq!(error.reason, "unsuitable certificate purpose");
+    }
+
+    #[test]
+    fn test_intermediate_must_be_a_ca() {
+        let root = issue("Root CA", Kind::Ca, None, &next_year());
+        let not_a_ca = issue("Not a CA", Kind::Signing, Some(&root), &next_year());
+        let leaf = issue(
+            "DOE.JANE.QUINN.1234567890",
+            Kind::Signing,
+            Some(&not_a_ca),
+            &next_year(),
+        );
+
+        let store = build_cac_ca_store([root.cert], [not_a_ca.cert]).unwrap();
+        let error = chain_error(verify_cert(&store, &leaf.cert, &[]));
+        assert_eq!(error.depth, 1);
+        assert_eq!(error.subject.as_deref(), Some("CN=Not a CA"));
+    }
+}
@@ -0,0 +1,208 @@
+//! Long-running ElectionGuard operations that the JX runs in the background.
+//!
+//! Tallying, decrypting, and mixing can take minutes, so rather than running
+//! them while an HTTP request waits, the JX records each as a [`Job`] and a
+//! worker runs it. The job outlives the request that started it, so a browser
+//! timeout or a restart does not leave the operation in an unknown state.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// The operation a job performs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobKind {
+    /// Accumulates an election's cast ballots into an encrypted tally.
+    GenerateEncryptedTally,
+
+    /// Decrypts an election's encrypted tally.
+    DecryptTally,
+
+    /// Shuffles an election's cast ballots through the mixnet.
+    MixBallots,
+
+    /// Checks the proofs of shuffle of an election's mixed ballots.
+    VerifyMixedBallots,
+
+    /// Decrypts an election's mixed ballots into anonymous ballots.
+    DecryptMixedBallots,
+}
+
+impl JobKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::GenerateEncryptedTally => "generateEncryptedTally",
+            Self::DecryptTally => "decryptTally",
+            Self::MixBallots => "mixBallots",
+            Self::VerifyMixedBallots => "verifyMixedBallots",
+            Self::DecryptMixedBallots => "decryptMixedBallots",
+        }
+    }
+}
+
+impl fmt::Display for JobKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JobKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "generateEncryptedTally" => Ok(Self::GenerateEncryptedTally),
+            "decryptTally" => Ok(Self::DecryptTally),
+            "mixBallots" => Ok(Self::MixBallots),
+            "verifyMixedBallots" => Ok(Self::VerifyMixedBallots),
+            "decryptMixedBallots" => Ok(Self::DecryptMixedBallots),
+            _ => Err(format!("unknown job kind: {s}")),
+        }
+    }
+}
+
+/// Where a job is in its run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    /// Waiting for the worker to pick it up.
+    Pending,
+
+    /// Being run by the worker.
+    Running,
+
+    /// Finished, producing [`Job::result_object_id`].
+    Succeeded,
+
+    /// Stopped because of [`Job::error`].
+    Failed,
+
+    /// Stopped at an operator's request before producing anything.
+    Cancelled,
+}
+
+impl JobStatus {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether the job has stopped and will not run again.
+    pub const fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "running" => Ok(Self::Running),
+            "succeeded" => Ok(Self::Succeeded),
+            "failed" => Ok(Self::Failed),
+            "cancelled" => Ok(Self::Cancelled),
+            _ => Err(format!("unknown job status: {s}")),
+        }
+    }
+}
+
+/// A background job and how far it has got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: Uuid,
+    pub kind: JobKind,
+    pub election_object_id: Uuid,
+    pub status: JobStatus,
+
+    /// What the job is doing now, while it runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<String>,
+
+    /// Whether an operator has asked to cancel the job while it runs.
+    pub cancel_requested: bool,
+
+    /// How many times the job has been started, counting restarts.
+    pub attempts: u32,
+
+    /// The object the job produced, once it has succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_object_id: Option<Uuid>,
+
+    /// Why the job failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub started_at: Option<OffsetDateTime>,
+
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub finished_at: Option<OffsetDateTime>,
+}
+
+impl Job {
+    /// Whether the job is waiting or running.
+    pub const fn is_active(&self) -> bool {
+        !self.status.is_finished()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_strings() {
+        for kind in [
+            JobKind::GenerateEncryptedTally,
+            JobKind::DecryptTally,
+            JobKind::MixBallots,
+            JobKind::VerifyMixedBallots,
+            JobKind::DecryptMixedBallots,
+        ] {
+            assert_eq!(kind.as_str().parse::<JobKind>(), Ok(kind));
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.as_str());
+        }
+
+        for status in [
+            JobStatus::Pending,
+            JobStatus::Running,
+            JobStatus::Succeeded,
+            JobStatus::Failed,
+            JobStatus::Cancelled,
+        ] {
+            assert_eq!(status.as_str().parse::<JobStatus>(), Ok(status));
+            assert_eq!(serde_json::to_value(status).unwrap(), status.as_str());
+        }
+
+        assert!("unknown".parse::<JobKind>().is_err());
+        assert!("unknown".parse::<JobStatus>().is_err());
+    }
+}
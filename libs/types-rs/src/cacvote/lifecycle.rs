@@ -0,0 +1,241 @@
+//! The stages an election moves through, from creation to certified results.
+//!
+//! Each step is recorded as a signed [`ElectionLifecycleTransition`] object.
+//! An election's current state is found by replaying its transitions in the
+//! order they were created, which fails if any of them is out of order.
+//! Elections created before transitions were recorded were already open for
+//! voting, so their transitions are replayed from [`ElectionLifecycleState::Open`]
+//! instead.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{JurisdictionCode, JurisdictionScoped};
+
+/// Where an election is in its lifecycle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ElectionLifecycleState {
+    /// The election exists but voting has not started.
+    #[default]
+    Created,
+
+    /// Voters may cast ballots.
+    Open,
+
+    /// Voting is over. The ballots may be tallied and mixed.
+    Closed,
+
+    /// The results have been certified. No further changes are allowed.
+    Certified,
+}
+
+impl ElectionLifecycleState {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Open => "open",
+            Self::Closed => "closed",
+            Self::Certified => "certified",
+        }
+    }
+
+    /// Determines the state after `action`, or why `action` is not allowed in
+    /// this state.
+    pub fn apply(self, action: ElectionLifecycleAction) -> Result<Self, String> {
+        if self == action.required_state() {
+            Ok(action.resulting_state())
+        } else {
+            Err(format!(
+                "cannot {action} while the election is {self}; it must be {}",
+                action.required_state()
+            ))
+        }
+    }
+
+    /// Replays `actions` in order from [`Self::Created`].
+    pub fn replay(
+        actions: impl IntoIterator<Item = ElectionLifecycleAction>,
+    ) -> Result<Self, String> {
+        Self::Created.replay_from(actions)
+    }
+
+    /// Replays `actions` in order from this state.
+    pub fn replay_from(
+        self,
+        actions: impl IntoIterator<Item = ElectionLifecycleAction>,
+    ) -> Result<Self, String> {
+        actions
+            .into_iter()
+            .try_fold(self, |state, action| state.apply(action))
+    }
+
+    /// Whether ballots may be cast in this state.
+    pub const fn is_accepting_ballots(self) -> bool {
+        matches!(self, Self::Open)
+    }
+
+    /// Whether voting has ended, so the ballots may be tallied and mixed.
+    pub const fn is_voting_closed(self) -> bool {
+        matches!(self, Self::Closed | Self::Certified)
+    }
+}
+
+impl fmt::Display for ElectionLifecycleState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A step from one [`ElectionLifecycleState`] to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ElectionLifecycleAction {
+    OpenVoting,
+    CloseVoting,
+    CertifyResults,
+}
+
+impl ElectionLifecycleAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::OpenVoting => "openVoting",
+            Self::CloseVoting => "closeVoting",
+            Self::CertifyResults => "certifyResults",
+        }
+    }
+
+    /// The state the election must be in for this action.
+    pub const fn required_state(self) -> ElectionLifecycleState {
+        match self {
+            Self::OpenVoting => ElectionLifecycleState::Created,
+            Self::CloseVoting => ElectionLifecycleState::Open,
+            Self::CertifyResults => ElectionLifecycleState::Closed,
+        }
+    }
+
+    /// The state the election is in after this action.
+    pub const fn resulting_state(self) -> ElectionLifecycleState {
+        match self {
+            Self::OpenVoting => ElectionLifecycleState::Open,
+            Self::CloseVoting => ElectionLifecycleState::Closed,
+            Self::CertifyResults => ElectionLifecycleState::Certified,
+        }
+    }
+}
+
+impl fmt::Display for ElectionLifecycleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::OpenVoting => "open voting",
+            Self::CloseVoting => "close voting",
+            Self::CertifyResults => "certify results",
+        })
+    }
+}
+
+impl FromStr for ElectionLifecycleAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "openVoting" => Ok(Self::OpenVoting),
+            "closeVoting" => Ok(Self::CloseVoting),
+            "certifyResults" => Ok(Self::CertifyResults),
+            _ => Err(format!("unknown election lifecycle action: {s}")),
+        }
+    }
+}
+
+/// Moves an election to the next stage of its lifecycle. Signed by the JX.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionLifecycleTransition {
+    pub jurisdiction_code: JurisdictionCode,
+    pub election_object_id: Uuid,
+    pub action: ElectionLifecycleAction,
+}
+
+impl JurisdictionScoped for ElectionLifecycleTransition {
+    fn jurisdiction_code(&self) -> JurisdictionCode {
+        self.jurisdiction_code.clone()
+    }
+}
+
+/// Asks the JX to record an [`ElectionLifecycleTransition`] for an election.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionLifecycleRequest {
+    pub action: ElectionLifecycleAction,
+
+    /// PIN of the official's card. When present, the resulting object is
+    /// signed with the card rather than the machine key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ElectionLifecycleAction::{CertifyResults, CloseVoting, OpenVoting};
+
+    #[test]
+    fn test_replay() {
+        assert_eq!(
+            ElectionLifecycleState::replay([]),
+            Ok(ElectionLifecycleState::Created)
+        );
+        assert_eq!(
+            ElectionLifecycleState::replay([OpenVoting, CloseVoting]),
+            Ok(ElectionLifecycleState::Closed)
+        );
+        assert_eq!(
+            ElectionLifecycleState::replay([OpenVoting, CloseVoting, CertifyResults]),
+            Ok(ElectionLifecycleState::Certified)
+        );
+        assert_eq!(
+            ElectionLifecycleState::replay([OpenVoting, CertifyResults]),
+            Err("cannot certify results while the election is open; it must be closed".to_owned())
+        );
+        assert_eq!(
+            ElectionLifecycleState::replay([OpenVoting, OpenVoting]),
+            Err("cannot open voting while the election is open; it must be created".to_owned())
+        );
+        assert_eq!(
+            ElectionLifecycleState::Open.replay_from([CloseVoting]),
+            Ok(ElectionLifecycleState::Closed)
+        );
+        assert_eq!(
+            ElectionLifecycleState::Open.replay_from([OpenVoting]),
+            Err("cannot open voting while the election is open; it must be created".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_state_predicates() {
+        use ElectionLifecycleState::{Certified, Closed, Created, Open};
+
+        assert_eq!(
+            [Created, Open, Closed, Certified].map(ElectionLifecycleState::is_accepting_ballots),
+            [false, true, false, false]
+        );
+        assert_eq!(
+            [Created, Open, Closed, Certified].map(ElectionLifecycleState::is_voting_closed),
+            [false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn test_action_round_trip() {
+        for action in [OpenVoting, CloseVoting, CertifyResults] {
+            assert_eq!(action.as_str().parse(), Ok(action));
+            assert_eq!(
+                serde_json::to_string(&action).unwrap(),
+                format!("\"{}\"", action.as_str())
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Checking the proofs of shuffle produced by mixing an election's cast
+//! ballots.
+//!
+//! Each phase of the mixnet shuffles and re-encrypts the ballots output by the
+//! phase before it, the first phase taking the cast ballots, and proves that
+//! it did so without adding, removing, or altering any. A
+//! [`ShuffledEncryptedCastBallotsVerification`] records the result of checking
+//! every phase's proof against its input, signed by the machine that checked
+//! it, so that auditors can see that no phase went unchecked.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::{JurisdictionCode, JurisdictionScoped};
+
+/// The result of checking one mixnet phase's proof of shuffle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MixPhaseVerification {
+    /// The name of the phase's directory in the mixnet output, e.g. `mix1`.
+    pub mix_name: String,
+
+    /// How many ballots the phase output.
+    pub ballot_count: u64,
+
+    /// Why the phase's proof of shuffle did not verify.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl MixPhaseVerification {
+    pub const fn is_verified(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The result of checking every phase of a [`ShuffledEncryptedCastBallots`]
+/// object against the election's cast ballots.
+///
+/// [`ShuffledEncryptedCastBallots`]: super::ShuffledEncryptedCastBallots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShuffledEncryptedCastBallotsVerification {
+    pub jurisdiction_code: JurisdictionCode,
+    pub election_object_id: Uuid,
+
+    /// The object whose proofs of shuffle were checked.
+    pub shuffled_encrypted_cast_ballots_object_id: Uuid,
+
+    /// How many cast ballots were input to the first phase.
+    pub cast_ballot_count: u64,
+
+    /// Each phase in the order it was run.
+    pub phases: Vec<MixPhaseVerification>,
+}
+
+impl ShuffledEncryptedCastBallotsVerification {
+    pub fn election_object_id_field_name() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `ShuffledEncryptedCastBallotsVerification` struct.
+        "electionObjectId"
+    }
+
+    /// Whether there was at least one phase, every phase's proof verified, and
+    /// every phase output as many ballots as were cast.
+    pub fn is_verified(&self) -> bool {
+        !self.phases.is_empty()
+            && self
+                .phases
+                .iter()
+                .all(|phase| phase.is_verified() && phase.ballot_count == self.cast_ballot_count)
+    }
+}
+
+impl JurisdictionScoped for ShuffledEncryptedCastBallotsVerification {
+    fn jurisdiction_code(&self) -> JurisdictionCode {
+        self.jurisdiction_code.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShuffledEncryptedCastBallotsVerificationPresenter {
+    #[serde(flatten)]
+    pub shuffled_encrypted_cast_ballots_verification: ShuffledEncryptedCastBallotsVerification,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub synced_at: Option<OffsetDateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(mix_name: &str, ballot_count: u64, error: Option<&str>) -> MixPhaseVerification {
+        MixPhaseVerification {
+            mix_name: mix_name.to_owned(),
+            ballot_count,
+            error: error.map(ToOwned::to_owned),
+        }
+    }
+
+    fn verification(phases: Vec<MixPhaseVerification>) -> ShuffledEncryptedCastBallotsVerification {
+        ShuffledEncryptedCastBallotsVerification {
+            jurisdiction_code: JurisdictionCode::try_from("st.dev-jurisdiction").unwrap(),
+            election_object_id: Uuid::new_v4(),
+            shuffled_encrypted_cast_ballots_object_id: Uuid::new_v4(),
+            cast_ballot_count: 3,
+            phases,
+        }
+    }
+
+    #[test]
+    fn test_is_verified() {
+        assert!(verification(vec![phase("mix1", 3, None), phase("mix2", 3, None)]).is_verified());
+
+        // nothing was checked
+        assert!(!verification(vec![]).is_verified());
+
+        // a phase's proof did not verify
+        assert!(!verification(vec![
+            phase("mix1", 3, None),
+            phase("mix2", 3, Some("proof of shuffle is invalid")),
+        ])
+        .is_verified());
+
+        // a phase dropped a ballot
+        assert!(!verification(vec![phase("mix1", 3, None), phase("mix2", 2, None)]).is_verified());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let verification = verification(vec![phase("mix1", 3, None), phase("mix2", 3, Some("x"))]);
+        let json = serde_json::to_value(&verification).unwrap();
+        assert_eq!(
+            json["phases"][0],
+            serde_json::json!({ "mixName": "mix1", "ballotCount": 3 })
+        );
+        assert_eq!(json["phases"][1]["error"], "x");
+        assert_eq!(
+            serde_json::from_value::<ShuffledEncryptedCastBallotsVerification>(json).unwrap(),
+            verification
+        );
+    }
+}
@@ -17,8 +17,66 @@ use crate::election::ElectionDefinition;
 use crate::election::ElectionHash;
 use crate::election::PrecinctId;
 
+mod anonymous_ballots;
+#[cfg(feature = "openssl")]
+mod certs;
+mod job;
+mod lifecycle;
+mod mix_verification;
+mod street_segments;
+mod transfer;
+
+pub use anonymous_ballots::{
+    AnonymousBallot, AnonymousBallotContest, AnonymousBallotSelection, DecryptMixedBallotsRequest,
+    DecryptedShuffledBallots, DecryptedShuffledBallotsPresenter,
+};
+#[cfg(feature = "openssl")]
+pub use certs::{
+    build_cac_ca_store, verify_cert, verify_cert_single_ca, CacCaStore, CertificateChainError,
+    VerificationError,
+};
+pub use job::{Job, JobKind, JobStatus};
+pub use lifecycle::{
+    ElectionLifecycleAction, ElectionLifecycleRequest, ElectionLifecycleState,
+    ElectionLifecycleTransition,
+};
+pub use mix_verification::{
+    MixPhaseVerification, ShuffledEncryptedCastBallotsVerification,
+    ShuffledEncryptedCastBallotsVerificationPresenter,
+};
+pub use street_segments::{find_precinct, HouseNumberParity, StreetSegment, VoterAddress};
+pub use transfer::{RejectedObject, SignedTransferBundle, TransferBundle, TransferReceipt};
+
 base64_serde_type!(Base64Standard, base64::engine::general_purpose::STANDARD);
 
+/// Like [`Base64Standard`], but for optional data.
+mod base64_standard_option {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        value: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::Base64Standard::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(value)
+                    .map_err(serde::de::Error::custom)
+            })
+            .transpose()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct JurisdictionCode(String);
@@ -107,6 +165,16 @@ pub struct SignedObject {
     #[serde(with = "Base64Standard")]
     pub certificate: Vec<u8>,
 
+    /// PEM-encoded X.509 certificates linking `certificate` to a trusted root,
+    /// e.g. the DoD intermediate CA that issued a CAC's certificate. Chains
+    /// may also be completed using intermediates configured on the verifier.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "base64_standard_option"
+    )]
+    pub certificate_chain: Option<Vec<u8>>,
+
     /// The signature of the payload.
     #[serde(with = "Base64Standard")]
     pub signature: Vec<u8>,
@@ -176,6 +244,13 @@ impl Debug for SignedObject {
             .field("election_id", &self.election_id)
             .field("payload", &DebuggablePayload(&self.payload))
             .field("certificate", &certificate)
+            .field(
+                "certificate_chain",
+                &self
+                    .certificate_chain
+                    .as_deref()
+                    .map(String::from_utf8_lossy),
+            )
             .field("signature", &DebuggableSignature(&self.signature))
             .finish()
     }
@@ -200,6 +275,7 @@ impl SignedObject {
             election_id,
             payload,
             certificate: certificate.to_pem()?,
+            certificate_chain: None,
             signature,
         })
     }
@@ -213,13 +289,27 @@ impl SignedObject {
         openssl::x509::X509::from_pem(&self.certificate)
     }
 
+    /// Parses [`Self::certificate_chain`], which is empty if there is none.
+    #[cfg(feature = "openssl")]
+    pub fn to_certificate_chain(
+        &self,
+    ) -> Result<Vec<openssl::x509::X509>, openssl::error::ErrorStack> {
+        match &self.certificate_chain {
+            Some(certificate_chain) => openssl::x509::X509::stack_from_pem(certificate_chain),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Verifies that the payload was signed by the certificate and that the
+    /// certificate was issued by the authority expected for the payload.
     #[cfg(feature = "openssl")]
     pub fn verify(
         &self,
         vx_root_ca_cert: &openssl::x509::X509,
-        cac_root_ca_store: &openssl::x509::store::X509Store,
-    ) -> Result<bool, openssl::error::ErrorStack> {
-        let public_key = self.to_x509()?.public_key()?;
+        cac_root_ca_store: &CacCaStore,
+    ) -> Result<(), VerificationError> {
+        let certificate = self.to_x509()?;
+        let public_key = certificate.public_key()?;
         let mut verifier =
             openssl::sign::Verifier::new(openssl::hash::MessageDigest::sha256(), &public_key)?;
 
@@ -228,30 +318,33 @@ impl SignedObject {
 
         if !verifier.verify(&self.signature)? {
             // signature verification failed, no need to continue
-            return Ok(false);
+            return Err(VerificationError::SignatureMismatch);
         }
 
         // verify that the certificate is signed by the expected CA
-        let payload = match self.try_to_inner() {
-            Ok(payload) => payload,
-            Err(_) => {
-                return Ok(false);
-            }
-        };
+        let payload = self
+            .try_to_inner()
+            .map_err(VerificationError::InvalidPayload)?;
 
         match payload {
             // signed by the CAC, check the CAC CA store
-            Payload::RegistrationRequest(_) | Payload::CastBallot(_) => {
-                verify_cert(cac_root_ca_store, &self.to_x509()?)
-            }
+            Payload::RegistrationRequest(_) | Payload::CastBallot(_) => verify_cert(
+                cac_root_ca_store,
+                &certificate,
+                &self.to_certificate_chain()?,
+            ),
 
             // signed by the machine TPM, check the VX root CA cert
             Payload::Registration(_)
+            | Payload::RegistrationRejection(_)
             | Payload::Election(_)
+            | Payload::ElectionLifecycleTransition(_)
             | Payload::EncryptedElectionTally(_)
             | Payload::DecryptedElectionTally(_)
-            | Payload::ShuffledEncryptedCastBallots(_) => {
-                verify_cert_single_ca(vx_root_ca_cert, &self.to_x509()?)
+            | Payload::ShuffledEncryptedCastBallots(_)
+            | Payload::ShuffledEncryptedCastBallotsVerification(_)
+            | Payload::DecryptedShuffledBallots(_) => {
+                verify_cert_single_ca(vx_root_ca_cert, &certificate)
             }
         }
     }
@@ -299,11 +392,15 @@ impl SignedObject {
 pub enum Payload {
     RegistrationRequest(RegistrationRequest),
     Registration(Registration),
+    RegistrationRejection(RegistrationRejection),
     Election(Election),
+    ElectionLifecycleTransition(ElectionLifecycleTransition),
     CastBallot(CastBallot),
     EncryptedElectionTally(EncryptedElectionTally),
     DecryptedElectionTally(DecryptedElectionTally),
     ShuffledEncryptedCastBallots(ShuffledEncryptedCastBallots),
+    ShuffledEncryptedCastBallotsVerification(ShuffledEncryptedCastBallotsVerification),
+    DecryptedShuffledBallots(DecryptedShuffledBallots),
 }
 
 impl Payload {
@@ -311,13 +408,21 @@ impl Payload {
         match self {
             Self::RegistrationRequest(_) => Self::registration_request_object_type(),
             Self::Registration(_) => Self::registration_object_type(),
+            Self::RegistrationRejection(_) => Self::registration_rejection_object_type(),
             Self::Election(_) => Self::election_object_type(),
+            Self::ElectionLifecycleTransition(_) => {
+                Self::election_lifecycle_transition_object_type()
+            }
             Self::CastBallot(_) => Self::cast_ballot_object_type(),
             Self::EncryptedElectionTally(_) => Self::encrypted_election_tally_object_type(),
             Self::DecryptedElectionTally(_) => Self::decrypted_election_tally_object_type(),
             Self::ShuffledEncryptedCastBallots(_) => {
                 Self::shuffled_encrypted_cast_ballots_object_type()
             }
+            Self::ShuffledEncryptedCastBallotsVerification(_) => {
+                Self::shuffled_encrypted_cast_ballots_verification_object_type()
+            }
+            Self::DecryptedShuffledBallots(_) => Self::decrypted_shuffled_ballots_object_type(),
         }
     }
 
@@ -325,11 +430,17 @@ impl Payload {
         match self {
             Self::RegistrationRequest(_) => None,
             Self::Registration(r) => Some(r.election_object_id),
+            Self::RegistrationRejection(_) => None,
             Self::Election(_) => None,
+            Self::ElectionLifecycleTransition(transition) => Some(transition.election_object_id),
             Self::CastBallot(cb) => Some(cb.election_object_id),
             Self::EncryptedElectionTally(tally) => Some(tally.election_object_id),
             Self::DecryptedElectionTally(tally) => Some(tally.election_object_id),
             Self::ShuffledEncryptedCastBallots(ballots) => Some(ballots.election_object_id),
+            Self::ShuffledEncryptedCastBallotsVerification(verification) => {
+                Some(verification.election_object_id)
+            }
+            Self::DecryptedShuffledBallots(ballots) => Some(ballots.election_object_id),
         }
     }
 
@@ -345,12 +456,24 @@ impl Payload {
         "Registration"
     }
 
+    pub fn registration_rejection_object_type() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `Payload` enum.
+        "RegistrationRejection"
+    }
+
     pub fn election_object_type() -> &'static str {
         // This must match the naming rules of the `serde` attribute in the
         // `Payload` enum.
         "Election"
     }
 
+    pub fn election_lifecycle_transition_object_type() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `Payload` enum.
+        "ElectionLifecycleTransition"
+    }
+
     pub fn cast_ballot_object_type() -> &'static str {
         // This must match the naming rules of the `serde` attribute in the
         // `Payload` enum.
@@ -374,6 +497,18 @@ impl Payload {
         // `Payload` enum.
         "ShuffledEncryptedCastBallots"
     }
+
+    pub fn shuffled_encrypted_cast_ballots_verification_object_type() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `Payload` enum.
+        "ShuffledEncryptedCastBallotsVerification"
+    }
+
+    pub fn decrypted_shuffled_ballots_object_type() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `Payload` enum.
+        "DecryptedShuffledBallots"
+    }
 }
 
 impl JurisdictionScoped for Payload {
@@ -381,11 +516,17 @@ impl JurisdictionScoped for Payload {
         match self {
             Self::RegistrationRequest(request) => request.jurisdiction_code(),
             Self::Registration(registration) => registration.jurisdiction_code(),
+            Self::RegistrationRejection(rejection) => rejection.jurisdiction_code(),
             Self::Election(election) => election.jurisdiction_code(),
+            Self::ElectionLifecycleTransition(transition) => transition.jurisdiction_code(),
             Self::CastBallot(cast_ballot) => cast_ballot.jurisdiction_code(),
             Self::EncryptedElectionTally(tally) => tally.jurisdiction_code(),
             Self::DecryptedElectionTally(tally) => tally.jurisdiction_code(),
             Self::ShuffledEncryptedCastBallots(ballots) => ballots.jurisdiction_code(),
+            Self::ShuffledEncryptedCastBallotsVerification(verification) => {
+                verification.jurisdiction_code()
+            }
+            Self::DecryptedShuffledBallots(ballots) => ballots.jurisdiction_code(),
         }
     }
 }
@@ -494,28 +635,6 @@ impl sqlx::Type<sqlx::Postgres> for JournalEntryAction {
     }
 }
 
-#[cfg(feature = "openssl")]
-pub fn verify_cert(
-    ca_store: &openssl::x509::store::X509Store,
-    certificate: &openssl::x509::X509,
-) -> Result<bool, openssl::error::ErrorStack> {
-    let mut context = openssl::x509::X509StoreContext::new()?;
-    let intermediates = openssl::stack::Stack::new()?;
-    context.init(ca_store, certificate, &intermediates, |ctx| {
-        ctx.verify_cert()
-    })
-}
-
-#[cfg(feature = "openssl")]
-pub fn verify_cert_single_ca(
-    ca_cert: &openssl::x509::X509,
-    certificate: &openssl::x509::X509,
-) -> Result<bool, openssl::error::ErrorStack> {
-    let mut builder = openssl::x509::store::X509StoreBuilder::new()?;
-    builder.add_cert(ca_cert.clone())?;
-    verify_cert(&builder.build(), certificate)
-}
-
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub enum SmartcardStatus {
     #[default]
@@ -533,7 +652,7 @@ pub enum VerificationStatus {
         display_name: String,
     },
     #[serde(rename_all = "camelCase")]
-    Failure,
+    Failure { reason: String },
     #[serde(rename_all = "camelCase")]
     Error { message: String },
     #[default]
@@ -552,6 +671,10 @@ pub struct RegistrationRequest {
     pub jurisdiction_code: JurisdictionCode,
     pub given_name: String,
     pub family_name: String,
+
+    /// Where the voter lives, used to suggest their precinct.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<VoterAddress>,
 }
 
 impl JurisdictionScoped for RegistrationRequest {
@@ -645,12 +768,123 @@ impl Registration {
     }
 }
 
+/// Why a registration request was rejected. Shown to the voter, so each
+/// reason should be something they can act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RegistrationRejectionReason {
+    /// The voter is not eligible to vote in this jurisdiction.
+    NotEligible,
+    /// The voter's identity could not be confirmed.
+    IdentityNotVerified,
+    /// The voter already has a pending or approved request.
+    Duplicate,
+    /// The request was not acted on before it expired.
+    Expired,
+    /// Any other reason, described in the rejection's message.
+    Other,
+}
+
+impl RegistrationRejectionReason {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NotEligible => "notEligible",
+            Self::IdentityNotVerified => "identityNotVerified",
+            Self::Duplicate => "duplicate",
+            Self::Expired => "expired",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for RegistrationRejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A jurisdiction's decision not to approve a registration request. A
+/// request with a rejection is no longer pending.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationRejection {
+    pub common_access_card_id: String,
+    pub jurisdiction_code: JurisdictionCode,
+    pub registration_request_object_id: Uuid,
+    pub reason: RegistrationRejectionReason,
+
+    /// Optional explanation for the voter, e.g. what to do next.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl JurisdictionScoped for RegistrationRejection {
+    fn jurisdiction_code(&self) -> JurisdictionCode {
+        self.jurisdiction_code.clone()
+    }
+}
+
+impl RegistrationRejection {
+    pub fn common_access_card_id_field_name() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `RegistrationRejection` struct.
+        "commonAccessCardId"
+    }
+
+    pub fn registration_request_object_id_field_name() -> &'static str {
+        // This must match the naming rules of the `serde` attribute in the
+        // `RegistrationRejection` struct.
+        "registrationRequestObjectId"
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateElectionRequest {
     pub jurisdiction_code: JurisdictionCode,
     pub election_definition: ElectionDefinition,
     pub mailing_address: String,
+
+    /// When voters may start casting ballots.
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voting_starts_at: Option<OffsetDateTime>,
+
+    /// When voters may no longer cast ballots.
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voting_ends_at: Option<OffsetDateTime>,
+
+    /// Mailed ballots received after this time are too late to be counted.
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ballot_receipt_deadline: Option<OffsetDateTime>,
+
+    /// PIN of the official's card. When present, the resulting object is
+    /// signed with the card rather than the machine key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+}
+
+impl CreateElectionRequest {
+    /// Checks that the voting window is in order. See
+    /// [`check_voting_window_dates`].
+    pub fn check_voting_window_dates(&self) -> Result<(), String> {
+        check_voting_window_dates(
+            self.voting_starts_at,
+            self.voting_ends_at,
+            self.ballot_receipt_deadline,
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -662,6 +896,105 @@ pub struct Election {
 
     #[serde(with = "Base64Standard")]
     pub electionguard_election_metadata_blob: Vec<u8>,
+
+    /// When voters may start casting ballots. Elections without one accept
+    /// ballots as soon as voting is opened.
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voting_starts_at: Option<OffsetDateTime>,
+
+    /// When voters may no longer cast ballots. Elections without one accept
+    /// ballots until voting is closed.
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voting_ends_at: Option<OffsetDateTime>,
+
+    /// Mailed ballots received after this time are too late to be counted.
+    #[serde(
+        default,
+        with = "time::serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ballot_receipt_deadline: Option<OffsetDateTime>,
+}
+
+impl Election {
+    /// Checks that the voting window is in order. See
+    /// [`check_voting_window_dates`].
+    pub fn check_voting_window_dates(&self) -> Result<(), String> {
+        check_voting_window_dates(
+            self.voting_starts_at,
+            self.voting_ends_at,
+            self.ballot_receipt_deadline,
+        )
+    }
+
+    /// Checks whether a ballot cast at `cast_at` falls within the voting
+    /// window.
+    pub fn check_voting_window(&self, cast_at: OffsetDateTime) -> Result<(), String> {
+        if let Some(voting_starts_at) = self.voting_starts_at {
+            if cast_at < voting_starts_at {
+                return Err(format!(
+                    "voting does not start until {}",
+                    format_timestamp(voting_starts_at)
+                ));
+            }
+        }
+
+        if let Some(voting_ends_at) = self.voting_ends_at {
+            if cast_at >= voting_ends_at {
+                return Err(format!(
+                    "voting ended at {}",
+                    format_timestamp(voting_ends_at)
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a mailed ballot received at `received_at` missed the receipt
+    /// deadline.
+    pub fn is_received_after_deadline(&self, received_at: OffsetDateTime) -> bool {
+        self.ballot_receipt_deadline
+            .is_some_and(|deadline| received_at > deadline)
+    }
+}
+
+/// Checks that voting starts before it ends, and that ballots are not due
+/// before voting ends. Any of the dates may be missing.
+fn check_voting_window_dates(
+    voting_starts_at: Option<OffsetDateTime>,
+    voting_ends_at: Option<OffsetDateTime>,
+    ballot_receipt_deadline: Option<OffsetDateTime>,
+) -> Result<(), String> {
+    if let (Some(voting_starts_at), Some(voting_ends_at)) = (voting_starts_at, voting_ends_at) {
+        if voting_starts_at >= voting_ends_at {
+            return Err("voting must start before it ends".to_owned());
+        }
+    }
+
+    if let (Some(voting_ends_at), Some(ballot_receipt_deadline)) =
+        (voting_ends_at, ballot_receipt_deadline)
+    {
+        if ballot_receipt_deadline < voting_ends_at {
+            return Err("the ballot receipt deadline cannot be before voting ends".to_owned());
+        }
+    }
+
+    Ok(())
+}
+
+fn format_timestamp(timestamp: OffsetDateTime) -> String {
+    timestamp
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_else(|_| timestamp.to_string())
 }
 
 impl JurisdictionScoped for Election {
@@ -784,6 +1117,11 @@ pub struct EncryptedElectionTally {
     pub election_object_id: Uuid,
     #[serde(with = "Base64Standard")]
     pub electionguard_encrypted_tally: Vec<u8>,
+
+    /// The cast ballots the tally accumulates. Empty for tallies made before
+    /// they were recorded, which accumulate every ballot cast in the election.
+    #[serde(default)]
+    pub cast_ballot_ids: Vec<Uuid>,
 }
 
 impl EncryptedElectionTally {
@@ -849,6 +1187,16 @@ impl JurisdictionScoped for DecryptedElectionTally {
     }
 }
 
+/// Asks the JX to decrypt an election's encrypted tally.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptElectionTallyRequest {
+    /// PIN of the official's card, required to unwrap the election's private
+    /// key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ShuffledEncryptedCastBallots {
@@ -892,7 +1240,18 @@ pub enum SessionData {
     #[serde(rename_all = "camelCase")]
     Unauthenticated { has_smartcard: bool },
     #[serde(rename_all = "camelCase")]
-    Authenticating { auth_error: Option<String> },
+    Authenticating {
+        auth_error: Option<String>,
+
+        /// When the card's PIN lockout ends, if it has been locked out after
+        /// too many incorrect PIN attempts.
+        #[serde(
+            default,
+            with = "time::serde::iso8601::option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        locked_until: Option<OffsetDateTime>,
+    },
     #[serde(rename_all = "camelCase")]
     Authenticated {
         jurisdiction_code: JurisdictionCode,
@@ -900,6 +1259,7 @@ pub enum SessionData {
         pending_registration_requests: Vec<RegistrationRequestPresenter>,
         registrations: Vec<RegistrationPresenter>,
         cast_ballots: Vec<CastBallotPresenter>,
+        jobs: Vec<Job>,
     },
 }
 
@@ -917,11 +1277,13 @@ impl PartialEq for SessionData {
             (
                 Self::Authenticating {
                     auth_error: auth_error1,
+                    locked_until: locked_until1,
                 },
                 Self::Authenticating {
                     auth_error: auth_error2,
+                    locked_until: locked_until2,
                 },
-            ) => auth_error1 == auth_error2,
+            ) => auth_error1 == auth_error2 && locked_until1 == locked_until2,
             (
                 Self::Authenticated {
                     jurisdiction_code: jurisdiction_code1,
@@ -929,6 +1291,7 @@ impl PartialEq for SessionData {
                     pending_registration_requests: pending_registration_requests1,
                     registrations: registrations1,
                     cast_ballots: cast_ballots1,
+                    jobs: jobs1,
                 },
                 Self::Authenticated {
                     jurisdiction_code: jurisdiction_code2,
@@ -936,6 +1299,7 @@ impl PartialEq for SessionData {
                     pending_registration_requests: pending_registration_requests2,
                     registrations: registrations2,
                     cast_ballots: cast_ballots2,
+                    jobs: jobs2,
                 },
             ) => {
                 jurisdiction_code1 == jurisdiction_code2
@@ -943,6 +1307,7 @@ impl PartialEq for SessionData {
                     && pending_registration_requests1 == pending_registration_requests2
                     && registrations1 == registrations2
                     && cast_ballots1 == cast_ballots2
+                    && jobs1 == jobs2
             }
             _ => false,
         }
@@ -962,8 +1327,35 @@ impl Default for SessionData {
 pub struct CreateRegistrationRequest {
     pub election_id: Uuid,
     pub registration_request_id: Uuid,
-    pub ballot_style_id: BallotStyleId,
-    pub precinct_id: PrecinctId,
+
+    /// Ballot style to assign. When absent, the only ballot style used in the
+    /// precinct is assigned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ballot_style_id: Option<BallotStyleId>,
+
+    /// Precinct to assign. When absent, the precinct is found from the
+    /// voter's address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precinct_id: Option<PrecinctId>,
+
+    /// PIN of the official's card. When present, the resulting object is
+    /// signed with the card rather than the machine key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectRegistrationRequest {
+    pub registration_request_id: Uuid,
+    pub reason: RegistrationRejectionReason,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// PIN of the official's card. When present, the resulting object is
+    /// signed with the card rather than the machine key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -977,6 +1369,13 @@ pub struct ElectionPresenter {
     decrypted_tally: Option<DecryptedElectionTallyPresenter>,
     #[serde(skip_serializing_if = "Option::is_none")]
     shuffled_encrypted_cast_ballots: Option<ShuffledEncryptedCastBallotsPresenter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shuffled_encrypted_cast_ballots_verification:
+        Option<ShuffledEncryptedCastBallotsVerificationPresenter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    decrypted_shuffled_ballots: Option<DecryptedShuffledBallotsPresenter>,
+    #[serde(default)]
+    lifecycle_state: ElectionLifecycleState,
 }
 
 impl ElectionPresenter {
@@ -993,8 +1392,45 @@ impl ElectionPresenter {
             encrypted_tally,
             decrypted_tally,
             shuffled_encrypted_cast_ballots,
+            shuffled_encrypted_cast_ballots_verification: None,
+            decrypted_shuffled_ballots: None,
+            lifecycle_state: ElectionLifecycleState::default(),
         }
     }
+
+    pub const fn lifecycle_state(&self) -> ElectionLifecycleState {
+        self.lifecycle_state
+    }
+
+    #[must_use]
+    pub const fn with_lifecycle_state(mut self, lifecycle_state: ElectionLifecycleState) -> Self {
+        self.lifecycle_state = lifecycle_state;
+        self
+    }
+
+    /// Sets the latest check of the proofs of shuffle of the election's
+    /// shuffled encrypted cast ballots.
+    #[must_use]
+    pub fn with_shuffled_encrypted_cast_ballots_verification(
+        mut self,
+        shuffled_encrypted_cast_ballots_verification: Option<
+            ShuffledEncryptedCastBallotsVerificationPresenter,
+        >,
+    ) -> Self {
+        self.shuffled_encrypted_cast_ballots_verification =
+            shuffled_encrypted_cast_ballots_verification;
+        self
+    }
+
+    /// Sets the election's mixed ballots, once they have been decrypted.
+    #[must_use]
+    pub fn with_decrypted_shuffled_ballots(
+        mut self,
+        decrypted_shuffled_ballots: Option<DecryptedShuffledBallotsPresenter>,
+    ) -> Self {
+        self.decrypted_shuffled_ballots = decrypted_shuffled_ballots;
+        self
+    }
 }
 
 impl Deref for ElectionPresenter {
@@ -1013,6 +1449,8 @@ pub struct RegistrationRequestPresenter {
     registration_request: RegistrationRequest,
     #[serde(with = "time::serde::iso8601")]
     created_at: OffsetDateTime,
+    #[serde(default)]
+    suggested_assignments: Vec<SuggestedAssignment>,
 }
 
 impl Deref for RegistrationRequestPresenter {
@@ -1035,12 +1473,36 @@ impl RegistrationRequestPresenter {
             display_name,
             registration_request,
             created_at,
+            suggested_assignments: Vec::new(),
         }
     }
 
     pub fn created_at(&self) -> OffsetDateTime {
         self.created_at
     }
+
+    pub fn suggested_assignments(&self) -> &[SuggestedAssignment] {
+        &self.suggested_assignments
+    }
+
+    #[must_use]
+    pub fn with_suggested_assignments(
+        mut self,
+        suggested_assignments: Vec<SuggestedAssignment>,
+    ) -> Self {
+        self.suggested_assignments = suggested_assignments;
+        self
+    }
+}
+
+/// A precinct and ballot style a registration request could be assigned in an
+/// election, based on the voter's address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedAssignment {
+    pub election_id: Uuid,
+    pub precinct_id: PrecinctId,
+    pub ballot_style_id: BallotStyleId,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1126,6 +1588,10 @@ pub struct ScannedMailingLabel {
     original_payload: Vec<u8>,
     signed_buffer: SignedBuffer,
     ballot_verification_payload: BallotVerificationPayload,
+
+    /// When the mailing label was scanned, i.e. when the ballot was received.
+    #[serde(with = "time::serde::iso8601")]
+    created_at: OffsetDateTime,
 }
 
 impl ScannedMailingLabel {
@@ -1133,13 +1599,47 @@ impl ScannedMailingLabel {
         original_payload: Vec<u8>,
         signed_buffer: SignedBuffer,
         ballot_verification_payload: BallotVerificationPayload,
+        created_at: OffsetDateTime,
     ) -> Self {
         Self {
             original_payload,
             signed_buffer,
             ballot_verification_payload,
+            created_at,
+        }
+    }
+
+    pub const fn ballot_verification_payload(&self) -> &BallotVerificationPayload {
+        &self.ballot_verification_payload
+    }
+
+    pub const fn created_at(&self) -> OffsetDateTime {
+        self.created_at
+    }
+}
+
+/// A scanned mailing label along with whether it arrived too late to count.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedMailingLabelPresenter {
+    #[serde(flatten)]
+    scanned_mailing_label: ScannedMailingLabel,
+    received_after_deadline: bool,
+}
+
+impl ScannedMailingLabelPresenter {
+    pub fn new(scanned_mailing_label: ScannedMailingLabel, election: &Election) -> Self {
+        let received_after_deadline =
+            election.is_received_after_deadline(scanned_mailing_label.created_at);
+        Self {
+            scanned_mailing_label,
+            received_after_deadline,
         }
     }
+
+    pub const fn received_after_deadline(&self) -> bool {
+        self.received_after_deadline
+    }
 }
 
 /// A payload for verifying a ballot. This payload is encoded as a TLV structure.
@@ -1279,4 +1779,112 @@ mod tests {
             &encrypted_ballot_signature_hash
         );
     }
+
+    #[test]
+    fn test_signed_object_certificate_chain_serialization() {
+        let object = serde_json::json!({
+            "id": "5c1ad0f4-0c28-4d7e-9bd5-7e8e3bd8a1b2",
+            "payload": "e30=",
+            "certificate": "Y2VydA==",
+            "signature": "c2ln",
+        });
+
+        // objects signed before certificate chains were introduced have none
+        let mut signed_object: crate::cacvote::SignedObject =
+            serde_json::from_value(object.clone()).unwrap();
+        assert_eq!(signed_object.certificate_chain, None);
+        assert_eq!(serde_json::to_value(&signed_object).unwrap(), object);
+
+        signed_object.certificate_chain = Some(b"chain".to_vec());
+        let serialized = serde_json::to_value(&signed_object).unwrap();
+        assert_eq!(serialized["certificateChain"], "Y2hhaW4=");
+        assert_eq!(
+            serde_json::from_value::<crate::cacvote::SignedObject>(serialized)
+                .unwrap()
+                .certificate_chain,
+            Some(b"chain".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_encrypted_election_tally_cast_ballot_ids_deserialization() {
+        // tallies made before their cast ballots were recorded have none
+        let encrypted_election_tally: crate::cacvote::EncryptedElectionTally =
+            serde_json::from_value(serde_json::json!({
+                "jurisdictionCode": "st.test-jurisdiction",
+                "electionObjectId": "5c1ad0f4-0c28-4d7e-9bd5-7e8e3bd8a1b2",
+                "electionguardEncryptedTally": "e30=",
+            }))
+            .unwrap();
+        assert_eq!(encrypted_election_tally.cast_ballot_ids, vec![]);
+    }
+
+    #[test]
+    fn test_election_voting_window() {
+        use time::macros::datetime;
+
+        let election =
+            |voting_starts_at, voting_ends_at, ballot_receipt_deadline| crate::cacvote::Election {
+                jurisdiction_code: "st.test-jurisdiction".try_into().unwrap(),
+                election_definition: crate::election::ElectionDefinition::try_from(
+                    &include_bytes!("../../../fixtures/data/electionFamousNames2021/election.json")
+                        [..],
+                )
+                .unwrap(),
+                mailing_address: "123 Main St".to_owned(),
+                electionguard_election_metadata_blob: vec![],
+                voting_starts_at,
+                voting_ends_at,
+                ballot_receipt_deadline,
+            };
+
+        // without dates, ballots are accepted at any time
+        let open_ended = election(None, None, None);
+        assert_eq!(open_ended.check_voting_window_dates(), Ok(()));
+        assert_eq!(
+            open_ended.check_voting_window(datetime!(2024-11-05 12:00 UTC)),
+            Ok(())
+        );
+        assert!(!open_ended.is_received_after_deadline(datetime!(2030-01-01 0:00 UTC)));
+
+        let windowed = election(
+            Some(datetime!(2024-10-01 0:00 UTC)),
+            Some(datetime!(2024-11-05 20:00 UTC)),
+            Some(datetime!(2024-11-15 17:00 UTC)),
+        );
+        assert_eq!(windowed.check_voting_window_dates(), Ok(()));
+        assert_eq!(
+            windowed.check_voting_window(datetime!(2024-09-30 23:59 UTC)),
+            Err("voting does not start until 2024-10-01T00:00:00Z".to_owned())
+        );
+        assert_eq!(
+            windowed.check_voting_window(datetime!(2024-10-01 0:00 UTC)),
+            Ok(())
+        );
+        assert_eq!(
+            windowed.check_voting_window(datetime!(2024-11-05 20:00 UTC)),
+            Err("voting ended at 2024-11-05T20:00:00Z".to_owned())
+        );
+        assert!(!windowed.is_received_after_deadline(datetime!(2024-11-15 17:00 UTC)));
+        assert!(windowed.is_received_after_deadline(datetime!(2024-11-15 17:01 UTC)));
+
+        assert_eq!(
+            election(
+                Some(datetime!(2024-11-05 20:00 UTC)),
+                Some(datetime!(2024-10-01 0:00 UTC)),
+                None
+            )
+            .check_voting_window_dates(),
+            Err("voting must start before it ends".to_owned())
+        );
+        assert_eq!(
+            election(
+                None,
+                Some(datetime!(2024-11-05 20:00 UTC)),
+                Some(datetime!(2024-11-01 0:00 UTC))
+            )
+            .check_voting_window_dates(),
+            Err("the ballot receipt deadline cannot be before voting ends".to_owned())
+        );
+    }
 }
@@ -0,0 +1,198 @@
+//! Voter addresses and the street segments that map them to precincts.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::election::PrecinctId;
+
+/// A voter's residential address, as entered on their registration request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoterAddress {
+    pub house_number: u32,
+    pub street_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub city: String,
+    pub zip_code: String,
+}
+
+impl fmt::Display for VoterAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.house_number, self.street_name)?;
+        if let Some(unit) = &self.unit {
+            write!(f, " {unit}")?;
+        }
+        write!(f, ", {} {}", self.city, self.zip_code)
+    }
+}
+
+/// Which house numbers in a segment's range are on the segment. Streets are
+/// often split down the middle, with odd numbers on one side and even on the
+/// other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HouseNumberParity {
+    #[default]
+    All,
+    Odd,
+    Even,
+}
+
+impl HouseNumberParity {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Odd => "odd",
+            Self::Even => "even",
+        }
+    }
+
+    const fn matches(self, house_number: u32) -> bool {
+        let is_odd = house_number % 2 == 1;
+        match self {
+            Self::All => true,
+            Self::Odd => is_odd,
+            Self::Even => !is_odd,
+        }
+    }
+}
+
+impl FromStr for HouseNumberParity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "odd" => Ok(Self::Odd),
+            "even" => Ok(Self::Even),
+            _ => Err(format!("unknown house number parity: {s}")),
+        }
+    }
+}
+
+/// A range of addresses on one street that all vote in the same precinct.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreetSegment {
+    pub street_name: String,
+    pub zip_code: String,
+    pub low_house_number: u32,
+    pub high_house_number: u32,
+    #[serde(default)]
+    pub parity: HouseNumberParity,
+    pub precinct_id: PrecinctId,
+}
+
+impl StreetSegment {
+    /// Determines whether `address` lies on this segment. Street names are
+    /// compared ignoring case, punctuation, and extra whitespace.
+    pub fn contains(&self, address: &VoterAddress) -> bool {
+        self.zip_code.trim() == address.zip_code.trim()
+            && normalize_street_name(&self.street_name)
+                == normalize_street_name(&address.street_name)
+            && (self.low_house_number..=self.high_house_number).contains(&address.house_number)
+            && self.parity.matches(address.house_number)
+    }
+}
+
+fn normalize_street_name(street_name: &str) -> String {
+    street_name
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_uppercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finds the precinct `address` is in. Returns `None` if no segment contains
+/// the address, or if overlapping segments disagree about its precinct.
+pub fn find_precinct<'a>(
+    street_segments: &'a [StreetSegment],
+    address: &VoterAddress,
+) -> Option<&'a PrecinctId> {
+    let mut precinct_ids = street_segments
+        .iter()
+        .filter(|segment| segment.contains(address))
+        .map(|segment| &segment.precinct_id);
+    let precinct_id = precinct_ids.next()?;
+    precinct_ids
+        .all(|other| other == precinct_id)
+        .then_some(precinct_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(house_number: u32, street_name: &str) -> VoterAddress {
+        VoterAddress {
+            house_number,
+            street_name: street_name.to_owned(),
+            unit: None,
+            city: "Springfield".to_owned(),
+            zip_code: "12345".to_owned(),
+        }
+    }
+
+    fn segment(
+        street_name: &str,
+        low_house_number: u32,
+        high_house_number: u32,
+        parity: HouseNumberParity,
+        precinct_id: &str,
+    ) -> StreetSegment {
+        StreetSegment {
+            street_name: street_name.to_owned(),
+            zip_code: "12345".to_owned(),
+            low_house_number,
+            high_house_number,
+            parity,
+            precinct_id: PrecinctId::from(precinct_id.to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_find_precinct() {
+        let street_segments = vec![
+            segment("Main St.", 1, 99, HouseNumberParity::Odd, "north"),
+            segment("Main St.", 2, 98, HouseNumberParity::Even, "south"),
+            segment("Elm Ave", 1, 500, HouseNumberParity::All, "north"),
+            segment("Elm Ave", 400, 600, HouseNumberParity::All, "south"),
+        ];
+        let find = |house_number, street_name| {
+            find_precinct(&street_segments, &address(house_number, street_name))
+                .map(ToString::to_string)
+        };
+
+        assert_eq!(find(11, "main st"), Some("north".to_owned()));
+        assert_eq!(find(12, "  MAIN   ST "), Some("south".to_owned()));
+        assert_eq!(find(101, "Main St"), None);
+        assert_eq!(find(100, "Elm Ave"), Some("north".to_owned()));
+        // overlapping segments disagree
+        assert_eq!(find(450, "Elm Ave"), None);
+        assert_eq!(find(550, "Elm Ave"), Some("south".to_owned()));
+        assert_eq!(find(11, "Oak St"), None);
+
+        let mut other_zip_code = address(11, "Main St");
+        other_zip_code.zip_code = "54321".to_owned();
+        assert_eq!(find_precinct(&street_segments, &other_zip_code), None);
+    }
+
+    #[test]
+    fn test_voter_address_display() {
+        let mut voter_address = address(12, "Main St");
+        assert_eq!(voter_address.to_string(), "12 Main St, Springfield 12345");
+        voter_address.unit = Some("Apt 4".to_owned());
+        assert_eq!(
+            voter_address.to_string(),
+            "12 Main St Apt 4, Springfield 12345"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Transfer bundles for moving objects between a JX terminal and the CACvote
+//! Server on removable media, for sites without a reliable network connection.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::{Base64Standard, JournalEntry, JurisdictionCode, SignedObject};
+
+/// A set of objects, and optionally the journal entries describing them,
+/// exported from one side of a sync for import on the other.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferBundle {
+    /// Unique ID of the bundle, echoed back in the [`TransferReceipt`].
+    pub id: Uuid,
+
+    /// The jurisdiction the bundle's contents belong to.
+    pub jurisdiction_code: JurisdictionCode,
+
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+
+    /// Journal entries to import. Bundles exported from a JX terminal have
+    /// none, since the CACvote Server creates its own entries on import.
+    #[serde(default)]
+    pub journal_entries: Vec<JournalEntry>,
+
+    /// Objects to import. Each object is verified on its own during import.
+    pub objects: Vec<SignedObject>,
+}
+
+impl TransferBundle {
+    #[must_use]
+    pub fn new(
+        jurisdiction_code: JurisdictionCode,
+        journal_entries: Vec<JournalEntry>,
+        objects: Vec<SignedObject>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            jurisdiction_code,
+            created_at: OffsetDateTime::now_utc(),
+            journal_entries,
+            objects,
+        }
+    }
+}
+
+/// A [`TransferBundle`] as written to removable media, signed by the machine
+/// that exported it.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedTransferBundle {
+    /// JSON-encoded [`TransferBundle`].
+    #[serde(with = "Base64Standard")]
+    pub bundle: Vec<u8>,
+
+    /// A PEM-encoded X.509 certificate.
+    #[serde(with = "Base64Standard")]
+    pub certificate: Vec<u8>,
+
+    /// The signature of the bundle.
+    #[serde(with = "Base64Standard")]
+    pub signature: Vec<u8>,
+}
+
+impl SignedTransferBundle {
+    pub fn try_to_inner(&self) -> Result<TransferBundle, serde_json::Error> {
+        serde_json::from_slice(&self.bundle)
+    }
+
+    #[cfg(feature = "openssl")]
+    pub fn to_x509(&self) -> Result<openssl::x509::X509, openssl::error::ErrorStack> {
+        openssl::x509::X509::from_pem(&self.certificate)
+    }
+
+    /// Verifies that the bundle is signed by the certificate and that the
+    /// certificate is signed by the VX root CA. Bundles are always signed by
+    /// a machine, never by a CAC.
+    #[cfg(feature = "openssl")]
+    pub fn verify(
+        &self,
+        vx_root_ca_cert: &openssl::x509::X509,
+    ) -> Result<(), super::VerificationError> {
+        let certificate = self.to_x509()?;
+        let public_key = certificate.public_key()?;
+        let mut verifier =
+            openssl::sign::Verifier::new(openssl::hash::MessageDigest::sha256(), &public_key)?;
+        verifier.update(&self.bundle)?;
+
+        if !verifier.verify(&self.signature)? {
+            return Err(super::VerificationError::SignatureMismatch);
+        }
+
+        super::verify_cert_single_ca(vx_root_ca_cert, &certificate)
+    }
+}
+
+/// The result of importing a [`TransferBundle`]. Importing the same bundle
+/// again yields a receipt with every object skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferReceipt {
+    pub bundle_id: Uuid,
+
+    /// Number of journal entries in the bundle that were not already present.
+    pub journal_entries_imported: u64,
+
+    /// IDs of objects that were newly imported.
+    pub imported_object_ids: Vec<Uuid>,
+
+    /// IDs of objects that were already present.
+    pub skipped_object_ids: Vec<Uuid>,
+
+    /// Objects that could not be imported, e.g. because they failed
+    /// verification.
+    pub rejected_objects: Vec<RejectedObject>,
+}
+
+impl TransferReceipt {
+    #[must_use]
+    pub fn new(bundle_id: Uuid) -> Self {
+        Self {
+            bundle_id,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedObject {
+    pub id: Uuid,
+    pub reason: String,
+}
@@ -9,6 +9,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::{ballot_card::BallotSide, geometry::GridUnit, util::idtype};
 
+mod validation;
+
+pub use validation::ElectionProblem;
+
 idtype!(BallotStyleId);
 idtype!(CandidateId);
 idtype!(ContestId);
@@ -323,6 +327,48 @@ impl Election {
                 .collect(),
         )
     }
+
+    /// Finds the ballot styles used in `precinct_id`.
+    pub fn ballot_styles_for_precinct(&self, precinct_id: &PrecinctId) -> Vec<&BallotStyle> {
+        self.ballot_styles
+            .iter()
+            .filter(|ballot_style| ballot_style.precincts.contains(precinct_id))
+            .collect()
+    }
+
+    /// Checks that a voter in `precinct_id` may be given `ballot_style_id`,
+    /// i.e. that both exist and the ballot style is used in the precinct.
+    pub fn check_ballot_style_assignment(
+        &self,
+        ballot_style_id: &BallotStyleId,
+        precinct_id: &PrecinctId,
+    ) -> Result<(), String> {
+        if !self
+            .precincts
+            .iter()
+            .any(|precinct| &precinct.id == precinct_id)
+        {
+            return Err(format!("precinct {precinct_id} is not in the election"));
+        }
+
+        let Some(ballot_style) = self
+            .ballot_styles
+            .iter()
+            .find(|ballot_style| &ballot_style.id == ballot_style_id)
+        else {
+            return Err(format!(
+                "ballot style {ballot_style_id} is not in the election"
+            ));
+        };
+
+        if !ballot_style.precincts.contains(precinct_id) {
+            return Err(format!(
+                "ballot style {ballot_style_id} is not used in precinct {precinct_id}"
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -573,6 +619,61 @@ pub struct MarkThresholds {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_check_ballot_style_assignment() {
+        let election: Election = serde_json::from_value(serde_json::json!({
+            "title": "Test Election",
+            "date": "2021-06-06",
+            "ballotStyles": [
+                { "id": "1", "precincts": ["a"], "districts": [] },
+                { "id": "2", "precincts": ["a", "b"], "districts": [] },
+            ],
+            "precincts": [
+                { "id": "a", "name": "Precinct A" },
+                { "id": "b", "name": "Precinct B" },
+            ],
+            "districts": [],
+            "parties": [],
+            "contests": [],
+        }))
+        .unwrap();
+        let ballot_style_id = |id: &str| BallotStyleId::from(id.to_owned());
+        let precinct_id = |id: &str| PrecinctId::from(id.to_owned());
+
+        assert_eq!(
+            election.check_ballot_style_assignment(&ballot_style_id("1"), &precinct_id("a")),
+            Ok(())
+        );
+        assert_eq!(
+            election.check_ballot_style_assignment(&ballot_style_id("2"), &precinct_id("b")),
+            Ok(())
+        );
+        assert_eq!(
+            election.check_ballot_style_assignment(&ballot_style_id("1"), &precinct_id("b")),
+            Err("ballot style 1 is not used in precinct b".to_owned())
+        );
+        assert_eq!(
+            election.check_ballot_style_assignment(&ballot_style_id("3"), &precinct_id("a")),
+            Err("ballot style 3 is not in the election".to_owned())
+        );
+        assert_eq!(
+            election.check_ballot_style_assignment(&ballot_style_id("1"), &precinct_id("c")),
+            Err("precinct c is not in the election".to_owned())
+        );
+
+        assert_eq!(
+            election
+                .ballot_styles_for_precinct(&precinct_id("a"))
+                .into_iter()
+                .map(|ballot_style| ballot_style.id.clone())
+                .collect::<Vec<_>>(),
+            vec![ballot_style_id("1"), ballot_style_id("2")]
+        );
+        assert!(election
+            .ballot_styles_for_precinct(&precinct_id("c"))
+            .is_empty());
+    }
+
     #[test]
     fn test_grid_location() {
         let location = GridLocation::new(BallotSide::Front, 1, 2);
@@ -0,0 +1,634 @@
+//! Structural and referential checks on election definitions.
+//!
+//! An election definition can parse successfully and still be unusable, e.g.
+//! a ballot style may name a district that does not exist or a yes/no contest
+//! may be missing one of its options. [`Election::validate`] finds every such
+//! problem at once so they can all be fixed before the election is created.
+
+use std::{collections::HashSet, fmt, hash::Hash};
+
+use super::{
+    BallotStyleId, CandidateId, Contest, ContestId, DistrictId, Election, GridPosition, OptionId,
+    PartyId, PrecinctId,
+};
+
+/// A problem that makes an election definition unusable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElectionProblem {
+    MissingTitle,
+    NoBallotStyles,
+    NoContests,
+    DuplicateId {
+        kind: &'static str,
+        id: String,
+    },
+    DuplicateOptionId {
+        contest_id: ContestId,
+        option_id: OptionId,
+    },
+    BallotStyleWithoutPrecincts {
+        ballot_style_id: BallotStyleId,
+    },
+    BallotStyleWithoutContests {
+        ballot_style_id: BallotStyleId,
+    },
+    BallotStyleUnknownPrecinct {
+        ballot_style_id: BallotStyleId,
+        precinct_id: PrecinctId,
+    },
+    BallotStyleUnknownDistrict {
+        ballot_style_id: BallotStyleId,
+        district_id: DistrictId,
+    },
+    BallotStyleUnknownParty {
+        ballot_style_id: BallotStyleId,
+        party_id: PartyId,
+    },
+    ContestUnknownDistrict {
+        contest_id: ContestId,
+        district_id: DistrictId,
+    },
+    ContestUnknownParty {
+        contest_id: ContestId,
+        party_id: PartyId,
+    },
+    ContestWithoutSeats {
+        contest_id: ContestId,
+    },
+    ContestWithoutCandidates {
+        contest_id: ContestId,
+    },
+    CandidateUnknownParty {
+        contest_id: ContestId,
+        candidate_id: CandidateId,
+        party_id: PartyId,
+    },
+    MissingYesOption {
+        contest_id: ContestId,
+    },
+    MissingNoOption {
+        contest_id: ContestId,
+    },
+    GridLayoutUnknownBallotStyle {
+        ballot_style_id: BallotStyleId,
+    },
+    GridPositionUnknownContest {
+        ballot_style_id: BallotStyleId,
+        contest_id: ContestId,
+    },
+    GridPositionUnknownOption {
+        ballot_style_id: BallotStyleId,
+        contest_id: ContestId,
+        option_id: OptionId,
+    },
+}
+
+impl fmt::Display for ElectionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTitle => write!(f, "election has no title"),
+            Self::NoBallotStyles => write!(f, "election has no ballot styles"),
+            Self::NoContests => write!(f, "election has no contests"),
+            Self::DuplicateId { kind, id } => write!(f, "{kind} ID {id} is used more than once"),
+            Self::DuplicateOptionId {
+                contest_id,
+                option_id,
+            } => write!(
+                f,
+                "contest {contest_id} uses option ID {option_id} more than once"
+            ),
+            Self::BallotStyleWithoutPrecincts { ballot_style_id } => {
+                write!(f, "ballot style {ballot_style_id} has no precincts")
+            }
+            Self::BallotStyleWithoutContests { ballot_style_id } => {
+                write!(f, "ballot style {ballot_style_id} has no contests")
+            }
+            Self::BallotStyleUnknownPrecinct {
+                ballot_style_id,
+                precinct_id,
+            } => write!(
+                f,
+                "ballot style {ballot_style_id} references unknown precinct {precinct_id}"
+            ),
+            Self::BallotStyleUnknownDistrict {
+                ballot_style_id,
+                district_id,
+            } => write!(
+                f,
+                "ballot style {ballot_style_id} references unknown district {district_id}"
+            ),
+            Self::BallotStyleUnknownParty {
+                ballot_style_id,
+                party_id,
+            } => write!(
+                f,
+                "ballot style {ballot_style_id} references unknown party {party_id}"
+            ),
+            Self::ContestUnknownDistrict {
+                contest_id,
+                district_id,
+            } => write!(
+                f,
+                "contest {contest_id} references unknown district {district_id}"
+            ),
+            Self::ContestUnknownParty {
+                contest_id,
+                party_id,
+            } => write!(
+                f,
+                "contest {contest_id} references unknown party {party_id}"
+            ),
+            Self::ContestWithoutSeats { contest_id } => {
+                write!(f, "contest {contest_id} has no seats")
+            }
+            Self::ContestWithoutCandidates { contest_id } => {
+                write!(f, "contest {contest_id} has no candidates")
+            }
+            Self::CandidateUnknownParty {
+                contest_id,
+                candidate_id,
+                party_id,
+            } => write!(
+                f,
+                "candidate {candidate_id} in contest {contest_id} references unknown party {party_id}"
+            ),
+            Self::MissingYesOption { contest_id } => {
+                write!(f, "contest {contest_id} has no yes option")
+            }
+            Self::MissingNoOption { contest_id } => {
+                write!(f, "contest {contest_id} has no no option")
+            }
+            Self::GridLayoutUnknownBallotStyle { ballot_style_id } => write!(
+                f,
+                "grid layout references unknown ballot style {ballot_style_id}"
+            ),
+            Self::GridPositionUnknownContest {
+                ballot_style_id,
+                contest_id,
+            } => write!(
+                f,
+                "grid layout for ballot style {ballot_style_id} references contest {contest_id}, which is not on the ballot"
+            ),
+            Self::GridPositionUnknownOption {
+                ballot_style_id,
+                contest_id,
+                option_id,
+            } => write!(
+                f,
+                "grid layout for ballot style {ballot_style_id} references unknown option {option_id} in contest {contest_id}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ElectionProblem {}
+
+impl Election {
+    /// Checks the election's structure and the references between its parts,
+    /// returning every problem found.
+    pub fn validate(&self) -> Result<(), Vec<ElectionProblem>> {
+        let mut problems = Vec::new();
+
+        if self.title.trim().is_empty() {
+            problems.push(ElectionProblem::MissingTitle);
+        }
+        if self.ballot_styles.is_empty() {
+            problems.push(ElectionProblem::NoBallotStyles);
+        }
+        if self.contests.is_empty() {
+            problems.push(ElectionProblem::NoContests);
+        }
+
+        let precinct_ids = unique_ids(
+            "precinct",
+            self.precincts.iter().map(|precinct| &precinct.id),
+            &mut problems,
+        );
+        let district_ids = unique_ids(
+            "district",
+            self.districts.iter().map(|district| &district.id),
+            &mut problems,
+        );
+        let party_ids = unique_ids(
+            "party",
+            self.parties.iter().map(|party| &party.id),
+            &mut problems,
+        );
+        let ballot_style_ids = unique_ids(
+            "ballot style",
+            self.ballot_styles
+                .iter()
+                .map(|ballot_style| &ballot_style.id),
+            &mut problems,
+        );
+        unique_ids(
+            "contest",
+            self.contests.iter().map(Contest::id),
+            &mut problems,
+        );
+
+        self.validate_ballot_styles(&precinct_ids, &district_ids, &party_ids, &mut problems);
+        self.validate_contests(&district_ids, &party_ids, &mut problems);
+        self.validate_grid_layouts(&ballot_style_ids, &mut problems);
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    fn validate_ballot_styles(
+        &self,
+        precinct_ids: &HashSet<&PrecinctId>,
+        district_ids: &HashSet<&DistrictId>,
+        party_ids: &HashSet<&PartyId>,
+        problems: &mut Vec<ElectionProblem>,
+    ) {
+        for ballot_style in &self.ballot_styles {
+            if ballot_style.precincts.is_empty() {
+                problems.push(ElectionProblem::BallotStyleWithoutPrecincts {
+                    ballot_style_id: ballot_style.id.clone(),
+                });
+            }
+
+            for precinct_id in &ballot_style.precincts {
+                if !precinct_ids.contains(precinct_id) {
+                    problems.push(ElectionProblem::BallotStyleUnknownPrecinct {
+                        ballot_style_id: ballot_style.id.clone(),
+                        precinct_id: precinct_id.clone(),
+                    });
+                }
+            }
+
+            for district_id in &ballot_style.districts {
+                if !district_ids.contains(district_id) {
+                    problems.push(ElectionProblem::BallotStyleUnknownDistrict {
+                        ballot_style_id: ballot_style.id.clone(),
+                        district_id: district_id.clone(),
+                    });
+                }
+            }
+
+            if let Some(party_id) = &ballot_style.party_id {
+                if !party_ids.contains(party_id) {
+                    problems.push(ElectionProblem::BallotStyleUnknownParty {
+                        ballot_style_id: ballot_style.id.clone(),
+                        party_id: party_id.clone(),
+                    });
+                }
+            }
+
+            if !self
+                .contests
+                .iter()
+                .any(|contest| ballot_style.districts.contains(contest.district_id()))
+            {
+                problems.push(ElectionProblem::BallotStyleWithoutContests {
+                    ballot_style_id: ballot_style.id.clone(),
+                });
+            }
+        }
+    }
+
+    fn validate_contests(
+        &self,
+        district_ids: &HashSet<&DistrictId>,
+        party_ids: &HashSet<&PartyId>,
+        problems: &mut Vec<ElectionProblem>,
+    ) {
+        for contest in &self.contests {
+            if !district_ids.contains(contest.district_id()) {
+                problems.push(ElectionProblem::ContestUnknownDistrict {
+                    contest_id: contest.id().clone(),
+                    district_id: contest.district_id().clone(),
+                });
+            }
+
+            match contest {
+                Contest::Candidate(contest) => {
+                    if contest.seats == 0 {
+                        problems.push(ElectionProblem::ContestWithoutSeats {
+                            contest_id: contest.id.clone(),
+                        });
+                    }
+
+                    if contest.candidates.is_empty() && !contest.allow_write_ins {
+                        problems.push(ElectionProblem::ContestWithoutCandidates {
+                            contest_id: contest.id.clone(),
+                        });
+                    }
+
+                    if let Some(party_id) = &contest.party_id {
+                        if !party_ids.contains(party_id) {
+                            problems.push(ElectionProblem::ContestUnknownParty {
+                                contest_id: contest.id.clone(),
+                                party_id: party_id.clone(),
+                            });
+                        }
+                    }
+
+                    for candidate in &contest.candidates {
+                        for party_id in candidate.party_ids.iter().flatten() {
+                            if !party_ids.contains(party_id) {
+                                problems.push(ElectionProblem::CandidateUnknownParty {
+                                    contest_id: contest.id.clone(),
+                                    candidate_id: candidate.id.clone(),
+                                    party_id: party_id.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+                Contest::YesNo(contest) => {
+                    if contest.yes_option.is_none() {
+                        problems.push(ElectionProblem::MissingYesOption {
+                            contest_id: contest.id.clone(),
+                        });
+                    }
+                    if contest.no_option.is_none() {
+                        problems.push(ElectionProblem::MissingNoOption {
+                            contest_id: contest.id.clone(),
+                        });
+                    }
+                }
+            }
+
+            let mut option_ids = HashSet::new();
+            for option_id in contest.option_ids() {
+                if !option_ids.insert(option_id.clone()) {
+                    problems.push(ElectionProblem::DuplicateOptionId {
+                        contest_id: contest.id().clone(),
+                        option_id,
+                    });
+                }
+            }
+        }
+    }
+
+    fn validate_grid_layouts(
+        &self,
+        ballot_style_ids: &HashSet<&BallotStyleId>,
+        problems: &mut Vec<ElectionProblem>,
+    ) {
+        for grid_layout in self.grid_layouts.iter().flatten() {
+            let ballot_style_id = &grid_layout.ballot_style_id;
+            if !ballot_style_ids.contains(ballot_style_id) {
+                problems.push(ElectionProblem::GridLayoutUnknownBallotStyle {
+                    ballot_style_id: ballot_style_id.clone(),
+                });
+                continue;
+            }
+
+            let contests = self
+                .get_contests(ballot_style_id.clone())
+                .unwrap_or_default();
+
+            for grid_position in &grid_layout.grid_positions {
+                let contest_id = grid_position.contest_id();
+                let Some(contest) = contests.iter().find(|contest| contest.id() == &contest_id)
+                else {
+                    problems.push(ElectionProblem::GridPositionUnknownContest {
+                        ballot_style_id: ballot_style_id.clone(),
+                        contest_id,
+                    });
+                    continue;
+                };
+
+                let is_known_option = match grid_position {
+                    GridPosition::Option { option_id, .. } => {
+                        contest.option_ids().contains(option_id)
+                    }
+                    GridPosition::WriteIn { .. } => {
+                        matches!(contest, Contest::Candidate(contest) if contest.allow_write_ins)
+                    }
+                };
+
+                if !is_known_option {
+                    problems.push(ElectionProblem::GridPositionUnknownOption {
+                        ballot_style_id: ballot_style_id.clone(),
+                        contest_id,
+                        option_id: grid_position.option_id(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Collects `ids` into a set, recording a problem for each one that repeats.
+fn unique_ids<'a, T: Eq + Hash + fmt::Display>(
+    kind: &'static str,
+    ids: impl Iterator<Item = &'a T>,
+    problems: &mut Vec<ElectionProblem>,
+) -> HashSet<&'a T> {
+    let mut unique = HashSet::new();
+    for id in ids {
+        if !unique.insert(id) {
+            problems.push(ElectionProblem::DuplicateId {
+                kind,
+                id: id.to_string(),
+            });
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn election(value: serde_json::Value) -> Election {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_fixtures_are_valid() {
+        for election_data in [
+            &include_bytes!("../../../fixtures/data/electionFamousNames2021/election.json")[..],
+            &include_bytes!("../../../fixtures/data/electionGeneral/election.json")[..],
+            &include_bytes!("../../../fixtures/data/electionPrimaryPrecinctSplits/election.json")[..],
+            &include_bytes!("../../../fixtures/data/electionTwoPartyPrimary/election.json")[..],
+        ] {
+            let election: Election = serde_json::from_slice(election_data).unwrap();
+            assert_eq!(election.validate(), Ok(()), "{}", election.title);
+        }
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let election = election(json!({
+            "title": " ",
+            "date": "2021-06-06",
+            "ballotStyles": [
+                { "id": "1", "precincts": ["a", "z"], "districts": ["d1", "d9"], "partyId": "p9" },
+                { "id": "1", "precincts": [], "districts": [] },
+            ],
+            "precincts": [
+                { "id": "a", "name": "Precinct A" },
+                { "id": "b", "name": "Precinct B" },
+            ],
+            "districts": [{ "id": "d1", "name": "District 1" }],
+            "parties": [],
+            "contests": [
+                {
+                    "type": "candidate",
+                    "id": "mayor",
+                    "districtId": "d1",
+                    "title": "Mayor",
+                    "seats": 0,
+                    "candidates": [],
+                    "allowWriteIns": false,
+                },
+                {
+                    "type": "candidate",
+                    "id": "council",
+                    "districtId": "d1",
+                    "title": "Council",
+                    "seats": 2,
+                    "candidates": [
+                        { "id": "alice", "name": "Alice", "partyIds": ["p1"] },
+                        { "id": "alice", "name": "Alice Again" },
+                    ],
+                    "allowWriteIns": false,
+                },
+                {
+                    "type": "yesno",
+                    "id": "measure",
+                    "districtId": "d2",
+                    "title": "Measure",
+                    "description": "Should we?",
+                    "yesOption": { "id": "measure-yes", "label": "Yes" },
+                },
+            ],
+            "gridLayouts": [
+                {
+                    "ballotStyleId": "1",
+                    "optionBoundsFromTargetMark": { "top": 1, "right": 1, "bottom": 1, "left": 1 },
+                    "gridPositions": [
+                        {
+                            "type": "option",
+                            "side": "front",
+                            "column": 1,
+                            "row": 1,
+                            "contestId": "council",
+                            "optionId": "bob",
+                        },
+                        {
+                            "type": "write-in",
+                            "side": "front",
+                            "column": 1,
+                            "row": 2,
+                            "contestId": "council",
+                            "writeInIndex": 0,
+                        },
+                        {
+                            "type": "option",
+                            "side": "front",
+                            "column": 1,
+                            "row": 3,
+                            "contestId": "measure",
+                            "optionId": "measure-yes",
+                        },
+                    ],
+                },
+                {
+                    "ballotStyleId": "2",
+                    "optionBoundsFromTargetMark": { "top": 1, "right": 1, "bottom": 1, "left": 1 },
+                    "gridPositions": [],
+                },
+            ],
+        }));
+        let ballot_style_id = || BallotStyleId::from("1".to_owned());
+        let contest_id = |id: &str| ContestId::from(id.to_owned());
+
+        assert_eq!(
+            election.validate(),
+            Err(vec![
+                ElectionProblem::MissingTitle,
+                ElectionProblem::DuplicateId {
+                    kind: "ballot style",
+                    id: "1".to_owned(),
+                },
+                ElectionProblem::BallotStyleUnknownPrecinct {
+                    ballot_style_id: ballot_style_id(),
+                    precinct_id: PrecinctId::from("z".to_owned()),
+                },
+                ElectionProblem::BallotStyleUnknownDistrict {
+                    ballot_style_id: ballot_style_id(),
+                    district_id: DistrictId::from("d9".to_owned()),
+                },
+                ElectionProblem::BallotStyleUnknownParty {
+                    ballot_style_id: ballot_style_id(),
+                    party_id: PartyId::from("p9".to_owned()),
+                },
+                ElectionProblem::BallotStyleWithoutPrecincts {
+                    ballot_style_id: ballot_style_id(),
+                },
+                ElectionProblem::BallotStyleWithoutContests {
+                    ballot_style_id: ballot_style_id(),
+                },
+                ElectionProblem::ContestWithoutSeats {
+                    contest_id: contest_id("mayor"),
+                },
+                ElectionProblem::ContestWithoutCandidates {
+                    contest_id: contest_id("mayor"),
+                },
+                ElectionProblem::CandidateUnknownParty {
+                    contest_id: contest_id("council"),
+                    candidate_id: CandidateId::from("alice".to_owned()),
+                    party_id: PartyId::from("p1".to_owned()),
+                },
+                ElectionProblem::DuplicateOptionId {
+                    contest_id: contest_id("council"),
+                    option_id: OptionId::from("alice".to_owned()),
+                },
+                ElectionProblem::ContestUnknownDistrict {
+                    contest_id: contest_id("measure"),
+                    district_id: DistrictId::from("d2".to_owned()),
+                },
+                ElectionProblem::MissingNoOption {
+                    contest_id: contest_id("measure"),
+                },
+                ElectionProblem::GridPositionUnknownOption {
+                    ballot_style_id: ballot_style_id(),
+                    contest_id: contest_id("council"),
+                    option_id: OptionId::from("bob".to_owned()),
+                },
+                ElectionProblem::GridPositionUnknownOption {
+                    ballot_style_id: ballot_style_id(),
+                    contest_id: contest_id("council"),
+                    option_id: OptionId::from("write-in-0".to_owned()),
+                },
+                ElectionProblem::GridPositionUnknownContest {
+                    ballot_style_id: ballot_style_id(),
+                    contest_id: contest_id("measure"),
+                },
+                ElectionProblem::GridLayoutUnknownBallotStyle {
+                    ballot_style_id: BallotStyleId::from("2".to_owned()),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_problem_display() {
+        assert_eq!(
+            ElectionProblem::MissingYesOption {
+                contest_id: ContestId::from("measure".to_owned()),
+            }
+            .to_string(),
+            "contest measure has no yes option"
+        );
+        assert_eq!(
+            ElectionProblem::DuplicateId {
+                kind: "precinct",
+                id: "a".to_owned(),
+            }
+            .to_string(),
+            "precinct ID a is used more than once"
+        );
+    }
+}